@@ -0,0 +1,335 @@
+//! Local scripting API served by the proxy server.
+//!
+//! Lets external tools (stream decks, scripts) list projects, queue clip
+//! exports, poll their status and record live action markers. The API is off
+//! by default and every request must carry `Authorization: Bearer <token>`
+//! with the token from settings.
+//! Exports queued here run in the background and don't show up in the UI.
+
+use axum::{
+    extract::{Path, Request},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use futures_util::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::commands::{run_export, validate_name, ClipRequest, ExportResult, ProgressEmitter};
+use crate::config::get_config;
+use crate::export::ExportProgress;
+use crate::{markers, project};
+
+/// State of a queued export job
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum JobState {
+    Running,
+    Finished,
+    Failed,
+}
+
+/// Status of an export job started through the API
+#[derive(Debug, Clone, Serialize)]
+struct ExportJob {
+    id: String,
+    project_name: String,
+    state: JobState,
+    total_clips: usize,
+    completed_clips: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<ExportResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// When the job finished or failed, for pruning
+    #[serde(skip)]
+    ended_at: Option<Instant>,
+}
+
+#[derive(Deserialize)]
+struct QueueExportBody {
+    project_name: String,
+    clips: Vec<ClipRequest>,
+}
+
+#[derive(Deserialize, Default)]
+struct AddMarkerBody {
+    #[serde(default)]
+    label: String,
+}
+
+#[derive(Serialize)]
+struct QueueExportResponse {
+    id: String,
+}
+
+static JOBS: OnceLock<Mutex<HashMap<String, ExportJob>>> = OnceLock::new();
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+/// How long the status of an ended job can still be polled
+const JOB_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+fn jobs() -> &'static Mutex<HashMap<String, ExportJob>> {
+    JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Forget jobs that ended more than [`JOB_RETENTION`] ago
+fn prune_jobs(jobs: &mut HashMap<String, ExportJob>) {
+    jobs.retain(|_, job| {
+        job.ended_at
+            .is_none_or(|ended| ended.elapsed() < JOB_RETENTION)
+    });
+}
+
+/// Apply `f` to a job if it still exists
+fn update_job(id: &str, f: impl FnOnce(&mut ExportJob)) {
+    if let Some(job) = jobs().lock().unwrap().get_mut(id) {
+        f(job);
+    }
+}
+
+/// Build the API routes (merged into the proxy router)
+pub fn router() -> Router {
+    Router::new()
+        .route("/api/projects", get(list_projects))
+        .route("/api/exports", post(queue_export))
+        .route("/api/exports/:id", get(export_status))
+        .route("/api/markers", post(add_marker))
+        .route_layer(middleware::from_fn(require_token))
+        .layer(
+            CorsLayer::new()
+                .allow_origin(AllowOrigin::predicate(|origin, _| is_local_origin(origin)))
+                .allow_methods([Method::GET, Method::POST])
+                .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE]),
+        )
+}
+
+/// Whether a browser origin is the app's own webview or a page served from
+/// this machine; other websites can't call the API from a browser
+fn is_local_origin(origin: &HeaderValue) -> bool {
+    let Ok(origin) = origin.to_str() else {
+        return false;
+    };
+    if matches!(origin, "tauri://localhost" | "http://tauri.localhost") {
+        return true;
+    }
+    let Some(rest) = origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+    else {
+        return false;
+    };
+    let host = rest.rsplit_once(':').map_or(rest, |(host, port)| {
+        if port.chars().all(|c| c.is_ascii_digit()) {
+            host
+        } else {
+            rest
+        }
+    });
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+/// Compare tokens in constant time, so response timing doesn't reveal how
+/// much of a guess was right
+fn tokens_match(expected: &str, provided: &str) -> bool {
+    let (a, b) = (expected.as_bytes(), provided.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Reject requests when the API is disabled or the bearer token doesn't match
+async fn require_token(request: Request, next: Next) -> Response {
+    let config = get_config();
+    if !config.api_enabled() {
+        return (StatusCode::FORBIDDEN, "Scripting API is disabled").into_response();
+    }
+
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    match (config.api_token(), provided) {
+        (Some(expected), Some(provided)) if tokens_match(expected, provided) => {
+            next.run(request).await
+        }
+        _ => (StatusCode::UNAUTHORIZED, "Invalid API token").into_response(),
+    }
+}
+
+async fn list_projects() -> Response {
+    match project::list_projects() {
+        Ok(projects) => Json(projects).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Names in an export become folders and files under the work dir: only
+/// plain names that can't point outside it are accepted
+fn validate_export_names(body: &QueueExportBody) -> Result<(), String> {
+    std::iter::once(&body.project_name)
+        .chain(
+            body.clips
+                .iter()
+                .flat_map(|c| [&c.streamer_name, &c.action_id]),
+        )
+        .try_for_each(|name| validate_name(name))
+}
+
+async fn queue_export(Json(body): Json<QueueExportBody>) -> Response {
+    if let Err(e) = validate_export_names(&body) {
+        return (StatusCode::BAD_REQUEST, e).into_response();
+    }
+    match project::load_project(&body.project_name) {
+        Ok(Some(_)) => {}
+        Ok(None) => return (StatusCode::NOT_FOUND, "Unknown project").into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+
+    let id = format!("job-{}", NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed));
+
+    let mut queued = jobs().lock().unwrap();
+    prune_jobs(&mut queued);
+    queued.insert(
+        id.clone(),
+        ExportJob {
+            id: id.clone(),
+            project_name: body.project_name.clone(),
+            state: JobState::Running,
+            total_clips: body.clips.len(),
+            completed_clips: 0,
+            result: None,
+            error: None,
+            ended_at: None,
+        },
+    );
+    drop(queued);
+
+    log::info!(
+        "[API] Queued export {} ({} clips for {})",
+        id,
+        body.clips.len(),
+        body.project_name
+    );
+
+    let job_id = id.clone();
+    let emit: ProgressEmitter = Arc::new(move |event| {
+        if let ExportProgress::ClipCompleted { .. } = event {
+            update_job(&job_id, |job| job.completed_clips += 1);
+        }
+    });
+
+    let job_id = id.clone();
+    tokio::spawn(async move {
+        // A panic must still end the job, or it stays running and is never pruned
+        let result = AssertUnwindSafe(run_export(&body.project_name, body.clips, emit))
+            .catch_unwind()
+            .await
+            .unwrap_or_else(|_| Err("Export stopped unexpectedly".to_string()));
+        update_job(&job_id, |job| {
+            match result {
+                Ok(result) => {
+                    job.state = JobState::Finished;
+                    job.result = Some(result);
+                }
+                Err(e) => {
+                    job.state = JobState::Failed;
+                    job.error = Some(e);
+                }
+            }
+            job.ended_at = Some(Instant::now());
+        });
+    });
+
+    (StatusCode::ACCEPTED, Json(QueueExportResponse { id })).into_response()
+}
+
+async fn export_status(Path(id): Path<String>) -> Response {
+    let mut jobs = jobs().lock().unwrap();
+    prune_jobs(&mut jobs);
+    match jobs.get(&id) {
+        Some(job) => Json(job.clone()).into_response(),
+        None => (StatusCode::NOT_FOUND, "Unknown export job").into_response(),
+    }
+}
+
+async fn add_marker(body: Option<Json<AddMarkerBody>>) -> Response {
+    let body = body.map(|Json(b)| b).unwrap_or_default();
+
+    match markers::add_marker(body.label) {
+        Ok(marker) => (StatusCode::CREATED, Json(marker)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_origins() {
+        for origin in [
+            "tauri://localhost",
+            "http://tauri.localhost",
+            "http://localhost:1420",
+            "http://127.0.0.1:8080",
+            "http://[::1]:3000",
+        ] {
+            assert!(
+                is_local_origin(&HeaderValue::from_static(origin)),
+                "{origin}"
+            );
+        }
+        for origin in [
+            "https://example.com",
+            "http://localhost.example.com",
+            "http://127.0.0.1.nip.io:80",
+            "null",
+        ] {
+            assert!(
+                !is_local_origin(&HeaderValue::from_static(origin)),
+                "{origin}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_export_names() {
+        let body = |project: &str, streamer: &str| -> QueueExportBody {
+            serde_json::from_value(serde_json::json!({
+                "project_name": project,
+                "clips": [{
+                    "vod_url": "https://www.twitch.tv/videos/1",
+                    "streamer_name": streamer,
+                    "action_id": "abc123",
+                    "action_name": "Ace",
+                    "game_start_time": 0.0,
+                    "action_game_time": 60.0,
+                    "sync_offset": 0.0,
+                    "in_point": -5.0,
+                    "out_point": 5.0,
+                    "index": 0
+                }]
+            }))
+            .unwrap()
+        };
+        assert!(validate_export_names(&body("Scrim", "Streamer Name")).is_ok());
+        assert!(validate_export_names(&body("../..", "Streamer")).is_err());
+        assert!(validate_export_names(&body("Scrim", "../../tmp")).is_err());
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("abc123", "abc123"));
+        assert!(!tokens_match("abc123", "abc124"));
+        assert!(!tokens_match("abc123", "abc12"));
+    }
+}
@@ -1,5 +1,6 @@
 use super::paths::{ensure_bin_dir, get_binary_path};
 use crate::error::{BinaryError, BinaryResult};
+use crate::scratch::ScratchDir;
 use futures_util::StreamExt;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -245,14 +246,14 @@ pub async fn download_ffmpeg(progress: Option<ProgressCallback>) -> BinaryResult
     let url = get_download_url(BinaryType::Ffmpeg)?;
     let bin_dir = ensure_bin_dir()?;
 
-    // Download to temp file
-    let temp_dir = std::env::temp_dir();
+    // Download to a private temp folder (removed when done)
+    let scratch = ScratchDir::new("ffmpeg-download")?;
     let archive_ext = if cfg!(target_os = "linux") {
         "tar.xz"
     } else {
         "zip"
     };
-    let archive_path = temp_dir.join(format!("ffmpeg_download.{}", archive_ext));
+    let archive_path = scratch.join(&format!("ffmpeg_download.{}", archive_ext));
 
     download_file(url, &archive_path, progress).await?;
 
@@ -263,9 +264,6 @@ pub async fn download_ffmpeg(progress: Option<ProgressCallback>) -> BinaryResult
     #[cfg(target_os = "linux")]
     let result = extract_ffmpeg_tar(&archive_path, &bin_dir)?;
 
-    // Clean up temp file
-    let _ = std::fs::remove_file(&archive_path);
-
     Ok(result)
 }
 
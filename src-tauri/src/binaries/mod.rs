@@ -2,7 +2,10 @@ mod download;
 mod paths;
 
 pub use download::{download_binary, BinaryType};
-pub use paths::{ensure_config_dir, get_binary_path, get_config_path};
+pub use paths::{
+    ensure_config_dir, get_bin_dir, get_binary_path, get_config_dir, get_config_path,
+    get_inbox_path, get_log_dir, get_markers_path, get_overlay_presets_path,
+};
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -10,12 +13,14 @@ use std::process::Command;
 
 /// Status of installed binaries
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
 pub struct BinaryStatus {
     pub ffmpeg: BinaryInfo,
     pub ytdlp: BinaryInfo,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
 pub struct BinaryInfo {
     pub installed: bool,
     pub path: Option<String>,
@@ -24,6 +29,7 @@ pub struct BinaryInfo {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
 #[serde(rename_all = "snake_case")]
 pub enum BinarySource {
     /// Found in system PATH
@@ -37,6 +37,26 @@ pub fn get_config_path() -> PathBuf {
     get_config_dir().join("config.json")
 }
 
+/// Get the overlay style presets file path
+pub fn get_overlay_presets_path() -> PathBuf {
+    get_config_dir().join("overlay_presets.json")
+}
+
+/// Get the watch-later inbox file path
+pub fn get_inbox_path() -> PathBuf {
+    get_config_dir().join("inbox.json")
+}
+
+/// Get the live action markers file path
+pub fn get_markers_path() -> PathBuf {
+    get_config_dir().join("live_markers.json")
+}
+
+/// Get the log file directory
+pub fn get_log_dir() -> PathBuf {
+    get_config_dir().join("logs")
+}
+
 /// Ensure the binary directory exists
 pub fn ensure_bin_dir() -> std::io::Result<PathBuf> {
     let dir = get_bin_dir();
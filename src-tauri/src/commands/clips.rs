@@ -0,0 +1,1060 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+
+use super::{generate_filename, ClipRequest};
+use crate::config::get_config;
+use crate::error::{ExportError, NoxError};
+use crate::export::cache::{self, vod_key};
+use crate::export::{
+    ClipTiming, Container, ExportOptions, FfmpegExporter, FrameFormat, NormalizeFormat,
+    ProgressCallback, SmartExporter,
+};
+use crate::manifest;
+use crate::montage::OutputFormat;
+use crate::platform::VodResolverChain;
+use crate::project::ClipStatus;
+use crate::scratch::{self, ScratchDir};
+use crate::thumbnails::{self, ThumbnailLayout};
+
+/// Re-cut an already exported clip into a new file next to the original
+///
+/// `new_start` and `new_end` are relative to the start of the existing clip,
+/// which must sit in the work folder. Returns the path of the trimmed file.
+#[tauri::command]
+pub async fn trim_exported_clip(
+    path: String,
+    new_start: f64,
+    new_end: f64,
+) -> Result<String, String> {
+    let input = PathBuf::from(&path);
+    ensure_in_work_dir(&input)?;
+    let exporter = FfmpegExporter::new();
+    let duration = exporter.probe_duration(&input).await?;
+    validate_trim(new_start, new_end, duration)?;
+
+    let output_path = trimmed_path(&input);
+    let timing = ClipTiming::new(new_start, new_end - new_start);
+    // The trimmed file keeps the source's extension, so mux it the same way
+    let options = ExportOptions {
+        container: Container::from_path(&input).unwrap_or_default(),
+        ..Default::default()
+    };
+
+    log::info!(
+        "Trimming {} -> {} (start={:.2}s, duration={:.2}s)",
+        input.display(),
+        output_path.display(),
+        timing.start,
+        timing.duration
+    );
+
+    exporter
+        .trim_local(&input, &timing, &options, &output_path, None, None)
+        .await?;
+
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+/// Check a trim range against the length of the clip it cuts
+fn validate_trim(start: f64, end: f64, duration: f64) -> Result<(), NoxError> {
+    if start < 0.0 || end <= start {
+        return Err(ExportError::InvalidTimeRange { start, end }.into());
+    }
+    // ffprobe durations are rounded, so allow a frame of slack at the end
+    if end > duration + 0.05 {
+        return Err(ExportError::TrimOutOfRange {
+            start,
+            end,
+            duration,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Re-cut an exported clip in place after its in/out points moved inward
+///
+/// `clip` carries the new in/out points; `previous_in_point` and
+/// `previous_out_point` are the ones the file on disk was exported with. The
+/// existing file is cut locally (stream copy), so nothing is downloaded. A
+/// range reaching outside the old one fails; that clip has to be exported
+/// again. Returns the clip path.
+#[tauri::command]
+pub async fn trim_local_clip(
+    project_name: String,
+    clip: ClipRequest,
+    previous_in_point: f64,
+    previous_out_point: f64,
+) -> Result<String, String> {
+    let timing = local_trim_timing(
+        (previous_in_point, previous_out_point),
+        (clip.in_point, clip.out_point),
+        clip.speed.unwrap_or(1.0),
+    )
+    .ok_or_else(|| {
+        format!(
+            "New range {:.2}-{:.2} is not inside the exported range {:.2}-{:.2}; export the clip again",
+            clip.in_point, clip.out_point, previous_in_point, previous_out_point
+        )
+    })?;
+
+    let filename = generate_filename(&clip.action_id, &clip.action_name, clip.container);
+    let clip_dir = get_config().streamer_clips_dir(&project_name, &clip.streamer_name);
+    let path = clip_dir.join(&filename);
+
+    // Cut next to the clip, then swap it in
+    let scratch = ScratchDir::new_in(&clip_dir, "trim").map_err(|e| e.to_string())?;
+    let trimmed = scratch.join(&filename);
+    let options = ExportOptions {
+        container: clip.container,
+        ..Default::default()
+    };
+
+    log::info!(
+        "Re-trimming {} locally (start={:.2}s, duration={:.2}s)",
+        path.display(),
+        timing.start,
+        timing.duration
+    );
+
+    FfmpegExporter::new()
+        .trim_local(&path, &timing, &options, &trimmed, None, None)
+        .await?;
+    std::fs::rename(&trimmed, &path)
+        .map_err(|e| format!("Failed to replace {}: {}", path.display(), e))?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Delete one exported clip (and its proxy) so it can be exported again
+///
+/// All three names must be plain file/folder names; anything that could
+/// point outside the project's clips folder is rejected.
+#[tauri::command]
+pub async fn delete_clip_file(
+    project_name: String,
+    streamer_name: String,
+    filename: String,
+) -> Result<(), String> {
+    for name in [&project_name, &streamer_name, &filename] {
+        validate_name(name)?;
+    }
+    if Container::from_path(Path::new(&filename)).is_none() {
+        return Err(format!("Not a clip file: {}", filename));
+    }
+
+    let config = get_config();
+    let clips_dir = config.clips_dir(&project_name);
+    let path = config
+        .streamer_clips_dir(&project_name, &streamer_name)
+        .join(&filename);
+
+    // Catches symlinked folders pointing elsewhere
+    let inside = match (path.canonicalize(), clips_dir.canonicalize()) {
+        (Ok(path), Ok(clips_dir)) => path.starts_with(clips_dir),
+        _ => return Err(format!("Clip not found: {}", path.display())),
+    };
+    if !inside {
+        return Err(format!("{} is outside the clips folder", path.display()));
+    }
+
+    std::fs::remove_file(&path).map_err(|e| NoxError::from(e).to_string())?;
+    if let Some(proxy) = proxy_path(&clips_dir, &path) {
+        let _ = std::fs::remove_file(proxy);
+    }
+
+    log::info!("Deleted clip {}", path.display());
+    Ok(())
+}
+
+/// Result of renaming one clip file
+#[derive(Debug, Serialize)]
+pub struct ClipRename {
+    pub from: String,
+    pub to: String,
+    /// Why the file was left in place
+    pub error: Option<String>,
+}
+
+/// Rename a project's exported clips after one of its actions was renamed
+///
+/// Clip filenames embed the action name, so without this the files of a
+/// renamed action are orphaned. Matching files in every streamer folder are
+/// renamed along with their proxies, and `checksums.json` is updated. A clip
+/// whose new name is already taken is left in place.
+#[tauri::command]
+pub async fn rename_action_clips(
+    project_name: String,
+    action_id: String,
+    old_name: String,
+    new_name: String,
+) -> Result<Vec<ClipRename>, String> {
+    validate_name(&project_name)?;
+    let renames = renamed_filenames(&action_id, &old_name, &new_name);
+    if renames.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let config = get_config();
+    let clips_dir = config.clips_dir(&project_name);
+    let mut report = Vec::new();
+    let mut moved = Vec::new();
+
+    for path in clip_files(&clips_dir) {
+        let Some(filename) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some((_, new_filename)) = renames.iter().find(|(old, _)| old == filename) else {
+            continue;
+        };
+
+        let target = path.with_file_name(new_filename);
+        let error = if target.exists() {
+            Some(format!("{} already exists", target.display()))
+        } else {
+            std::fs::rename(&path, &target)
+                .err()
+                .map(|e| NoxError::from(e).to_string())
+        };
+
+        if error.is_none() {
+            log::info!("Renamed clip {} -> {}", path.display(), target.display());
+            if let (Some(old_proxy), Some(new_proxy)) = (
+                proxy_path(&clips_dir, &path),
+                proxy_path(&clips_dir, &target),
+            ) {
+                if old_proxy.exists() {
+                    let _ = std::fs::rename(old_proxy, new_proxy);
+                }
+            }
+            moved.push((path.clone(), target.clone()));
+        }
+
+        report.push(ClipRename {
+            from: path.to_string_lossy().to_string(),
+            to: target.to_string_lossy().to_string(),
+            error,
+        });
+    }
+
+    if !moved.is_empty() {
+        manifest::rename_entries(&config.project_dir(&project_name), &moved)
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(report)
+}
+
+/// Old and new clip filename of an action, per container (empty if unchanged)
+fn renamed_filenames(action_id: &str, old_name: &str, new_name: &str) -> Vec<(String, String)> {
+    Container::ALL
+        .iter()
+        .map(|&c| {
+            (
+                generate_filename(action_id, old_name, c),
+                generate_filename(action_id, new_name, c),
+            )
+        })
+        .filter(|(old, new)| old != new)
+        .collect()
+}
+
+/// Health of one clip file
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ClipHealth {
+    Ok,
+    /// Not exported yet (nothing to repair)
+    Missing,
+    /// Zero-byte file, e.g. left by a crashed export
+    Empty,
+    /// ffprobe can't read the file
+    Corrupt {
+        error: String,
+    },
+    /// Duration differs from the clip's in/out range
+    DurationMismatch {
+        expected: f64,
+        actual: f64,
+    },
+}
+
+impl ClipHealth {
+    /// Whether the file is broken and should be exported again
+    fn is_broken(&self) -> bool {
+        matches!(
+            self,
+            Self::Empty | Self::Corrupt { .. } | Self::DurationMismatch { .. }
+        )
+    }
+}
+
+/// Verification result for one project clip
+#[derive(Debug, Serialize)]
+pub struct ClipVerification {
+    pub path: String,
+    pub streamer_name: String,
+    pub action_name: String,
+    #[serde(flatten)]
+    pub health: ClipHealth,
+    /// The broken file was deleted, so the next export downloads it again
+    pub deleted: bool,
+}
+
+/// Check every non-excluded clip of a project with ffprobe
+///
+/// Flags zero-byte, unreadable and duration-mismatched files. With
+/// `delete_broken`, those files (and their proxies) are removed so the next
+/// export re-downloads them. The project doesn't record export options, so
+/// clips exported with a speed change need that `speed` passed in.
+#[tauri::command]
+pub async fn verify_project_clips(
+    project_name: String,
+    delete_broken: bool,
+    speed: Option<f64>,
+) -> Result<Vec<ClipVerification>, String> {
+    let project = crate::project::load_project(&project_name)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Project not found: {}", project_name))?;
+    let config = get_config();
+    let clips_dir = config.clips_dir(&project_name);
+    let exporter = FfmpegExporter::new();
+
+    let mut report = Vec::new();
+    for action in &project.actions {
+        for clip in &action.clips {
+            if matches!(clip.status, ClipStatus::Excluded) {
+                continue;
+            }
+            let Some(streamer) = project.streamers.iter().find(|s| s.id == clip.streamer_id) else {
+                continue;
+            };
+
+            // Clips may have been exported to any container
+            let streamer_dir = config.streamer_clips_dir(&project_name, &streamer.name);
+            let candidates: Vec<PathBuf> = Container::ALL
+                .iter()
+                .map(|&c| streamer_dir.join(generate_filename(&action.id, &action.name, c)))
+                .collect();
+            let existing = candidates.iter().find(|p| p.exists());
+            let path = existing.unwrap_or(&candidates[0]).clone();
+
+            let health = match existing {
+                None => ClipHealth::Missing,
+                Some(path) => {
+                    let expected = expected_clip_duration(clip.in_point, clip.out_point, speed);
+                    check_clip(&exporter, path, expected).await
+                }
+            };
+
+            let deleted = delete_broken && health.is_broken() && {
+                log::info!("Deleting broken clip {} ({:?})", path.display(), health);
+                if let Some(proxy) = proxy_path(&clips_dir, &path) {
+                    let _ = std::fs::remove_file(proxy);
+                }
+                std::fs::remove_file(&path).is_ok()
+            };
+
+            report.push(ClipVerification {
+                path: path.to_string_lossy().to_string(),
+                streamer_name: streamer.name.clone(),
+                action_name: action.name.clone(),
+                health,
+                deleted,
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+/// Length of an exported clip: its in/out range played at `speed`
+fn expected_clip_duration(in_point: f64, out_point: f64, speed: Option<f64>) -> f64 {
+    let speed = speed.filter(|s| *s > 0.0).unwrap_or(1.0);
+    (out_point - in_point) / speed
+}
+
+/// Probe one existing clip file
+async fn check_clip(exporter: &FfmpegExporter, path: &Path, expected: f64) -> ClipHealth {
+    if std::fs::metadata(path).map(|m| m.len()).unwrap_or(0) == 0 {
+        return ClipHealth::Empty;
+    }
+
+    match exporter.verify_output(path, expected).await {
+        Ok(()) => ClipHealth::Ok,
+        Err(ExportError::DurationMismatch { expected, actual }) => {
+            ClipHealth::DurationMismatch { expected, actual }
+        }
+        Err(e) => ClipHealth::Corrupt {
+            error: e.to_string(),
+        },
+    }
+}
+
+/// Result of normalizing a clip in place
+#[derive(Debug, Serialize)]
+pub struct NormalizeClipResult {
+    pub path: String,
+    pub backup_path: String,
+}
+
+/// Re-encode a single clip in place to `format` (the montage's output size
+/// and rate), or its own frame size at a constant rate when unset
+///
+/// Used when montage preflight flags one clip with an odd frame rate or codec.
+/// The clip must sit in the work folder; the original file is kept next to
+/// it with a `.bak` suffix.
+#[tauri::command]
+pub async fn normalize_clip(
+    path: String,
+    format: Option<OutputFormat>,
+) -> Result<NormalizeClipResult, String> {
+    let clip_path = PathBuf::from(&path);
+    ensure_in_work_dir(&clip_path)?;
+    let format = format.map(|f| NormalizeFormat::new(f.width, f.height, f.fps));
+
+    let backup_path = FfmpegExporter::new()
+        .normalize_local(&clip_path, format.as_ref(), None)
+        .await?;
+
+    Ok(NormalizeClipResult {
+        path,
+        backup_path: backup_path.to_string_lossy().to_string(),
+    })
+}
+
+/// Largest size of the extracted frame cache (oldest frames go first)
+const MAX_FRAME_CACHE_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Largest size of the sync audio cache (oldest windows go first)
+const MAX_AUDIO_CACHE_BYTES: u64 = 500 * 1024 * 1024;
+
+/// Extract a still frame from a clip file or resolved VOD URL
+///
+/// `timestamp` is in seconds from the start of the source. Local files must
+/// sit in the work folder (project clips, caches). Returns the path of the
+/// image in the temp dir, for display through the asset protocol. Frames are
+/// kept, so asking for the same one again doesn't run ffmpeg.
+#[tauri::command]
+pub async fn get_frame(
+    source: String,
+    timestamp: f64,
+    format: Option<FrameFormat>,
+) -> Result<String, String> {
+    if !is_frame_source_allowed(&source, &get_config().output_dir) {
+        return Err(format!("Not a project or cache file: {}", source));
+    }
+
+    let format = format.unwrap_or_default();
+    let output = frame_path(&source, timestamp, format);
+
+    if !output.exists() {
+        if let Some(dir) = output.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        FfmpegExporter::new()
+            .extract_frame(&source, timestamp, format, &output)
+            .await?;
+        if let Some(dir) = output.parent() {
+            cache::prune_dir(dir, MAX_FRAME_CACHE_BYTES);
+        }
+    }
+
+    Ok(output.to_string_lossy().to_string())
+}
+
+/// Download a window of a VOD's audio for sync offset detection
+///
+/// Uses the audio-only rendition where the platform has one (Twitch
+/// `audio_only`, yt-dlp `bestaudio`), which is a small fraction of the video's
+/// size. Returns the path of a mono 16 kHz WAV in the temp dir; windows are
+/// kept, so asking for the same one again downloads nothing.
+#[tauri::command]
+pub async fn extract_sync_audio(
+    vod_url: String,
+    start: f64,
+    duration: f64,
+) -> Result<String, String> {
+    let timing = ClipTiming::new(start, duration);
+    let output = sync_audio_path(&vod_url, &timing);
+
+    if !output.exists() {
+        let vod = VodResolverChain::new()
+            .resolve_audio(&vod_url)
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(dir) = output.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        FfmpegExporter::new()
+            .extract_audio_window(&vod.url, &timing, &output)
+            .await?;
+        if let Some(dir) = output.parent() {
+            cache::prune_dir(dir, MAX_AUDIO_CACHE_BYTES);
+        }
+    }
+
+    Ok(output.to_string_lossy().to_string())
+}
+
+/// Length of the clip cut by a smoke test (seconds)
+const SMOKE_CLIP_DURATION: f64 = 3.0;
+
+/// Stage of a VOD smoke test, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmokeStage {
+    Resolve,
+    Probe,
+    Cut,
+    Verify,
+}
+
+/// Outcome of one smoke test stage
+#[derive(Debug, Clone, Serialize)]
+pub struct SmokeStageResult {
+    pub stage: SmokeStage,
+    pub ok: bool,
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+/// Result of `smoke_test_vod`
+#[derive(Debug, Clone, Serialize)]
+pub struct SmokeTestReport {
+    /// Every stage passed
+    pub ok: bool,
+    /// VOD duration in seconds, if it could be probed
+    pub duration: Option<f64>,
+    /// Stages that ran; the test stops at the first failure
+    pub stages: Vec<SmokeStageResult>,
+}
+
+impl SmokeTestReport {
+    /// Record a stage, returning its value if it passed
+    fn record<T>(
+        &mut self,
+        stage: SmokeStage,
+        started: std::time::Instant,
+        result: Result<T, String>,
+    ) -> Option<T> {
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        match &result {
+            Ok(_) => log::info!("[SmokeTest] {:?} ok ({} ms)", stage, elapsed_ms),
+            Err(e) => log::warn!("[SmokeTest] {:?} failed: {}", stage, e),
+        }
+        self.stages.push(SmokeStageResult {
+            stage,
+            ok: result.is_ok(),
+            error: result.as_ref().err().cloned(),
+            elapsed_ms,
+        });
+        self.ok = result.is_ok();
+        result.ok()
+    }
+}
+
+/// Where a smoke test cuts its clip: a minute in, or as close as the VOD allows
+fn smoke_cut_start(duration: f64) -> f64 {
+    (duration - SMOKE_CLIP_DURATION).clamp(0.0, 60.0)
+}
+
+/// Check that a VOD can be clipped before building a project around it
+///
+/// Resolves the URL, probes the stream's duration, cuts a 3-second clip into
+/// a scratch folder and verifies it, the same way a real export would.
+/// Failures are reported per stage rather than returned as an error.
+#[tauri::command]
+pub async fn smoke_test_vod(url: String) -> Result<SmokeTestReport, String> {
+    let mut report = SmokeTestReport {
+        ok: false,
+        duration: None,
+        stages: Vec::new(),
+    };
+    let ffmpeg = FfmpegExporter::new();
+
+    let started = std::time::Instant::now();
+    let resolved = VodResolverChain::new()
+        .resolve(&url)
+        .await
+        .map_err(|e| e.to_string());
+    let Some(vod) = report.record(SmokeStage::Resolve, started, resolved) else {
+        return Ok(report);
+    };
+
+    let started = std::time::Instant::now();
+    let probed = ffmpeg
+        .probe_duration(Path::new(&vod.url))
+        .await
+        .map_err(|e| e.to_string());
+    let Some(duration) = report.record(SmokeStage::Probe, started, probed) else {
+        return Ok(report);
+    };
+    report.duration = Some(duration);
+
+    let scratch = ScratchDir::new("smoke-test").map_err(|e| e.to_string())?;
+    let output = scratch.join("clip.mp4");
+    let timing = ClipTiming::new(smoke_cut_start(duration), SMOKE_CLIP_DURATION);
+
+    let started = std::time::Instant::now();
+    let cut = SmartExporter::new()
+        .export_with_progress(
+            &vod,
+            &timing,
+            &ExportOptions::default(),
+            &output,
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| e.to_string());
+    if report.record(SmokeStage::Cut, started, cut).is_none() {
+        return Ok(report);
+    }
+
+    let started = std::time::Instant::now();
+    let verified = ffmpeg
+        .verify_output(&output, SMOKE_CLIP_DURATION)
+        .await
+        .map_err(|e| e.to_string());
+    report.record(SmokeStage::Verify, started, verified);
+
+    Ok(report)
+}
+
+/// Storyboard sprite sheets of a clip file or resolved VOD URL, for hover-scrubbing
+///
+/// `interval` is the requested spacing of tiles in seconds; long sources get
+/// a longer one. Sheets are cached, so repeated calls are cheap.
+#[tauri::command]
+pub async fn get_thumbnails(
+    source: String,
+    interval: Option<f64>,
+) -> Result<ThumbnailLayout, String> {
+    Ok(thumbnails::get_thumbnails(&source, interval).await?)
+}
+
+/// Progress events for proxy generation (emitted as "proxy-progress")
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProxyProgress {
+    Started {
+        total_clips: usize,
+    },
+    ClipStarted {
+        index: usize,
+        filename: String,
+    },
+    ClipProgress {
+        index: usize,
+        percent: f32,
+    },
+    ClipCompleted {
+        index: usize,
+        proxy_path: Option<String>,
+        error: Option<String>,
+    },
+    Finished {
+        generated: usize,
+        skipped: usize,
+        failed: usize,
+    },
+}
+
+/// Result of generating proxies for a project
+#[derive(Debug, Serialize)]
+pub struct GenerateProxiesResult {
+    pub generated: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub proxies_dir: String,
+}
+
+/// Generate 360p proxies of a project's exported clips into `clips/.proxies/`
+///
+/// Clips whose proxy is already newer than the clip are skipped.
+#[tauri::command]
+pub async fn generate_proxies(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<GenerateProxiesResult, String> {
+    let config = get_config();
+    let clips_dir = config.clips_dir(&project_name);
+    let clip_paths = clip_files(&clips_dir);
+    let exporter = FfmpegExporter::new();
+
+    let emit = |event: ProxyProgress| {
+        let _ = app.emit("proxy-progress", event);
+    };
+
+    let (mut generated, mut skipped, mut failed) = (0, 0, 0);
+    emit(ProxyProgress::Started {
+        total_clips: clip_paths.len(),
+    });
+
+    for (index, clip_path) in clip_paths.iter().enumerate() {
+        if let Some(proxy) = fresh_proxy(&clips_dir, clip_path) {
+            skipped += 1;
+            emit(ProxyProgress::ClipCompleted {
+                index,
+                proxy_path: Some(proxy.to_string_lossy().to_string()),
+                error: None,
+            });
+            continue;
+        }
+
+        let Some(proxy) = proxy_path(&clips_dir, clip_path) else {
+            continue;
+        };
+
+        emit(ProxyProgress::ClipStarted {
+            index,
+            filename: clip_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        });
+
+        let progress_app = app.clone();
+        let progress_callback: ProgressCallback = Box::new(move |update| {
+            let _ = progress_app.emit(
+                "proxy-progress",
+                ProxyProgress::ClipProgress {
+                    index,
+                    percent: update.percent,
+                },
+            );
+        });
+
+        let result = match proxy.parent().map(std::fs::create_dir_all) {
+            Some(Err(e)) => Err(ExportError::OutputDir(format!(
+                "Failed to create proxy folder: {}",
+                e
+            ))),
+            _ => {
+                exporter
+                    .generate_proxy(clip_path, &proxy, Some(&progress_callback))
+                    .await
+            }
+        };
+
+        match result {
+            Ok(()) => {
+                generated += 1;
+                emit(ProxyProgress::ClipCompleted {
+                    index,
+                    proxy_path: Some(proxy.to_string_lossy().to_string()),
+                    error: None,
+                });
+            }
+            Err(e) => {
+                log::warn!(
+                    "Failed to generate proxy for {}: {}",
+                    clip_path.display(),
+                    e
+                );
+                failed += 1;
+                emit(ProxyProgress::ClipCompleted {
+                    index,
+                    proxy_path: None,
+                    error: Some(e.to_string()),
+                });
+            }
+        }
+    }
+
+    emit(ProxyProgress::Finished {
+        generated,
+        skipped,
+        failed,
+    });
+
+    Ok(GenerateProxiesResult {
+        generated,
+        skipped,
+        failed,
+        proxies_dir: config
+            .proxies_dir(&project_name)
+            .to_string_lossy()
+            .to_string(),
+    })
+}
+
+// ============ Helpers ============
+
+/// Proxy location for a clip: `clips/.proxies/<same relative path>.mp4`
+pub(crate) fn proxy_path(clips_dir: &Path, clip_path: &Path) -> Option<PathBuf> {
+    let relative = clip_path.strip_prefix(clips_dir).ok()?;
+    Some(
+        clips_dir
+            .join(".proxies")
+            .join(relative)
+            .with_extension("mp4"),
+    )
+}
+
+/// Clip files in the clips folder and its streamer folders (hidden entries skipped)
+pub(crate) fn clip_files(clips_dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let visible = |entry: &std::fs::DirEntry| !entry.file_name().to_string_lossy().starts_with('.');
+
+    let Ok(entries) = std::fs::read_dir(clips_dir) else {
+        return files;
+    };
+    for entry in entries.flatten().filter(visible) {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Ok(sub_entries) = std::fs::read_dir(&path) {
+                files.extend(
+                    sub_entries
+                        .flatten()
+                        .filter(visible)
+                        .map(|e| e.path())
+                        .filter(|p| p.is_file() && Container::from_path(p).is_some()),
+                );
+            }
+        } else if Container::from_path(&path).is_some() {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/// A clip's proxy, if it exists and isn't older than the clip
+pub(crate) fn fresh_proxy(clips_dir: &Path, clip_path: &Path) -> Option<PathBuf> {
+    let proxy = proxy_path(clips_dir, clip_path)?;
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(&proxy), modified(clip_path)) {
+        (Some(proxy_time), Some(clip_time)) if proxy_time >= clip_time => Some(proxy),
+        _ => None,
+    }
+}
+
+/// Frame cache location: `<temp>/frames/<source key>_<ms>.<ext>`
+///
+/// Local files include their modification time in the key, so a re-exported
+/// clip doesn't show stale frames.
+fn frame_path(source: &str, timestamp: f64, format: FrameFormat) -> PathBuf {
+    let modified = std::fs::metadata(source)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    scratch::temp_root().join("frames").join(format!(
+        "{}_{}.{}",
+        vod_key(&format!("{}@{}", source, modified)),
+        (timestamp.max(0.0) * 1000.0).round() as u64,
+        format.extension()
+    ))
+}
+
+/// Whether `get_frame` may read `source`: a stream URL, or a file inside the
+/// work folder
+fn is_frame_source_allowed(source: &str, work_dir: &Path) -> bool {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        return true;
+    }
+    is_in_work_dir(Path::new(source), work_dir)
+}
+
+/// Whether `path` is an existing file or folder inside the work folder
+fn is_in_work_dir(path: &Path, work_dir: &Path) -> bool {
+    // Resolve `..` and links before comparing
+    match (std::fs::canonicalize(path), std::fs::canonicalize(work_dir)) {
+        (Ok(path), Ok(root)) => path.starts_with(root),
+        _ => false,
+    }
+}
+
+/// Refuse to rewrite clip files outside the work folder
+fn ensure_in_work_dir(path: &Path) -> Result<(), String> {
+    if !is_in_work_dir(path, &get_config().output_dir) {
+        return Err(format!("Not a file in the work folder: {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Cache path of a VOD's sync audio window
+fn sync_audio_path(vod_url: &str, timing: &ClipTiming) -> PathBuf {
+    scratch::temp_root().join("audio").join(format!(
+        "{}_{}_{}.wav",
+        vod_key(vod_url),
+        (timing.start.max(0.0) * 1000.0).round() as u64,
+        (timing.duration.max(0.0) * 1000.0).round() as u64
+    ))
+}
+
+/// Accept only a single, visible path component
+pub(crate) fn validate_name(name: &str) -> Result<(), String> {
+    let mut components = Path::new(name).components();
+    let single = matches!(
+        (components.next(), components.next()),
+        (Some(std::path::Component::Normal(_)), None)
+    );
+    if !single || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("Invalid name: {}", name));
+    }
+    Ok(())
+}
+
+/// Where a new in/out range sits inside a clip exported with the previous one
+///
+/// Offsets are in file time, so they shrink with the clip's speed factor.
+/// None if the new range isn't inside the previous one.
+fn local_trim_timing(previous: (f64, f64), new: (f64, f64), speed: f64) -> Option<ClipTiming> {
+    const EPSILON: f64 = 0.001;
+    let ((previous_in, previous_out), (new_in, new_out)) = (previous, new);
+    let inside = new_in >= previous_in - EPSILON && new_out <= previous_out + EPSILON;
+    if !inside || new_out <= new_in || speed <= 0.0 {
+        return None;
+    }
+
+    Some(ClipTiming::new(
+        (new_in - previous_in).max(0.0) / speed,
+        (new_out - new_in) / speed,
+    ))
+}
+
+/// Build a non-conflicting output path: {stem}_trim.{ext}, {stem}_trim2.{ext}, ...
+fn trimmed_path(input: &Path) -> PathBuf {
+    let stem = input
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "clip".to_string());
+    let ext = input
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mp4".to_string());
+    let dir = input.parent().map(Path::to_path_buf).unwrap_or_default();
+
+    let mut candidate = dir.join(format!("{}_trim.{}", stem, ext));
+    let mut n = 2;
+    while candidate.exists() {
+        candidate = dir.join(format!("{}_trim{}.{}", stem, n, ext));
+        n += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_clip_duration() {
+        assert_eq!(expected_clip_duration(10.0, 40.0, None), 30.0);
+        assert_eq!(expected_clip_duration(10.0, 40.0, Some(2.0)), 15.0);
+        assert_eq!(expected_clip_duration(10.0, 40.0, Some(0.5)), 60.0);
+        assert_eq!(expected_clip_duration(10.0, 40.0, Some(0.0)), 30.0);
+    }
+
+    #[test]
+    fn test_frame_source_allowed() {
+        let work_dir = std::env::temp_dir().join(format!("nox-frames-{}", std::process::id()));
+        let clips = work_dir.join("Project").join("clips");
+        std::fs::create_dir_all(&clips).unwrap();
+        std::fs::write(clips.join("a.mp4"), b"clip").unwrap();
+        let clip = clips.join("a.mp4").to_string_lossy().to_string();
+
+        assert!(is_frame_source_allowed(&clip, &work_dir));
+        assert!(is_frame_source_allowed(
+            "https://example.com/index.m3u8",
+            &work_dir
+        ));
+        let escape = clips.join("..").join("..").join("..");
+        assert!(!is_frame_source_allowed(
+            &escape.to_string_lossy(),
+            &work_dir
+        ));
+        assert!(!is_frame_source_allowed("file:///etc/passwd", &work_dir));
+        // Trim and normalize rewrite files in place: the same rule
+        assert!(is_in_work_dir(Path::new(&clip), &work_dir));
+        assert!(!is_in_work_dir(&escape, &work_dir));
+
+        std::fs::remove_dir_all(&work_dir).unwrap();
+    }
+
+    #[test]
+    fn test_smoke_cut_start() {
+        assert_eq!(smoke_cut_start(3.0 * 3600.0), 60.0);
+        assert_eq!(smoke_cut_start(10.0), 7.0);
+        assert_eq!(smoke_cut_start(2.0), 0.0);
+    }
+
+    #[test]
+    fn test_validate_trim() {
+        assert!(validate_trim(1.0, 9.0, 10.0).is_ok());
+        assert!(validate_trim(0.0, 10.02, 10.0).is_ok());
+        assert!(matches!(
+            validate_trim(2.0, 12.0, 10.0),
+            Err(NoxError::Export(ExportError::TrimOutOfRange { .. }))
+        ));
+        assert!(matches!(
+            validate_trim(-1.0, 5.0, 10.0),
+            Err(NoxError::Export(ExportError::InvalidTimeRange { .. }))
+        ));
+        assert!(validate_trim(5.0, 5.0, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_renamed_filenames() {
+        let renames = renamed_filenames("abcdef123", "Ace", "Ace: round 3");
+        assert_eq!(renames.len(), Container::ALL.len());
+        assert_eq!(
+            renames[0],
+            (
+                "abcdef_Ace.mp4".to_string(),
+                "abcdef_Ace_ round 3.mp4".to_string()
+            )
+        );
+        // Only characters that sanitize to the same name changed
+        assert!(renamed_filenames("abcdef", "a/b", "a:b").is_empty());
+    }
+
+    #[test]
+    fn test_local_trim_timing() {
+        let timing = local_trim_timing((-5.0, 10.0), (-3.0, 8.0), 1.0).unwrap();
+        assert_eq!((timing.start, timing.duration), (2.0, 11.0));
+
+        let fast = local_trim_timing((0.0, 20.0), (4.0, 12.0), 2.0).unwrap();
+        assert_eq!((fast.start, fast.duration), (2.0, 4.0));
+
+        assert!(local_trim_timing((0.0, 10.0), (-1.0, 5.0), 1.0).is_none());
+        assert!(local_trim_timing((0.0, 10.0), (2.0, 11.0), 1.0).is_none());
+        assert!(local_trim_timing((0.0, 10.0), (5.0, 5.0), 1.0).is_none());
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("abc123_ace.mp4").is_ok());
+        assert!(validate_name("Streamer Name").is_ok());
+        for bad in [
+            "", ".", "..", "../x.mp4", "a/b.mp4", "a\\b.mp4", ".proxies", "/etc",
+        ] {
+            assert!(validate_name(bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn test_clip_verification_json() {
+        let entry = ClipVerification {
+            path: "/clips/A/abc_ace.mp4".to_string(),
+            streamer_name: "A".to_string(),
+            action_name: "ace".to_string(),
+            health: ClipHealth::DurationMismatch {
+                expected: 10.0,
+                actual: 3.0,
+            },
+            deleted: false,
+        };
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["status"], "duration_mismatch");
+        assert_eq!(json["actual"], 3.0);
+        assert!(!ClipHealth::Missing.is_broken());
+        assert!(ClipHealth::Empty.is_broken());
+    }
+}
@@ -0,0 +1,11 @@
+use crate::game_events::{self, GameEvent, MatchSource};
+
+/// Fetch a match's kills and objectives with their game time
+///
+/// The frontend turns the events into project actions.
+#[tauri::command]
+pub async fn import_match_events(source: MatchSource) -> Result<Vec<GameEvent>, String> {
+    game_events::fetch_events(&source)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,49 @@
+use crate::inbox::{self, InboxItem};
+
+/// List saved watch-later VOD links
+#[tauri::command]
+pub async fn list_inbox() -> Result<Vec<InboxItem>, String> {
+    inbox::load_inbox().map_err(|e| e.to_string())
+}
+
+/// Save a VOD link to the watch-later inbox
+#[tauri::command]
+pub async fn add_inbox_item(
+    vod_url: String,
+    streamer_name: Option<String>,
+    note: Option<String>,
+) -> Result<InboxItem, String> {
+    if vod_url.trim().is_empty() {
+        return Err("VOD URL is empty".to_string());
+    }
+
+    inbox::add_item(&vod_url, streamer_name, note.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+/// Edit the note and streamer name of an inbox item
+#[tauri::command]
+pub async fn update_inbox_item(
+    id: String,
+    streamer_name: Option<String>,
+    note: String,
+) -> Result<InboxItem, String> {
+    inbox::update_item(&id, streamer_name, note).map_err(|e| e.to_string())
+}
+
+/// Delete an inbox item
+#[tauri::command]
+pub async fn remove_inbox_item(id: String) -> Result<(), String> {
+    inbox::take_item(&id).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Take an item out of the inbox to attach it to a project as a streamer
+///
+/// The item is removed from the inbox and returned so the frontend can add
+/// it to the current project.
+#[tauri::command]
+pub async fn attach_inbox_item(id: String) -> Result<InboxItem, String> {
+    inbox::take_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Inbox item not found: {}", id))
+}
@@ -0,0 +1,76 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::config::get_config;
+use crate::export::FfmpegExporter;
+use crate::manifest::{self, Manifest, ManifestCheck, ManifestEntry};
+
+/// Where a manifest was written and what it covers
+#[derive(Debug, Serialize)]
+pub struct ManifestSummary {
+    pub manifest_path: String,
+    pub checksums_path: String,
+    pub files: usize,
+    pub total_bytes: u64,
+}
+
+/// Write `checksums.json` / `checksums.txt` for all exported clips and montages
+/// of a project, so a copy handed to an editor can be verified
+#[tauri::command]
+pub async fn generate_manifest(project_name: String) -> Result<ManifestSummary, String> {
+    let project_dir = get_config().project_dir(&project_name);
+    if !project_dir.is_dir() {
+        return Err(format!(
+            "Project folder not found: {}",
+            project_dir.display()
+        ));
+    }
+
+    // Hashing multi-GB files blocks, keep it off the async runtime
+    let base = project_dir.clone();
+    let mut entries: Vec<ManifestEntry> = tokio::task::spawn_blocking(move || {
+        manifest::delivery_files(&base)
+            .iter()
+            .map(|path| manifest::entry_for(&base, path))
+            .collect::<crate::error::Result<Vec<_>>>()
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    let exporter = FfmpegExporter::new();
+    for entry in &mut entries {
+        entry.duration = exporter
+            .probe_duration(&project_dir.join(&entry.path))
+            .await
+            .ok();
+    }
+
+    let manifest = Manifest::new(&project_name, entries);
+    let manifest_path = manifest::write(&project_dir, &manifest).map_err(|e| e.to_string())?;
+    log::info!(
+        "Wrote manifest for {} files to {}",
+        manifest.files.len(),
+        manifest_path.display()
+    );
+
+    Ok(ManifestSummary {
+        manifest_path: manifest_path.to_string_lossy().to_string(),
+        checksums_path: project_dir
+            .join(manifest::CHECKSUMS_FILE)
+            .to_string_lossy()
+            .to_string(),
+        files: manifest.files.len(),
+        total_bytes: manifest.files.iter().map(|f| f.size).sum(),
+    })
+}
+
+/// Check the files next to a `checksums.json` against it
+#[tauri::command]
+pub async fn verify_manifest(manifest_path: String) -> Result<Vec<ManifestCheck>, String> {
+    let path = PathBuf::from(manifest_path);
+    tokio::task::spawn_blocking(move || manifest::verify(&path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,120 @@
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::config::{get_config, get_config_mut};
+use crate::markers::{self, LiveMarker, MarkerSession};
+use crate::project::{self, timeline::ConvertedMarker};
+
+/// List live marker sessions (most recent last)
+#[tauri::command]
+pub async fn list_marker_sessions() -> Result<Vec<MarkerSession>, String> {
+    markers::load_sessions().map_err(|e| e.to_string())
+}
+
+/// Start a new live marker session, closing the active one
+#[tauri::command]
+pub async fn start_marker_session(name: String) -> Result<MarkerSession, String> {
+    markers::start_session(name).map_err(|e| e.to_string())
+}
+
+/// Stop the active live marker session
+#[tauri::command]
+pub async fn stop_marker_session() -> Result<Option<MarkerSession>, String> {
+    markers::stop_session().map_err(|e| e.to_string())
+}
+
+/// Record an "action at now" marker in the active session
+#[tauri::command]
+pub async fn add_live_marker(label: Option<String>) -> Result<LiveMarker, String> {
+    markers::add_marker(label.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+/// Global shortcut recording a live marker (None = off)
+#[tauri::command]
+pub fn get_marker_hotkey() -> Option<String> {
+    get_config().marker_hotkey().map(str::to_string)
+}
+
+/// Set the global shortcut recording a live marker (e.g.
+/// "CommandOrControl+Shift+M"); None turns it off
+#[tauri::command]
+pub async fn set_marker_hotkey(app: AppHandle, hotkey: Option<String>) -> Result<(), String> {
+    let hotkey = hotkey
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty());
+    if let Some(hotkey) = &hotkey {
+        hotkey
+            .parse::<Shortcut>()
+            .map_err(|e| format!("Invalid shortcut {}: {}", hotkey, e))?;
+    }
+
+    if let Some(old) = get_config().marker_hotkey() {
+        let _ = app.global_shortcut().unregister(old);
+    }
+    if let Some(hotkey) = &hotkey {
+        on_marker_hotkey(&app, hotkey)?;
+    }
+
+    get_config_mut()
+        .set_marker_hotkey(hotkey)
+        .map_err(|e| e.to_string())
+}
+
+/// Register the saved marker shortcut (called once at startup)
+pub fn register_marker_hotkey(app: &AppHandle) {
+    let Some(hotkey) = get_config().marker_hotkey().map(str::to_string) else {
+        return;
+    };
+    if let Err(e) = on_marker_hotkey(app, &hotkey) {
+        log::warn!("[Markers] Shortcut {} not registered: {}", hotkey, e);
+    }
+}
+
+/// Record a marker each time `hotkey` is pressed, telling the frontend with
+/// a `live-marker-added` event
+fn on_marker_hotkey(app: &AppHandle, hotkey: &str) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(hotkey, |app, _, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            match markers::add_marker(String::new()) {
+                Ok(marker) => {
+                    let _ = app.emit("live-marker-added", marker);
+                }
+                Err(e) => log::error!("[Markers] Failed to record marker: {}", e),
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a live marker session
+#[tauri::command]
+pub async fn delete_marker_session(id: String) -> Result<(), String> {
+    markers::delete_session(&id).map_err(|e| e.to_string())
+}
+
+/// Convert a session's wall-clock markers into VOD offsets for a saved project
+///
+/// Streamers need `recorded_at` set (see `fetch_vod_recorded_at`); the frontend
+/// turns markers with a game time into project actions.
+#[tauri::command]
+pub async fn convert_marker_session(
+    project_name: String,
+    session_id: String,
+) -> Result<Vec<ConvertedMarker>, String> {
+    let project = project::load_project(&project_name)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Project not found: {}", project_name))?;
+
+    let session = markers::load_sessions()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|s| s.id == session_id)
+        .ok_or_else(|| format!("Marker session not found: {}", session_id))?;
+
+    Ok(project::timeline::convert_markers(
+        &project,
+        &session.markers,
+    ))
+}
@@ -1,18 +1,61 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tauri::Emitter;
 
 use crate::binaries::{
     download_binary as do_download_binary, get_binary_manager, BinaryStatus, BinaryType,
 };
-use crate::config::{get_config, get_config_mut};
-use crate::export::{ClipResult, ClipTiming, ExportProgress, SmartExporter};
-use crate::platform::VodResolverChain;
+use crate::config::{get_config, get_config_mut, VideoEncoder};
+use crate::error::{ExportErrorKind, NoxError};
+use crate::export::{
+    batch, cache::SegmentCache, is_valid_speed, space, AttemptCallback, ClipFilters, ClipResult,
+    ClipTiming, Container, ExportOptions, ExportProgress, FfmpegExporter, ProgressCallback,
+    SmartExporter, MAX_DURATION,
+};
+use crate::localtime;
+use crate::platform::{self, LocalFileResolver, MutedSegment, VodResolverChain};
+use crate::project::archive::ArchiveSummary;
+use crate::project::migrate::MigrationReport;
 use crate::project::{self, ProjectFile};
 use crate::proxy;
+use crate::scratch::{self, ScratchDir};
+use crate::workdir::{self, open_folder};
 
+mod clips;
+mod game_events;
+mod inbox;
+mod manifest;
+mod markers;
 mod montage;
-pub use montage::{export_montage, list_project_clips, open_montages_folder};
+mod paths;
+pub(crate) use clips::validate_name;
+pub use clips::{
+    delete_clip_file, extract_sync_audio, generate_proxies, get_frame, get_thumbnails,
+    normalize_clip, rename_action_clips, smoke_test_vod, trim_exported_clip, trim_local_clip,
+    verify_project_clips,
+};
+pub use game_events::import_match_events;
+pub use inbox::{
+    add_inbox_item, attach_inbox_item, list_inbox, remove_inbox_item, update_inbox_item,
+};
+pub use manifest::{generate_manifest, verify_manifest};
+pub use markers::{
+    add_live_marker, convert_marker_session, delete_marker_session, get_marker_hotkey,
+    list_marker_sessions, register_marker_hotkey, set_marker_hotkey, start_marker_session,
+    stop_marker_session,
+};
+pub use montage::{
+    analyze_music_beats, cancel_montage, delete_montage, delete_overlay_preset,
+    export_action_compilation, export_action_grid, export_montage, export_montage_config,
+    export_montages, export_streamer_reels, import_montage_config, list_overlay_presets,
+    list_project_clips, list_project_montages, load_montage, open_montages_folder, pick_image_file,
+    pick_music_file, pick_video_file, preview_montage, relink_montage_clips, render_saved_montage,
+    save_montage, save_overlay_preset, validate_montage_inputs,
+};
+pub use paths::{get_app_paths, open_app_path};
 
 // ============ Request/Response Types ============
 
@@ -28,19 +71,72 @@ pub struct ClipRequest {
     pub in_point: f64,
     pub out_point: f64,
     pub index: usize,
+    /// Maximum output size in MB (re-encode to fit, e.g. for attachment limits)
+    #[serde(default)]
+    pub max_size_mb: Option<f64>,
+    /// Frame-accurate start via smart cut (re-encode only the first GOP)
+    #[serde(default)]
+    pub smart_cut: bool,
+    /// Playback speed factor (0.5 = slow motion, 2.0 = timelapse)
+    #[serde(default)]
+    pub speed: Option<f64>,
+    /// Burn the absolute VOD timecode into a corner of the clip
+    #[serde(default)]
+    pub burn_timecode: bool,
+    /// Export without audio (-an)
+    #[serde(default)]
+    pub strip_audio: bool,
+    /// Output container (mp4, mkv, mov, ts)
+    #[serde(default)]
+    pub container: Container,
+    /// Crop/scale/denoise filters for this clip (override streamer and project)
+    #[serde(default)]
+    pub filters: Option<ClipFilters>,
 }
 
-#[derive(Debug, Serialize)]
+impl ClipRequest {
+    /// Start of the clip in its VOD (seconds)
+    pub(crate) fn vod_start(&self) -> f64 {
+        self.game_start_time + self.action_game_time + self.sync_offset + self.in_point
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ExportResult {
     pub exported: usize,
     pub skipped: usize,
     pub failed: usize,
     pub errors: Vec<String>,
     pub output_dir: String,
+    /// When the export finished, in the project's timezone
+    pub finished_at: String,
+    /// Exported clips with audio the platform muted
+    pub muted: Vec<MutedClip>,
+    /// Clips above the default duration limit, allowed by the project's raised one
+    pub long: Vec<LongClip>,
+}
+
+/// A clip overlapping muted parts of its VOD
+#[derive(Debug, Clone, Serialize)]
+pub struct MutedClip {
+    pub index: usize,
+    pub filename: String,
+    /// Seconds of the clip without audio
+    pub muted_seconds: f64,
+}
+
+/// A clip longer than the default duration limit
+#[derive(Debug, Clone, Serialize)]
+pub struct LongClip {
+    pub index: usize,
+    pub filename: String,
+    /// Length of the clip in the VOD (seconds)
+    pub duration: f64,
 }
 
 #[derive(Debug, Serialize)]
 pub struct ClipStatus {
+    pub action_id: String,
     pub action_name: String,
     pub streamer_name: String,
     pub filename: String,
@@ -55,15 +151,302 @@ pub async fn export_clips(
     app: tauri::AppHandle,
     project_name: String,
     clips: Vec<ClipRequest>,
+) -> Result<ExportResult, String> {
+    let emit: ProgressEmitter = Arc::new(move |event| {
+        let _ = app.emit("export-progress", event);
+    });
+
+    run_export(&project_name, clips, emit).await
+}
+
+/// Progress callback forwarding a clip's updates as export events
+fn clip_progress_callback(emit: &ProgressEmitter, clip_index: usize) -> ProgressCallback {
+    let progress_emit = emit.clone();
+    Box::new(move |update| {
+        progress_emit(ExportProgress::ClipProgress {
+            index: clip_index,
+            percent: update.percent,
+            speed: update.speed,
+            out_time: update.out_time,
+            bytes_written: update.total_size,
+            eta_secs: update.eta_secs,
+        });
+    })
+}
+
+/// Attempt callback forwarding a clip's retries as export events
+fn clip_attempt_callback(emit: &ProgressEmitter, clip_index: usize) -> AttemptCallback {
+    let attempt_emit = emit.clone();
+    Box::new(move |attempt| {
+        attempt_emit(ExportProgress::ClipAttemptStarted {
+            index: clip_index,
+            attempt: attempt.attempt,
+            method: attempt.method,
+        })
+    })
+}
+
+/// Progress callback forwarding a batch download's progress as export events
+fn batch_progress_callback(emit: &ProgressEmitter, batch: usize) -> ProgressCallback {
+    let progress_emit = emit.clone();
+    Box::new(move |update| {
+        progress_emit(ExportProgress::BatchProgress {
+            batch,
+            percent: update.percent,
+            speed: update.speed,
+        });
+    })
+}
+
+/// VOD time at the start of a range downloaded from `requested`
+///
+/// A stream copy begins at the keyframe before the requested time; the file's
+/// start time says how far before.
+async fn probed_source_start(path: &Path, requested: f64) -> f64 {
+    match FfmpegExporter::new().probe_start_time(path).await {
+        Ok(offset) => requested + offset,
+        Err(e) => {
+            log::warn!("Couldn't probe start of {}: {}", path.display(), e);
+            requested
+        }
+    }
+}
+
+/// Download the covering range of overlapping clips from the same VOD once
+///
+/// Returns the downloaded file and its VOD start time per clip index. Clips
+/// whose batch fails to download aren't in the map and export on their own.
+async fn download_batches(
+    project_name: &str,
+    clips: &[ClipRequest],
+    scratch: &ScratchDir,
+    resolver: &VodResolverChain,
+    exporter: &SmartExporter,
+    max_duration: Option<f64>,
+    emit: &ProgressEmitter,
+) -> HashMap<usize, (PathBuf, f64)> {
+    let config = get_config();
+    let mut by_vod: HashMap<&str, Vec<(usize, f64, f64)>> = HashMap::new();
+    for clip in clips {
+        let output_path = config
+            .streamer_clips_dir(project_name, &clip.streamer_name)
+            .join(generate_filename(
+                &clip.action_id,
+                &clip.action_name,
+                clip.container,
+            ));
+        // Local recordings are cut from directly
+        if output_path.exists() || LocalFileResolver::is_local_path(&clip.vod_url) {
+            continue;
+        }
+        let start = clip.vod_start();
+        by_vod.entry(&clip.vod_url).or_default().push((
+            clip.index,
+            start,
+            start + clip.out_point - clip.in_point,
+        ));
+    }
+
+    let planned: Vec<_> = by_vod
+        .into_iter()
+        .map(|(vod_url, ranges)| (vod_url, batch::plan_batches(&ranges)))
+        .filter(|(_, batches)| !batches.is_empty())
+        .collect();
+    let total_batches = planned.iter().map(|(_, batches)| batches.len()).sum();
+
+    let mut sources = HashMap::new();
+    let mut batch_number = 0;
+    for (vod_url, batches) in planned {
+        let resolved = match resolver.resolve(vod_url).await {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                log::warn!("Failed to resolve {} for batching: {}", vod_url, e);
+                batch_number += batches.len();
+                continue;
+            }
+        };
+
+        for batch in batches {
+            batch_number += 1;
+            let source = scratch.join(&format!("batch_{}.mp4", batch_number));
+            let options = ExportOptions {
+                max_duration,
+                ..Default::default()
+            };
+
+            log::info!(
+                "Downloading {:.0}s of {} once for {} clips",
+                batch.duration(),
+                vod_url,
+                batch.members.len()
+            );
+            emit(ExportProgress::BatchStarted {
+                batch: batch_number,
+                total_batches,
+                clips: batch.members.clone(),
+            });
+
+            let progress_callback = batch_progress_callback(emit, batch_number);
+            let result = exporter
+                .export_with_progress(
+                    &resolved,
+                    &ClipTiming::new(batch.start, batch.duration()),
+                    &options,
+                    &source,
+                    Some(&progress_callback),
+                    None,
+                )
+                .await;
+
+            match result {
+                Ok(()) => {
+                    let start = probed_source_start(&source, batch.start).await;
+                    for index in batch.members {
+                        sources.insert(index, (source.clone(), start));
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Batch download failed, exporting clips one by one: {}", e);
+                    let _ = std::fs::remove_file(&source);
+                }
+            }
+        }
+    }
+
+    sources
+}
+
+/// Cached range covering a clip, downloading a padded one on a cache miss
+///
+/// Returns the file and its VOD start time, or None if the download failed
+/// or the VOD is a local recording (the clip then exports directly).
+async fn cached_source(
+    cache: &SegmentCache,
+    vod_url: &str,
+    timing: &ClipTiming,
+    resolver: &VodResolverChain,
+    exporter: &SmartExporter,
+    progress: Option<&ProgressCallback>,
+    attempts: Option<&AttemptCallback>,
+) -> Option<(PathBuf, f64)> {
+    if LocalFileResolver::is_local_path(vod_url) {
+        return None;
+    }
+    let end = timing.start + timing.duration;
+    if let Some(segment) = cache.find(vod_url, timing.start, end) {
+        log::info!("Using cached segment {}", segment.path.display());
+        return Some((segment.path, segment.start));
+    }
+
+    let (start, end, path) = cache.entry_for(vod_url, timing.start, end);
+    if let Err(e) = cache.ensure_dir() {
+        log::warn!("Failed to create segment cache: {}", e);
+        return None;
+    }
+
+    let resolved = resolver.resolve(vod_url).await.ok()?;
+    let result = exporter
+        .export_with_progress(
+            &resolved,
+            &ClipTiming::new(start, end - start),
+            &ExportOptions::default(),
+            &path,
+            progress,
+            attempts,
+        )
+        .await;
+
+    match result {
+        Ok(()) => {
+            let actual_start = probed_source_start(&path, start).await;
+            match cache.record_start(vod_url, &path, actual_start, end) {
+                Ok(segment) => Some((segment.path, segment.start)),
+                Err(e) => {
+                    log::warn!("Failed to rename cached segment: {}", e);
+                    Some((path, actual_start))
+                }
+            }
+        }
+        Err(e) => {
+            log::warn!("Failed to cache segment of {}: {}", vod_url, e);
+            let _ = std::fs::remove_file(&path);
+            None
+        }
+    }
+}
+
+/// Filters for a clip: its own, then its streamer's crop, then the project defaults
+fn clip_filters(clip: &ClipRequest, project: Option<&ProjectFile>) -> ClipFilters {
+    let streamer = ClipFilters {
+        crop: project
+            .and_then(|p| p.streamers.iter().find(|s| s.name == clip.streamer_name))
+            .and_then(|s| s.crop),
+        ..Default::default()
+    };
+    let project = project.and_then(|p| p.filters.clone()).unwrap_or_default();
+
+    clip.filters
+        .clone()
+        .unwrap_or_default()
+        .or(&streamer)
+        .or(&project)
+}
+
+/// Muted parts of each VOD of `clips` (VODs that couldn't be checked are left out)
+async fn muted_segments_by_vod(
+    clips: &[ClipRequest],
+    resolver: &VodResolverChain,
+) -> HashMap<String, Vec<MutedSegment>> {
+    let mut muted = HashMap::new();
+    for clip in clips {
+        if muted.contains_key(&clip.vod_url) {
+            continue;
+        }
+        match resolver.muted_segments(&clip.vod_url).await {
+            Ok(segments) => {
+                muted.insert(clip.vod_url.clone(), segments);
+            }
+            Err(e) => log::warn!("Couldn't check muted parts of {}: {}", clip.vod_url, e),
+        }
+    }
+    muted
+}
+
+/// How much of `clip` falls into muted parts of its VOD (None = none)
+fn muted_clip(clip: &ClipRequest, muted: &HashMap<String, Vec<MutedSegment>>) -> Option<MutedClip> {
+    let segments = muted.get(&clip.vod_url)?;
+    let start = clip.vod_start();
+    let muted_seconds =
+        platform::muted_overlap(segments, start, start + clip.out_point - clip.in_point);
+    (muted_seconds > 0.0).then(|| MutedClip {
+        index: clip.index,
+        filename: generate_filename(&clip.action_id, &clip.action_name, clip.container),
+        muted_seconds,
+    })
+}
+
+/// Sink for export progress events (Tauri event, API job status, ...)
+pub(crate) type ProgressEmitter = Arc<dyn Fn(ExportProgress) + Send + Sync>;
+
+/// Export clips, reporting progress through `emit`
+pub(crate) async fn run_export(
+    project_name: &str,
+    clips: Vec<ClipRequest>,
+    emit: ProgressEmitter,
 ) -> Result<ExportResult, String> {
     let config = get_config();
     let clips_dir = config
-        .ensure_clips_dir(&project_name)
-        .map_err(|e| e.to_string())?;
+        .ensure_clips_dir(project_name)
+        .map_err(|e| NoxError::from(e).to_string())?;
 
     let resolver = VodResolverChain::new();
     let exporter = SmartExporter::new();
 
+    // Projects can raise the clip length ceiling (full maps, co-stream blocks)
+    // and set default filters; streamers can set a crop
+    let project_file = project::load_project(project_name).ok().flatten();
+    let max_duration = project_file.as_ref().and_then(|p| p.max_clip_duration);
+
     let mut exported = 0;
     let mut skipped = 0;
     let mut failed = 0;
@@ -71,47 +454,105 @@ pub async fn export_clips(
 
     let total_clips = clips.len();
 
+    // Fail early if the clips that still need exporting won't fit (clips
+    // with an invalid speed fail validation later and write nothing)
+    let required = clips
+        .iter()
+        .filter(|clip| clip.speed.is_none_or(is_valid_speed))
+        .filter(|clip| {
+            !config
+                .streamer_clips_dir(project_name, &clip.streamer_name)
+                .join(generate_filename(
+                    &clip.action_id,
+                    &clip.action_name,
+                    clip.container,
+                ))
+                .exists()
+        })
+        .map(|clip| {
+            let duration = (clip.out_point - clip.in_point) / clip.speed.unwrap_or(1.0);
+            space::estimate_clip_bytes(duration, clip.max_size_mb.or(config.ffmpeg.max_size_mb))
+        })
+        .fold(0, u64::saturating_add);
+    space::ensure_free_space(&clips_dir, required).map_err(|e| e.to_string())?;
+
     // Emit started event
-    let _ = app.emit("export-progress", ExportProgress::Started { total_clips });
+    emit(ExportProgress::Started { total_clips });
+
+    // Batch downloads go to this job's own scratch folder on the clips volume
+    scratch::remove_stale(&clips_dir);
+    let scratch =
+        ScratchDir::new_in(&clips_dir, "export").map_err(|e| NoxError::from(e).to_string())?;
+    let batch_sources = download_batches(
+        project_name,
+        &clips,
+        &scratch,
+        &resolver,
+        &exporter,
+        max_duration,
+        &emit,
+    )
+    .await;
+
+    let segment_cache = config
+        .segment_cache_enabled()
+        .then(|| SegmentCache::new(config.segment_cache_dir()));
+
+    let muted_segments = muted_segments_by_vod(&clips, &resolver).await;
+    let mut muted = Vec::new();
+    let mut long = Vec::new();
 
     for clip in clips {
-        let filename = generate_filename(&clip.action_id, &clip.action_name);
+        let filename = generate_filename(&clip.action_id, &clip.action_name, clip.container);
 
         // Get streamer-specific directory
         let streamer_dir = config
-            .ensure_streamer_clips_dir(&project_name, &clip.streamer_name)
-            .map_err(|e| e.to_string())?;
+            .ensure_streamer_clips_dir(project_name, &clip.streamer_name)
+            .map_err(|e| NoxError::from(e).to_string())?;
         let output_path = streamer_dir.join(&filename);
 
         // Skip if already exists
         if output_path.exists() {
             log::info!("Skipping existing: {}", filename);
             skipped += 1;
-            let _ = app.emit(
-                "export-progress",
-                ExportProgress::ClipCompleted {
-                    index: clip.index,
-                    status: ClipResult::Skipped,
-                },
-            );
+            emit(ExportProgress::ClipCompleted {
+                index: clip.index,
+                status: ClipResult::Skipped,
+                size_bytes: file_size(&output_path),
+            });
             continue;
         }
 
-        // Emit clip started event
-        let _ = app.emit(
-            "export-progress",
-            ExportProgress::ClipStarted {
+        let muted_clip = muted_clip(&clip, &muted_segments);
+        if let Some(m) = &muted_clip {
+            log::warn!("{} has {:.0}s of muted audio", filename, m.muted_seconds);
+        }
+
+        let duration = clip.out_point - clip.in_point;
+        if duration > MAX_DURATION {
+            log::warn!(
+                "{} is {:.1} min long, above the default {:.0} min limit",
+                filename,
+                duration / 60.0,
+                MAX_DURATION / 60.0
+            );
+            long.push(LongClip {
                 index: clip.index,
-                action_name: clip.action_name.clone(),
-                streamer_name: clip.streamer_name.clone(),
-            },
-        );
+                filename: filename.clone(),
+                duration,
+            });
+        }
 
-        // Calculate VOD timestamp
-        let vod_start =
-            clip.game_start_time + clip.action_game_time + clip.sync_offset + clip.in_point;
+        // Emit clip started event
+        emit(ExportProgress::ClipStarted {
+            index: clip.index,
+            action_name: clip.action_name.clone(),
+            streamer_name: clip.streamer_name.clone(),
+            muted_seconds: muted_clip.as_ref().map(|m| m.muted_seconds),
+        });
 
-        let timing = ClipTiming::new(vod_start, clip.out_point - clip.in_point);
+        // Calculate VOD timestamp
+        let timing = ClipTiming::new(clip.vod_start(), clip.out_point - clip.in_point);
 
         log::info!(
             "Exporting: {} (start={:.2}s, duration={:.2}s)",
@@ -120,83 +561,186 @@ pub async fn export_clips(
             timing.duration
         );
 
+        // A VOD whose stream is still live only has the part recorded so far
+        if let Ok(vod) = resolver.resolve_cached(&clip.vod_url).await {
+            if let Err(e) = vod.check_available(timing.start + timing.duration) {
+                log::warn!("Can't export {} yet: {}", filename, e);
+                errors.push(format!("{}: {}", filename, e));
+                failed += 1;
+                emit(ExportProgress::ClipCompleted {
+                    index: clip.index,
+                    status: ClipResult::Failed {
+                        error: e.to_string(),
+                        error_kind: ExportErrorKind::StillProcessing,
+                    },
+                    size_bytes: None,
+                });
+                continue;
+            }
+        }
+
+        let options = ExportOptions {
+            max_size_mb: clip.max_size_mb,
+            smart_cut: clip.smart_cut,
+            speed: clip.speed,
+            burn_timecode: clip.burn_timecode,
+            // Batch and cached sources are cut at a local offset
+            timecode_start: Some(timing.start),
+            strip_audio: clip.strip_audio,
+            container: clip.container,
+            max_duration,
+            filters: clip_filters(&clip, project_file.as_ref()),
+        };
+
+        // Slice from a shared batch download or a cached range when there is one
+        let local_source = match (batch_sources.get(&clip.index), &segment_cache) {
+            (Some(source), _) => Some(source.clone()),
+            (None, Some(cache)) => {
+                let progress_callback = clip_progress_callback(&emit, clip.index);
+                let attempt_callback = clip_attempt_callback(&emit, clip.index);
+                cached_source(
+                    cache,
+                    &clip.vod_url,
+                    &timing,
+                    &resolver,
+                    &exporter,
+                    Some(&progress_callback),
+                    Some(&attempt_callback),
+                )
+                .await
+            }
+            (None, None) => None,
+        };
+
+        if let Some((source, source_start)) = local_source {
+            let local_timing = ClipTiming::new(timing.start - source_start, timing.duration);
+            let progress_callback = clip_progress_callback(&emit, clip.index);
+            let attempt_callback = clip_attempt_callback(&emit, clip.index);
+            let result = exporter
+                .export_from_local(
+                    &source,
+                    &local_timing,
+                    &options,
+                    &output_path,
+                    Some(&progress_callback),
+                    Some(&attempt_callback),
+                )
+                .await;
+            match result {
+                Ok(()) => {
+                    log::info!("Exported: {} (from local source)", filename);
+                    exported += 1;
+                    muted.extend(muted_clip);
+                    emit(ExportProgress::ClipCompleted {
+                        index: clip.index,
+                        status: ClipResult::Success,
+                        size_bytes: file_size(&output_path),
+                    });
+                    continue;
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Slicing {} from local source failed, exporting directly: {}",
+                        filename,
+                        e
+                    );
+                }
+            }
+        }
+
         // Resolve VOD URL
-        let resolved = match resolver.resolve(&clip.vod_url).await {
+        let resolved = match resolver.resolve_cached(&clip.vod_url).await {
             Ok(r) => r,
             Err(e) => {
                 log::error!("Failed to resolve {}: {}", clip.vod_url, e);
                 errors.push(format!("{}: {}", filename, e));
                 failed += 1;
-                let _ = app.emit(
-                    "export-progress",
-                    ExportProgress::ClipCompleted {
-                        index: clip.index,
-                        status: ClipResult::Failed {
-                            error: e.to_string(),
-                        },
+                emit(ExportProgress::ClipCompleted {
+                    index: clip.index,
+                    status: ClipResult::Failed {
+                        error: e.to_string(),
+                        error_kind: ExportErrorKind::from_message(&e.to_string()),
                     },
-                );
+                    size_bytes: None,
+                });
                 continue;
             }
         };
 
-        // Create progress callback
-        let app_handle = app.clone();
-        let clip_index = clip.index;
-        let progress_callback: Box<dyn Fn(f32, Option<String>) + Send + Sync> =
-            Box::new(move |percent, speed| {
-                let _ = app_handle.emit(
-                    "export-progress",
-                    ExportProgress::ClipProgress {
-                        index: clip_index,
-                        percent,
-                        speed,
-                    },
-                );
-            });
+        // Create progress and attempt callbacks
+        let progress_callback = clip_progress_callback(&emit, clip.index);
+        let attempt_callback = clip_attempt_callback(&emit, clip.index);
 
         // Export clip with progress
-        match exporter
-            .export_with_progress(&resolved, &timing, &output_path, Some(&progress_callback))
-            .await
-        {
+        let mut result = exporter
+            .export_with_progress(
+                &resolved,
+                &timing,
+                &options,
+                &output_path,
+                Some(&progress_callback),
+                Some(&attempt_callback),
+            )
+            .await;
+
+        // Playlist URLs can expire while earlier clips export; resolve again once
+        if matches!(&result, Err(e) if e.kind() == ExportErrorKind::Forbidden403) {
+            log::warn!(
+                "Got 403 exporting {}, re-resolving {}",
+                filename,
+                clip.vod_url
+            );
+            if let Ok(fresh) = resolver.resolve(&clip.vod_url).await {
+                result = exporter
+                    .export_with_progress(
+                        &fresh,
+                        &timing,
+                        &options,
+                        &output_path,
+                        Some(&progress_callback),
+                        Some(&attempt_callback),
+                    )
+                    .await;
+            }
+        }
+
+        match result {
             Ok(()) => {
                 log::info!("Exported: {}", filename);
                 exported += 1;
-                let _ = app.emit(
-                    "export-progress",
-                    ExportProgress::ClipCompleted {
-                        index: clip.index,
-                        status: ClipResult::Success,
-                    },
-                );
+                muted.extend(muted_clip);
+                emit(ExportProgress::ClipCompleted {
+                    index: clip.index,
+                    status: ClipResult::Success,
+                    size_bytes: file_size(&output_path),
+                });
             }
             Err(e) => {
                 log::error!("Failed to export {}: {}", filename, e);
                 errors.push(format!("{}: {}", filename, e));
                 failed += 1;
-                let _ = app.emit(
-                    "export-progress",
-                    ExportProgress::ClipCompleted {
-                        index: clip.index,
-                        status: ClipResult::Failed {
-                            error: e.to_string(),
-                        },
+                emit(ExportProgress::ClipCompleted {
+                    index: clip.index,
+                    status: ClipResult::Failed {
+                        error: e.to_string(),
+                        error_kind: e.kind(),
                     },
-                );
+                    size_bytes: None,
+                });
             }
         }
     }
 
+    if let Some(cache) = &segment_cache {
+        cache.prune();
+    }
+
     // Emit finished event
-    let _ = app.emit(
-        "export-progress",
-        ExportProgress::Finished {
-            exported,
-            skipped,
-            failed,
-        },
-    );
+    emit(ExportProgress::Finished {
+        exported,
+        skipped,
+        failed,
+    });
 
     Ok(ExportResult {
         exported,
@@ -204,9 +748,25 @@ pub async fn export_clips(
         failed,
         errors,
         output_dir: clips_dir.to_string_lossy().to_string(),
+        finished_at: localtime::format_report(
+            Utc::now(),
+            project_file.as_ref().and_then(|p| p.timezone.as_deref()),
+        ),
+        muted,
+        long,
     })
 }
 
+/// Find the clips that overlap parts of their VOD muted by the platform
+#[tauri::command]
+pub async fn check_muted_segments(clips: Vec<ClipRequest>) -> Result<Vec<MutedClip>, String> {
+    let muted = muted_segments_by_vod(&clips, &VodResolverChain::new()).await;
+    Ok(clips
+        .iter()
+        .filter_map(|clip| muted_clip(clip, &muted))
+        .collect())
+}
+
 /// Check which clips are already downloaded
 #[tauri::command]
 pub async fn check_clips_status(
@@ -218,11 +778,12 @@ pub async fn check_clips_status(
     let statuses = clips
         .iter()
         .map(|clip| {
-            let filename = generate_filename(&clip.action_id, &clip.action_name);
+            let filename = generate_filename(&clip.action_id, &clip.action_name, clip.container);
             let streamer_dir = config.streamer_clips_dir(&project_name, &clip.streamer_name);
             let is_downloaded = streamer_dir.join(&filename).exists();
 
             ClipStatus {
+                action_id: clip.action_id.clone(),
                 action_name: clip.action_name.clone(),
                 streamer_name: clip.streamer_name.clone(),
                 filename,
@@ -248,7 +809,7 @@ pub async fn open_clips_folder(project_name: String) -> Result<(), String> {
     let config = get_config();
     let clips_dir = config.clips_dir(&project_name);
 
-    open_folder(&clips_dir).map_err(|e| e.to_string())
+    open_folder(&clips_dir).map_err(|e| NoxError::from(e).to_string())
 }
 
 /// Resolve a VOD URL to a direct stream URL
@@ -257,13 +818,68 @@ pub async fn resolve_vod_url(vod_url: String) -> Result<String, String> {
     let resolver = VodResolverChain::new();
 
     let resolved = resolver
-        .resolve(&vod_url)
+        .resolve_cached(&vod_url)
         .await
         .map_err(|e| e.to_string())?;
 
     Ok(resolved.url)
 }
 
+/// Whether a streamer's VOD can be played, as found by `prepare_project`
+#[derive(Debug, Serialize)]
+pub struct StreamerReadiness {
+    pub streamer_id: String,
+    pub streamer_name: String,
+    pub platform: String,
+    /// URL for the player: proxied HLS for Twitch, the VOD URL otherwise
+    pub playback_url: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Resolve every streamer's VOD of a project at once
+///
+/// Resolutions run concurrently and are cached, so the player's own
+/// `resolve_vod_url` calls afterwards return immediately.
+#[tauri::command]
+pub async fn prepare_project(project: ProjectFile) -> Vec<StreamerReadiness> {
+    let resolver = VodResolverChain::new();
+    let checks = project.streamers.iter().map(|streamer| {
+        let resolver = &resolver;
+        async move {
+            let platform = platform::detect_platform(&streamer.vod_url);
+            let resolved = resolver.resolve_cached(&streamer.vod_url).await;
+            let (playback_url, error) = match resolved {
+                Ok(vod) if platform == "twitch" => (Some(proxy::get_proxy_url(&vod.url)), None),
+                // The player handles YouTube and direct links itself
+                Ok(_) => (Some(streamer.vod_url.clone()), None),
+                Err(e) => {
+                    log::warn!("Failed to resolve {}: {}", streamer.vod_url, e);
+                    (None, Some(e.to_string()))
+                }
+            };
+
+            StreamerReadiness {
+                streamer_id: streamer.id.clone(),
+                streamer_name: streamer.name.clone(),
+                platform: platform.to_string(),
+                playback_url,
+                error,
+            }
+        }
+    });
+
+    futures_util::future::join_all(checks).await
+}
+
+/// Get the wall-clock time a VOD recording started, from platform metadata
+#[tauri::command]
+pub async fn fetch_vod_recorded_at(vod_url: String) -> Result<Option<DateTime<Utc>>, String> {
+    VodResolverChain::new()
+        .recorded_at(&vod_url)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Get proxied URL for HLS streams (used for Twitch sub-only VODs)
 #[tauri::command]
 pub fn get_proxy_url(url: String) -> String {
@@ -272,8 +888,12 @@ pub fn get_proxy_url(url: String) -> String {
 
 // ============ Helpers ============
 
-/// Generate filename for a clip: {action_id_short}_{action_name}.mp4
-fn generate_filename(action_id: &str, action_name: &str) -> String {
+/// Generate filename for a clip: {action_id_short}_{action_name}.{container extension}
+pub(crate) fn generate_filename(
+    action_id: &str,
+    action_name: &str,
+    container: Container,
+) -> String {
     let safe_action = sanitize_filename(action_name);
     // Use first 6 chars of action_id for short identifier
     let id_short = if action_id.len() > 6 {
@@ -281,10 +901,15 @@ fn generate_filename(action_id: &str, action_name: &str) -> String {
     } else {
         action_id
     };
-    format!("{}_{}.mp4", id_short, safe_action)
+    format!("{}_{}.{}", id_short, safe_action, container.extension())
+}
+
+/// Size of a file in bytes, if it exists
+fn file_size(path: &std::path::Path) -> Option<u64> {
+    std::fs::metadata(path).map(|m| m.len()).ok()
 }
 
-fn sanitize_filename(s: &str) -> String {
+pub(crate) fn sanitize_filename(s: &str) -> String {
     s.chars()
         .map(|c| match c {
             '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
@@ -295,25 +920,6 @@ fn sanitize_filename(s: &str) -> String {
         .to_string()
 }
 
-fn open_folder(path: &PathBuf) -> std::io::Result<()> {
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("explorer").arg(path).spawn()?;
-    }
-
-    #[cfg(target_os = "macos")]
-    {
-        std::process::Command::new("open").arg(path).spawn()?;
-    }
-
-    #[cfg(target_os = "linux")]
-    {
-        std::process::Command::new("xdg-open").arg(path).spawn()?;
-    }
-
-    Ok(())
-}
-
 // ============ Binary Management Commands ============
 
 /// Check the status of required binaries (ffmpeg, yt-dlp)
@@ -351,20 +957,230 @@ pub fn get_work_dir() -> Result<String, String> {
 /// Set the work directory
 #[tauri::command]
 pub async fn set_work_dir(path: String) -> Result<(), String> {
-    let path_buf = PathBuf::from(&path);
+    let path_buf = workdir::normalize_work_dir(&PathBuf::from(&path));
+
+    if workdir::is_network_path(&path_buf) {
+        log::info!("Using network work directory: {}", path_buf.display());
+    }
 
     // Validate the path exists or can be created
     if !path_buf.exists() {
-        std::fs::create_dir_all(&path_buf).map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(&path_buf).map_err(|e| NoxError::from(e).to_string())?;
     }
 
     get_config_mut()
         .set_output_dir(path_buf)
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| NoxError::from(e).to_string())?;
 
     Ok(())
 }
 
+/// Get the maximum clip size in MB (None = no limit)
+#[tauri::command]
+pub fn get_max_clip_size() -> Option<f64> {
+    get_config().ffmpeg.max_size_mb
+}
+
+/// Set the maximum clip size in MB; clips are two-pass encoded to fit it
+#[tauri::command]
+pub async fn set_max_clip_size(max_size_mb: Option<f64>) -> Result<(), String> {
+    if let Some(mb) = max_size_mb {
+        if mb <= 0.0 {
+            return Err(format!("Invalid max clip size: {}MB", mb));
+        }
+    }
+
+    get_config_mut()
+        .set_max_clip_size(max_size_mb)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the loudness normalization target in LUFS (None = off)
+#[tauri::command]
+pub fn get_loudness_target() -> Option<f64> {
+    get_config().ffmpeg.loudness_lufs
+}
+
+/// Set the loudness normalization target in LUFS (e.g. -14); None turns it off
+#[tauri::command]
+pub async fn set_loudness_target(lufs: Option<f64>) -> Result<(), String> {
+    if let Some(lufs) = lufs {
+        if !crate::export::LOUDNESS_TARGET_RANGE.contains(&lufs) {
+            return Err(format!("Invalid loudness target: {} LUFS", lufs));
+        }
+    }
+
+    get_config_mut()
+        .set_loudness_target(lufs)
+        .map_err(|e| e.to_string())
+}
+
+/// Whether dropped HLS/HTTP inputs are reconnected during export
+#[tauri::command]
+pub fn get_hls_reconnect() -> bool {
+    get_config().ffmpeg.hls_reconnect
+}
+
+/// Turn reconnecting dropped HLS/HTTP inputs on or off
+#[tauri::command]
+pub async fn set_hls_reconnect(enabled: bool) -> Result<(), String> {
+    get_config_mut()
+        .set_hls_reconnect(enabled)
+        .map_err(|e| e.to_string())
+}
+
+/// Seconds a montage export may take per second of output (0 = no timeout)
+#[tauri::command]
+pub fn get_montage_timeout_factor() -> f64 {
+    get_config().ffmpeg.montage_timeout_factor
+}
+
+/// Set the montage timeout per second of output; 0 turns the timeout off
+/// (a stuck export can still be cancelled)
+#[tauri::command]
+pub async fn set_montage_timeout_factor(factor: f64) -> Result<(), String> {
+    if !factor.is_finite() || factor < 0.0 {
+        return Err(format!("Invalid montage timeout factor: {}", factor));
+    }
+
+    get_config_mut()
+        .set_montage_timeout_factor(factor)
+        .map_err(|e| e.to_string())
+}
+
+/// Whether downloaded VOD ranges are cached for re-exports
+#[tauri::command]
+pub fn get_segment_cache_enabled() -> bool {
+    get_config().segment_cache_enabled()
+}
+
+/// Turn the source segment cache on or off
+#[tauri::command]
+pub async fn set_segment_cache_enabled(enabled: bool) -> Result<(), String> {
+    get_config_mut()
+        .set_segment_cache_enabled(enabled)
+        .map_err(|e| e.to_string())
+}
+
+/// Delete every cached VOD range
+#[tauri::command]
+pub async fn clear_segment_cache() -> Result<(), String> {
+    let dir = get_config().segment_cache_dir();
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| NoxError::from(e).to_string())?;
+    }
+    Ok(())
+}
+
+/// Benchmark outcome for one encoder
+#[derive(Debug, Serialize)]
+pub struct EncoderBenchmark {
+    pub encoder: VideoEncoder,
+    /// Multiple of realtime (None = failed)
+    pub speed: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// Result of `benchmark_encoders`
+#[derive(Debug, Serialize)]
+pub struct EncoderBenchmarkReport {
+    pub results: Vec<EncoderBenchmark>,
+    /// Encoder now stored in config
+    pub selected: VideoEncoder,
+    pub speed: f64,
+}
+
+/// Benchmark every available encoder and store the fastest working one
+///
+/// Encoders are run one after another so they don't compete for the GPU/CPU.
+#[tauri::command]
+pub async fn benchmark_encoders() -> Result<EncoderBenchmarkReport, String> {
+    let exporter = FfmpegExporter::new();
+    let scratch = ScratchDir::new("benchmark").map_err(|e| e.to_string())?;
+    let mut results = Vec::new();
+
+    for encoder in VideoEncoder::ALL {
+        if !encoder.is_available() {
+            continue;
+        }
+
+        let result = exporter.benchmark_encoder(encoder, scratch.path()).await;
+        match &result {
+            Ok(speed) => log::info!("[FFmpeg] Benchmark {}: {:.2}x", encoder.as_str(), speed),
+            Err(e) => log::warn!("[FFmpeg] Benchmark {} failed: {}", encoder.as_str(), e),
+        }
+        results.push(EncoderBenchmark {
+            encoder,
+            speed: result.as_ref().ok().copied(),
+            error: result.err().map(|e| e.to_string()),
+        });
+    }
+
+    let (selected, speed) = fastest_encoder(&results)
+        .ok_or_else(|| "No encoder produced a valid sample".to_string())?;
+
+    get_config_mut()
+        .set_encoder(selected)
+        .map_err(|e| e.to_string())?;
+
+    log::info!(
+        "[FFmpeg] Selected encoder {} ({:.2}x)",
+        selected.as_str(),
+        speed
+    );
+
+    Ok(EncoderBenchmarkReport {
+        results,
+        selected,
+        speed,
+    })
+}
+
+/// Fastest encoder that completed the benchmark
+fn fastest_encoder(results: &[EncoderBenchmark]) -> Option<(VideoEncoder, f64)> {
+    results
+        .iter()
+        .filter_map(|r| r.speed.map(|speed| (r.encoder, speed)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Local scripting API settings
+#[derive(Debug, Serialize)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub token: Option<String>,
+    pub base_url: String,
+}
+
+/// Get the local scripting API settings
+#[tauri::command]
+pub fn get_api_settings() -> ApiSettings {
+    let config = get_config();
+    ApiSettings {
+        enabled: config.api_enabled(),
+        token: config.api_token().map(str::to_string),
+        base_url: proxy::get_api_base_url(),
+    }
+}
+
+/// Enable or disable the local scripting API
+#[tauri::command]
+pub async fn set_api_enabled(enabled: bool) -> Result<ApiSettings, String> {
+    get_config_mut()
+        .set_api_enabled(enabled)
+        .map_err(|e| e.to_string())?;
+
+    Ok(get_api_settings())
+}
+
+/// Generate a new scripting API token (the old one stops working)
+#[tauri::command]
+pub async fn regenerate_api_token() -> Result<String, String> {
+    get_config_mut()
+        .regenerate_api_token()
+        .map_err(|e| e.to_string())
+}
+
 /// Open a folder picker dialog and return the selected path
 #[tauri::command]
 pub async fn pick_work_dir(app: tauri::AppHandle) -> Result<Option<String>, String> {
@@ -391,7 +1207,20 @@ pub async fn pick_work_dir(app: tauri::AppHandle) -> Result<Option<String>, Stri
 
 /// Save a project to disk
 #[tauri::command]
-pub async fn save_project(project: ProjectFile) -> Result<(), String> {
+pub async fn save_project(mut project: ProjectFile) -> Result<(), String> {
+    // Saved montages only change through save_montage/delete_montage: the
+    // ones on disk are kept, whatever the frontend's copy holds
+    project.montages = match project::load_project(&project.name) {
+        Ok(existing) => existing.map(|p| p.montages).unwrap_or_default(),
+        Err(e) => {
+            log::warn!(
+                "[Project] Saved montages of {} not kept: {}",
+                project.name,
+                e
+            );
+            Vec::new()
+        }
+    };
     project::save_project(&project.name, &project).map_err(|e| e.to_string())
 }
 
@@ -407,8 +1236,84 @@ pub async fn list_projects() -> Result<Vec<String>, String> {
     project::list_projects().map_err(|e| e.to_string())
 }
 
+/// Zip a project (optionally with its clips and montages) for sharing.
+/// A non-empty password AES-256 encrypts the archive.
+#[tauri::command]
+pub async fn export_project_archive(
+    project_name: String,
+    output_path: String,
+    password: Option<String>,
+    include_media: bool,
+) -> Result<ArchiveSummary, String> {
+    tokio::task::spawn_blocking(move || {
+        project::archive::export_archive(
+            &project_name,
+            Path::new(&output_path),
+            password.as_deref().filter(|p| !p.is_empty()),
+            include_media,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Unpack a project archive into the work directory, returning the project name
+#[tauri::command]
+pub async fn import_project_archive(
+    archive_path: String,
+    password: Option<String>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        project::archive::import_archive(
+            Path::new(&archive_path),
+            password.as_deref().filter(|p| !p.is_empty()),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Reports from the startup migration of legacy clip folders
+#[tauri::command]
+pub async fn get_migration_report() -> Vec<MigrationReport> {
+    project::migrate::migrate_all().to_vec()
+}
+
 /// Delete a project from disk
 #[tauri::command]
 pub async fn delete_project_files(project_name: String) -> Result<(), String> {
     project::delete_project(&project_name).map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn benchmark(encoder: VideoEncoder, speed: Option<f64>) -> EncoderBenchmark {
+        EncoderBenchmark {
+            encoder,
+            speed,
+            error: speed.is_none().then(|| "failed".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_fastest_encoder() {
+        let results = vec![
+            benchmark(VideoEncoder::H264Nvenc, None),
+            benchmark(VideoEncoder::H264Qsv, Some(3.5)),
+            benchmark(VideoEncoder::Libx264, Some(1.2)),
+        ];
+        // Failed encoders are skipped even when listed first
+        assert_eq!(
+            fastest_encoder(&results),
+            Some((VideoEncoder::H264Qsv, 3.5))
+        );
+
+        let failed = vec![benchmark(VideoEncoder::H264Amf, None)];
+        assert_eq!(fastest_encoder(&failed), None);
+        assert_eq!(fastest_encoder(&[]), None);
+    }
+}
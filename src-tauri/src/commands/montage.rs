@@ -1,63 +1,136 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::Emitter;
 
-use crate::config::get_config;
+use super::clips::{clip_files, fresh_proxy};
+use super::{generate_filename, run_export, sanitize_filename, ClipRequest, ProgressEmitter};
+use crate::config::{get_config, VideoEncoder};
+use crate::error::{ExportError, ExportResult, NoxError};
+use crate::export::{space, Container, ProgressCallback};
+use crate::localtime;
 use crate::montage::{
-    MontageClip as MontageConcatClip, MontageConfig, MontageExporter, OverlayConfig,
-    OverlayPosition,
+    beats::{self, BeatAnalysis},
+    check_inputs, presets, request_cancel, Bookend, CancelScope, GridConfig, GridLayout,
+    InputReport, MontageAspect, MontageClip as MontageConcatClip, MontageClipInput, MontageConfig,
+    MontageContainer, MontageEncoding, MontageExportInput, MontageExporter, MontageFit,
+    MontageOrder, MusicTrack, OrderKey, OverlayConfig, OverlayStylePreset, PipStyle, RenderMode,
+    TransitionStyle,
 };
+use crate::project::{
+    self, ActionInfo, ClipInfo as ProjectClipInfo, ClipStatus, MontagePreset, MontageRender,
+    ProjectFile, StreamerInfo,
+};
+use crate::scratch;
 
-/// Input for a single clip in the montage
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MontageClipInput {
-    /// Clip filename (for display)
-    pub filename: String,
-    /// Full path to the clip file
-    pub path: String,
-    /// Duration in seconds
-    pub duration: f64,
-    /// Streamer name for overlay
-    pub streamer_name: String,
+/// Point every clip at where its file is now (see [`MontageClipInput::resolved_path`])
+fn resolve_clip_paths(config: &mut MontageExportInput) {
+    for clip in &mut config.clips {
+        clip.path = clip.resolved_path().to_string_lossy().to_string();
+    }
 }
 
-/// Overlay position for the frontend
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub enum OverlayPositionInput {
-    TopLeft,
-    TopRight,
-    BottomLeft,
-    BottomRight,
-}
-
-impl From<OverlayPositionInput> for OverlayPosition {
-    fn from(pos: OverlayPositionInput) -> Self {
-        match pos {
-            OverlayPositionInput::TopLeft => OverlayPosition::TopLeft,
-            OverlayPositionInput::TopRight => OverlayPosition::TopRight,
-            OverlayPositionInput::BottomLeft => OverlayPosition::BottomLeft,
-            OverlayPositionInput::BottomRight => OverlayPosition::BottomRight,
+/// Rearrange the clips by `config.order`, from what the project knows about them
+fn apply_order(project: Option<&ProjectFile>, config: &mut MontageExportInput) {
+    if config.order == MontageOrder::AsGiven {
+        return;
+    }
+    let keys: Vec<OrderKey> = config
+        .clips
+        .iter()
+        .map(|clip| order_key(project, clip))
+        .collect();
+    let sequence = config.order.sequence(&keys);
+    reorder_clips(config, &sequence);
+}
+
+/// The project action a montage clip was cut from, and the streamer's clip
+/// of it, found by the clip's action id and streamer name
+fn project_clip<'a>(
+    project: &'a ProjectFile,
+    clip: &MontageClipInput,
+) -> Option<(&'a ActionInfo, Option<&'a ProjectClipInfo>)> {
+    let action_id = clip.action_id.as_deref()?;
+    let action = project.actions.iter().find(|a| a.id == action_id)?;
+    let streamer = project
+        .streamers
+        .iter()
+        .find(|s| s.name == clip.streamer_name);
+    let own = streamer.and_then(|s| action.clips.iter().find(|c| c.streamer_id == s.id));
+    Some((action, own))
+}
+
+/// Streamer and game time of a clip
+fn order_key(project: Option<&ProjectFile>, clip: &MontageClipInput) -> OrderKey {
+    let Some(project) = project else {
+        return OrderKey::default();
+    };
+    OrderKey {
+        streamer: project
+            .streamers
+            .iter()
+            .position(|s| s.name == clip.streamer_name),
+        game_time: project_clip(project, clip)
+            .map(|(action, own)| action.game_time + own.map_or(0.0, |c| c.in_point)),
+    }
+}
+
+/// Head credits without a title with the project name
+fn name_credits(config: &mut MontageExportInput, project_name: &str) {
+    for bookend in [&mut config.intro, &mut config.outro].into_iter().flatten() {
+        if let Bookend::Credits(credits) = bookend {
+            if credits.title.trim().is_empty() {
+                credits.title = project_name.to_string();
+            }
         }
     }
 }
 
-/// Overlay configuration input from frontend
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OverlayInput {
-    pub text: String,
-    pub position: OverlayPositionInput,
-    pub font_size: u32,
-    pub color: String,
-    pub box_color: Option<String>,
+/// Where each clip's action happens in its file, for the overlay counter
+fn set_action_times(
+    project: Option<&ProjectFile>,
+    input: &MontageExportInput,
+    config: &mut MontageConfig,
+) {
+    let Some(project) = project else {
+        return;
+    };
+    for (clip, input) in config.clips.iter_mut().zip(&input.clips) {
+        // Clips start `in_point` seconds from their action
+        clip.action_at = project_clip(project, input)
+            .and_then(|(_, own)| own)
+            .map(|own| -own.in_point);
+    }
 }
 
-/// Export configuration from frontend
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MontageExportInput {
-    pub clips: Vec<MontageClipInput>,
-    pub transition_duration: f64,
-    pub overlay: Option<OverlayInput>,
-    pub output_filename: Option<String>,
+/// Put the clips in `sequence` order (old indices); section cards stay
+/// before the clip they preceded
+fn reorder_clips(config: &mut MontageExportInput, sequence: &[usize]) {
+    let mut position = vec![0; sequence.len()];
+    for (new, &old) in sequence.iter().enumerate() {
+        position[old] = new;
+    }
+    for section in &mut config.sections {
+        if let Some(&new) = position.get(section.before) {
+            section.before = new;
+        }
+    }
+    config.clips = sequence.iter().map(|&i| config.clips[i].clone()).collect();
+}
+
+/// `path` relative to `base` with `/` separators, if it's inside it
+fn relative_to(base: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(base).ok()?;
+    Some(
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
 }
 
 /// Result of montage export
@@ -67,91 +140,820 @@ pub struct MontageExportResult {
     pub output_path: String,
     pub duration: f64,
     pub error: Option<String>,
+    /// Encoder that wrote the file (None when joined by stream copy)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoder: Option<String>,
 }
 
-/// Get current timestamp string for filename
-fn get_timestamp() -> String {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default();
-    format!("{}", now.as_secs())
+/// Current time for a file name, in the project's timezone
+fn get_timestamp(project_name: &str) -> String {
+    let timezone = project::load_project(project_name)
+        .ok()
+        .flatten()
+        .and_then(|p| p.timezone);
+    localtime::format_filename(Utc::now(), timezone.as_deref())
 }
 
-/// Tauri command to export a montage
-#[tauri::command]
-pub async fn export_montage(
-    project_name: String,
-    config: MontageExportInput,
-) -> Result<MontageExportResult, String> {
-    let app_config = get_config();
-
-    // Build paths
-    let project_dir = app_config.output_dir.join(&project_name);
-    let montages_dir = project_dir.join("montages");
+/// Output file name: the requested one, or `<project>_montage_<time>[_<aspect>].<ext>`
+fn montage_filename(project_name: &str, config: &MontageExportInput, timestamp: &str) -> String {
+    let ext = config.container.extension();
+    match &config.output_filename {
+        Some(name) if name.ends_with(&format!(".{}", ext)) => name.clone(),
+        Some(name) => format!("{}.{}", name, ext),
+        None if config.aspect == MontageAspect::Landscape => {
+            format!("{}_montage_{}.{}", project_name, timestamp, ext)
+        }
+        None => format!(
+            "{}_montage_{}_{}.{}",
+            project_name,
+            timestamp,
+            config.aspect.as_str(),
+            ext
+        ),
+    }
+}
 
-    // Create montages directory if needed
+/// Create the project's montages directory
+fn ensure_montages_dir(project_name: &str) -> Result<PathBuf, String> {
+    let montages_dir = get_config().output_dir.join(project_name).join("montages");
     std::fs::create_dir_all(&montages_dir)
-        .map_err(|e| format!("Failed to create montages directory: {}", e))?;
-
-    // Generate output filename
-    let output_filename = if let Some(name) = &config.output_filename {
-        if name.ends_with(".mp4") {
-            name.clone()
-        } else {
-            format!("{}.mp4", name)
-        }
-    } else {
-        let timestamp = get_timestamp();
-        format!("{}_montage_{}.mp4", project_name, timestamp)
-    };
+        .map_err(|e| format!("Failed to create montages directory: {}", NoxError::from(e)))?;
+    Ok(montages_dir)
+}
 
-    let output_path = montages_dir.join(&output_filename);
+/// Total size of the clip files (missing files count as 0)
+///
+/// The montage is re-encoded at a similar bitrate, so its inputs are a fair
+/// estimate of the output size.
+fn clips_size<'a>(paths: impl Iterator<Item = &'a str>) -> u64 {
+    paths
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|m| m.len())
+        .sum()
+}
 
-    // Convert input clips to internal format
-    let clips: Vec<MontageConcatClip> = config
+/// Convert frontend montage input to the internal format
+fn montage_config(config: MontageExportInput) -> MontageConfig {
+    let clips = config
         .clips
         .iter()
         .map(|c| MontageConcatClip {
             path: PathBuf::from(&c.path),
             duration: c.duration,
             streamer_name: c.streamer_name.clone(),
+            action_name: c.action_name.clone(),
+            overlay: c.overlay.clone(),
+            volume: c.volume,
+            trim_start: c.trim_start,
+            trim_end: c.trim_end,
+            crop: c.crop,
+            zoom: c.zoom,
+            slowmo: c.slowmo,
+            freeze: c.freeze,
+            // Set from the project by `set_action_times`
+            action_at: None,
+            silent: false,
+            generated: false,
         })
         .collect();
 
-    // Convert overlay config if present
     let overlay = config.overlay.map(|o| OverlayConfig {
         text: o.text,
         position: o.position.into(),
         font_size: o.font_size,
         color: o.color,
         box_color: o.box_color,
+        font: o.font.map(PathBuf::from),
+        animation: o.animation,
+        custom_position: o.custom_position,
+        outline: o.outline,
+        shadow: o.shadow,
     });
 
-    let montage_config = MontageConfig {
+    MontageConfig {
         clips,
         transition_duration: config.transition_duration,
+        transition: config.transition,
+        music: config.music.map(|m| MusicTrack {
+            path: PathBuf::from(m.path),
+            volume: m.volume,
+            snap_to_beats: m.snap_to_beats,
+        }),
+        intro: config.intro,
+        outro: config.outro,
+        sections: config.sections,
         overlay,
+        image_overlay: config.image_overlay,
+        aspect: config.aspect,
+        fit: config.fit,
+        format: config.format,
+        loudness_lufs: config.loudness_lufs,
+        render: config.render,
+        encoding: config.encoding,
+        container: config.container,
+    }
+}
+
+/// Tauri command to export a montage
+#[tauri::command]
+pub async fn export_montage(
+    app: tauri::AppHandle,
+    project_name: String,
+    mut config: MontageExportInput,
+) -> Result<MontageExportResult, String> {
+    resolve_clip_paths(&mut config);
+    name_credits(&mut config, &project_name);
+    let project = project::load_project(&project_name).ok().flatten();
+    apply_order(project.as_ref(), &mut config);
+    let montages_dir = ensure_montages_dir(&project_name)?;
+    let output_filename = montage_filename(&project_name, &config, &get_timestamp(&project_name));
+    let output_path = montages_dir.join(&output_filename);
+
+    let required = clips_size(config.clips.iter().map(|c| c.path.as_str()));
+    space::ensure_free_space(&montages_dir, required).map_err(|e| e.to_string())?;
+
+    let mut montage_config = montage_config(config.clone());
+    set_action_times(project.as_ref(), &config, &mut montage_config);
+    let total_duration = montage_config.total_duration();
+
+    let result = MontageExporter::new()
+        .export(&montage_config, &output_path)
+        .await;
+    if matches!(result, Err(ExportError::Cancelled)) {
+        let _ = app.emit(
+            "montage-cancelled",
+            output_path.to_string_lossy().to_string(),
+        );
+    }
+    Ok(montage_result(result, &output_path, total_duration))
+}
+
+/// Tauri command to render a quick low-resolution preview of a montage
+///
+/// Renders what `export_montage` would (order, overlays, transitions, music)
+/// at 480p with a fast encode, into the temp dir rather than the project.
+/// Returns the preview's path; each preview of a project replaces the last.
+#[tauri::command]
+pub async fn preview_montage(
+    project_name: String,
+    mut config: MontageExportInput,
+) -> Result<String, String> {
+    resolve_clip_paths(&mut config);
+    name_credits(&mut config, &project_name);
+    let project = project::load_project(&project_name).ok().flatten();
+    apply_order(project.as_ref(), &mut config);
+
+    let preview_dir = scratch::temp_root().join("previews");
+    std::fs::create_dir_all(&preview_dir)
+        .map_err(|e| format!("Failed to create preview dir: {}", e))?;
+    let output_path = preview_dir.join(format!("{}.mp4", sanitize_filename(&project_name)));
+
+    let mut montage_config = montage_config(config.clone());
+    set_action_times(project.as_ref(), &config, &mut montage_config);
+    MontageExporter::preview()
+        .export(&montage_config, &output_path)
+        .await?;
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+/// Tauri command to stop the montage being exported
+///
+/// FFmpeg is killed and the partial file removed; the export command then
+/// returns a failed result and "montage-cancelled" is emitted. Returns false
+/// if no montage was being exported.
+#[tauri::command]
+pub fn cancel_montage() -> bool {
+    let cancelled = request_cancel();
+    if cancelled {
+        log::info!("[Montage] Cancel requested");
+    }
+    cancelled
+}
+
+/// Progress events for batch montage rendering (emitted as "montage-batch-progress")
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MontageBatchProgress {
+    Started {
+        total_variants: usize,
+        total_clips: usize,
+    },
+    VariantStarted {
+        index: usize,
+        output_path: String,
+    },
+    /// Progress across the whole batch (all variants)
+    Progress {
+        percent: f32,
+    },
+    VariantCompleted {
+        index: usize,
+        result: MontageExportResult,
+    },
+    Finished {
+        succeeded: usize,
+        failed: usize,
+    },
+}
+
+/// Render several montage variants (e.g. 16:9 and 9:16 cuts) in one run
+///
+/// Each variant renders straight from the source clips, so none of them goes
+/// through an extra lossy encode. Variants render one after another; a failed
+/// variant doesn't stop the others.
+#[tauri::command]
+pub async fn export_montages(
+    app: tauri::AppHandle,
+    project_name: String,
+    mut configs: Vec<MontageExportInput>,
+) -> Result<Vec<MontageExportResult>, String> {
+    if configs.is_empty() {
+        return Err("No montage variants to export".to_string());
+    }
+    configs.iter_mut().for_each(resolve_clip_paths);
+    let project = project::load_project(&project_name).ok().flatten();
+    for config in &mut configs {
+        name_credits(config, &project_name);
+        apply_order(project.as_ref(), config);
+    }
+
+    let montages_dir = ensure_montages_dir(&project_name)?;
+    let progress_app = app.clone();
+    let emit = Arc::new(move |event: MontageBatchProgress| {
+        let _ = progress_app.emit("montage-batch-progress", event);
+    });
+
+    // Same default name for two variants of the same shape would collide
+    let timestamp = get_timestamp(&project_name);
+    let mut used_paths = HashSet::new();
+    let output_paths: Vec<PathBuf> = configs
+        .iter()
+        .map(|config| {
+            let path = montages_dir.join(montage_filename(&project_name, config, &timestamp));
+            let stem = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let mut unique = path.clone();
+            let mut n = 2;
+            while !used_paths.insert(unique.clone()) {
+                unique =
+                    path.with_file_name(format!("{}_{}.{}", stem, n, config.container.extension()));
+                n += 1;
+            }
+            unique
+        })
+        .collect();
+
+    // Every clip once, in first-use order
+    let mut unique_clips: Vec<&str> = Vec::new();
+    for clip in configs.iter().flat_map(|c| &c.clips) {
+        if !unique_clips.contains(&clip.path.as_str()) {
+            unique_clips.push(&clip.path);
+        }
+    }
+
+    let clip_bytes = clips_size(unique_clips.iter().copied());
+    let required = clip_bytes * configs.len() as u64;
+    space::ensure_free_space(&montages_dir, required).map_err(|e| e.to_string())?;
+
+    // Keeps the batch cancellable between variants
+    let exporter = MontageExporter::new();
+    let _scope = CancelScope::enter(exporter.cancel_token());
+    emit(MontageBatchProgress::Started {
+        total_variants: configs.len(),
+        total_clips: unique_clips.len(),
+    });
+
+    let montage_configs: Vec<MontageConfig> = configs
+        .into_iter()
+        .map(|input| {
+            let mut config = montage_config(input.clone());
+            set_action_times(project.as_ref(), &input, &mut config);
+            config
+        })
+        .collect();
+
+    // Overall progress is weighted by seconds of media rendered per variant
+    let total_work: f64 = montage_configs
+        .iter()
+        .map(MontageConfig::total_duration)
+        .sum();
+    let step_progress = |done: f64, weight: f64| -> ProgressCallback {
+        let emit = emit.clone();
+        Box::new(move |update| {
+            let done = done + weight * update.percent as f64 / 100.0;
+            emit(MontageBatchProgress::Progress {
+                percent: (done / total_work.max(0.001) * 100.0).min(100.0) as f32,
+            });
+        })
     };
 
+    let mut done = 0.0;
+    let mut results = Vec::new();
+    for (index, (montage_config, output_path)) in
+        montage_configs.into_iter().zip(output_paths).enumerate()
+    {
+        if exporter.cancel_token().is_cancelled() {
+            break;
+        }
+
+        emit(MontageBatchProgress::VariantStarted {
+            index,
+            output_path: output_path.to_string_lossy().to_string(),
+        });
+
+        let total_duration = montage_config.total_duration();
+        let progress = step_progress(done, total_duration);
+        let result = exporter
+            .export_with_progress(&montage_config, &output_path, Some(&progress))
+            .await;
+        let result = montage_result(result, &output_path, total_duration);
+        done += total_duration;
+
+        emit(MontageBatchProgress::VariantCompleted {
+            index,
+            result: result.clone(),
+        });
+        results.push(result);
+    }
+
+    if exporter.cancel_token().is_cancelled() {
+        log::info!("[Montage] Batch cancelled after {} variants", results.len());
+        let _ = app.emit(
+            "montage-cancelled",
+            montages_dir.to_string_lossy().to_string(),
+        );
+    }
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    emit(MontageBatchProgress::Finished {
+        succeeded,
+        failed: results.len() - succeeded,
+    });
+
+    Ok(results)
+}
+
+/// Export every streamer's clip for one action and join them into a single file
+///
+/// Clips are exported (or reused if already on disk) like `export_clips`, then
+/// concatenated with hard cuts into `montages/<id>_<action>_compilation.mp4`.
+#[tauri::command]
+pub async fn export_action_compilation(
+    app: tauri::AppHandle,
+    project_name: String,
+    action_id: String,
+) -> Result<MontageExportResult, String> {
+    let project = crate::project::load_project(&project_name)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Project not found: {}", project_name))?;
+    let action = project
+        .actions
+        .iter()
+        .find(|a| a.id == action_id)
+        .ok_or_else(|| format!("Action not found: {}", action_id))?;
+
+    let requests: Vec<ClipRequest> = action
+        .clips
+        .iter()
+        .filter(|clip| !matches!(clip.status, ClipStatus::Excluded))
+        .filter_map(|clip| {
+            let streamer = project
+                .streamers
+                .iter()
+                .find(|s| s.id == clip.streamer_id)?;
+            Some((clip, streamer))
+        })
+        .enumerate()
+        .map(|(index, (clip, streamer))| {
+            project_clip_request(&project, action, clip, streamer, index)
+        })
+        .collect();
+
+    if requests.is_empty() {
+        return Err(format!("Action '{}' has no clips to export", action.name));
+    }
+
+    let clips = export_for_montage(&app, &project_name, requests).await?;
+    if clips.is_empty() {
+        return Ok(MontageExportResult {
+            success: false,
+            output_path: String::new(),
+            duration: 0.0,
+            error: Some("No clips were exported for this action".to_string()),
+            encoder: None,
+        });
+    }
+
+    let montages_dir = ensure_montages_dir(&project_name)?;
+    let filename = generate_filename(&action.id, &action.name, Container::Mp4);
+    let output_path = montages_dir.join(filename.replace(".mp4", "_compilation.mp4"));
+
+    let montage_config = MontageConfig {
+        clips,
+        transition_duration: 0.0,
+        transition: TransitionStyle::None,
+        music: None,
+        intro: None,
+        outro: None,
+        sections: Vec::new(),
+        overlay: None,
+        image_overlay: None,
+        aspect: MontageAspect::Landscape,
+        fit: MontageFit::Crop,
+        format: None,
+        loudness_lufs: None,
+        render: RenderMode::Single,
+        encoding: MontageEncoding::default(),
+        container: MontageContainer::Mp4,
+    };
     let total_duration = montage_config.total_duration();
 
-    // Export
+    log::info!(
+        "[Montage] Compiling {} clip(s) for action '{}' -> {}",
+        montage_config.clips.len(),
+        action.name,
+        output_path.display()
+    );
+
+    let result = MontageExporter::new()
+        .export(&montage_config, &output_path)
+        .await;
+    Ok(montage_result(result, &output_path, total_duration))
+}
+
+/// Export the clips of one action side by side, in a 2x2 grid or as
+/// picture-in-picture
+///
+/// `streamer_ids` picks the POVs in cell order (the first included clips when
+/// empty; in picture-in-picture the first one fills the frame);
+/// `audio_streamer_id` keeps only that streamer's audio instead of mixing all
+/// of them. Writes `montages/<id>_<action>_grid.mp4`.
+#[tauri::command]
+pub async fn export_action_grid(
+    app: tauri::AppHandle,
+    project_name: String,
+    action_id: String,
+    layout: GridLayout,
+    streamer_ids: Vec<String>,
+    audio_streamer_id: Option<String>,
+    pip: Option<PipStyle>,
+) -> Result<MontageExportResult, String> {
+    let project = crate::project::load_project(&project_name)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Project not found: {}", project_name))?;
+    let action = project
+        .actions
+        .iter()
+        .find(|a| a.id == action_id)
+        .ok_or_else(|| format!("Action not found: {}", action_id))?;
+
+    let mut povs: Vec<(&ProjectClipInfo, &StreamerInfo)> = action
+        .clips
+        .iter()
+        .filter(|clip| !matches!(clip.status, ClipStatus::Excluded))
+        .filter_map(|clip| {
+            let streamer = project
+                .streamers
+                .iter()
+                .find(|s| s.id == clip.streamer_id)?;
+            Some((clip, streamer))
+        })
+        .collect();
+    if !streamer_ids.is_empty() {
+        povs = streamer_ids
+            .iter()
+            .filter_map(|id| povs.iter().find(|(_, s)| &s.id == id).copied())
+            .collect();
+    }
+    povs.truncate(layout.max_clips());
+
+    let audio_name = audio_streamer_id.and_then(|id| {
+        povs.iter()
+            .find(|(_, s)| s.id == id)
+            .map(|(_, s)| s.name.clone())
+    });
+
+    let requests: Vec<ClipRequest> = povs
+        .into_iter()
+        .enumerate()
+        .map(|(index, (clip, streamer))| {
+            project_clip_request(&project, action, clip, streamer, index)
+        })
+        .collect();
+    if requests.len() < 2 {
+        return Err(format!(
+            "Action '{}' needs at least 2 clips for a grid",
+            action.name
+        ));
+    }
+
+    let clips = export_for_montage(&app, &project_name, requests).await?;
+    let audio_from = audio_name.and_then(|name| clips.iter().position(|c| c.streamer_name == name));
+    let grid = GridConfig {
+        clips,
+        layout,
+        audio_from,
+        pip: pip.unwrap_or_default(),
+    };
+    if let Err(e) = grid.validate() {
+        return Ok(MontageExportResult {
+            success: false,
+            output_path: String::new(),
+            duration: 0.0,
+            error: Some(e),
+            encoder: None,
+        });
+    }
+
+    let montages_dir = ensure_montages_dir(&project_name)?;
+    let filename = generate_filename(&action.id, &action.name, Container::Mp4);
+    let output_path = montages_dir.join(filename.replace(".mp4", "_grid.mp4"));
+    let total_duration = grid.duration();
+
+    let result = MontageExporter::new()
+        .export_grid(&grid, &output_path, None)
+        .await
+        .map(|()| Some(VideoEncoder::Libx264.as_str()));
+    Ok(montage_result(result, &output_path, total_duration))
+}
+
+/// Progress events for streamer reels (emitted as "reel-progress")
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReelProgress {
+    Started {
+        total_streamers: usize,
+    },
+    StreamerStarted {
+        index: usize,
+        streamer_name: String,
+        total_clips: usize,
+    },
+    /// Rendering progress of the current streamer's reel
+    StreamerProgress {
+        index: usize,
+        percent: f32,
+    },
+    StreamerCompleted {
+        index: usize,
+        streamer_name: String,
+        result: MontageExportResult,
+    },
+    Finished {
+        succeeded: usize,
+        failed: usize,
+    },
+}
+
+/// Join each chosen streamer's included clips, in game order, into a personal reel
+///
+/// `streamer_ids` empty means every streamer in the project. Missing clips are
+/// exported first like `export_clips`; each reel is written to
+/// `montages/<streamer>_reel.mp4`. A failed reel doesn't stop the others.
+#[tauri::command]
+pub async fn export_streamer_reels(
+    app: tauri::AppHandle,
+    project_name: String,
+    streamer_ids: Vec<String>,
+) -> Result<Vec<MontageExportResult>, String> {
+    let project = crate::project::load_project(&project_name)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Project not found: {}", project_name))?;
+    let streamers: Vec<&StreamerInfo> = project
+        .streamers
+        .iter()
+        .filter(|s| streamer_ids.is_empty() || streamer_ids.contains(&s.id))
+        .collect();
+    if streamers.is_empty() {
+        return Err("No streamers selected".to_string());
+    }
+
+    let montages_dir = ensure_montages_dir(&project_name)?;
+    let progress_app = app.clone();
+    let emit = Arc::new(move |event: ReelProgress| {
+        let _ = progress_app.emit("reel-progress", event);
+    });
+    emit(ReelProgress::Started {
+        total_streamers: streamers.len(),
+    });
+
     let exporter = MontageExporter::new();
+    let mut results = Vec::new();
+    for (index, streamer) in streamers.into_iter().enumerate() {
+        let requests = streamer_reel_requests(&project, streamer);
+        emit(ReelProgress::StreamerStarted {
+            index,
+            streamer_name: streamer.name.clone(),
+            total_clips: requests.len(),
+        });
+
+        let output_path =
+            montages_dir.join(format!("{}_reel.mp4", sanitize_filename(&streamer.name)));
+        let result = match export_for_montage(&app, &project_name, requests).await {
+            Ok(clips) if clips.is_empty() => MontageExportResult {
+                success: false,
+                output_path: String::new(),
+                duration: 0.0,
+                error: Some(format!("{} has no included clips", streamer.name)),
+                encoder: None,
+            },
+            Ok(clips) => {
+                let montage_config = MontageConfig {
+                    clips,
+                    transition_duration: 0.0,
+                    transition: TransitionStyle::None,
+                    music: None,
+                    intro: None,
+                    outro: None,
+                    sections: Vec::new(),
+                    overlay: None,
+                    image_overlay: None,
+                    aspect: MontageAspect::Landscape,
+                    fit: MontageFit::Crop,
+                    format: None,
+                    loudness_lufs: None,
+                    render: RenderMode::Single,
+                    encoding: MontageEncoding::default(),
+                    container: MontageContainer::Mp4,
+                };
+                let total_duration = montage_config.total_duration();
+                log::info!(
+                    "[Montage] Reel of {} clip(s) for {} -> {}",
+                    montage_config.clips.len(),
+                    streamer.name,
+                    output_path.display()
+                );
+
+                let progress_emit = emit.clone();
+                let progress: ProgressCallback = Box::new(move |update| {
+                    progress_emit(ReelProgress::StreamerProgress {
+                        index,
+                        percent: update.percent,
+                    });
+                });
+                let result = exporter
+                    .export_with_progress(&montage_config, &output_path, Some(&progress))
+                    .await;
+                montage_result(result, &output_path, total_duration)
+            }
+            Err(e) => MontageExportResult {
+                success: false,
+                output_path: String::new(),
+                duration: 0.0,
+                error: Some(e),
+                encoder: None,
+            },
+        };
+
+        emit(ReelProgress::StreamerCompleted {
+            index,
+            streamer_name: streamer.name.clone(),
+            result: result.clone(),
+        });
+        results.push(result);
+    }
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    emit(ReelProgress::Finished {
+        succeeded,
+        failed: results.len() - succeeded,
+    });
+
+    Ok(results)
+}
+
+/// Export requests for a streamer's included clips, in game order
+fn streamer_reel_requests(project: &ProjectFile, streamer: &StreamerInfo) -> Vec<ClipRequest> {
+    let mut clips: Vec<(&ActionInfo, &ProjectClipInfo)> = project
+        .actions
+        .iter()
+        .flat_map(|action| action.clips.iter().map(move |clip| (action, clip)))
+        .filter(|(_, clip)| {
+            clip.streamer_id == streamer.id && matches!(clip.status, ClipStatus::Included)
+        })
+        .collect();
+    clips.sort_by(|a, b| (a.0.game_time + a.1.in_point).total_cmp(&(b.0.game_time + b.1.in_point)));
+
+    clips
+        .into_iter()
+        .enumerate()
+        .map(|(index, (action, clip))| project_clip_request(project, action, clip, streamer, index))
+        .collect()
+}
+
+/// Export request for a project clip as a plain MP4
+fn project_clip_request(
+    project: &ProjectFile,
+    action: &ActionInfo,
+    clip: &ProjectClipInfo,
+    streamer: &StreamerInfo,
+    index: usize,
+) -> ClipRequest {
+    ClipRequest {
+        vod_url: streamer.vod_url.clone(),
+        streamer_name: streamer.name.clone(),
+        action_id: action.id.clone(),
+        action_name: action.name.clone(),
+        game_start_time: project.game_start_time.unwrap_or(0.0),
+        action_game_time: action.game_time,
+        sync_offset: streamer.sync_offset.unwrap_or(0.0),
+        in_point: clip.in_point,
+        out_point: clip.out_point,
+        index,
+        max_size_mb: None,
+        smart_cut: false,
+        speed: None,
+        burn_timecode: false,
+        strip_audio: false,
+        container: Container::Mp4,
+        filters: None,
+    }
+}
 
-    match exporter.export(&montage_config, &output_path).await {
-        Ok(()) => Ok(MontageExportResult {
+/// Export the clips that aren't on disk yet, then list the ones that are, in order
+///
+/// Export progress goes out as "export-progress" events like `export_clips`.
+async fn export_for_montage(
+    app: &tauri::AppHandle,
+    project_name: &str,
+    requests: Vec<ClipRequest>,
+) -> Result<Vec<MontageConcatClip>, String> {
+    // Remember where each clip lands before the requests are consumed
+    let app_config = get_config();
+    let clip_paths: Vec<(PathBuf, String, String, f64, f64)> = requests
+        .iter()
+        .map(|r| {
+            (
+                app_config
+                    .streamer_clips_dir(project_name, &r.streamer_name)
+                    .join(generate_filename(&r.action_id, &r.action_name, r.container)),
+                r.streamer_name.clone(),
+                r.action_name.clone(),
+                r.out_point - r.in_point,
+                r.in_point,
+            )
+        })
+        .collect();
+
+    if !requests.is_empty() {
+        let app = app.clone();
+        let emit: ProgressEmitter = Arc::new(move |event| {
+            let _ = app.emit("export-progress", event);
+        });
+        run_export(project_name, requests, emit).await?;
+    }
+
+    let mut clips = Vec::new();
+    for (path, streamer_name, action_name, fallback_duration, in_point) in clip_paths {
+        if !path.exists() {
+            log::warn!("[Montage] Skipping missing clip: {}", path.display());
+            continue;
+        }
+        let duration = get_video_duration(&path).await.unwrap_or(fallback_duration);
+        clips.push(MontageConcatClip {
+            path,
+            duration,
+            streamer_name,
+            action_name,
+            overlay: None,
+            volume: None,
+            trim_start: None,
+            trim_end: None,
+            crop: None,
+            zoom: None,
+            slowmo: None,
+            freeze: None,
+            action_at: Some(-in_point),
+            silent: false,
+            generated: false,
+        });
+    }
+    Ok(clips)
+}
+
+/// Frontend result for a finished (or failed) montage render
+fn montage_result(
+    result: ExportResult<Option<&'static str>>,
+    output_path: &Path,
+    total_duration: f64,
+) -> MontageExportResult {
+    match result {
+        Ok(encoder) => MontageExportResult {
             success: true,
             output_path: output_path.to_string_lossy().to_string(),
             duration: total_duration,
             error: None,
-        }),
-        Err(e) => Ok(MontageExportResult {
+            encoder: encoder.map(str::to_string),
+        },
+        Err(e) => MontageExportResult {
             success: false,
             output_path: String::new(),
             duration: 0.0,
             error: Some(e.to_string()),
-        }),
+            encoder: None,
+        },
     }
 }
 
@@ -174,14 +976,21 @@ pub async fn list_project_clips(project_name: String) -> Result<Vec<ClipInfo>, S
     for entry in entries.flatten() {
         let path = entry.path();
 
+        // Skip proxies and job scratch folders
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+
         // Check if it's an MP4 directly in clips folder
-        if path.is_file() && path.extension().is_some_and(|ext| ext == "mp4") {
+        if path.is_file() && Container::from_path(&path).is_some() {
             if let Some(filename) = path.file_name() {
                 let duration = get_video_duration(&path).await.unwrap_or(0.0);
                 clips.push(ClipInfo {
                     filename: filename.to_string_lossy().to_string(),
                     duration,
                     path: path.to_string_lossy().to_string(),
+                    proxy_path: fresh_proxy(&clips_dir, &path)
+                        .map(|p| p.to_string_lossy().to_string()),
                 });
             }
         }
@@ -191,13 +1000,15 @@ pub async fn list_project_clips(project_name: String) -> Result<Vec<ClipInfo>, S
             if let Ok(sub_entries) = std::fs::read_dir(&path) {
                 for sub_entry in sub_entries.flatten() {
                     let sub_path = sub_entry.path();
-                    if sub_path.is_file() && sub_path.extension().is_some_and(|ext| ext == "mp4") {
+                    if sub_path.is_file() && Container::from_path(&sub_path).is_some() {
                         if let Some(filename) = sub_path.file_name() {
                             let duration = get_video_duration(&sub_path).await.unwrap_or(0.0);
                             clips.push(ClipInfo {
                                 filename: filename.to_string_lossy().to_string(),
                                 duration,
                                 path: sub_path.to_string_lossy().to_string(),
+                                proxy_path: fresh_proxy(&clips_dir, &sub_path)
+                                    .map(|p| p.to_string_lossy().to_string()),
                             });
                         }
                     }
@@ -218,6 +1029,9 @@ pub struct ClipInfo {
     pub filename: String,
     pub duration: f64,
     pub path: String,
+    /// Low-res proxy for scrubbing/previews, if one was generated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_path: Option<String>,
 }
 
 /// Get video duration using ffprobe
@@ -270,31 +1084,593 @@ pub async fn open_montages_folder(project_name: String) -> Result<(), String> {
     let montages_dir = app_config.output_dir.join(&project_name).join("montages");
 
     std::fs::create_dir_all(&montages_dir)
-        .map_err(|e| format!("Failed to create montages directory: {}", e))?;
+        .map_err(|e| format!("Failed to create montages directory: {}", NoxError::from(e)))?;
 
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("explorer")
-            .arg(&montages_dir)
-            .spawn()
-            .map_err(|e| format!("Failed to open folder: {}", e))?;
+    crate::workdir::open_folder(&montages_dir).map_err(|e| format!("Failed to open folder: {}", e))
+}
+
+/// Find the beats of a music file, as suggested cut points
+#[tauri::command]
+pub async fn analyze_music_beats(path: String) -> Result<BeatAnalysis, String> {
+    Ok(beats::analyze(Path::new(&path)).await?)
+}
+
+/// Let the user pick a background music file
+#[tauri::command]
+pub async fn pick_music_file(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    Ok(pick_file(
+        &app,
+        "Select Background Music",
+        "Audio",
+        &["mp3", "wav", "m4a", "aac", "flac", "ogg", "opus"],
+    ))
+}
+
+/// Let the user pick an intro/outro video
+#[tauri::command]
+pub async fn pick_video_file(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    Ok(pick_file(
+        &app,
+        "Select Video",
+        "Video",
+        &["mp4", "mov", "mkv", "webm"],
+    ))
+}
+
+/// Let the user pick a watermark image
+#[tauri::command]
+pub async fn pick_image_file(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    Ok(pick_file(
+        &app,
+        "Select Image",
+        "Image",
+        &["png", "jpg", "jpeg", "webp"],
+    ))
+}
+
+/// Blocking file dialog with one extension filter (None if cancelled)
+fn pick_file(
+    app: &tauri::AppHandle,
+    title: &str,
+    filter: &str,
+    extensions: &[&str],
+) -> Option<String> {
+    use std::sync::mpsc;
+    use tauri_plugin_dialog::DialogExt;
+
+    let (tx, rx) = mpsc::channel();
+
+    app.dialog()
+        .file()
+        .set_title(title)
+        .add_filter(filter, extensions)
+        .pick_file(move |result| {
+            let _ = tx.send(result);
+        });
+
+    rx.recv().ok().flatten().map(|path| path.to_string())
+}
+
+// ============ Overlay Preset Commands ============
+
+/// List saved overlay style presets
+#[tauri::command]
+pub async fn list_overlay_presets() -> Result<Vec<OverlayStylePreset>, String> {
+    presets::load_presets().map_err(|e| e.to_string())
+}
+
+/// Create or update an overlay style preset
+#[tauri::command]
+pub async fn save_overlay_preset(preset: OverlayStylePreset) -> Result<(), String> {
+    presets::save_preset(preset).map_err(|e| e.to_string())
+}
+
+/// Delete an overlay style preset
+#[tauri::command]
+pub async fn delete_overlay_preset(id: String) -> Result<(), String> {
+    presets::delete_preset(&id).map_err(|e| e.to_string())
+}
+
+// ============ Montage Config Import/Export ============
+
+/// Current version of the standalone montage config file format
+const MONTAGE_CONFIG_VERSION: u32 = 1;
+
+/// Standalone montage config file, used to share a montage style between projects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MontageConfigFile {
+    pub version: u32,
+    pub config: MontageExportInput,
+}
+
+/// Write a montage config (clips, transitions, overlay) to a JSON file
+#[tauri::command]
+pub async fn export_montage_config(
+    mut config: MontageExportInput,
+    path: String,
+) -> Result<(), String> {
+    store_relative_paths(&mut config);
+
+    let file = MontageConfigFile {
+        version: MONTAGE_CONFIG_VERSION,
+        config,
+    };
+
+    let content = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize montage config: {}", e))?;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write montage config: {}", e))?;
+
+    log::info!("[Montage] Exported config to {}", path);
+    Ok(())
+}
+
+/// A montage config read from a file
+#[derive(Debug, Serialize)]
+pub struct MontageConfigImport {
+    pub config: MontageExportInput,
+    /// Filenames of clips whose files don't exist on this machine; pass
+    /// `config` to `relink_montage_clips` to find them
+    pub missing: Vec<String>,
+}
+
+/// Read a montage config from a JSON file
+///
+/// Clips whose files don't exist on this machine are kept (with their
+/// sections and order) and listed in `missing`.
+#[tauri::command]
+pub async fn import_montage_config(path: String) -> Result<MontageConfigImport, String> {
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read montage config: {}", e))?;
+    let file: MontageConfigFile = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid montage config file: {}", e))?;
+
+    if file.version > MONTAGE_CONFIG_VERSION {
+        return Err(format!(
+            "Montage config version {} is newer than supported ({})",
+            file.version, MONTAGE_CONFIG_VERSION
+        ));
     }
 
-    #[cfg(target_os = "macos")]
-    {
-        std::process::Command::new("open")
-            .arg(&montages_dir)
-            .spawn()
-            .map_err(|e| format!("Failed to open folder: {}", e))?;
+    let mut config = file.config;
+    resolve_clip_paths(&mut config);
+    let missing: Vec<String> = config
+        .clips
+        .iter()
+        .filter(|clip| !Path::new(&clip.path).exists())
+        .map(|clip| clip.filename.clone())
+        .collect();
+
+    if !missing.is_empty() {
+        log::info!(
+            "[Montage] Imported config from {}: {} clip(s) need relinking",
+            path,
+            missing.len()
+        );
+    }
+
+    Ok(MontageConfigImport { config, missing })
+}
+
+/// Remember each clip's path relative to the work dir, for
+/// [`MontageClipInput::resolved_path`] after the work dir moved
+fn store_relative_paths(config: &mut MontageExportInput) {
+    let work_dir = get_config().output_dir;
+    for clip in &mut config.clips {
+        clip.relative_path = relative_to(&work_dir, Path::new(&clip.path));
+    }
+}
+
+// ============ Saved Montages ============
+
+/// A saved montage of a project, as listed
+#[derive(Debug, Clone, Serialize)]
+pub struct MontageSummary {
+    pub id: String,
+    pub name: String,
+    pub updated_at: DateTime<Utc>,
+    pub clip_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_render: Option<MontageRender>,
+    /// The montage was saved with other settings, or a clip file changed,
+    /// since its last render
+    pub stale: bool,
+}
+
+impl MontageSummary {
+    fn new(montage: &MontagePreset) -> Self {
+        Self {
+            id: montage.id.clone(),
+            name: montage.name.clone(),
+            updated_at: montage.updated_at,
+            clip_count: montage.config.clips.len(),
+            last_render: montage.last_render.clone(),
+            stale: is_stale(montage, |clip| {
+                let modified = std::fs::metadata(clip.resolved_path())
+                    .and_then(|m| m.modified())
+                    .ok()?;
+                Some(DateTime::<Utc>::from(modified))
+            }),
+        }
+    }
+}
+
+/// SHA-256 of a saved montage config, to tell whether a render is of it
+fn settings_hash(config: &MontageExportInput) -> String {
+    let json = serde_json::to_vec(config).unwrap_or_default();
+    format!("{:x}", Sha256::digest(json))
+}
+
+/// Whether a saved montage's last render is out of date, given when each
+/// clip file was last modified (None if unknown)
+fn is_stale(
+    montage: &MontagePreset,
+    modified: impl Fn(&MontageClipInput) -> Option<DateTime<Utc>>,
+) -> bool {
+    let Some(render) = &montage.last_render else {
+        return false;
+    };
+    render.settings_hash != settings_hash(&montage.config)
+        || montage
+            .config
+            .clips
+            .iter()
+            .any(|clip| modified(clip).is_some_and(|t| t > render.rendered_at))
+}
+
+/// A saved montage by ID
+fn saved_montage(project_name: &str, id: &str) -> Result<MontagePreset, String> {
+    project::load_project(project_name)
+        .map_err(|e| e.to_string())?
+        .and_then(|p| p.montages.into_iter().find(|m| m.id == id))
+        .ok_or_else(|| format!("Saved montage not found: {}", id))
+}
+
+/// List the montages saved in a project, with their last render
+#[tauri::command]
+pub async fn list_project_montages(project_name: String) -> Result<Vec<MontageSummary>, String> {
+    let project = project::load_project(&project_name).map_err(|e| e.to_string())?;
+    Ok(project
+        .map(|p| p.montages.iter().map(MontageSummary::new).collect())
+        .unwrap_or_default())
+}
+
+/// Save a montage setup in its project, replacing the one with the same ID
+#[tauri::command]
+pub async fn save_montage(
+    project_name: String,
+    id: String,
+    name: String,
+    mut config: MontageExportInput,
+) -> Result<(), String> {
+    store_relative_paths(&mut config);
+    let montage = MontagePreset {
+        id,
+        name,
+        updated_at: Utc::now(),
+        config,
+        last_render: None,
+    };
+    project::save_montage(&project_name, montage).map_err(|e| e.to_string())
+}
+
+/// Reopen a saved montage
+///
+/// Clips are pointed at where their files are now; missing ones are kept so
+/// they can be relinked.
+#[tauri::command]
+pub async fn load_montage(project_name: String, id: String) -> Result<MontageExportInput, String> {
+    let mut config = saved_montage(&project_name, &id)?.config;
+    resolve_clip_paths(&mut config);
+    Ok(config)
+}
+
+/// Render a saved montage and remember the render
+///
+/// The file is named after the project and the montage (unless the montage
+/// has its own output name), so each render replaces the previous one.
+#[tauri::command]
+pub async fn render_saved_montage(
+    app: tauri::AppHandle,
+    project_name: String,
+    id: String,
+) -> Result<MontageExportResult, String> {
+    let montage = saved_montage(&project_name, &id)?;
+    let mut config = montage.config.clone();
+    if config.output_filename.is_none() {
+        config.output_filename = Some(format!(
+            "{}_{}",
+            sanitize_filename(&project_name),
+            sanitize_filename(&montage.name)
+        ));
     }
 
-    #[cfg(target_os = "linux")]
+    let result = export_montage(app, project_name.clone(), config).await?;
+    if result.success {
+        let render = MontageRender {
+            output_path: result.output_path.clone(),
+            rendered_at: Utc::now(),
+            settings_hash: settings_hash(&montage.config),
+        };
+        project::record_montage_render(&project_name, &id, render).map_err(|e| e.to_string())?;
+    }
+    Ok(result)
+}
+
+/// Delete a saved montage (no-op if it doesn't exist)
+#[tauri::command]
+pub async fn delete_montage(project_name: String, id: String) -> Result<(), String> {
+    project::delete_montage(&project_name, &id).map_err(|e| e.to_string())
+}
+
+/// Result of relinking a montage config's clips
+#[derive(Debug, Serialize)]
+pub struct RelinkResult {
+    pub config: MontageExportInput,
+    /// Clips whose path was changed
+    pub relinked: usize,
+    /// Filenames of clips that couldn't be found
+    pub missing: Vec<String>,
+}
+
+/// Find the files of a montage's clips again after they moved or were renamed
+///
+/// A missing clip is looked up by its work-dir-relative path, then by file
+/// name anywhere in the project's clips, then by action ID in its streamer's
+/// folder (the action was renamed). Unfound clips are kept and listed.
+#[tauri::command]
+pub async fn relink_montage_clips(
+    project_name: String,
+    mut config: MontageExportInput,
+) -> Result<RelinkResult, String> {
+    let app_config = get_config();
+    let candidates = clip_files(&app_config.clips_dir(&project_name));
+
+    let mut relinked = 0;
+    let mut missing = Vec::new();
+    for clip in &mut config.clips {
+        let resolved = clip.resolved_path();
+        let found = if resolved.exists() {
+            Some(resolved)
+        } else {
+            let streamer_dir = app_config.streamer_clips_dir(&project_name, &clip.streamer_name);
+            relink_candidate(&clip.filename, &streamer_dir, &candidates).cloned()
+        };
+
+        match found {
+            Some(path) => {
+                let path = path.to_string_lossy().to_string();
+                if path != clip.path {
+                    log::info!("[Montage] Relinked {} -> {}", clip.path, path);
+                    clip.relative_path = relative_to(&app_config.output_dir, Path::new(&path));
+                    clip.path = path;
+                    relinked += 1;
+                }
+            }
+            None => missing.push(clip.filename.clone()),
+        }
+    }
+
+    Ok(RelinkResult {
+        config,
+        relinked,
+        missing,
+    })
+}
+
+/// Tauri command to probe a montage's clips before exporting it
+///
+/// Lists, per clip, its codecs and frame size and what would make the export
+/// fail: a missing or unreadable file, no video track, or a frame size that
+/// differs from the first clip's without an output resolution. Clips without
+/// audio are listed too; they're exported with silence.
+#[tauri::command]
+pub async fn validate_montage_inputs(
+    project_name: String,
+    mut config: MontageExportInput,
+) -> Result<InputReport, String> {
+    resolve_clip_paths(&mut config);
+    let project = project::load_project(&project_name).ok().flatten();
+    apply_order(project.as_ref(), &mut config);
+    let report = check_inputs(&montage_config(config)).await;
+    if let Some(problem) = report.first_problem() {
+        log::info!("[Montage] Input check: {}", problem);
+    }
+    Ok(report)
+}
+
+/// A project clip file that is most likely the moved/renamed `filename`
+///
+/// Same file name first; otherwise a file in the streamer's folder with the
+/// same `<action id>_` prefix (see `generate_filename`).
+fn relink_candidate<'a>(
+    filename: &str,
+    streamer_dir: &Path,
+    candidates: &'a [PathBuf],
+) -> Option<&'a PathBuf> {
+    let name_of = |p: &PathBuf| p.file_name().map(|n| n.to_string_lossy().to_string());
+    if let Some(same_name) = candidates
+        .iter()
+        .find(|p| name_of(p).as_deref() == Some(filename))
     {
-        std::process::Command::new("xdg-open")
-            .arg(&montages_dir)
-            .spawn()
-            .map_err(|e| format!("Failed to open folder: {}", e))?;
+        return Some(same_name);
     }
 
-    Ok(())
+    let (id_prefix, _) = filename.split_once('_')?;
+    candidates.iter().find(|p| {
+        p.parent() == Some(streamer_dir)
+            && name_of(p).is_some_and(|name| name.starts_with(&format!("{}_", id_prefix)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relink_candidate() {
+        let candidates = vec![
+            PathBuf::from("/work/p/clips/A/abc123_Clutch.mp4"),
+            PathBuf::from("/work/p/clips/B/abc123_Clutch.mp4"),
+            PathBuf::from("/work/p/clips/B/def456_Ace.mp4"),
+        ];
+        let dir_a = Path::new("/work/p/clips/A");
+
+        // Moved: same name anywhere in the project
+        assert_eq!(
+            relink_candidate("def456_Ace.mp4", dir_a, &candidates),
+            Some(&candidates[2])
+        );
+        // Action renamed: same ID prefix in the streamer's folder
+        assert_eq!(
+            relink_candidate("abc123_1v3.mp4", dir_a, &candidates),
+            Some(&candidates[0])
+        );
+        assert_eq!(relink_candidate("zzz999_Ace.mp4", dir_a, &candidates), None);
+    }
+
+    #[test]
+    fn test_saved_montages_in_project() {
+        let mut project: ProjectFile = serde_json::from_value(serde_json::json!({
+            "version": 1, "id": "p", "name": "p",
+            "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z",
+            "reference_streamer_id": "a", "streamers": [], "actions": [],
+        }))
+        .unwrap();
+        // Older projects have no saved montages; an empty list isn't written out
+        assert!(project.montages.is_empty());
+        assert!(!serde_json::to_string(&project)
+            .unwrap()
+            .contains("montages"));
+
+        project.montages.push(MontagePreset {
+            id: "m1".into(),
+            name: "Best of".into(),
+            updated_at: Utc::now(),
+            config: serde_json::from_str(
+                r#"{"clips": [{"filename": "a.mp4", "path": "/w/a.mp4", "duration": 5.0,
+                    "streamer_name": "A"}], "transition_duration": 0.5, "overlay": null,
+                    "output_filename": null, "order": {"mode": "chronological"}}"#,
+            )
+            .unwrap(),
+            last_render: None,
+        });
+        let json = serde_json::to_string(&project).unwrap();
+        let reloaded: ProjectFile = serde_json::from_str(&json).unwrap();
+        let summary = MontageSummary::new(&reloaded.montages[0]);
+        assert_eq!((summary.name.as_str(), summary.clip_count), ("Best of", 1));
+        assert_eq!(
+            reloaded.montages[0].config.order,
+            MontageOrder::Chronological
+        );
+    }
+
+    #[test]
+    fn test_saved_montage_stale() {
+        let rendered_at = Utc::now();
+        let mut montage = MontagePreset {
+            id: "m1".into(),
+            name: "Best of".into(),
+            updated_at: rendered_at,
+            config: serde_json::from_str(
+                r#"{"clips": [{"filename": "a.mp4", "path": "/w/a.mp4", "duration": 5.0,
+                    "streamer_name": "A"}], "transition_duration": 0.5, "overlay": null,
+                    "output_filename": null}"#,
+            )
+            .unwrap(),
+            last_render: None,
+        };
+        let before = |_: &MontageClipInput| Some(rendered_at - chrono::Duration::hours(1));
+        // Never rendered: nothing to be out of date
+        assert!(!is_stale(&montage, before));
+
+        montage.last_render = Some(MontageRender {
+            output_path: "/w/montages/p_Best of.mp4".into(),
+            rendered_at,
+            settings_hash: settings_hash(&montage.config),
+        });
+        assert!(!is_stale(&montage, before));
+        assert!(!is_stale(&montage, |_| None));
+        // A clip file written after the render
+        assert!(is_stale(&montage, |_| {
+            Some(rendered_at + chrono::Duration::minutes(5))
+        }));
+        // Saved again with other settings
+        montage.config.transition_duration = 1.0;
+        assert!(is_stale(&montage, before));
+    }
+
+    #[test]
+    fn test_reorder_keeps_sections() {
+        let clip = |name: &str| MontageClipInput {
+            filename: name.into(),
+            path: name.into(),
+            duration: 5.0,
+            streamer_name: "A".into(),
+            ..Default::default()
+        };
+        let mut config: MontageExportInput = serde_json::from_str(
+            r#"{"clips": [], "transition_duration": 0.5, "overlay": null,
+                "output_filename": null, "sections": [{"before": 2, "text": "B", "duration": 2.0}],
+                "order": {"mode": "shuffle", "seed": 1}}"#,
+        )
+        .unwrap();
+        config.clips = vec![clip("a"), clip("b"), clip("c")];
+
+        reorder_clips(&mut config, &[2, 0, 1]);
+        let names: Vec<_> = config.clips.iter().map(|c| c.filename.as_str()).collect();
+        assert_eq!(names, ["c", "a", "b"]);
+        // Still right before "c"
+        assert_eq!(config.sections[0].before, 0);
+
+        // No project: every clip is unknown, chronological keeps them as they are
+        config.order = MontageOrder::Chronological;
+        apply_order(None, &mut config);
+        assert_eq!(config.clips[0].filename, "c");
+    }
+
+    #[test]
+    fn test_order_key_by_action_id() {
+        let project: ProjectFile = serde_json::from_value(serde_json::json!({
+            "version": 1, "id": "p", "name": "p",
+            "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z",
+            "reference_streamer_id": "a",
+            "streamers": [{"id": "a", "name": "Alpha", "vod_url": "", "platform": "twitch",
+                "is_reference": true, "color": "#ffffff"}],
+            "actions": [{"id": "abc123def", "name": "Ace", "game_time": 60.0, "clips": [
+                {"id": "c1", "action_id": "abc123def", "streamer_id": "a",
+                 "in_point": -5.0, "out_point": 5.0, "status": "included"}]}],
+        }))
+        .unwrap();
+        // A renamed file is still linked to its action by the id
+        let clip = MontageClipInput {
+            filename: "best_ace.mp4".into(),
+            path: "/w/best_ace.mp4".into(),
+            duration: 10.0,
+            streamer_name: "Alpha".into(),
+            action_id: Some("abc123def".into()),
+            ..Default::default()
+        };
+        let key = order_key(Some(&project), &clip);
+        assert_eq!((key.streamer, key.game_time), (Some(0), Some(55.0)));
+
+        // Without an id the file name isn't used to guess the action
+        let unlinked = MontageClipInput {
+            filename: "abc123def_Ace.mp4".into(),
+            action_id: None,
+            ..clip
+        };
+        assert_eq!(order_key(Some(&project), &unlinked).game_time, None);
+    }
+
+    #[test]
+    fn test_relative_to() {
+        assert_eq!(
+            relative_to(Path::new("/work"), Path::new("/work/p/clips/A/x.mp4")).as_deref(),
+            Some("p/clips/A/x.mp4")
+        );
+        assert_eq!(
+            relative_to(Path::new("/work"), Path::new("/elsewhere/x.mp4")),
+            None
+        );
+    }
 }
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::binaries::{get_bin_dir, get_config_dir, get_log_dir};
+use crate::config::get_config;
+use crate::error::NoxError;
+use crate::scratch;
+use crate::workdir::open_folder;
+
+/// A location where Nox stores data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppPathKind {
+    /// Config, presets, inbox and live markers
+    Config,
+    /// Downloaded ffmpeg / yt-dlp
+    Bin,
+    /// Projects, clips and montages
+    Work,
+    /// Per-job scratch directories
+    Temp,
+    /// Log files
+    Log,
+    /// Downloaded VOD ranges kept for re-exports
+    SegmentCache,
+}
+
+impl AppPathKind {
+    const ALL: [AppPathKind; 6] = [
+        AppPathKind::Config,
+        AppPathKind::Bin,
+        AppPathKind::Work,
+        AppPathKind::Temp,
+        AppPathKind::Log,
+        AppPathKind::SegmentCache,
+    ];
+
+    /// Directory for this kind (None = not stored on disk)
+    fn dir(self) -> Option<PathBuf> {
+        match self {
+            AppPathKind::Config => Some(get_config_dir()),
+            AppPathKind::Bin => Some(get_bin_dir()),
+            AppPathKind::Work => Some(get_config().output_dir),
+            AppPathKind::Temp => Some(scratch::temp_root()),
+            AppPathKind::Log => Some(get_log_dir()),
+            AppPathKind::SegmentCache => Some(get_config().segment_cache_dir()),
+        }
+    }
+}
+
+/// Where a location is and how much it holds
+#[derive(Debug, Serialize)]
+pub struct AppPathInfo {
+    pub kind: AppPathKind,
+    pub path: Option<String>,
+    pub exists: bool,
+    /// Total size of the files inside (None = missing or not on disk)
+    pub size_bytes: Option<u64>,
+}
+
+/// List the directories Nox stores data in, with their sizes
+#[tauri::command]
+pub async fn get_app_paths() -> Result<Vec<AppPathInfo>, String> {
+    tokio::task::spawn_blocking(|| {
+        AppPathKind::ALL
+            .iter()
+            .map(|&kind| path_info(kind))
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Open one of the app directories in the file manager
+#[tauri::command]
+pub async fn open_app_path(kind: AppPathKind) -> Result<(), String> {
+    let dir = kind
+        .dir()
+        .ok_or_else(|| format!("{:?} is not stored on disk", kind))?;
+    std::fs::create_dir_all(&dir).map_err(|e| NoxError::from(e).to_string())?;
+    open_folder(&dir).map_err(|e| NoxError::from(e).to_string())
+}
+
+fn path_info(kind: AppPathKind) -> AppPathInfo {
+    let dir = kind.dir();
+    let exists = dir.as_deref().is_some_and(Path::is_dir);
+    AppPathInfo {
+        kind,
+        path: dir.as_ref().map(|d| d.to_string_lossy().to_string()),
+        exists,
+        size_bytes: dir.filter(|_| exists).map(|d| dir_size(&d)),
+    }
+}
+
+/// Total size of the files under `dir` (unreadable entries count as 0)
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dir_size() {
+        let dir = std::env::temp_dir().join("nox-dir-size-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("a.bin"), [0u8; 10]).unwrap();
+        std::fs::write(dir.join("nested").join("b.bin"), [0u8; 5]).unwrap();
+
+        assert_eq!(dir_size(&dir), 15);
+        assert_eq!(dir_size(&dir.join("missing")), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
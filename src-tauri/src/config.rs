@@ -10,6 +10,33 @@ use crate::binaries::{ensure_config_dir, get_binary_manager, get_config_path};
 pub struct PersistedConfig {
     /// Custom output directory (None = use default Documents/Nox)
     pub output_dir: Option<PathBuf>,
+    /// Maximum clip size in MB (None = no limit, enables two-pass encoding when set)
+    #[serde(default)]
+    pub max_clip_size_mb: Option<f64>,
+    /// Loudness normalization target in LUFS (None = off)
+    #[serde(default)]
+    pub loudness_target_lufs: Option<f64>,
+    /// Reconnect dropped HLS/HTTP inputs (None = on)
+    #[serde(default)]
+    pub hls_reconnect: Option<bool>,
+    /// Montage timeout per second of output (None = default, 0 = no timeout)
+    #[serde(default)]
+    pub montage_timeout_factor: Option<f64>,
+    /// Encoder picked by the benchmark (None = auto-detect on startup)
+    #[serde(default)]
+    pub encoder: Option<VideoEncoder>,
+    /// Keep downloaded VOD ranges in a local cache for re-exports
+    #[serde(default)]
+    pub segment_cache_enabled: bool,
+    /// Whether the local scripting API is enabled
+    #[serde(default)]
+    pub api_enabled: bool,
+    /// Bearer token required by the local scripting API
+    #[serde(default)]
+    pub api_token: Option<String>,
+    /// Global shortcut recording a live marker (None = off)
+    #[serde(default)]
+    pub marker_hotkey: Option<String>,
 }
 
 impl PersistedConfig {
@@ -61,9 +88,23 @@ pub struct FfmpegConfig {
     pub preset: String,
     pub crf: u8,
     pub audio_bitrate: String,
+    /// Maximum clip size in MB (None = quality-based encoding)
+    pub max_size_mb: Option<f64>,
+    /// EBU R128 loudness target in LUFS (None = keep source levels)
+    pub loudness_lufs: Option<f64>,
+    /// Pass -reconnect / -http_persistent for network inputs
+    pub hls_reconnect: bool,
+    /// Seconds a montage may take per second of output (0 = no timeout,
+    /// exports can still be cancelled)
+    pub montage_timeout_factor: f64,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Default montage timeout per second of output: slow software encodes of
+/// heavy filter graphs run around realtime, so this leaves a wide margin
+pub const DEFAULT_MONTAGE_TIMEOUT_FACTOR: f64 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum VideoEncoder {
     /// Software encoding (works everywhere)
     Libx264,
@@ -76,6 +117,9 @@ pub enum VideoEncoder {
 }
 
 impl VideoEncoder {
+    /// Every supported encoder, hardware first
+    pub const ALL: [Self; 4] = [Self::H264Nvenc, Self::H264Amf, Self::H264Qsv, Self::Libx264];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Libx264 => "libx264",
@@ -85,6 +129,24 @@ impl VideoEncoder {
         }
     }
 
+    /// Matching `-hwaccel` decoder for hardware encoders (None = software decode)
+    ///
+    /// Decoded frames are copied back to system memory, so the CPU filters
+    /// (timecode, speed, scaling) keep working. FFmpeg fails when the device
+    /// can't be opened; clip exports then retry with libx264 and no `-hwaccel`
+    /// (see [`FfmpegConfig::software_fallback`]).
+    pub fn hwaccel(&self) -> Option<&'static str> {
+        match self {
+            Self::Libx264 => None,
+            Self::H264Nvenc => Some("cuda"),
+            Self::H264Qsv => Some("qsv"),
+            #[cfg(target_os = "windows")]
+            Self::H264Amf => Some("d3d11va"),
+            #[cfg(not(target_os = "windows"))]
+            Self::H264Amf => Some("auto"),
+        }
+    }
+
     /// Detect the best available encoder on this system
     pub fn detect_best() -> Self {
         // Try to find ffmpeg first
@@ -97,10 +159,8 @@ impl VideoEncoder {
         };
 
         // Try hardware encoders first (faster)
-        let hw_encoders = [Self::H264Nvenc, Self::H264Amf, Self::H264Qsv];
-
-        for encoder in hw_encoders {
-            if encoder.is_available_with(&ffmpeg_cmd) {
+        for encoder in Self::ALL {
+            if encoder != Self::Libx264 && encoder.is_available_with(&ffmpeg_cmd) {
                 log::info!("Detected hardware encoder: {}", encoder.as_str());
                 return encoder;
             }
@@ -110,6 +170,17 @@ impl VideoEncoder {
         Self::Libx264
     }
 
+    /// Check if this encoder is compiled into the bundled/system ffmpeg
+    ///
+    /// This doesn't prove the encoder works (drivers, GPU); the benchmark does.
+    pub fn is_available(&self) -> bool {
+        let ffmpeg_cmd = get_binary_manager()
+            .ffmpeg_path()
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|| "ffmpeg".to_string());
+        self.is_available_with(&ffmpeg_cmd)
+    }
+
     /// Check if this encoder is available
     fn is_available_with(&self, ffmpeg_cmd: &str) -> bool {
         #[cfg(target_os = "windows")]
@@ -137,13 +208,33 @@ impl VideoEncoder {
 
 impl Default for FfmpegConfig {
     fn default() -> Self {
+        Self::with_encoder(VideoEncoder::detect_best())
+    }
+}
+
+impl FfmpegConfig {
+    /// Default settings for a given encoder
+    pub fn with_encoder(encoder: VideoEncoder) -> Self {
         Self {
-            encoder: VideoEncoder::detect_best(),
+            encoder,
             preset: "fast".to_string(),
             crf: 23,
             audio_bitrate: "128k".to_string(),
+            max_size_mb: None,
+            loudness_lufs: None,
+            hls_reconnect: true,
+            montage_timeout_factor: DEFAULT_MONTAGE_TIMEOUT_FACTOR,
         }
     }
+
+    /// The same settings with libx264, for retrying a failed hardware encode
+    /// (None when already encoding in software)
+    pub fn software_fallback(&self) -> Option<Self> {
+        (self.encoder != VideoEncoder::Libx264).then(|| Self {
+            encoder: VideoEncoder::Libx264,
+            ..self.clone()
+        })
+    }
 }
 
 /// Get the default output directory
@@ -161,8 +252,27 @@ impl Default for Config {
             .clone()
             .unwrap_or_else(default_output_dir);
 
+        // A benchmarked encoder wins as long as ffmpeg still has it
+        let encoder = match persisted.encoder {
+            Some(encoder) if encoder.is_available() => {
+                log::info!("Using benchmarked encoder: {}", encoder.as_str());
+                encoder
+            }
+            _ => VideoEncoder::detect_best(),
+        };
+
+        let ffmpeg = FfmpegConfig {
+            max_size_mb: persisted.max_clip_size_mb,
+            loudness_lufs: persisted.loudness_target_lufs,
+            hls_reconnect: persisted.hls_reconnect.unwrap_or(true),
+            montage_timeout_factor: persisted
+                .montage_timeout_factor
+                .unwrap_or(DEFAULT_MONTAGE_TIMEOUT_FACTOR),
+            ..FfmpegConfig::with_encoder(encoder)
+        };
+
         Self {
-            ffmpeg: FfmpegConfig::default(),
+            ffmpeg,
             output_dir,
             persisted,
         }
@@ -180,6 +290,11 @@ impl Config {
         self.project_dir(project_name).join("clips")
     }
 
+    /// Get the low-res proxy directory for a project's clips
+    pub fn proxies_dir(&self, project_name: &str) -> PathBuf {
+        self.clips_dir(project_name).join(".proxies")
+    }
+
     /// Get the clips directory for a specific streamer
     pub fn streamer_clips_dir(&self, project_name: &str, streamer_name: &str) -> PathBuf {
         self.clips_dir(project_name)
@@ -208,12 +323,113 @@ impl Config {
         Ok(dir)
     }
 
+    /// Directory of the source segment cache
+    pub fn segment_cache_dir(&self) -> PathBuf {
+        self.output_dir.join(".cache").join("segments")
+    }
+
+    /// Directory of cached thumbnail sprite sheets
+    pub fn thumbnail_cache_dir(&self) -> PathBuf {
+        self.output_dir.join(".cache").join("thumbnails")
+    }
+
     /// Set a custom output directory
     pub fn set_output_dir(&mut self, path: PathBuf) -> std::io::Result<()> {
         self.output_dir = path.clone();
         self.persisted.output_dir = Some(path);
         self.persisted.save()
     }
+
+    /// Set the maximum clip size (None = no limit)
+    pub fn set_max_clip_size(&mut self, max_size_mb: Option<f64>) -> std::io::Result<()> {
+        self.ffmpeg.max_size_mb = max_size_mb;
+        self.persisted.max_clip_size_mb = max_size_mb;
+        self.persisted.save()
+    }
+
+    /// Set the loudness normalization target (None = off)
+    pub fn set_loudness_target(&mut self, lufs: Option<f64>) -> std::io::Result<()> {
+        self.ffmpeg.loudness_lufs = lufs;
+        self.persisted.loudness_target_lufs = lufs;
+        self.persisted.save()
+    }
+
+    /// Turn reconnecting network inputs on or off
+    pub fn set_hls_reconnect(&mut self, enabled: bool) -> std::io::Result<()> {
+        self.ffmpeg.hls_reconnect = enabled;
+        self.persisted.hls_reconnect = Some(enabled);
+        self.persisted.save()
+    }
+
+    /// Set the montage timeout per second of output (0 = no timeout)
+    pub fn set_montage_timeout_factor(&mut self, factor: f64) -> std::io::Result<()> {
+        self.ffmpeg.montage_timeout_factor = factor;
+        self.persisted.montage_timeout_factor = Some(factor);
+        self.persisted.save()
+    }
+
+    /// Store the encoder picked by the benchmark
+    pub fn set_encoder(&mut self, encoder: VideoEncoder) -> std::io::Result<()> {
+        self.ffmpeg.encoder = encoder;
+        self.persisted.encoder = Some(encoder);
+        self.persisted.save()
+    }
+
+    /// Whether downloaded VOD ranges are cached for re-exports
+    pub fn segment_cache_enabled(&self) -> bool {
+        self.persisted.segment_cache_enabled
+    }
+
+    /// Turn the source segment cache on or off
+    pub fn set_segment_cache_enabled(&mut self, enabled: bool) -> std::io::Result<()> {
+        self.persisted.segment_cache_enabled = enabled;
+        self.persisted.save()
+    }
+
+    /// Whether the local scripting API is enabled
+    pub fn api_enabled(&self) -> bool {
+        self.persisted.api_enabled
+    }
+
+    /// Token required by the local scripting API
+    pub fn api_token(&self) -> Option<&str> {
+        self.persisted.api_token.as_deref()
+    }
+
+    /// Enable or disable the local scripting API (creates a token on first enable)
+    pub fn set_api_enabled(&mut self, enabled: bool) -> std::io::Result<()> {
+        self.persisted.api_enabled = enabled;
+        if enabled && self.persisted.api_token.is_none() {
+            self.persisted.api_token = Some(generate_token());
+        }
+        self.persisted.save()
+    }
+
+    /// Replace the scripting API token, invalidating the old one
+    pub fn regenerate_api_token(&mut self) -> std::io::Result<String> {
+        let token = generate_token();
+        self.persisted.api_token = Some(token.clone());
+        self.persisted.save()?;
+        Ok(token)
+    }
+
+    /// Global shortcut recording a live marker
+    pub fn marker_hotkey(&self) -> Option<&str> {
+        self.persisted.marker_hotkey.as_deref()
+    }
+
+    /// Set the global marker shortcut (None = off)
+    pub fn set_marker_hotkey(&mut self, hotkey: Option<String>) -> std::io::Result<()> {
+        self.persisted.marker_hotkey = hotkey;
+        self.persisted.save()
+    }
+}
+
+/// Generate a random 128-bit hex token from the OS RNG
+fn generate_token() -> String {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).expect("OS random number generator unavailable");
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
 }
 
 /// Sanitize a name for use in file paths
@@ -259,6 +475,114 @@ impl ConfigGuard {
             ))
         }
     }
+
+    pub fn set_max_clip_size(&self, max_size_mb: Option<f64>) -> std::io::Result<()> {
+        let mut guard = CONFIG.write().unwrap();
+        if let Some(ref mut config) = *guard {
+            config.set_max_clip_size(max_size_mb)
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Config not initialized",
+            ))
+        }
+    }
+
+    pub fn set_loudness_target(&self, lufs: Option<f64>) -> std::io::Result<()> {
+        let mut guard = CONFIG.write().unwrap();
+        if let Some(ref mut config) = *guard {
+            config.set_loudness_target(lufs)
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Config not initialized",
+            ))
+        }
+    }
+
+    pub fn set_hls_reconnect(&self, enabled: bool) -> std::io::Result<()> {
+        let mut guard = CONFIG.write().unwrap();
+        if let Some(ref mut config) = *guard {
+            config.set_hls_reconnect(enabled)
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Config not initialized",
+            ))
+        }
+    }
+
+    pub fn set_montage_timeout_factor(&self, factor: f64) -> std::io::Result<()> {
+        let mut guard = CONFIG.write().unwrap();
+        if let Some(ref mut config) = *guard {
+            config.set_montage_timeout_factor(factor)
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Config not initialized",
+            ))
+        }
+    }
+
+    pub fn set_encoder(&self, encoder: VideoEncoder) -> std::io::Result<()> {
+        let mut guard = CONFIG.write().unwrap();
+        if let Some(ref mut config) = *guard {
+            config.set_encoder(encoder)
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Config not initialized",
+            ))
+        }
+    }
+
+    pub fn set_segment_cache_enabled(&self, enabled: bool) -> std::io::Result<()> {
+        let mut guard = CONFIG.write().unwrap();
+        if let Some(ref mut config) = *guard {
+            config.set_segment_cache_enabled(enabled)
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Config not initialized",
+            ))
+        }
+    }
+
+    pub fn set_api_enabled(&self, enabled: bool) -> std::io::Result<()> {
+        let mut guard = CONFIG.write().unwrap();
+        if let Some(ref mut config) = *guard {
+            config.set_api_enabled(enabled)
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Config not initialized",
+            ))
+        }
+    }
+
+    pub fn regenerate_api_token(&self) -> std::io::Result<String> {
+        let mut guard = CONFIG.write().unwrap();
+        if let Some(ref mut config) = *guard {
+            config.regenerate_api_token()
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Config not initialized",
+            ))
+        }
+    }
+
+    pub fn set_marker_hotkey(&self, hotkey: Option<String>) -> std::io::Result<()> {
+        let mut guard = CONFIG.write().unwrap();
+        if let Some(ref mut config) = *guard {
+            config.set_marker_hotkey(hotkey)
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Config not initialized",
+            ))
+        }
+    }
 }
 
 pub fn init_config() {
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use thiserror::Error;
 
 /// Main error type for Nox
@@ -13,12 +14,92 @@ pub enum NoxError {
     Binary(#[from] BinaryError),
 
     #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
+    Io(std::io::Error),
+
+    #[error("Permission denied ({0}). {hint}", hint = IoFailure::PermissionDenied.hint())]
+    PermissionDenied(std::io::Error),
+
+    #[error("File is in use ({0}). {hint}", hint = IoFailure::FileLocked.hint())]
+    FileLocked(std::io::Error),
+
+    #[error("Location is read-only ({0}). {hint}", hint = IoFailure::ReadOnly.hint())]
+    ReadOnly(std::io::Error),
+
+    #[error("Not enough disk space ({0}). {hint}", hint = IoFailure::DiskFull.hint())]
+    DiskFull(std::io::Error),
 
     #[error("Config error: {0}")]
     Config(String),
 }
 
+impl From<std::io::Error> for NoxError {
+    fn from(err: std::io::Error) -> Self {
+        match IoFailure::classify(&err) {
+            Some(IoFailure::PermissionDenied) => Self::PermissionDenied(err),
+            Some(IoFailure::FileLocked) => Self::FileLocked(err),
+            Some(IoFailure::ReadOnly) => Self::ReadOnly(err),
+            Some(IoFailure::DiskFull) => Self::DiskFull(err),
+            None => Self::Io(err),
+        }
+    }
+}
+
+/// Common OS-level IO failures that have an actionable fix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoFailure {
+    PermissionDenied,
+    FileLocked,
+    ReadOnly,
+    DiskFull,
+}
+
+impl IoFailure {
+    /// Classify an IO error by kind and, on Windows, by raw error code
+    pub fn classify(err: &std::io::Error) -> Option<Self> {
+        use std::io::ErrorKind;
+
+        #[cfg(target_os = "windows")]
+        match err.raw_os_error() {
+            // ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION
+            Some(32) | Some(33) => return Some(Self::FileLocked),
+            // ERROR_WRITE_PROTECT
+            Some(19) => return Some(Self::ReadOnly),
+            // ERROR_HANDLE_DISK_FULL, ERROR_DISK_FULL
+            Some(39) | Some(112) => return Some(Self::DiskFull),
+            _ => {}
+        }
+
+        match err.kind() {
+            ErrorKind::PermissionDenied => Some(Self::PermissionDenied),
+            ErrorKind::ResourceBusy | ErrorKind::ExecutableFileBusy => Some(Self::FileLocked),
+            ErrorKind::ReadOnlyFilesystem => Some(Self::ReadOnly),
+            ErrorKind::StorageFull | ErrorKind::QuotaExceeded => Some(Self::DiskFull),
+            _ => None,
+        }
+    }
+
+    /// What the user can do about it
+    pub fn hint(self) -> &'static str {
+        match self {
+            Self::PermissionDenied => {
+                "Check that the folder is writable and not blocked by antivirus or Controlled Folder Access."
+            }
+            Self::FileLocked => {
+                "Close the program that has it open (video player, OneDrive sync, antivirus scan) and try again."
+            }
+            Self::ReadOnly => "Choose a writable work directory in Settings.",
+            Self::DiskFull => "Free some space or choose another work directory.",
+        }
+    }
+}
+
+/// Hint suffix for an IO error message (empty if there's nothing to suggest)
+fn io_hint_suffix(err: &std::io::Error) -> String {
+    IoFailure::classify(err)
+        .map(|failure| format!(". {}", failure.hint()))
+        .unwrap_or_default()
+}
+
 /// Errors related to binary management (ffmpeg, yt-dlp)
 #[derive(Error, Debug)]
 pub enum BinaryError {
@@ -37,10 +118,16 @@ pub enum BinaryError {
     #[error("Binary verification failed: {0}")]
     VerificationFailed(String),
 
-    #[error("IO error: {0}")]
+    #[error("IO error: {0}{hint}", hint = io_hint_suffix(.0))]
     Io(#[from] std::io::Error),
 }
 
+/// `h:mm:ss` of a VOD position in seconds
+fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    format!("{}:{:02}:{:02}", total / 3600, total / 60 % 60, total % 60)
+}
+
 /// Errors related to VOD platform resolution
 #[derive(Error, Debug)]
 pub enum PlatformError {
@@ -59,6 +146,10 @@ pub enum PlatformError {
     #[error("No valid quality found for VOD")]
     NoValidQuality,
 
+    /// The stream is still live; only `available` seconds are recorded yet
+    #[error("VOD still processing beyond {}", format_timestamp(*.available))]
+    StillProcessing { available: f64 },
+
     #[error("Platform not supported: {0}")]
     UnsupportedPlatform(String),
 }
@@ -81,15 +172,30 @@ pub enum ExportError {
     #[error("Invalid time range: start={start}, end={end}")]
     InvalidTimeRange { start: f64, end: f64 },
 
+    #[error("Trim {start:.2}-{end:.2}s is outside the clip ({duration:.2}s long)")]
+    TrimOutOfRange { start: f64, end: f64, duration: f64 },
+
     #[error("Export timeout: {0}")]
     Timeout(String),
 
-    #[error("Invalid duration: {0}s (must be between 0 and 3600)")]
-    InvalidDuration(f64),
+    #[error("Invalid duration: {duration:.2}s (must be between 0.1 and {max:.0}s)")]
+    InvalidDuration { duration: f64, max: f64 },
+
+    #[error("Clip too long: {duration:.0}s (project limit is {max:.0}s)")]
+    DurationTooLong { duration: f64, max: f64 },
 
     #[error("Invalid start time: {0}s (must be >= 0)")]
     InvalidStartTime(f64),
 
+    #[error("Invalid speed: {0}x (must be between 0.25 and 4)")]
+    InvalidSpeed(f64),
+
+    #[error("Invalid filter: {0}")]
+    InvalidFilter(String),
+
+    #[error("Target size too small: {size_mb}MB can't hold {duration:.1}s of video")]
+    TargetSizeTooSmall { size_mb: f64, duration: f64 },
+
     #[error("Duration mismatch: expected {expected:.2}s, got {actual:.2}s")]
     DurationMismatch { expected: f64, actual: f64 },
 
@@ -101,6 +207,89 @@ pub enum ExportError {
 
     #[error("Download error: {0}")]
     DownloadError(String),
+
+    #[error("Export cancelled")]
+    Cancelled,
+}
+
+/// Broad category of an export failure, so the frontend can suggest a fix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum ExportErrorKind {
+    NetworkError,
+    /// Access denied (sub-only VOD, expired playlist URL)
+    Forbidden403,
+    EncoderError,
+    Timeout,
+    DurationMismatch,
+    DiskFull,
+    /// Clip past the end of a VOD whose stream is still live
+    StillProcessing,
+    Other,
+}
+
+impl ExportErrorKind {
+    /// Classify an error message (including FFmpeg/yt-dlp stderr) by keyword
+    pub fn from_message(message: &str) -> Self {
+        let msg = message.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|n| msg.contains(n));
+
+        if has(&["403", "forbidden", "subscriber-only", "sub-only"]) {
+            Self::Forbidden403
+        } else if has(&["still processing"]) {
+            Self::StillProcessing
+        } else if has(&[
+            "no space left",
+            "disk full",
+            "not enough space",
+            "not enough disk space",
+        ]) {
+            Self::DiskFull
+        } else if has(&[
+            "unknown encoder",
+            "error while opening encoder",
+            "error initializing output stream",
+            "no nvenc capable devices",
+            "openencodesessionex failed",
+            "cannot load nvcuda",
+            "encoding failed",
+        ]) {
+            Self::EncoderError
+        } else if has(&[
+            "connection refused",
+            "connection reset",
+            "connection timed out",
+            "network is unreachable",
+            "failed to resolve hostname",
+            "name resolution",
+            "server returned 5",
+            "http error",
+            "unable to download",
+        ]) {
+            Self::NetworkError
+        } else if has(&["timed out"]) {
+            Self::Timeout
+        } else {
+            Self::Other
+        }
+    }
+}
+
+impl ExportError {
+    /// Category of this error for the frontend
+    pub fn kind(&self) -> ExportErrorKind {
+        match self {
+            Self::Timeout(_) => ExportErrorKind::Timeout,
+            Self::DurationMismatch { .. } => ExportErrorKind::DurationMismatch,
+            Self::CorruptedOutput(_) => ExportErrorKind::EncoderError,
+            Self::Ffmpeg(msg)
+            | Self::YtDlp(msg)
+            | Self::OutputDir(msg)
+            | Self::DownloadError(msg) => ExportErrorKind::from_message(msg),
+            _ => ExportErrorKind::Other,
+        }
+    }
 }
 
 // Allow converting to String for Tauri commands
@@ -132,3 +321,85 @@ pub type Result<T> = std::result::Result<T, NoxError>;
 pub type PlatformResult<T> = std::result::Result<T, PlatformError>;
 pub type ExportResult<T> = std::result::Result<T, ExportError>;
 pub type BinaryResult<T> = std::result::Result<T, BinaryError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_error_kind() {
+        let kind = |msg: &str| ExportError::Ffmpeg(msg.to_string()).kind();
+
+        assert_eq!(
+            kind("FFmpeg exited with code: 1: Server returned 403 Forbidden (access denied)"),
+            ExportErrorKind::Forbidden403
+        );
+        assert_eq!(
+            kind("FFmpeg exited with code: 1: Error while opening encoder for output stream #0:0"),
+            ExportErrorKind::EncoderError
+        );
+        assert_eq!(
+            kind("av_interleaved_write_frame(): No space left on device"),
+            ExportErrorKind::DiskFull
+        );
+        assert_eq!(
+            kind("Connection reset by peer"),
+            ExportErrorKind::NetworkError
+        );
+        assert_eq!(kind("FFmpeg exited with code: 1"), ExportErrorKind::Other);
+        assert_eq!(
+            ExportErrorKind::from_message(
+                &PlatformError::StillProcessing { available: 60.0 }.to_string()
+            ),
+            ExportErrorKind::StillProcessing
+        );
+
+        assert_eq!(
+            ExportError::Timeout("Export timed out after 300 seconds".to_string()).kind(),
+            ExportErrorKind::Timeout
+        );
+        assert_eq!(
+            ExportError::DurationMismatch {
+                expected: 10.0,
+                actual: 4.0
+            }
+            .kind(),
+            ExportErrorKind::DurationMismatch
+        );
+        assert_eq!(
+            ExportError::InvalidSpeed(8.0).kind(),
+            ExportErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn test_io_error_classification() {
+        use std::io::{Error, ErrorKind};
+
+        assert!(matches!(
+            NoxError::from(Error::from(ErrorKind::PermissionDenied)),
+            NoxError::PermissionDenied(_)
+        ));
+        assert!(matches!(
+            NoxError::from(Error::from(ErrorKind::ReadOnlyFilesystem)),
+            NoxError::ReadOnly(_)
+        ));
+        assert!(matches!(
+            NoxError::from(Error::from(ErrorKind::StorageFull)),
+            NoxError::DiskFull(_)
+        ));
+        assert!(matches!(
+            NoxError::from(Error::from(ErrorKind::NotFound)),
+            NoxError::Io(_)
+        ));
+
+        let message = NoxError::from(Error::from(ErrorKind::ResourceBusy)).to_string();
+        assert!(message.starts_with("File is in use ("));
+        assert!(message.ends_with(IoFailure::FileLocked.hint()));
+
+        assert_eq!(
+            BinaryError::Io(Error::from(ErrorKind::NotFound)).to_string(),
+            "IO error: entity not found"
+        );
+    }
+}
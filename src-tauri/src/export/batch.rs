@@ -0,0 +1,89 @@
+//! Grouping of overlapping clips from the same VOD.
+//!
+//! Several actions close together in one VOD would otherwise download the
+//! same segments once per clip. Clips whose ranges overlap or sit close
+//! together are merged into one covering range that is downloaded once and
+//! sliced locally.
+
+/// Largest gap between two clips that still merges them (seconds)
+const MAX_GAP: f64 = 30.0;
+
+/// Longest covering range downloaded in one go (seconds)
+const MAX_SPAN: f64 = 600.0;
+
+/// A covering range and the clips it contains
+#[derive(Debug, Clone, PartialEq)]
+pub struct Batch {
+    /// Start of the covering range in the VOD (seconds)
+    pub start: f64,
+    /// End of the covering range in the VOD (seconds)
+    pub end: f64,
+    /// Keys of the clips inside the range
+    pub members: Vec<usize>,
+}
+
+impl Batch {
+    pub fn duration(&self) -> f64 {
+        self.end - self.start
+    }
+}
+
+/// Merge `(key, start, end)` ranges of one VOD into batches of two or more clips
+///
+/// Clips that don't share a batch with another clip are left out; they are
+/// exported on their own as before.
+pub fn plan_batches(ranges: &[(usize, f64, f64)]) -> Vec<Batch> {
+    let mut sorted = ranges.to_vec();
+    sorted.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let mut batches: Vec<Batch> = Vec::new();
+    for (key, start, end) in sorted {
+        if let Some(last) = batches.last_mut() {
+            let merged_end = last.end.max(end);
+            if start <= last.end + MAX_GAP && merged_end - last.start <= MAX_SPAN {
+                last.end = merged_end;
+                last.members.push(key);
+                continue;
+            }
+        }
+        batches.push(Batch {
+            start,
+            end,
+            members: vec![key],
+        });
+    }
+
+    batches.retain(|batch| batch.members.len() > 1);
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_batches() {
+        let batches = plan_batches(&[
+            (0, 100.0, 110.0),
+            (1, 105.0, 120.0),
+            (2, 140.0, 150.0),
+            (3, 400.0, 410.0),
+        ]);
+        assert_eq!(
+            batches,
+            vec![Batch {
+                start: 100.0,
+                end: 150.0,
+                members: vec![0, 1, 2],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_plan_batches_caps_span() {
+        let batches = plan_batches(&[(0, 0.0, 300.0), (1, 290.0, 590.0), (2, 580.0, 700.0)]);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].members, vec![0, 1]);
+        assert_eq!(batches[0].duration(), 590.0);
+    }
+}
@@ -0,0 +1,236 @@
+//! Opt-in cache of downloaded VOD ranges.
+//!
+//! Each entry is a stream copy of a VOD range, padded on both sides, stored as
+//! `<vod key>_<start ms>_<end ms>.mp4`. Re-exporting a clip whose range lies
+//! inside an entry (e.g. after moving its out-point by a few seconds) slices
+//! the local file instead of downloading from the platform again.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Extra source kept before and after a clip when it's cached (seconds)
+pub const CACHE_PADDING: f64 = 30.0;
+
+/// Total cache size kept after pruning (oldest entries go first)
+const MAX_CACHE_BYTES: u64 = 20 * 1024 * 1024 * 1024;
+
+/// A cached VOD range
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedSegment {
+    pub path: PathBuf,
+    /// Start of the cached range in the VOD (seconds)
+    pub start: f64,
+}
+
+/// Segment cache rooted in a directory
+pub struct SegmentCache {
+    dir: PathBuf,
+}
+
+impl SegmentCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Find an entry of `vod_url` that covers `start..end`
+    pub fn find(&self, vod_url: &str, start: f64, end: f64) -> Option<CachedSegment> {
+        let key = vod_key(vod_url);
+        std::fs::read_dir(&self.dir)
+            .ok()?
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let (entry_key, entry_start, entry_end) = parse_entry_name(&name)?;
+                (entry_key == key && entry_start <= start && entry_end >= end).then(|| {
+                    CachedSegment {
+                        path: entry.path(),
+                        start: entry_start,
+                    }
+                })
+            })
+            .next()
+    }
+
+    /// Padded range to download for a clip, and the file to store it in
+    pub fn entry_for(&self, vod_url: &str, start: f64, end: f64) -> (f64, f64, PathBuf) {
+        let padded_start = (start - CACHE_PADDING).max(0.0);
+        let padded_end = end + CACHE_PADDING;
+        let path = self
+            .dir
+            .join(entry_name(&vod_key(vod_url), padded_start, padded_end));
+        (padded_start, padded_end, path)
+    }
+
+    /// Rename a downloaded entry after the start ffprobe found in it
+    ///
+    /// A stream copy begins at the keyframe before the requested start, so the
+    /// entry covers (and must be sliced from) a slightly earlier time.
+    pub fn record_start(
+        &self,
+        vod_url: &str,
+        path: &Path,
+        start: f64,
+        end: f64,
+    ) -> std::io::Result<CachedSegment> {
+        let start = start.max(0.0);
+        let actual = self.dir.join(entry_name(&vod_key(vod_url), start, end));
+        if actual != path {
+            std::fs::rename(path, &actual)?;
+        }
+        Ok(CachedSegment {
+            path: actual,
+            start,
+        })
+    }
+
+    /// Create the cache directory
+    pub fn ensure_dir(&self) -> std::io::Result<&Path> {
+        std::fs::create_dir_all(&self.dir)?;
+        Ok(&self.dir)
+    }
+
+    /// Delete the oldest entries until the cache fits its size limit
+    pub fn prune(&self) {
+        prune_dir(&self.dir, MAX_CACHE_BYTES);
+    }
+}
+
+/// Delete the oldest entries (files or folders) of a cache directory until
+/// it holds at most `max_bytes`
+///
+/// Hidden entries are skipped: they are scratch folders of running jobs.
+pub(crate) fn prune_dir(dir: &Path, max_bytes: u64) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut items: Vec<(PathBuf, u64, SystemTime)> = entries
+        .flatten()
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            Some((entry.path(), entry_size(&entry.path()), modified))
+        })
+        .collect();
+
+    let mut total: u64 = items.iter().map(|(_, size, _)| size).sum();
+    items.sort_by_key(|(_, _, modified)| *modified);
+
+    for (path, size, _) in items {
+        if total <= max_bytes {
+            break;
+        }
+        let removed = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        if removed.is_ok() {
+            log::info!("Pruned cache entry {}", path.display());
+            total -= size;
+        }
+    }
+}
+
+/// Size of a file, or of every file inside a folder
+fn entry_size(path: &Path) -> u64 {
+    if path.is_dir() {
+        std::fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|e| entry_size(&e.path())).sum())
+            .unwrap_or(0)
+    } else {
+        std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+/// Stable key for a VOD URL (FNV-1a, so it doesn't change between builds)
+pub(crate) fn vod_key(vod_url: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in vod_url.trim().bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn entry_name(key: &str, start: f64, end: f64) -> String {
+    format!(
+        "{}_{}_{}.mp4",
+        key,
+        (start * 1000.0).round() as u64,
+        (end * 1000.0).round() as u64
+    )
+}
+
+/// Parse `<key>_<start ms>_<end ms>.mp4` back into key and range (seconds)
+fn parse_entry_name(name: &str) -> Option<(&str, f64, f64)> {
+    let stem = name.strip_suffix(".mp4")?;
+    let mut parts = stem.rsplitn(3, '_');
+    let end: u64 = parts.next()?.parse().ok()?;
+    let start: u64 = parts.next()?.parse().ok()?;
+    let key = parts.next()?;
+    Some((key, start as f64 / 1000.0, end as f64 / 1000.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_name_roundtrip() {
+        let key = vod_key("https://www.twitch.tv/videos/123456");
+        assert_eq!(key, vod_key(" https://www.twitch.tv/videos/123456 "));
+        assert_ne!(key, vod_key("https://www.twitch.tv/videos/654321"));
+
+        let name = entry_name(&key, 67.25, 127.5);
+        assert_eq!(parse_entry_name(&name), Some((key.as_str(), 67.25, 127.5)));
+        assert_eq!(parse_entry_name("notes.txt"), None);
+        assert_eq!(parse_entry_name("abc_1_x.mp4"), None);
+    }
+
+    #[test]
+    fn test_entry_for_pads_range() {
+        let cache = SegmentCache::new(PathBuf::from("/cache"));
+        let (start, end, _) = cache.entry_for("https://example.com/vod", 10.0, 40.0);
+        assert_eq!(start, 0.0);
+        assert_eq!(end, 70.0);
+    }
+
+    #[test]
+    fn test_prune_dir() {
+        let dir = std::env::temp_dir().join(format!("nox-prune-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sheets")).unwrap();
+        std::fs::create_dir_all(dir.join(".scratch")).unwrap();
+        std::fs::write(dir.join("sheets").join("0.jpg"), [0u8; 300]).unwrap();
+        std::fs::write(dir.join(".scratch").join("part"), [0u8; 300]).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        std::fs::write(dir.join("frame.jpg"), [0u8; 200]).unwrap();
+
+        // The older folder goes first; scratch folders are left alone
+        prune_dir(&dir, 250);
+        assert!(!dir.join("sheets").exists());
+        assert!(dir.join("frame.jpg").exists());
+        assert!(dir.join(".scratch").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_record_start() {
+        let dir = std::env::temp_dir().join(format!("nox-cache-test-{}", std::process::id()));
+        let cache = SegmentCache::new(dir.clone());
+        cache.ensure_dir().unwrap();
+        let vod = "https://example.com/vod";
+        let (start, end, path) = cache.entry_for(vod, 100.0, 110.0);
+        std::fs::write(&path, b"segment").unwrap();
+
+        // The copy began 1.5s early, at a keyframe
+        let segment = cache.record_start(vod, &path, start - 1.5, end).unwrap();
+        assert_eq!(segment.start, 68.5);
+        assert!(!path.exists());
+        assert_eq!(cache.find(vod, 69.0, 140.0), Some(segment));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
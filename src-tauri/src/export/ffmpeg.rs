@@ -1,4 +1,5 @@
-use std::path::Path;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
@@ -7,24 +8,79 @@ use tokio::time::timeout;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
-use super::{ClipTiming, FfmpegProgressParser};
+use super::stderr::StderrTail;
+use super::{
+    export_timeout, with_suffix, AttemptCallback, ClipTiming, Container, ExportAttempt,
+    ExportMethod, ExportOptions, FfmpegProgressParser, FrameFormat, ProgressUpdate,
+};
 use crate::binaries::get_binary_manager;
-use crate::config::{get_config, VideoEncoder};
-use crate::error::{ExportError, ExportResult};
+use crate::config::{get_config, FfmpegConfig, VideoEncoder};
+use crate::error::{ExportError, ExportErrorKind, ExportResult};
+use crate::montage::ffmpeg_font_path;
 use crate::platform::ResolvedVod;
 
-/// Timeout for a single clip export (5 minutes)
-const EXPORT_TIMEOUT: Duration = Duration::from_secs(300);
-
 /// Maximum number of retry attempts
 const MAX_RETRIES: u32 = 2;
 
+/// Share of the size budget kept for container/muxing overhead
+const SIZE_OVERHEAD_FACTOR: f64 = 0.97;
+
+/// Lowest video bitrate accepted when fitting a size target (kbit/s)
+const MIN_VIDEO_KBPS: u32 = 150;
+
+/// True peak and loudness range used with the loudnorm target
+const LOUDNORM_TRUE_PEAK: f64 = -1.5;
+const LOUDNORM_RANGE: f64 = 11.0;
+
+/// Integrated loudness targets loudnorm accepts (LUFS)
+pub const LOUDNESS_TARGET_RANGE: std::ops::RangeInclusive<f64> = -70.0..=-5.0;
+
+/// Font size of the burned-in VOD timecode
+const TIMECODE_FONT_SIZE: u32 = 28;
+
+/// How far past the clip start smart cut looks for a keyframe (seconds)
+const SMART_CUT_WINDOW: f64 = 10.0;
+
+/// Height of low-res proxy clips used for scrubbing and previews
+const PROXY_HEIGHT: u32 = 360;
+
+/// Length of the generated benchmark sample (seconds)
+const BENCHMARK_SAMPLE_SECS: f64 = 5.0;
+
+/// Longest wait between reconnect attempts on a dropped network input (seconds)
+const RECONNECT_DELAY_MAX_SECS: u32 = 5;
+
+/// Timeout for extracting a single still frame
+const FRAME_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout for downloading one audio window
+const AUDIO_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Sample rate of extracted sync audio (plenty for offset detection)
+const SYNC_AUDIO_RATE: u32 = 16_000;
+
+/// Shortest head segment worth re-encoding for smart cut (seconds)
+const SMART_CUT_MIN_HEAD: f64 = 0.05;
+
+/// Audio sample rate of normalized files
+const NORMALIZE_SAMPLE_RATE: u32 = 48000;
+
+/// Variable frame rate files can report absurd rates; normalized files are capped
+const MAX_NORMALIZE_FPS: u32 = 60;
+
+/// Progress function type (borrowed form of ProgressCallback)
+type ProgressFn<'a> = dyn Fn(ProgressUpdate) + Send + Sync + 'a;
+
 /// Progress callback type
-pub type ProgressCallback = Box<dyn Fn(f32, Option<String>) + Send + Sync>;
+pub type ProgressCallback = Box<ProgressFn<'static>>;
 
 pub struct FfmpegExporter {
     /// Try copy first, then re-encode if it fails
     try_copy_first: bool,
+    /// Maximum output size in MB (enables two-pass bitrate-targeted encoding)
+    max_size_mb: Option<f64>,
+    /// Loudness normalization target in LUFS (forces audio re-encode)
+    loudness_lufs: Option<f64>,
 }
 
 impl Default for FfmpegExporter {
@@ -37,9 +93,37 @@ impl FfmpegExporter {
     pub fn new() -> Self {
         Self {
             try_copy_first: true,
+            max_size_mb: None,
+            loudness_lufs: None,
         }
     }
 
+    /// Fit the output into `max_size_mb` using two-pass encoding
+    pub fn with_max_size_mb(mut self, max_size_mb: Option<f64>) -> Self {
+        self.max_size_mb = max_size_mb;
+        self
+    }
+
+    /// Normalize audio loudness to `lufs` (EBU R128) when re-encoding
+    pub fn with_loudness_target(mut self, lufs: Option<f64>) -> Self {
+        self.loudness_lufs = lufs;
+        self
+    }
+
+    /// Whether an export with these options can't be done by stream copy
+    pub fn needs_reencode(&self, options: &ExportOptions) -> bool {
+        options.needs_reencode() || (self.loudness_lufs.is_some() && !options.strip_audio)
+    }
+
+    /// Whether a file of `size` bytes is over the options' size limit, or
+    /// the exporter's own when they don't set one
+    pub fn exceeds_size_limit(&self, size: u64, options: &ExportOptions) -> bool {
+        options
+            .max_size_mb
+            .or(self.max_size_mb)
+            .is_some_and(|max_size_mb| (size as f64) > max_size_mb * 1024.0 * 1024.0)
+    }
+
     /// Get the ffmpeg binary path
     fn ffmpeg_path(&self) -> String {
         get_binary_manager()
@@ -57,10 +141,30 @@ impl FfmpegExporter {
     }
 
     /// Build FFmpeg command for stream copy (fastest)
-    fn build_copy_command(&self, input: &str, timing: &ClipTiming, output: &Path) -> Command {
+    fn build_copy_command(
+        &self,
+        input: &str,
+        timing: &ClipTiming,
+        options: &ExportOptions,
+        output: &Path,
+    ) -> Command {
+        self.copy_command_with(input, timing, options, output, &get_config().ffmpeg)
+    }
+
+    /// Stream copy command for an explicit FFmpeg config
+    fn copy_command_with(
+        &self,
+        input: &str,
+        timing: &ClipTiming,
+        options: &ExportOptions,
+        output: &Path,
+        ffmpeg_config: &FfmpegConfig,
+    ) -> Command {
         let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.arg("-y"); // Overwrite output
+        Self::add_reconnect_args(&mut cmd, input, ffmpeg_config);
+        Self::add_hls_args(&mut cmd, input);
         cmd.args([
-            "-y", // Overwrite output
             "-ss",
             &timing.start.to_string(), // Seek before input (fast)
             "-i",
@@ -69,11 +173,13 @@ impl FfmpegExporter {
             &timing.duration.to_string(), // Duration
             "-c",
             "copy", // Copy codecs (no re-encode)
-            "-movflags",
-            "+faststart", // Web optimization
             "-progress",
             "pipe:2", // Output progress to stderr
         ]);
+        if options.strip_audio {
+            cmd.arg("-an");
+        }
+        Self::add_container_args(&mut cmd, options.container);
         cmd.arg(output);
         cmd.stdin(std::process::Stdio::null());
         cmd.stderr(std::process::Stdio::piped());
@@ -83,23 +189,40 @@ impl FfmpegExporter {
         cmd
     }
 
-    /// Build FFmpeg command for re-encoding
-    fn build_encode_command(&self, input: &str, timing: &ClipTiming, output: &Path) -> Command {
-        let config = get_config();
-        let ffmpeg_config = &config.ffmpeg;
-
-        let mut cmd = Command::new(self.ffmpeg_path());
+    /// Add input options that reconnect dropped HTTP/HLS streams (no-op for local files)
+    fn add_reconnect_args(cmd: &mut Command, input: &str, ffmpeg_config: &FfmpegConfig) {
+        if !ffmpeg_config.hls_reconnect || !input.starts_with("http") {
+            return;
+        }
         cmd.args([
-            "-y",
-            "-ss",
-            &timing.start.to_string(),
-            "-i",
-            input,
-            "-t",
-            &timing.duration.to_string(),
+            "-reconnect",
+            "1",
+            "-reconnect_streamed",
+            "1",
+            "-reconnect_delay_max",
+            &RECONNECT_DELAY_MAX_SECS.to_string(),
+            "-http_persistent",
+            "1",
         ]);
+    }
 
-        // Video encoding
+    /// Read an HLS playlist from its first segment even while it's still
+    /// growing (a VOD whose stream is live), rather than from the live edge
+    fn add_hls_args(cmd: &mut Command, input: &str) {
+        if input.starts_with("http") && input.contains(".m3u8") {
+            cmd.args(["-live_start_index", "0"]);
+        }
+    }
+
+    /// Add `-hwaccel` decoding to match a hardware encoder (before `-i`)
+    fn add_hwaccel_args(cmd: &mut Command, ffmpeg_config: &FfmpegConfig) {
+        if let Some(hwaccel) = ffmpeg_config.encoder.hwaccel() {
+            cmd.args(["-hwaccel", hwaccel]);
+        }
+    }
+
+    /// Add the video encoder arguments for the configured encoder
+    pub(crate) fn add_video_encoder_args(cmd: &mut Command, ffmpeg_config: &FfmpegConfig) {
         match ffmpeg_config.encoder {
             VideoEncoder::Libx264 => {
                 cmd.args([
@@ -144,12 +267,77 @@ impl FfmpegExporter {
                 ]);
             }
         }
+    }
+
+    /// Add the video/audio filters required by the options (timecode, speed, loudness)
+    fn add_filters(
+        &self,
+        cmd: &mut Command,
+        options: &ExportOptions,
+        timing: &ClipTiming,
+        with_audio: bool,
+    ) {
+        let timecode_start = options.timecode_start.unwrap_or(timing.start);
+        if let Some(filter) = video_filter_chain(options, timecode_start, &ffmpeg_font_path()) {
+            cmd.args(["-filter:v", &filter]);
+        }
+        if with_audio && !options.strip_audio {
+            if let Some(filter) = audio_filter_chain(options, self.loudness_lufs) {
+                cmd.args(["-filter:a", &filter]);
+            }
+        }
+    }
+
+    /// Add the audio encoder arguments, or drop audio entirely when requested
+    fn add_audio_args(cmd: &mut Command, options: &ExportOptions, ffmpeg_config: &FfmpegConfig) {
+        if options.strip_audio {
+            cmd.arg("-an");
+        } else {
+            cmd.args(["-c:a", "aac", "-b:a", &ffmpeg_config.audio_bitrate]);
+        }
+    }
+
+    /// Add `-movflags +faststart` when the output container supports it
+    fn add_container_args(cmd: &mut Command, container: Container) {
+        if container.supports_faststart() {
+            cmd.args(["-movflags", "+faststart"]);
+        }
+    }
+
+    /// Build FFmpeg command for re-encoding with an explicit FFmpeg config
+    fn encode_command_with(
+        &self,
+        input: &str,
+        timing: &ClipTiming,
+        options: &ExportOptions,
+        output: &Path,
+        ffmpeg_config: &FfmpegConfig,
+    ) -> Command {
+        // -t is an output option, so it limits the speed-adjusted duration
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.arg("-y");
+        Self::add_reconnect_args(&mut cmd, input, ffmpeg_config);
+        Self::add_hls_args(&mut cmd, input);
+        Self::add_hwaccel_args(&mut cmd, ffmpeg_config);
+        cmd.args([
+            "-ss",
+            &timing.start.to_string(),
+            "-i",
+            input,
+            "-t",
+            &options.output_duration(timing.duration).to_string(),
+        ]);
+        self.add_filters(&mut cmd, options, timing, true);
+
+        // Video encoding
+        Self::add_video_encoder_args(&mut cmd, ffmpeg_config);
 
         // Audio encoding
-        cmd.args(["-c:a", "aac", "-b:a", &ffmpeg_config.audio_bitrate]);
+        Self::add_audio_args(&mut cmd, options, ffmpeg_config);
 
         // Output optimization + progress
-        cmd.args(["-movflags", "+faststart", "-progress", "pipe:2"]);
+        Self::add_container_args(&mut cmd, options.container);
+        cmd.args(["-progress", "pipe:2"]);
         cmd.arg(output);
         cmd.stdin(std::process::Stdio::null());
         cmd.stderr(std::process::Stdio::piped());
@@ -160,193 +348,1590 @@ impl FfmpegExporter {
         cmd
     }
 
-    /// Run a command with timeout and optional progress callback
-    async fn run_command_with_progress(
+    /// Build FFmpeg command for a small proxy of a local clip
+    ///
+    /// Always libx264 ultrafast with a short GOP so seeking in the proxy is cheap.
+    fn build_proxy_command(&self, input: &Path, output: &Path) -> Command {
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.arg("-y");
+        cmd.arg("-i");
+        cmd.arg(input);
+        cmd.args([
+            "-vf",
+            &format!("scale=-2:{}", PROXY_HEIGHT),
+            "-c:v",
+            "libx264",
+            "-preset",
+            "ultrafast",
+            "-crf",
+            "30",
+            "-g",
+            "30",
+            "-c:a",
+            "aac",
+            "-b:a",
+            "64k",
+            // Output is a temp name without .mp4 extension, so force the muxer
+            "-f",
+            "mp4",
+            "-movflags",
+            "+faststart",
+            "-progress",
+            "pipe:2",
+        ]);
+        cmd.arg(output);
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdout(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+        cmd
+    }
+
+    /// Build FFmpeg command that writes the frame at `timestamp` as an image
+    fn frame_command_with(
         &self,
-        mut cmd: Command,
-        duration: f64,
-        progress: Option<&ProgressCallback>,
-    ) -> ExportResult<()> {
-        log::debug!("Running: {:?}", cmd);
+        input: &str,
+        timestamp: f64,
+        format: FrameFormat,
+        output: &Path,
+        ffmpeg_config: &FfmpegConfig,
+    ) -> Command {
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.arg("-y");
+        Self::add_reconnect_args(&mut cmd, input, ffmpeg_config);
+        Self::add_hls_args(&mut cmd, input);
+        cmd.args(["-ss", &timestamp.to_string(), "-i", input, "-frames:v", "1"]);
+        if format == FrameFormat::Jpeg {
+            cmd.args(["-q:v", "2"]);
+        }
+        // Output name may not carry the format's extension, so force the muxer
+        cmd.args(["-f", "image2", "-update", "1"]);
+        cmd.arg(output);
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdout(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
 
-        let mut child = cmd
-            .spawn()
-            .map_err(|e| ExportError::Ffmpeg(format!("Failed to start FFmpeg: {}", e)))?;
+        cmd
+    }
 
-        let stderr = child
-            .stderr
-            .take()
-            .ok_or_else(|| ExportError::Ffmpeg("Failed to capture stderr".to_string()))?;
+    /// Build FFmpeg command that writes a window of a source's audio as mono 16 kHz WAV
+    fn audio_window_command_with(
+        &self,
+        input: &str,
+        timing: &ClipTiming,
+        output: &Path,
+        ffmpeg_config: &FfmpegConfig,
+    ) -> Command {
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.arg("-y");
+        Self::add_reconnect_args(&mut cmd, input, ffmpeg_config);
+        Self::add_hls_args(&mut cmd, input);
+        cmd.args([
+            "-ss",
+            &timing.start.to_string(),
+            "-i",
+            input,
+            "-t",
+            &timing.duration.to_string(),
+            "-vn",
+            "-sn",
+            "-dn",
+            "-ac",
+            "1",
+            "-ar",
+            &SYNC_AUDIO_RATE.to_string(),
+            "-c:a",
+            "pcm_s16le",
+            "-f",
+            "wav",
+        ]);
+        cmd.arg(output);
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdout(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
 
-        let parser = FfmpegProgressParser::new(duration);
-        let mut reader = BufReader::new(stderr).lines();
+        cmd
+    }
 
-        // Read progress in background
-        let progress_task = async {
-            while let Ok(Some(line)) = reader.next_line().await {
-                if let Some((percent, speed)) = parser.parse_line(&line) {
-                    if let Some(cb) = progress {
-                        cb(percent, speed);
-                    }
-                }
-            }
-        };
+    /// Build FFmpeg command that re-encodes a whole local file to `format`
+    fn build_normalize_command(
+        &self,
+        input: &Path,
+        output: &Path,
+        format: &NormalizeFormat,
+        ffmpeg_config: &FfmpegConfig,
+    ) -> Command {
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.arg("-y");
+        Self::add_hwaccel_args(&mut cmd, ffmpeg_config);
+        cmd.arg("-i");
+        cmd.arg(input);
 
-        // Wait for process with timeout
-        let result = timeout(EXPORT_TIMEOUT, async {
-            tokio::select! {
-                _ = progress_task => {},
-                status = child.wait() => {
-                    return status;
-                }
-            }
-            child.wait().await
-        })
-        .await;
+        // Scale into the target frame (letterboxed), constant frame rate, 8-bit 4:2:0
+        cmd.args(["-vf", &format.video_filter()]);
+        Self::add_video_encoder_args(&mut cmd, ffmpeg_config);
 
-        match result {
-            Ok(Ok(status)) if status.success() => Ok(()),
-            Ok(Ok(status)) => Err(ExportError::Ffmpeg(format!(
-                "FFmpeg exited with code: {}",
-                status
-            ))),
-            Ok(Err(e)) => Err(ExportError::Ffmpeg(format!("FFmpeg error: {}", e))),
-            Err(_) => {
-                // Timeout - kill the process
-                let _ = child.kill().await;
-                Err(ExportError::Timeout(format!(
-                    "Export timed out after {} seconds",
-                    EXPORT_TIMEOUT.as_secs()
-                )))
-            }
-        }
-    }
+        // Audio: AAC, fixed sample rate and stereo layout
+        cmd.args([
+            "-c:a",
+            "aac",
+            "-b:a",
+            &ffmpeg_config.audio_bitrate,
+            "-ar",
+            &format.sample_rate.to_string(),
+            "-ac",
+            "2",
+        ]);
 
-    /// Verify the output file with ffprobe
-    pub async fn verify_output(&self, path: &Path, expected_duration: f64) -> ExportResult<()> {
-        let mut cmd = Command::new(self.ffprobe_path());
+        // Output is a temp name without .mp4 extension, so force the muxer
         cmd.args([
-            "-v",
-            "error",
-            "-show_entries",
-            "format=duration",
-            "-of",
-            "default=noprint_wrappers=1:nokey=1",
+            "-f",
+            "mp4",
+            "-movflags",
+            "+faststart",
+            "-progress",
+            "pipe:2",
         ]);
-        cmd.arg(path);
+        cmd.arg(output);
         cmd.stdin(std::process::Stdio::null());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdout(std::process::Stdio::null());
         #[cfg(target_os = "windows")]
         cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
 
-        let output = cmd
-            .output()
-            .await
-            .map_err(|e| ExportError::Ffmpeg(format!("Failed to run ffprobe: {}", e)))?;
+        cmd
+    }
 
-        if !output.status.success() {
-            return Err(ExportError::CorruptedOutput(
-                "ffprobe failed to read output file".to_string(),
-            ));
-        }
+    /// Build one pass of a two-pass, bitrate-targeted libx264 encode
+    ///
+    /// Pass 1 only analyzes video and discards its output; pass 2 writes the file.
+    #[allow(clippy::too_many_arguments)]
+    fn build_two_pass_command(
+        &self,
+        input: &str,
+        timing: &ClipTiming,
+        options: &ExportOptions,
+        output: &Path,
+        pass: u8,
+        video_kbps: u32,
+        passlog: &Path,
+    ) -> Command {
+        let config = get_config();
+        let ffmpeg_config = &config.ffmpeg;
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let actual_duration: f64 = stdout.trim().parse().map_err(|_| {
-            ExportError::CorruptedOutput("Failed to parse duration from ffprobe".to_string())
-        })?;
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.arg("-y");
+        Self::add_reconnect_args(&mut cmd, input, ffmpeg_config);
+        Self::add_hls_args(&mut cmd, input);
+        cmd.args([
+            "-ss",
+            &timing.start.to_string(),
+            "-i",
+            input,
+            "-t",
+            &options.output_duration(timing.duration).to_string(),
+        ]);
+        self.add_filters(&mut cmd, options, timing, pass == 2);
 
-        // Allow 15% tolerance or 1.5 seconds, whichever is larger
-        // This handles cases where VOD streams may have slight gaps or end slightly early
-        let tolerance = (expected_duration * 0.15).max(1.5);
-        if (actual_duration - expected_duration).abs() > tolerance {
-            return Err(ExportError::DurationMismatch {
-                expected: expected_duration,
-                actual: actual_duration,
-            });
+        // Two-pass rate control is only reliable with libx264
+        cmd.args([
+            "-c:v",
+            "libx264",
+            "-preset",
+            &ffmpeg_config.preset,
+            "-b:v",
+            &format!("{}k", video_kbps),
+            "-pass",
+            &pass.to_string(),
+            "-passlogfile",
+        ]);
+        cmd.arg(passlog);
+
+        if pass == 1 {
+            cmd.args(["-an", "-f", "null", "-progress", "pipe:2", "-"]);
+        } else {
+            Self::add_audio_args(&mut cmd, options, ffmpeg_config);
+            Self::add_container_args(&mut cmd, options.container);
+            cmd.args(["-progress", "pipe:2"]);
+            cmd.arg(output);
         }
 
-        Ok(())
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdout(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+        cmd
     }
 
-    /// Export with retry logic
-    #[allow(unused_assignments)]
-    pub async fn export_with_retry(
+    /// Build FFmpeg command that joins files listed in a concat demuxer list (stream copy)
+    fn build_concat_command(
         &self,
-        vod: &ResolvedVod,
-        timing: &ClipTiming,
-        output_path: &Path,
-        progress: Option<&ProgressCallback>,
-    ) -> ExportResult<()> {
-        // Validate timing first
-        timing.validate()?;
+        list_path: &Path,
+        options: &ExportOptions,
+        output: &Path,
+    ) -> Command {
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.args(["-y", "-f", "concat", "-safe", "0", "-i"]);
+        cmd.arg(list_path);
+        cmd.args(["-c", "copy", "-progress", "pipe:2"]);
+        Self::add_container_args(&mut cmd, options.container);
+        cmd.arg(output);
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdout(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
 
-        let mut last_error = None;
+        cmd
+    }
 
-        for attempt in 1..=MAX_RETRIES {
-            log::info!(
-                "[FFmpeg] Export attempt {}/{}: start={:.2}s, duration={:.2}s",
-                attempt,
-                MAX_RETRIES,
-                timing.start,
-                timing.duration
-            );
+    /// Build FFmpeg command that copies a whole local file into the container
+    /// of `output` (picked from its extension)
+    fn build_remux_command(&self, input: &Path, output: &Path) -> Command {
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.arg("-y");
+        cmd.arg("-i");
+        cmd.arg(input);
+        cmd.args(["-c", "copy", "-progress", "pipe:2"]);
+        if let Some(container) = Container::from_path(output) {
+            Self::add_container_args(&mut cmd, container);
+        }
+        cmd.arg(output);
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdout(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+        cmd
+    }
+
+    /// Build FFmpeg command re-encoding the head of a smart cut with the codec
+    /// parameters of the copied tail, so the concat demuxer can join the two
+    ///
+    /// None when libx264/AAC can't produce matching streams (other codecs).
+    fn build_smart_cut_head_command(
+        &self,
+        input: &str,
+        timing: &ClipTiming,
+        options: &ExportOptions,
+        tail: &StreamParams,
+        output: &Path,
+    ) -> Option<Command> {
+        let ffmpeg_config = &get_config().ffmpeg;
+        let video = tail.first("video")?;
+        if video.codec_name.as_deref() != Some("h264") {
+            return None;
+        }
+
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.arg("-y");
+        Self::add_reconnect_args(&mut cmd, input, ffmpeg_config);
+        Self::add_hls_args(&mut cmd, input);
+        cmd.args([
+            "-ss",
+            &timing.start.to_string(),
+            "-i",
+            input,
+            "-t",
+            &timing.duration.to_string(),
+            "-c:v",
+            "libx264",
+            "-preset",
+            &ffmpeg_config.preset,
+            "-crf",
+            &ffmpeg_config.crf.to_string(),
+        ]);
+        if let Some(pix_fmt) = &video.pix_fmt {
+            cmd.args(["-pix_fmt", pix_fmt]);
+        }
+        if let Some(profile) = video.profile.as_deref().and_then(x264_profile) {
+            cmd.args(["-profile:v", profile]);
+        }
+        if let Some(rate) = &video.r_frame_rate {
+            cmd.args(["-r", rate]);
+        }
+        if let Some((_, timescale)) = video.time_base.as_deref().and_then(|tb| tb.split_once('/')) {
+            cmd.args(["-video_track_timescale", timescale]);
+        }
+
+        match tail.first("audio") {
+            _ if options.strip_audio => {
+                cmd.arg("-an");
+            }
+            Some(audio) if audio.codec_name.as_deref() == Some("aac") => {
+                cmd.args(["-c:a", "aac", "-b:a", &ffmpeg_config.audio_bitrate]);
+                if let Some(rate) = &audio.sample_rate {
+                    cmd.args(["-ar", rate]);
+                }
+                if let Some(channels) = audio.channels {
+                    cmd.args(["-ac", &channels.to_string()]);
+                }
+            }
+            Some(_) => return None,
+            None => {
+                cmd.arg("-an");
+            }
+        }
+
+        Self::add_container_args(&mut cmd, options.container);
+        cmd.args(["-progress", "pipe:2"]);
+        cmd.arg(output);
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdout(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+        Some(cmd)
+    }
+
+    /// Find the first video keyframe at or after `start` (seconds from the input start)
+    ///
+    /// Only looks `window` seconds ahead; returns None if no keyframe is found.
+    async fn find_keyframe_after(
+        &self,
+        input: &str,
+        start: f64,
+        window: f64,
+    ) -> ExportResult<Option<f64>> {
+        let mut cmd = Command::new(self.ffprobe_path());
+        cmd.args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-skip_frame",
+            "nokey",
+            "-show_entries",
+            "frame=pts_time:format=start_time",
+            "-of",
+            "json",
+            "-read_intervals",
+            &format!("{:.3}%+{:.3}", (start - 1.0).max(0.0), window + 1.0),
+            input,
+        ]);
+        cmd.stdin(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+        let output = cmd
+            .output()
+            .await
+            .map_err(|e| ExportError::Ffmpeg(format!("Failed to run ffprobe: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(ExportError::Ffmpeg(
+                "ffprobe failed to read keyframes".to_string(),
+            ));
+        }
+
+        let probe: KeyframeProbe = serde_json::from_slice(&output.stdout)
+            .map_err(|e| ExportError::Ffmpeg(format!("Failed to parse keyframe probe: {}", e)))?;
+
+        Ok(probe.first_keyframe_after(start, start + window))
+    }
+
+    /// Run a command with a timeout scaled to the `duration` seconds it
+    /// outputs, and optional progress callback
+    async fn run_command_with_progress(
+        &self,
+        mut cmd: Command,
+        duration: f64,
+        progress: Option<&ProgressFn<'_>>,
+    ) -> ExportResult<()> {
+        log::debug!("Running: {:?}", cmd);
+
+        // A caller that gives up on the command (e.g. a cancelled montage)
+        // stops FFmpeg by dropping this future
+        cmd.kill_on_drop(true);
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| ExportError::Ffmpeg(format!("Failed to start FFmpeg: {}", e)))?;
+
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| ExportError::Ffmpeg("Failed to capture stderr".to_string()))?;
+
+        let mut parser = FfmpegProgressParser::new(duration);
+        let mut reader = BufReader::new(stderr).lines();
+        let mut stderr_tail = StderrTail::default();
+
+        // Read progress in background, keeping the last error lines for the report
+        let progress_task = async {
+            while let Ok(Some(line)) = reader.next_line().await {
+                if let Some(update) = parser.parse_line(&line) {
+                    if let Some(cb) = progress {
+                        cb(update);
+                    }
+                } else {
+                    stderr_tail.push(&line);
+                }
+            }
+        };
+
+        // Wait for process with timeout
+        let limit = export_timeout(duration);
+        let result = timeout(limit, async {
+            tokio::select! {
+                _ = progress_task => {},
+                status = child.wait() => {
+                    return status;
+                }
+            }
+            child.wait().await
+        })
+        .await;
+
+        match result {
+            Ok(Ok(status)) if status.success() => Ok(()),
+            Ok(Ok(status)) => {
+                log::error!("[FFmpeg] Last output:\n{}", stderr_tail.dump());
+                Err(ExportError::Ffmpeg(format!(
+                    "FFmpeg exited with code: {}{}",
+                    status,
+                    stderr_tail.suffix()
+                )))
+            }
+            Ok(Err(e)) => Err(ExportError::Ffmpeg(format!("FFmpeg error: {}", e))),
+            Err(_) => {
+                // Timeout - kill the process
+                let _ = child.kill().await;
+                Err(ExportError::Timeout(format!(
+                    "Export timed out after {} seconds",
+                    limit.as_secs()
+                )))
+            }
+        }
+    }
+
+    /// Read a media file's duration with ffprobe
+    pub async fn probe_duration(&self, path: &Path) -> ExportResult<f64> {
+        self.probe_format_entry(path, "duration").await
+    }
+
+    /// Read a media file's start time with ffprobe (seconds)
+    ///
+    /// Negative for stream copies that begin at the keyframe before the
+    /// requested cut point.
+    pub async fn probe_start_time(&self, path: &Path) -> ExportResult<f64> {
+        self.probe_format_entry(path, "start_time").await
+    }
+
+    /// Read a numeric `format` entry of a media file with ffprobe
+    async fn probe_format_entry(&self, path: &Path, entry: &str) -> ExportResult<f64> {
+        let mut cmd = Command::new(self.ffprobe_path());
+        cmd.args([
+            "-v",
+            "error",
+            "-show_entries",
+            &format!("format={}", entry),
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+        ]);
+        cmd.arg(path);
+        cmd.stdin(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+        let output = cmd
+            .output()
+            .await
+            .map_err(|e| ExportError::Ffmpeg(format!("Failed to run ffprobe: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(ExportError::CorruptedOutput(
+                "ffprobe failed to read output file".to_string(),
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout.trim().parse().map_err(|_| {
+            ExportError::CorruptedOutput(format!("Failed to parse {} from ffprobe", entry))
+        })
+    }
+
+    /// Read the codec parameters of a media file's streams with ffprobe
+    pub async fn probe_stream_params(&self, path: &Path) -> ExportResult<StreamParams> {
+        let mut cmd = Command::new(self.ffprobe_path());
+        cmd.args([
+            "-v",
+            "error",
+            "-show_entries",
+            "stream=codec_type,codec_name,profile,width,height,pix_fmt,r_frame_rate,\
+             time_base,sample_rate,channels",
+            "-of",
+            "json",
+        ]);
+        cmd.arg(path);
+        cmd.stdin(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+        let output = cmd
+            .output()
+            .await
+            .map_err(|e| ExportError::Ffmpeg(format!("Failed to run ffprobe: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(ExportError::Ffmpeg(format!(
+                "ffprobe failed to read {}",
+                path.display()
+            )));
+        }
+
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| ExportError::Ffmpeg(format!("Failed to parse stream probe: {}", e)))
+    }
+
+    /// Verify the output file with ffprobe
+    ///
+    /// TS and MKV files may lack a container duration; for those an unreadable
+    /// duration only fails the check if the file is empty.
+    pub async fn verify_output(&self, path: &Path, expected_duration: f64) -> ExportResult<()> {
+        let actual_duration = match self.probe_duration(path).await {
+            Ok(duration) => duration,
+            Err(e) if Container::from_path(path).is_some_and(|c| !c.has_duration_header()) => {
+                log::warn!(
+                    "[FFmpeg] No duration in {}: {}; checking size only",
+                    path.display(),
+                    e
+                );
+                let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+                if size == 0 {
+                    return Err(ExportError::CorruptedOutput(format!(
+                        "{} is empty",
+                        path.display()
+                    )));
+                }
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Allow 15% tolerance or 1.5 seconds, whichever is larger
+        // This handles cases where VOD streams may have slight gaps or end slightly early
+        let tolerance = (expected_duration * 0.15).max(1.5);
+        if (actual_duration - expected_duration).abs() > tolerance {
+            return Err(ExportError::DurationMismatch {
+                expected: expected_duration,
+                actual: actual_duration,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Transcode a generated 1080p60 sample with `encoder` and return its speed
+    ///
+    /// Speed is a multiple of realtime. Fails if the encoder errors out (e.g.
+    /// compiled in but no usable GPU) or produces an unreadable file.
+    pub async fn benchmark_encoder(&self, encoder: VideoEncoder, dir: &Path) -> ExportResult<f64> {
+        let ffmpeg_config = FfmpegConfig::with_encoder(encoder);
+        let output = dir.join(format!("nox_benchmark_{}.mp4", encoder.as_str()));
+
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.args([
+            "-y",
+            "-f",
+            "lavfi",
+            "-i",
+            &format!(
+                "testsrc2=size=1920x1080:rate=60:duration={}",
+                BENCHMARK_SAMPLE_SECS
+            ),
+        ]);
+        Self::add_video_encoder_args(&mut cmd, &ffmpeg_config);
+        cmd.args(["-pix_fmt", "yuv420p", "-an", "-progress", "pipe:2"]);
+        cmd.arg(&output);
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdout(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+        let started = std::time::Instant::now();
+        let result = async {
+            self.run_command_with_progress(cmd, BENCHMARK_SAMPLE_SECS, None)
+                .await?;
+            let elapsed = started.elapsed().as_secs_f64();
+            self.verify_output(&output, BENCHMARK_SAMPLE_SECS).await?;
+            Ok(BENCHMARK_SAMPLE_SECS / elapsed.max(0.001))
+        }
+        .await;
+
+        let _ = std::fs::remove_file(&output);
+        result
+    }
+
+    /// Export with retry logic
+    #[allow(unused_assignments)]
+    pub async fn export_with_retry(
+        &self,
+        vod: &ResolvedVod,
+        timing: &ClipTiming,
+        options: &ExportOptions,
+        output_path: &Path,
+        progress: Option<&ProgressCallback>,
+        attempts: Option<&AttemptCallback>,
+    ) -> ExportResult<()> {
+        // Validate timing and options first
+        timing.validate_with_max(options.max_duration())?;
+        options.validate()?;
+
+        let output_duration = options.output_duration(timing.duration);
+
+        // Size-targeted exports can't use stream copy; compute the bitrate up front
+        let target_kbps = match options.max_size_mb.or(self.max_size_mb) {
+            Some(max_size_mb) => {
+                let audio_kbps = if options.strip_audio {
+                    0
+                } else {
+                    parse_kbps(&get_config().ffmpeg.audio_bitrate)
+                };
+                Some(target_video_kbps(max_size_mb, output_duration, audio_kbps)?)
+            }
+            None => None,
+        };
+
+        // Filters (speed, timecode, loudness) rule out stream copy and smart cut
+        let can_copy = !self.needs_reencode(options);
+
+        // Swapped for libx264 once a hardware re-encode fails
+        let mut ffmpeg_config = get_config().ffmpeg.clone();
+        let mut last_error: Option<ExportError> = None;
+
+        for attempt in 1..=MAX_RETRIES {
+            // A 403 means the resolved URL is stale; the caller re-resolves instead
+            if last_error
+                .as_ref()
+                .is_some_and(|e| e.kind() == ExportErrorKind::Forbidden403)
+            {
+                break;
+            }
+
+            log::info!(
+                "[FFmpeg] Export attempt {}/{}: start={:.2}s, duration={:.2}s",
+                attempt,
+                MAX_RETRIES,
+                timing.start,
+                timing.duration
+            );
+
+            if let Some(video_kbps) = target_kbps {
+                report_attempt(attempts, attempt, ExportMethod::TwoPass);
+                match self
+                    .export_two_pass(&vod.url, timing, options, output_path, video_kbps, progress)
+                    .await
+                {
+                    Ok(()) => {
+                        log::info!("[FFmpeg] Export successful (two-pass, {}k)", video_kbps);
+                        return Ok(());
+                    }
+                    Err(e) => {
+                        log::warn!("[FFmpeg] Two-pass encode failed: {}", e);
+                        last_error = Some(e);
+                        continue;
+                    }
+                }
+            }
+
+            // Smart cut: frame-accurate start without re-encoding the whole clip
+            if options.smart_cut && can_copy && attempt == 1 {
+                report_attempt(attempts, attempt, ExportMethod::SmartCut);
+                match self
+                    .export_smart_cut(&vod.url, timing, options, output_path, progress)
+                    .await
+                {
+                    Ok(()) => {
+                        log::info!("[FFmpeg] Export successful (smart cut)");
+                        return Ok(());
+                    }
+                    Err(e) => {
+                        log::warn!("[FFmpeg] Smart cut failed: {}", e);
+                        last_error = Some(e);
+                    }
+                }
+            }
+
+            // Try copy first if enabled and this is the first attempt. A failed
+            // smart cut goes straight to a re-encode, which is frame-accurate too
+            if self.try_copy_first && can_copy && attempt == 1 && !options.smart_cut {
+                report_attempt(attempts, attempt, ExportMethod::Copy);
+                let copy_cmd = self.build_copy_command(&vod.url, timing, options, output_path);
+
+                match self
+                    .run_command_with_progress(
+                        copy_cmd,
+                        timing.duration,
+                        progress.map(|cb| cb.as_ref()),
+                    )
+                    .await
+                {
+                    Ok(()) => {
+                        // Verify output
+                        if let Err(e) = self.verify_output(output_path, timing.duration).await {
+                            log::warn!("[FFmpeg] Output verification failed: {}", e);
+                            let _ = std::fs::remove_file(output_path);
+                        } else {
+                            log::info!("[FFmpeg] Export successful (stream copy)");
+                            return Ok(());
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!("[FFmpeg] Copy failed: {}", e);
+                        last_error = Some(e);
+                    }
+                }
+            }
+
+            // Try re-encoding
+            report_attempt(attempts, attempt, ExportMethod::Reencode);
+            let encode_cmd =
+                self.encode_command_with(&vod.url, timing, options, output_path, &ffmpeg_config);
+
+            match self
+                .run_command_with_progress(
+                    encode_cmd,
+                    output_duration,
+                    progress.map(|cb| cb.as_ref()),
+                )
+                .await
+            {
+                Ok(()) => {
+                    // Verify output
+                    if let Err(e) = self.verify_output(output_path, output_duration).await {
+                        log::warn!("[FFmpeg] Output verification failed: {}", e);
+                        let _ = std::fs::remove_file(output_path);
+                        last_error = Some(e);
+                    } else {
+                        log::info!("[FFmpeg] Export successful (re-encoded)");
+                        return Ok(());
+                    }
+                }
+                Err(e) => {
+                    log::warn!("[FFmpeg] Encode failed: {}", e);
+                    if let Some(software) = ffmpeg_config.software_fallback() {
+                        log::warn!(
+                            "[FFmpeg] Retrying without {}, falling back to libx264",
+                            ffmpeg_config.encoder.as_str()
+                        );
+                        ffmpeg_config = software;
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| ExportError::Ffmpeg("Export failed".to_string())))
+    }
+
+    /// Run both passes of a bitrate-targeted encode and verify the result
+    async fn export_two_pass(
+        &self,
+        input: &str,
+        timing: &ClipTiming,
+        options: &ExportOptions,
+        output_path: &Path,
+        video_kbps: u32,
+        progress: Option<&ProgressCallback>,
+    ) -> ExportResult<()> {
+        let output_duration = options.output_duration(timing.duration);
+        let passlog = with_suffix(output_path, ".2pass");
+
+        // Each pass reports half of the overall progress. Pass 1 writes nothing
+        // and its ETA doesn't cover pass 2, so only pass 2 reports those.
+        let first_half = move |update: ProgressUpdate| {
+            if let Some(cb) = progress {
+                cb(ProgressUpdate {
+                    percent: update.percent / 2.0,
+                    speed: update.speed,
+                    ..Default::default()
+                });
+            }
+        };
+        let second_half = move |update: ProgressUpdate| {
+            if let Some(cb) = progress {
+                cb(ProgressUpdate {
+                    percent: 50.0 + update.percent / 2.0,
+                    ..update
+                });
+            }
+        };
+
+        let pass1 = self.build_two_pass_command(
+            input,
+            timing,
+            options,
+            output_path,
+            1,
+            video_kbps,
+            &passlog,
+        );
+        let mut result = self
+            .run_command_with_progress(pass1, output_duration, Some(&first_half))
+            .await;
+
+        if result.is_ok() {
+            let pass2 = self.build_two_pass_command(
+                input,
+                timing,
+                options,
+                output_path,
+                2,
+                video_kbps,
+                &passlog,
+            );
+            result = self
+                .run_command_with_progress(pass2, output_duration, Some(&second_half))
+                .await;
+        }
+
+        // ffmpeg writes {passlog}-0.log and {passlog}-0.log.mbtree
+        for suffix in ["-0.log", "-0.log.mbtree"] {
+            let _ = std::fs::remove_file(with_suffix(&passlog, suffix));
+        }
+
+        result?;
+
+        if let Err(e) = self.verify_output(output_path, output_duration).await {
+            let _ = std::fs::remove_file(output_path);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Re-encode from `start` up to the first keyframe, stream copy the rest, then join
+    ///
+    /// The head is encoded to match the probed tail. Fails (so the caller falls
+    /// back to a full re-encode) when the codecs can't be matched or the parts
+    /// or joined file don't share codec parameters.
+    async fn export_smart_cut(
+        &self,
+        input: &str,
+        timing: &ClipTiming,
+        options: &ExportOptions,
+        output_path: &Path,
+        progress: Option<&ProgressCallback>,
+    ) -> ExportResult<()> {
+        let end = timing.start + timing.duration;
+        let keyframe = self
+            .find_keyframe_after(input, timing.start, SMART_CUT_WINDOW.min(timing.duration))
+            .await?
+            .ok_or_else(|| {
+                ExportError::Ffmpeg("No keyframe found near the clip start".to_string())
+            })?;
+
+        // Start already sits on a keyframe: a plain stream copy is frame-accurate
+        if keyframe - timing.start < SMART_CUT_MIN_HEAD {
+            let copy_cmd = self.build_copy_command(input, timing, options, output_path);
+            self.run_command_with_progress(
+                copy_cmd,
+                timing.duration,
+                progress.map(|cb| cb.as_ref()),
+            )
+            .await?;
+            return self.verify_output(output_path, timing.duration).await;
+        }
+
+        log::info!(
+            "[FFmpeg] Smart cut: re-encoding {:.2}s-{:.2}s, copying {:.2}s-{:.2}s",
+            timing.start,
+            keyframe,
+            keyframe,
+            end
+        );
+
+        let head_path = with_suffix(output_path, ".head.mp4");
+        let tail_path = with_suffix(output_path, ".tail.mp4");
+        let list_path = with_suffix(output_path, ".concat.txt");
+
+        let result = async {
+            let head_timing = ClipTiming::new(timing.start, keyframe - timing.start);
+            let tail_timing = ClipTiming::new(keyframe, end - keyframe);
+
+            // The copied tail sets the codec parameters the head has to match.
+            // The re-encoded head is short; report progress for the tail only
+            let tail_cmd = self.build_copy_command(input, &tail_timing, options, &tail_path);
+            self.run_command_with_progress(
+                tail_cmd,
+                tail_timing.duration,
+                progress.map(|cb| cb.as_ref()),
+            )
+            .await?;
+            let tail_params = self.probe_stream_params(&tail_path).await?;
+
+            let head_cmd = self
+                .build_smart_cut_head_command(
+                    input,
+                    &head_timing,
+                    options,
+                    &tail_params,
+                    &head_path,
+                )
+                .ok_or_else(|| {
+                    ExportError::Ffmpeg("Source codecs can't be matched for smart cut".to_string())
+                })?;
+            self.run_command_with_progress(head_cmd, head_timing.duration, None)
+                .await?;
+            let head_params = self.probe_stream_params(&head_path).await?;
+            if !head_params.joinable_with(&tail_params) {
+                return Err(ExportError::Ffmpeg(
+                    "Smart cut head doesn't match the source codec parameters".to_string(),
+                ));
+            }
+
+            let list = [&head_path, &tail_path]
+                .iter()
+                .map(|p| concat_list_entry(p))
+                .collect::<Vec<_>>()
+                .join("\n");
+            std::fs::write(&list_path, list).map_err(|e| {
+                ExportError::OutputDir(format!("Failed to write concat list: {}", e))
+            })?;
+
+            let concat_cmd = self.build_concat_command(&list_path, options, output_path);
+            self.run_command_with_progress(concat_cmd, timing.duration, None)
+                .await?;
+
+            self.verify_output(output_path, timing.duration).await?;
+            if !self
+                .probe_stream_params(output_path)
+                .await?
+                .joinable_with(&tail_params)
+            {
+                return Err(ExportError::Ffmpeg(
+                    "Smart cut output has different codec parameters than the source".to_string(),
+                ));
+            }
+            Ok(())
+        }
+        .await;
+
+        for path in [&head_path, &tail_path, &list_path] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        if result.is_err() {
+            let _ = std::fs::remove_file(output_path);
+        }
+
+        result
+    }
+
+    /// Re-cut a local clip file into a new file (stream copy first, re-encode as fallback)
+    pub async fn trim_local(
+        &self,
+        input: &Path,
+        timing: &ClipTiming,
+        options: &ExportOptions,
+        output_path: &Path,
+        progress: Option<&ProgressCallback>,
+        attempts: Option<&AttemptCallback>,
+    ) -> ExportResult<()> {
+        if !input.exists() {
+            return Err(ExportError::Ffmpeg(format!(
+                "Clip file not found: {}",
+                input.display()
+            )));
+        }
+
+        let source = ResolvedVod {
+            url: input.to_string_lossy().to_string(),
+            is_hls: false,
+            recorded_until: None,
+        };
+
+        self.export_with_retry(&source, timing, options, output_path, progress, attempts)
+            .await
+    }
+
+    /// Re-encode a local clip in place to `format` (its own frame size at a
+    /// constant rate when unset), keeping a `.bak` copy
+    ///
+    /// Returns the path of the backup file.
+    pub async fn normalize_local(
+        &self,
+        path: &Path,
+        format: Option<&NormalizeFormat>,
+        progress: Option<&ProgressCallback>,
+    ) -> ExportResult<PathBuf> {
+        if !path.exists() {
+            return Err(ExportError::Ffmpeg(format!(
+                "Clip file not found: {}",
+                path.display()
+            )));
+        }
+
+        let temp_path = with_suffix(path, ".normalizing");
+        let backup_path = backup_path(path);
+
+        log::info!("[FFmpeg] Normalizing {}", path.display());
+        self.normalize_to(path, &temp_path, format, progress)
+            .await?;
+
+        // Swap files: original -> backup, normalized -> original
+        replace_file(path, &backup_path)
+            .map_err(|e| ExportError::OutputDir(format!("Failed to create backup: {}", e)))?;
+        if let Err(e) = replace_file(&temp_path, path) {
+            let _ = replace_file(&backup_path, path);
+            return Err(ExportError::OutputDir(format!(
+                "Failed to replace clip: {}",
+                e
+            )));
+        }
+
+        log::info!(
+            "[FFmpeg] Normalized {} (backup: {})",
+            path.display(),
+            backup_path.display()
+        );
+        Ok(backup_path)
+    }
+
+    /// Write a copy of a local file converted to `format` to `output` (the
+    /// input's own frame size at a constant rate when unset)
+    ///
+    /// `output` is removed again if encoding or verification fails.
+    pub async fn normalize_to(
+        &self,
+        input: &Path,
+        output: &Path,
+        format: Option<&NormalizeFormat>,
+        progress: Option<&ProgressCallback>,
+    ) -> ExportResult<()> {
+        let duration = self.probe_duration(input).await?;
+        let format = match format {
+            Some(format) => format.clone(),
+            None => NormalizeFormat::of_source(&self.probe_stream_params(input).await?),
+        };
+        let format = &format;
+        let ffmpeg_config = get_config().ffmpeg.clone();
+        let run = |ffmpeg_config: FfmpegConfig| async move {
+            let cmd = self.build_normalize_command(input, output, format, &ffmpeg_config);
+            self.run_command_with_progress(cmd, duration, progress.map(|cb| cb.as_ref()))
+                .await?;
+            self.verify_output(output, duration).await
+        };
+
+        let mut result = run(ffmpeg_config.clone()).await;
+        if let (Err(e), Some(software)) = (&result, ffmpeg_config.software_fallback()) {
+            log::warn!(
+                "[FFmpeg] {} failed, falling back to libx264: {}",
+                ffmpeg_config.encoder.as_str(),
+                e
+            );
+            result = run(software).await;
+        }
+
+        if result.is_err() {
+            let _ = std::fs::remove_file(output);
+        }
+        result
+    }
+
+    /// Write a local file into the container of `output`: stream copy first,
+    /// a full re-encode when the streams don't fit that container
+    ///
+    /// `output` is removed again if both fail.
+    pub async fn remux_local(
+        &self,
+        input: &Path,
+        output: &Path,
+        progress: Option<&ProgressCallback>,
+    ) -> ExportResult<()> {
+        let duration = self.probe_duration(input).await?;
+        let cmd = self.build_remux_command(input, output);
 
-            // Try copy first if enabled and this is the first attempt
-            if self.try_copy_first && attempt == 1 {
-                let copy_cmd = self.build_copy_command(&vod.url, timing, output_path);
+        let copied = async {
+            self.run_command_with_progress(cmd, duration, progress.map(|cb| cb.as_ref()))
+                .await?;
+            self.verify_output(output, duration).await
+        }
+        .await;
 
-                match self
-                    .run_command_with_progress(copy_cmd, timing.duration, progress)
-                    .await
-                {
-                    Ok(()) => {
-                        // Verify output
-                        if let Err(e) = self.verify_output(output_path, timing.duration).await {
-                            log::warn!("[FFmpeg] Output verification failed: {}", e);
-                            let _ = std::fs::remove_file(output_path);
-                        } else {
-                            log::info!("[FFmpeg] Export successful (stream copy)");
-                            return Ok(());
-                        }
-                    }
-                    Err(e) => {
-                        log::warn!("[FFmpeg] Copy failed: {}", e);
-                        last_error = Some(e);
-                    }
-                }
+        match copied {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                log::warn!(
+                    "[FFmpeg] Remux of {} failed, re-encoding: {}",
+                    input.display(),
+                    e
+                );
+                let _ = std::fs::remove_file(output);
+                self.normalize_to(input, output, None, progress).await
             }
+        }
+    }
 
-            // Try re-encoding
-            let encode_cmd = self.build_encode_command(&vod.url, timing, output_path);
+    /// Write the frame at `timestamp` (seconds) of a local file or stream URL to `output`
+    pub async fn extract_frame(
+        &self,
+        input: &str,
+        timestamp: f64,
+        format: FrameFormat,
+        output: &Path,
+    ) -> ExportResult<()> {
+        if !timestamp.is_finite() || timestamp < 0.0 {
+            return Err(ExportError::InvalidTimeRange {
+                start: timestamp,
+                end: timestamp,
+            });
+        }
 
-            match self
-                .run_command_with_progress(encode_cmd, timing.duration, progress)
-                .await
-            {
-                Ok(()) => {
-                    // Verify output
-                    if let Err(e) = self.verify_output(output_path, timing.duration).await {
-                        log::warn!("[FFmpeg] Output verification failed: {}", e);
-                        let _ = std::fs::remove_file(output_path);
-                        last_error = Some(e);
-                    } else {
-                        log::info!("[FFmpeg] Export successful (re-encoded)");
-                        return Ok(());
-                    }
-                }
-                Err(e) => {
-                    log::warn!("[FFmpeg] Encode failed: {}", e);
-                    last_error = Some(e);
-                }
+        let mut cmd =
+            self.frame_command_with(input, timestamp, format, output, &get_config().ffmpeg);
+        cmd.kill_on_drop(true);
+        let result = timeout(FRAME_TIMEOUT, cmd.output()).await.map_err(|_| {
+            ExportError::Timeout(format!(
+                "Frame extraction timed out after {} seconds",
+                FRAME_TIMEOUT.as_secs()
+            ))
+        })?;
+        let output_log =
+            result.map_err(|e| ExportError::Ffmpeg(format!("Failed to run FFmpeg: {}", e)))?;
+
+        let size = std::fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+        if !output_log.status.success() || size == 0 {
+            let _ = std::fs::remove_file(output);
+            let stderr = String::from_utf8_lossy(&output_log.stderr);
+            log::warn!("[FFmpeg] Frame extraction failed: {}", stderr.trim());
+            return Err(ExportError::Ffmpeg(format!(
+                "No frame at {:.3}s in {}",
+                timestamp, input
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Write a window of a source's audio to `output` as mono 16 kHz WAV
+    ///
+    /// Meant for an audio-only stream (see `VodResolverChain::resolve_audio`),
+    /// so only the audio of the window is downloaded.
+    pub async fn extract_audio_window(
+        &self,
+        input: &str,
+        timing: &ClipTiming,
+        output: &Path,
+    ) -> ExportResult<()> {
+        timing.validate()?;
+
+        let temp_path = with_suffix(output, ".partial");
+        let mut cmd =
+            self.audio_window_command_with(input, timing, &temp_path, &get_config().ffmpeg);
+        cmd.kill_on_drop(true);
+        let result = match timeout(AUDIO_TIMEOUT, cmd.output()).await {
+            Ok(result) => {
+                result.map_err(|e| ExportError::Ffmpeg(format!("Failed to run FFmpeg: {}", e)))
             }
+            Err(_) => Err(ExportError::Timeout(format!(
+                "Audio download timed out after {} seconds",
+                AUDIO_TIMEOUT.as_secs()
+            ))),
+        };
+        let output_log = result.inspect_err(|_| {
+            let _ = std::fs::remove_file(&temp_path);
+        })?;
+
+        let size = std::fs::metadata(&temp_path).map(|m| m.len()).unwrap_or(0);
+        if !output_log.status.success() || size == 0 {
+            let _ = std::fs::remove_file(&temp_path);
+            let stderr = String::from_utf8_lossy(&output_log.stderr);
+            log::warn!("[FFmpeg] Audio extraction failed: {}", stderr.trim());
+            return Err(ExportError::Ffmpeg(format!(
+                "No audio between {:.1}s and {:.1}s in {}",
+                timing.start,
+                timing.start + timing.duration,
+                input
+            )));
         }
 
-        Err(last_error.unwrap_or_else(|| ExportError::Ffmpeg("Export failed".to_string())))
+        std::fs::rename(&temp_path, output)
+            .map_err(|e| ExportError::OutputDir(format!("Failed to save audio: {}", e)))?;
+        Ok(())
+    }
+
+    /// Write a 360p proxy of a local clip to `output`
+    pub async fn generate_proxy(
+        &self,
+        input: &Path,
+        output: &Path,
+        progress: Option<&ProgressCallback>,
+    ) -> ExportResult<()> {
+        let duration = self.probe_duration(input).await?;
+        let temp_path = with_suffix(output, ".partial");
+
+        let cmd = self.build_proxy_command(input, &temp_path);
+        let result = async {
+            self.run_command_with_progress(cmd, duration, progress.map(|cb| cb.as_ref()))
+                .await?;
+            self.verify_output(&temp_path, duration).await?;
+            std::fs::rename(&temp_path, output)
+                .map_err(|e| ExportError::OutputDir(format!("Failed to move proxy: {}", e)))
+        }
+        .await;
+
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        result
+    }
+}
+
+/// ffprobe JSON output for keyframe lookup
+#[derive(Debug, Deserialize)]
+struct KeyframeProbe {
+    #[serde(default)]
+    frames: Vec<ProbeFrame>,
+    format: Option<ProbeFormat>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProbeFrame {
+    pts_time: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProbeFormat {
+    start_time: Option<String>,
+}
+
+impl KeyframeProbe {
+    /// First keyframe in [from, to], in seconds relative to the input start
+    fn first_keyframe_after(&self, from: f64, to: f64) -> Option<f64> {
+        let start_time = self
+            .format
+            .as_ref()
+            .and_then(|f| f.start_time.as_deref())
+            .and_then(|t| t.parse::<f64>().ok())
+            .unwrap_or(0.0);
+
+        self.frames
+            .iter()
+            .filter_map(|f| f.pts_time.as_deref()?.parse::<f64>().ok())
+            .map(|pts| pts - start_time)
+            .filter(|t| *t >= from && *t <= to)
+            .min_by(|a, b| a.total_cmp(b))
+    }
+}
+
+/// Codec parameters of a file's streams; files with equal parameters can be
+/// joined by the concat demuxer without re-encoding
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StreamParams {
+    #[serde(default)]
+    streams: Vec<ProbeStream>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct ProbeStream {
+    codec_type: Option<String>,
+    codec_name: Option<String>,
+    profile: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    pix_fmt: Option<String>,
+    r_frame_rate: Option<String>,
+    time_base: Option<String>,
+    sample_rate: Option<String>,
+    channels: Option<u32>,
+}
+
+impl StreamParams {
+    /// Whether the file has both a video and an audio stream
+    pub fn has_video_and_audio(&self) -> bool {
+        let has = |kind: &str| {
+            self.streams
+                .iter()
+                .any(|s| s.codec_type.as_deref() == Some(kind))
+        };
+        has("video") && has("audio")
+    }
+
+    fn first(&self, kind: &str) -> Option<&ProbeStream> {
+        self.streams
+            .iter()
+            .find(|s| s.codec_type.as_deref() == Some(kind))
+    }
+
+    /// Codec name and frame size of the first video stream
+    pub fn video(&self) -> Option<(String, Option<u32>, Option<u32>)> {
+        self.first("video")
+            .map(|s| (s.codec_name.clone().unwrap_or_default(), s.width, s.height))
+    }
+
+    /// Codec name of the first audio stream
+    pub fn audio_codec(&self) -> Option<String> {
+        self.first("audio")
+            .map(|s| s.codec_name.clone().unwrap_or_default())
+    }
+
+    /// Whether the concat demuxer can join this file and `other` without
+    /// re-encoding: same codecs, profile, frame size, pixel format, time base
+    /// and audio layout (frame rates of short parts are allowed to differ)
+    pub fn joinable_with(&self, other: &StreamParams) -> bool {
+        ["video", "audio"]
+            .iter()
+            .all(|kind| match (self.first(kind), other.first(kind)) {
+                (Some(a), Some(b)) => {
+                    a.codec_name == b.codec_name
+                        && a.profile == b.profile
+                        && a.width == b.width
+                        && a.height == b.height
+                        && a.pix_fmt == b.pix_fmt
+                        && a.time_base == b.time_base
+                        && a.sample_rate == b.sample_rate
+                        && a.channels == b.channels
+                }
+                (None, None) => true,
+                _ => false,
+            })
+    }
+
+    /// Frame rate of the first video stream
+    pub fn frame_rate(&self) -> Option<f64> {
+        let rate = self.first("video")?.r_frame_rate.as_deref()?;
+        let (num, den) = rate.split_once('/').unwrap_or((rate, "1"));
+        let (num, den): (f64, f64) = (num.parse().ok()?, den.parse().ok()?);
+        (num > 0.0 && den > 0.0).then(|| num / den)
+    }
+}
+
+/// libx264 `-profile:v` value for an H.264 profile reported by ffprobe
+fn x264_profile(profile: &str) -> Option<&'static str> {
+    match profile {
+        "Baseline" | "Constrained Baseline" => Some("baseline"),
+        "Main" => Some("main"),
+        "High" => Some("high"),
+        "High 10" => Some("high10"),
+        "High 4:2:2" => Some("high422"),
+        "High 4:4:4 Predictive" => Some("high444"),
+        _ => None,
+    }
+}
+
+/// First free `<file>.bak`, `<file>.2.bak`, ... next to `path`, so an earlier
+/// backup is never overwritten
+fn backup_path(path: &Path) -> PathBuf {
+    std::iter::once(with_suffix(path, ".bak"))
+        .chain((2..).map(|n| with_suffix(path, &format!(".{n}.bak"))))
+        .find(|candidate| !candidate.exists())
+        .expect("unbounded candidates")
+}
+
+/// Rename `from` to `to`, removing `to` first: renaming onto an existing file
+/// isn't reliable on Windows
+fn replace_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if to.exists() {
+        std::fs::remove_file(to)?;
+    }
+    std::fs::rename(from, to)
+}
+
+/// Format a path as a line of an ffmpeg concat demuxer list
+///
+/// Verbatim (`\\?\`) prefixes are dropped since they don't survive the
+/// slash conversion; UNC shares become `//server/share/...`.
+pub(crate) fn concat_list_entry(path: &Path) -> String {
+    let path = crate::workdir::simplify(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+        .replace('\'', "'\\''");
+    format!("file '{}'", path)
+}
+
+/// Clip format files are converted to so they can be joined
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalizeFormat {
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    pub sample_rate: u32,
+}
+
+impl NormalizeFormat {
+    pub fn new(width: u32, height: u32, fps: u32) -> Self {
+        Self {
+            width,
+            height,
+            fps,
+            sample_rate: NORMALIZE_SAMPLE_RATE,
+        }
+    }
+
+    /// A source's own frame size at its rate rounded to a whole, constant
+    /// one (1080p60 for a file without video)
+    pub fn of_source(params: &StreamParams) -> Self {
+        let Some((_, Some(width), Some(height))) = params.video() else {
+            return Self::new(1920, 1080, 60);
+        };
+        let fps = params
+            .frame_rate()
+            .map_or(60, |fps| (fps.round() as u32).clamp(1, MAX_NORMALIZE_FPS));
+        // yuv420p needs even sizes
+        Self::new(width & !1, height & !1, fps)
+    }
+
+    /// Build the -vf chain that scales/pads into the target frame
+    fn video_filter(&self) -> String {
+        let (w, h) = (self.width, self.height);
+        format!(
+            "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1,fps={},format=yuv420p",
+            self.fps
+        )
+    }
+}
+
+/// Tell the progress callback that a new attempt is starting
+fn report_attempt(attempts: Option<&AttemptCallback>, attempt: u32, method: ExportMethod) {
+    if let Some(cb) = attempts {
+        cb(ExportAttempt { attempt, method });
+    }
+}
+
+/// Build the -filter:v chain for the options, if any filter is needed
+///
+/// The timecode is drawn before setpts so it always shows source VOD time.
+/// Timestamps start at 0 after input seeking, so `start` is added as the offset.
+fn video_filter_chain(options: &ExportOptions, start: f64, font_path: &str) -> Option<String> {
+    // Crop/scale first so the timecode is drawn at output size
+    let mut filters = options.filters.video_filters();
+
+    if options.burn_timecode {
+        filters.push(format!(
+            "drawtext=fontfile='{}':text='%{{pts\\:hms\\:{:.3}}}':x=20:y=h-th-20:fontsize={}:fontcolor=white:box=1:boxcolor=black@0.5:boxborderw=6",
+            font_path, start, TIMECODE_FONT_SIZE
+        ));
+    }
+
+    if options.changes_speed() {
+        filters.push(format!("setpts=PTS/{}", options.speed()));
+    }
+
+    if filters.is_empty() {
+        None
+    } else {
+        Some(filters.join(","))
+    }
+}
+
+/// Single-pass EBU R128 normalization to `lufs`
+pub fn loudnorm_filter(lufs: f64) -> String {
+    format!(
+        "loudnorm=I={}:TP={}:LRA={}",
+        lufs, LOUDNORM_TRUE_PEAK, LOUDNORM_RANGE
+    )
+}
+
+/// Build the -filter:a chain (denoise, speed change, then loudness normalization), if any
+fn audio_filter_chain(options: &ExportOptions, loudness_lufs: Option<f64>) -> Option<String> {
+    let mut filters = options.filters.audio_filters();
+
+    if options.changes_speed() {
+        filters.push(atempo_filter(options.speed()));
+    }
+
+    // loudnorm resamples to 192kHz internally; bring it back down
+    if let Some(lufs) = loudness_lufs {
+        filters.push(loudnorm_filter(lufs));
+        filters.push(format!("aresample={}", NORMALIZE_SAMPLE_RATE));
+    }
+
+    if filters.is_empty() {
+        None
+    } else {
+        Some(filters.join(","))
+    }
+}
+
+/// Build an atempo filter chain for `speed`
+///
+/// A single atempo stage only accepts 0.5-2.0, so larger changes are chained.
+fn atempo_filter(speed: f64) -> String {
+    let mut stages = Vec::new();
+    let mut remaining = speed;
+    while remaining < 0.5 {
+        stages.push("atempo=0.5".to_string());
+        remaining /= 0.5;
+    }
+    while remaining > 2.0 {
+        stages.push("atempo=2".to_string());
+        remaining /= 2.0;
+    }
+    stages.push(format!("atempo={}", remaining));
+    stages.join(",")
+}
+
+/// Compute the video bitrate (kbit/s) that fits `duration` seconds into `max_size_mb`
+pub fn target_video_kbps(max_size_mb: f64, duration: f64, audio_kbps: u32) -> ExportResult<u32> {
+    if duration <= 0.0 {
+        return Err(ExportError::InvalidTimeRange {
+            start: 0.0,
+            end: duration,
+        });
+    }
+
+    let total_kbits = max_size_mb * 1024.0 * 1024.0 * 8.0 / 1000.0 * SIZE_OVERHEAD_FACTOR;
+    let video_kbps = total_kbits / duration - audio_kbps as f64;
+
+    if video_kbps < MIN_VIDEO_KBPS as f64 {
+        return Err(ExportError::TargetSizeTooSmall {
+            size_mb: max_size_mb,
+            duration,
+        });
+    }
+
+    Ok(video_kbps as u32)
+}
+
+/// Parse an ffmpeg bitrate string like "128k" into kbit/s
+fn parse_kbps(bitrate: &str) -> u32 {
+    let trimmed = bitrate.trim().to_lowercase();
+    match trimmed.strip_suffix('k') {
+        Some(kbps) => kbps.parse().unwrap_or(128),
+        None => trimmed.parse::<u32>().map(|bps| bps / 1000).unwrap_or(128),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::export::{argv, ClipFilters};
+
+    #[test]
+    fn test_exceeds_size_limit() {
+        let options = ExportOptions::default();
+        let size = 60 * 1024 * 1024;
+        assert!(!FfmpegExporter::new().exceeds_size_limit(size, &options));
+        // Over the global cap only
+        let capped = FfmpegExporter::new().with_max_size_mb(Some(50.0));
+        assert!(capped.exceeds_size_limit(size, &options));
+        // The options' limit wins over the global one
+        let larger = ExportOptions {
+            max_size_mb: Some(100.0),
+            ..Default::default()
+        };
+        assert!(!capped.exceeds_size_limit(size, &larger));
+    }
+
+    #[test]
+    fn test_export_timeout() {
+        // Short clips keep the 5 minute floor, long ones scale with their length
+        assert_eq!(export_timeout(30.0), Duration::from_secs(300));
+        assert_eq!(export_timeout(5400.0), Duration::from_secs(21600));
+        assert_eq!(export_timeout(f64::INFINITY), Duration::from_secs(300));
+    }
 
     #[test]
     fn test_timing() {
@@ -368,5 +1953,420 @@ mod tests {
 
         let too_long = ClipTiming::new(10.0, 4000.0);
         assert!(too_long.validate().is_err());
+        // A raised per-project ceiling allows longer clips
+        let full_map = ClipTiming::new(10.0, 5400.0);
+        assert!(full_map.validate_with_max(5400.0).is_ok());
+        assert!(matches!(
+            full_map.validate_with_max(3600.0),
+            Err(ExportError::DurationTooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn test_target_video_kbps() {
+        // 25MB over 60s with 128k audio
+        let kbps = target_video_kbps(25.0, 60.0, 128).unwrap();
+        assert!(kbps > 3000 && kbps < 3500);
+
+        // 1MB can't hold 10 minutes of video
+        assert!(target_video_kbps(1.0, 600.0, 128).is_err());
+    }
+
+    #[test]
+    fn test_parse_kbps() {
+        assert_eq!(parse_kbps("128k"), 128);
+        assert_eq!(parse_kbps("192K"), 192);
+        assert_eq!(parse_kbps("96000"), 96);
+    }
+
+    #[test]
+    fn test_atempo_filter() {
+        assert_eq!(atempo_filter(0.5), "atempo=0.5");
+        assert_eq!(atempo_filter(1.5), "atempo=1.5");
+        assert_eq!(atempo_filter(0.25), "atempo=0.5,atempo=0.5");
+        assert_eq!(atempo_filter(4.0), "atempo=2,atempo=2");
+    }
+
+    #[test]
+    fn test_video_filter_chain() {
+        assert_eq!(
+            video_filter_chain(&ExportOptions::default(), 10.0, "font.ttf"),
+            None
+        );
+
+        let options = ExportOptions {
+            speed: Some(2.0),
+            burn_timecode: true,
+            ..Default::default()
+        };
+        let chain = video_filter_chain(&options, 3725.5, "font.ttf").unwrap();
+        assert!(chain.starts_with("drawtext=fontfile='font.ttf':text='%{pts\\:hms\\:3725.500}'"));
+        assert!(chain.ends_with(",setpts=PTS/2"));
+
+        // A clip cut from a local download keeps the VOD timecode
+        let options = ExportOptions {
+            burn_timecode: true,
+            timecode_start: Some(3725.5),
+            ..Default::default()
+        };
+        let args = argv(&FfmpegExporter::new().encode_command_with(
+            "clip.full",
+            &ClipTiming::new(0.0, 10.0),
+            &options,
+            Path::new("clip.mp4"),
+            &FfmpegConfig::with_encoder(VideoEncoder::Libx264),
+        ))
+        .join(" ");
+        assert!(args.contains("%{pts\\:hms\\:3725.500}"));
+    }
+
+    #[test]
+    fn test_audio_filter_chain() {
+        let options = ExportOptions::default();
+        assert_eq!(audio_filter_chain(&options, None), None);
+        assert_eq!(
+            audio_filter_chain(&options, Some(-14.0)).as_deref(),
+            Some("loudnorm=I=-14:TP=-1.5:LRA=11,aresample=48000")
+        );
+
+        let slow = ExportOptions {
+            speed: Some(0.5),
+            ..Default::default()
+        };
+        assert_eq!(
+            audio_filter_chain(&slow, Some(-14.0)).as_deref(),
+            Some("atempo=0.5,loudnorm=I=-14:TP=-1.5:LRA=11,aresample=48000")
+        );
+    }
+
+    #[test]
+    fn test_speed_options() {
+        let options = ExportOptions {
+            speed: Some(0.5),
+            ..Default::default()
+        };
+        assert!(options.needs_reencode());
+        assert_eq!(options.output_duration(10.0), 20.0);
+        assert!(options.validate().is_ok());
+
+        assert!(!ExportOptions::default().needs_reencode());
+        assert!(ExportOptions {
+            speed: Some(8.0),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_first_keyframe_after() {
+        let probe: KeyframeProbe = serde_json::from_str(
+            r#"{"frames":[{"pts_time":"11.400000"},{"pts_time":"13.400000"},{"pts_time":"15.400000"}],
+                "format":{"start_time":"1.400000"}}"#,
+        )
+        .unwrap();
+        assert_eq!(probe.first_keyframe_after(10.5, 20.0), Some(12.0));
+        assert_eq!(probe.first_keyframe_after(14.5, 20.0), None);
+    }
+
+    #[test]
+    fn test_concat_list_entry() {
+        assert_eq!(
+            concat_list_entry(Path::new("/clips/it's.mp4")),
+            "file '/clips/it'\\''s.mp4'"
+        );
+        assert_eq!(
+            concat_list_entry(Path::new(r"\\?\UNC\nas\media\a.mp4")),
+            "file '//nas/media/a.mp4'"
+        );
+    }
+
+    #[test]
+    fn test_normalize_filter() {
+        let filter = NormalizeFormat::new(1920, 1080, 60).video_filter();
+        assert!(filter.starts_with("scale=1920:1080"));
+        assert!(filter.contains("pad=1920:1080"));
+        assert!(filter.ends_with("fps=60,format=yuv420p"));
+    }
+
+    #[test]
+    fn test_normalize_format_of_source() {
+        let params: StreamParams = serde_json::from_str(
+            r#"{"streams":[
+                {"codec_type":"video","codec_name":"h264","width":1281,"height":720,"r_frame_rate":"30000/1001"},
+                {"codec_type":"audio","codec_name":"aac","sample_rate":"44100"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            NormalizeFormat::of_source(&params),
+            NormalizeFormat::new(1280, 720, 30)
+        );
+
+        let vfr: StreamParams = serde_json::from_str(
+            r#"{"streams":[{"codec_type":"video","width":1920,"height":1080,"r_frame_rate":"1000/1"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(NormalizeFormat::of_source(&vfr).fps, 60);
+    }
+
+    #[test]
+    fn test_smart_cut_head_matches_tail() {
+        let tail: StreamParams = serde_json::from_str(
+            r#"{"streams":[
+                {"codec_type":"video","codec_name":"h264","profile":"High","width":1920,"height":1080,
+                 "pix_fmt":"yuv420p","r_frame_rate":"30000/1001","time_base":"1/90000"},
+                {"codec_type":"audio","codec_name":"aac","profile":"LC","sample_rate":"44100","channels":2,
+                 "time_base":"1/44100"}
+            ]}"#,
+        )
+        .unwrap();
+        let exporter = FfmpegExporter::new();
+        let args = argv(
+            &exporter
+                .build_smart_cut_head_command(
+                    "vod.mp4",
+                    &ClipTiming::new(10.0, 1.5),
+                    &ExportOptions::default(),
+                    &tail,
+                    Path::new("clip.head.mp4"),
+                )
+                .unwrap(),
+        )
+        .join(" ");
+        assert!(args.contains("-c:v libx264"));
+        assert!(args.contains(
+            "-pix_fmt yuv420p -profile:v high -r 30000/1001 -video_track_timescale 90000"
+        ));
+        assert!(args.contains("-ar 44100 -ac 2"));
+        assert!(tail.joinable_with(&tail));
+
+        // A head at another size can't be joined
+        let mut head = tail.clone();
+        head.streams[0].width = Some(1280);
+        assert!(!head.joinable_with(&tail));
+
+        // No libx264 match for HEVC sources: fall back to a full re-encode
+        let mut hevc = tail.clone();
+        hevc.streams[0].codec_name = Some("hevc".into());
+        assert!(exporter
+            .build_smart_cut_head_command(
+                "vod.mp4",
+                &ClipTiming::new(10.0, 1.5),
+                &ExportOptions::default(),
+                &hevc,
+                Path::new("clip.head.mp4"),
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn test_backup_path() {
+        let dir = std::env::temp_dir().join(format!("nox-backup-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let clip = dir.join("clip.mp4");
+        assert_eq!(backup_path(&clip), dir.join("clip.mp4.bak"));
+        std::fs::write(dir.join("clip.mp4.bak"), b"old").unwrap();
+        assert_eq!(backup_path(&clip), dir.join("clip.mp4.2.bak"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_container() {
+        assert_eq!(
+            Container::from_path(Path::new("/clips/a.MKV")),
+            Some(Container::Mkv)
+        );
+        assert_eq!(Container::from_path(Path::new("/clips/a.webm")), None);
+        assert!(Container::Mov.supports_faststart());
+        assert!(!Container::Ts.supports_faststart());
+
+        let options = ExportOptions {
+            container: Container::Mkv,
+            ..Default::default()
+        };
+        let cmd = FfmpegExporter::new().build_copy_command(
+            "https://example.com/index.m3u8",
+            &ClipTiming::new(10.0, 5.0),
+            &options,
+            Path::new("/clips/a.mkv"),
+        );
+        let args: Vec<_> = cmd.as_std().get_args().collect();
+        assert!(!args.contains(&std::ffi::OsStr::new("+faststart")));
+    }
+
+    #[test]
+    fn test_reconnect_args() {
+        let args_for = |input: &str, enabled: bool| {
+            let mut cmd = Command::new("ffmpeg");
+            let ffmpeg_config = FfmpegConfig {
+                hls_reconnect: enabled,
+                ..FfmpegConfig::with_encoder(VideoEncoder::Libx264)
+            };
+            FfmpegExporter::add_reconnect_args(&mut cmd, input, &ffmpeg_config);
+            argv(&cmd)
+        };
+
+        let args = args_for("https://example.com/index.m3u8", true);
+        assert_eq!(args[0..2], ["-reconnect", "1"]);
+        assert!(args.contains(&"-reconnect_streamed".to_string()));
+        assert!(args.contains(&"-http_persistent".to_string()));
+        assert!(args_for("https://example.com/index.m3u8", false).is_empty());
+        assert!(args_for("/clips/a.mp4", true).is_empty());
+    }
+
+    #[test]
+    fn test_hwaccel_args() {
+        let args_for = |encoder: VideoEncoder| {
+            let mut cmd = Command::new("ffmpeg");
+            FfmpegExporter::add_hwaccel_args(&mut cmd, &FfmpegConfig::with_encoder(encoder));
+            argv(&cmd)
+        };
+
+        assert!(args_for(VideoEncoder::Libx264).is_empty());
+        assert_eq!(args_for(VideoEncoder::H264Nvenc), ["-hwaccel", "cuda"]);
+        assert_eq!(args_for(VideoEncoder::H264Qsv), ["-hwaccel", "qsv"]);
+        assert_eq!(args_for(VideoEncoder::H264Amf)[0], "-hwaccel");
+
+        // The software retry drops -hwaccel and keeps the other settings
+        let nvenc = FfmpegConfig {
+            crf: 20,
+            ..FfmpegConfig::with_encoder(VideoEncoder::H264Nvenc)
+        };
+        let software = nvenc.software_fallback().unwrap();
+        assert_eq!(software.encoder, VideoEncoder::Libx264);
+        assert_eq!(software.crf, 20);
+        assert!(software.software_fallback().is_none());
+    }
+
+    #[test]
+    fn test_remux_command() {
+        let exporter = FfmpegExporter::new();
+        let args =
+            argv(&exporter.build_remux_command(Path::new("a.webm"), Path::new("a.mp4"))).join(" ");
+        assert!(args.contains("-i a.webm -c copy"));
+        assert!(args.ends_with("-movflags +faststart a.mp4"));
+
+        let args =
+            argv(&exporter.build_remux_command(Path::new("a.ts"), Path::new("a.mkv"))).join(" ");
+        assert!(!args.contains("faststart"));
+    }
+
+    // Snapshots of full argument vectors. Update them deliberately when the
+    // generated commands change; an unexpected diff here is usually a bug.
+
+    const HLS_URL: &str = "https://example.com/index.m3u8";
+
+    #[test]
+    fn test_copy_command_snapshot() {
+        let cmd = FfmpegExporter::new().copy_command_with(
+            HLS_URL,
+            &ClipTiming::new(97.25, 10.0),
+            &ExportOptions::default(),
+            Path::new("/clips/a.mp4"),
+            &FfmpegConfig::with_encoder(VideoEncoder::Libx264),
+        );
+        assert_eq!(
+            argv(&cmd).join(" "),
+            concat!(
+                "-y -reconnect 1 -reconnect_streamed 1 -reconnect_delay_max 5 -http_persistent 1 ",
+                "-live_start_index 0 -ss 97.25 -i https://example.com/index.m3u8 -t 10 -c copy -progress pipe:2 ",
+                "-movflags +faststart /clips/a.mp4"
+            )
+        );
+    }
+
+    #[test]
+    fn test_frame_command_snapshot() {
+        let cmd = FfmpegExporter::new().frame_command_with(
+            HLS_URL,
+            3600.5,
+            FrameFormat::Jpeg,
+            Path::new("/tmp/frame.jpg"),
+            &FfmpegConfig {
+                hls_reconnect: false,
+                ..FfmpegConfig::with_encoder(VideoEncoder::Libx264)
+            },
+        );
+        assert_eq!(
+            argv(&cmd).join(" "),
+            concat!(
+                "-y -live_start_index 0 -ss 3600.5 -i https://example.com/index.m3u8 -frames:v 1 -q:v 2 ",
+                "-f image2 -update 1 /tmp/frame.jpg"
+            )
+        );
+    }
+
+    #[test]
+    fn test_audio_window_command_snapshot() {
+        let cmd = FfmpegExporter::new().audio_window_command_with(
+            HLS_URL,
+            &ClipTiming::new(600.0, 120.0),
+            Path::new("/tmp/sync.wav"),
+            &FfmpegConfig {
+                hls_reconnect: false,
+                ..FfmpegConfig::with_encoder(VideoEncoder::Libx264)
+            },
+        );
+        assert_eq!(
+            argv(&cmd).join(" "),
+            concat!(
+                "-y -live_start_index 0 -ss 600 -i https://example.com/index.m3u8 -t 120 -vn -sn -dn ",
+                "-ac 1 -ar 16000 -c:a pcm_s16le -f wav /tmp/sync.wav"
+            )
+        );
+    }
+
+    #[test]
+    fn test_encode_command_snapshot() {
+        let options = ExportOptions {
+            speed: Some(2.0),
+            filters: ClipFilters {
+                max_height: Some(720),
+                audio_denoise: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let cmd = FfmpegExporter::new().encode_command_with(
+            HLS_URL,
+            &ClipTiming::new(97.25, 10.0),
+            &options,
+            Path::new("/clips/a.mp4"),
+            &FfmpegConfig::with_encoder(VideoEncoder::Libx264),
+        );
+        assert_eq!(
+            argv(&cmd).join(" "),
+            concat!(
+                "-y -reconnect 1 -reconnect_streamed 1 -reconnect_delay_max 5 -http_persistent 1 ",
+                "-live_start_index 0 -ss 97.25 -i https://example.com/index.m3u8 -t 5 ",
+                "-filter:v scale=-2:'min(ih,720)',setpts=PTS/2 -filter:a afftdn,atempo=2 ",
+                "-c:v libx264 -preset fast -crf 23 -c:a aac -b:a 128k ",
+                "-movflags +faststart -progress pipe:2 /clips/a.mp4"
+            )
+        );
+    }
+
+    #[test]
+    fn test_encode_command_snapshot_nvenc() {
+        let options = ExportOptions {
+            strip_audio: true,
+            container: Container::Mkv,
+            ..Default::default()
+        };
+        let cmd = FfmpegExporter::new().encode_command_with(
+            "/clips/source.mp4",
+            &ClipTiming::new(5.0, 3.5),
+            &options,
+            Path::new("/clips/a.mkv"),
+            &FfmpegConfig::with_encoder(VideoEncoder::H264Nvenc),
+        );
+        assert_eq!(
+            argv(&cmd).join(" "),
+            concat!(
+                "-y -hwaccel cuda -ss 5 -i /clips/source.mp4 -t 3.5 ",
+                "-c:v h264_nvenc -preset p4 -cq 23 -an -progress pipe:2 /clips/a.mkv"
+            )
+        );
     }
 }
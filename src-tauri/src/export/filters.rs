@@ -0,0 +1,187 @@
+//! Optional video/audio filters for re-encoded clips.
+//!
+//! Filters are set per project, per streamer (crop) or per clip and are only
+//! ever built from typed values, never from user-supplied filter strings.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ExportError, ExportResult};
+
+/// Smallest allowed crop / scale dimension (pixels)
+const MIN_DIMENSION: u32 = 16;
+
+/// Largest allowed scale cap (8K)
+const MAX_HEIGHT: u32 = 4320;
+
+/// Crop rectangle in source pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Filters applied when a clip is re-encoded
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts-bindings",
+    derive(ts_rs::TS),
+    ts(export, optional_fields)
+)]
+pub struct ClipFilters {
+    /// Crop the source (e.g. cut a webcam or game area out of the stream layout)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crop: Option<CropRect>,
+    /// Scale down to at most this height, keeping the aspect ratio
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_height: Option<u32>,
+    /// Light spatial/temporal video denoise (hqdn3d)
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(as = "Option<_>", optional))]
+    pub denoise: bool,
+    /// FFT audio denoise (afftdn), for noisy mics
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(as = "Option<_>", optional))]
+    pub audio_denoise: bool,
+}
+
+impl ClipFilters {
+    /// Whether no filter is set
+    pub fn is_empty(&self) -> bool {
+        self.crop.is_none() && self.max_height.is_none() && !self.denoise && !self.audio_denoise
+    }
+
+    /// Fill unset values from lower-priority settings (clip > streamer > project)
+    pub fn or(&self, fallback: &ClipFilters) -> ClipFilters {
+        ClipFilters {
+            crop: self.crop.or(fallback.crop),
+            max_height: self.max_height.or(fallback.max_height),
+            denoise: self.denoise || fallback.denoise,
+            audio_denoise: self.audio_denoise || fallback.audio_denoise,
+        }
+    }
+
+    /// Check dimensions before they end up in a filter graph
+    pub fn validate(&self) -> ExportResult<()> {
+        if let Some(crop) = self.crop {
+            if crop.width < MIN_DIMENSION || crop.height < MIN_DIMENSION {
+                return Err(ExportError::InvalidFilter(format!(
+                    "crop {}x{} is smaller than {}px",
+                    crop.width, crop.height, MIN_DIMENSION
+                )));
+            }
+        }
+
+        if let Some(height) = self.max_height {
+            if !(MIN_DIMENSION..=MAX_HEIGHT).contains(&height) {
+                return Err(ExportError::InvalidFilter(format!(
+                    "max height {} must be between {} and {}",
+                    height, MIN_DIMENSION, MAX_HEIGHT
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Video filters, in order: crop, scale cap, denoise
+    pub fn video_filters(&self) -> Vec<String> {
+        let mut filters = Vec::new();
+
+        if let Some(crop) = self.crop {
+            // Even dimensions keep yuv420p encoders happy
+            filters.push(format!(
+                "crop={}:{}:{}:{}",
+                crop.width & !1,
+                crop.height & !1,
+                crop.x,
+                crop.y
+            ));
+        }
+
+        if let Some(height) = self.max_height {
+            filters.push(format!("scale=-2:'min(ih,{})'", height & !1));
+        }
+
+        if self.denoise {
+            filters.push("hqdn3d".to_string());
+        }
+
+        filters
+    }
+
+    /// Audio filters to run before speed change and loudness normalization
+    pub fn audio_filters(&self) -> Vec<String> {
+        if self.audio_denoise {
+            vec!["afftdn".to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_video_filters() {
+        let filters = ClipFilters {
+            crop: Some(CropRect {
+                x: 10,
+                y: 20,
+                width: 641,
+                height: 360,
+            }),
+            max_height: Some(720),
+            denoise: true,
+            audio_denoise: false,
+        };
+        assert_eq!(
+            filters.video_filters(),
+            ["crop=640:360:10:20", "scale=-2:'min(ih,720)'", "hqdn3d"]
+        );
+        assert!(filters.audio_filters().is_empty());
+        assert!(ClipFilters::default().is_empty());
+    }
+
+    #[test]
+    fn test_or() {
+        let project = ClipFilters {
+            max_height: Some(1080),
+            audio_denoise: true,
+            ..Default::default()
+        };
+        let clip = ClipFilters {
+            max_height: Some(720),
+            ..Default::default()
+        };
+        let merged = clip.or(&project);
+        assert_eq!(merged.max_height, Some(720));
+        assert!(merged.audio_denoise);
+        assert!(!merged.denoise);
+    }
+
+    #[test]
+    fn test_validate() {
+        let tiny_crop = ClipFilters {
+            crop: Some(CropRect {
+                x: 0,
+                y: 0,
+                width: 8,
+                height: 8,
+            }),
+            ..Default::default()
+        };
+        assert!(tiny_crop.validate().is_err());
+
+        let huge = ClipFilters {
+            max_height: Some(10_000),
+            ..Default::default()
+        };
+        assert!(huge.validate().is_err());
+        assert!(ClipFilters::default().validate().is_ok());
+    }
+}
@@ -1,14 +1,30 @@
+pub mod batch;
+pub mod cache;
 mod ffmpeg;
+mod filters;
 mod progress;
+pub mod space;
+mod stderr;
 mod ytdlp;
 
-pub use ffmpeg::FfmpegExporter;
-pub use progress::{ClipResult, ExportProgress, FfmpegProgressParser, YtDlpProgressParser};
+pub(crate) use ffmpeg::concat_list_entry;
+pub use ffmpeg::{
+    loudnorm_filter, FfmpegExporter, NormalizeFormat, StreamParams, LOUDNESS_TARGET_RANGE,
+};
+pub use filters::{ClipFilters, CropRect};
+pub use progress::{
+    AttemptCallback, ClipResult, ExportAttempt, ExportMethod, ExportProgress, FfmpegProgressParser,
+    ProgressUpdate, YtDlpProgressParser,
+};
+pub(crate) use stderr::StderrTail;
 pub use ytdlp::YtDlpExporter;
 
-use crate::error::ExportResult;
-use crate::platform::ResolvedVod;
-use std::path::Path;
+use crate::config::get_config;
+use crate::error::{ExportError, ExportResult};
+use crate::platform::{LocalFileResolver, ResolvedVod};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Clip timing information
 #[derive(Debug, Clone)]
@@ -19,12 +35,23 @@ pub struct ClipTiming {
     pub duration: f64,
 }
 
-/// Maximum allowed clip duration (1 hour)
-const MAX_DURATION: f64 = 3600.0;
+/// Default maximum clip duration (1 hour), overridable per project
+pub const MAX_DURATION: f64 = 3600.0;
 
 /// Minimum allowed clip duration (100ms)
 const MIN_DURATION: f64 = 0.1;
 
+/// Allowed playback speed range for speed-adjusted exports
+const MIN_SPEED: f64 = 0.25;
+const MAX_SPEED: f64 = 4.0;
+
+/// Time a single FFmpeg or yt-dlp run may always take (5 minutes)
+const MIN_EXPORT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Seconds a run may take per second of output: two-pass encodes and
+/// merged downloads of long clips run well past realtime
+const EXPORT_TIMEOUT_FACTOR: f64 = 4.0;
+
 impl ClipTiming {
     pub fn new(start: f64, duration: f64) -> Self {
         Self { start, duration }
@@ -40,24 +67,168 @@ impl ClipTiming {
         }
     }
 
-    /// Validate the timing parameters
+    /// Validate the timing parameters against the default duration limit
     pub fn validate(&self) -> ExportResult<()> {
-        use crate::error::ExportError;
+        self.validate_with_max(MAX_DURATION)
+    }
 
+    /// Validate the timing parameters, allowing clips up to `max_duration` seconds
+    ///
+    /// Clips above the default limit pass when the ceiling was raised; the export
+    /// reports them back to the UI.
+    pub fn validate_with_max(&self, max_duration: f64) -> ExportResult<()> {
         if self.start < 0.0 {
             return Err(ExportError::InvalidStartTime(self.start));
         }
 
-        if self.duration < MIN_DURATION || self.duration > MAX_DURATION {
-            return Err(ExportError::InvalidDuration(self.duration));
+        if self.duration < MIN_DURATION {
+            return Err(ExportError::InvalidDuration {
+                duration: self.duration,
+                max: max_duration,
+            });
+        }
+
+        if self.duration > max_duration {
+            return Err(ExportError::DurationTooLong {
+                duration: self.duration,
+                max: max_duration,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Per-clip export options
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Maximum output size in MB (re-encode with two passes to fit)
+    pub max_size_mb: Option<f64>,
+    /// Frame-accurate start: re-encode only up to the first keyframe
+    pub smart_cut: bool,
+    /// Playback speed factor (0.5 = slow motion, 2.0 = timelapse)
+    pub speed: Option<f64>,
+    /// Draw the absolute VOD timecode in a corner of the clip
+    pub burn_timecode: bool,
+    /// VOD position of the clip's first frame, for the timecode, when the clip
+    /// is cut from a local file (the cut start when unset)
+    pub timecode_start: Option<f64>,
+    /// Drop the audio track (-an), e.g. to avoid music copyright claims
+    pub strip_audio: bool,
+    /// Output container
+    pub container: Container,
+    /// Per-project override of the maximum clip duration (seconds)
+    pub max_duration: Option<f64>,
+    /// Crop, scale cap and denoise filters (force a re-encode)
+    pub filters: ClipFilters,
+}
+
+/// Output container for exported clips
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Container {
+    #[default]
+    Mp4,
+    /// Matroska, handy for later lossless editing
+    Mkv,
+    Mov,
+    /// MPEG transport stream
+    Ts,
+}
+
+impl Container {
+    pub const ALL: [Container; 4] = [Self::Mp4, Self::Mkv, Self::Mov, Self::Ts];
+
+    /// File extension (without the dot)
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Mp4 => "mp4",
+            Self::Mkv => "mkv",
+            Self::Mov => "mov",
+            Self::Ts => "ts",
+        }
+    }
+
+    /// Container for a file, from its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        Self::ALL.into_iter().find(|c| c.extension() == ext)
+    }
+
+    /// Whether `-movflags +faststart` applies (only to MP4/MOV)
+    pub fn supports_faststart(self) -> bool {
+        matches!(self, Self::Mp4 | Self::Mov)
+    }
+
+    /// Whether ffprobe can reliably read a container-level duration
+    ///
+    /// MPEG-TS and freshly muxed Matroska files may report none.
+    pub fn has_duration_header(self) -> bool {
+        matches!(self, Self::Mp4 | Self::Mov)
+    }
+}
+
+/// Image format for extracted still frames
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameFormat {
+    #[default]
+    Jpeg,
+    /// Lossless, larger
+    Png,
+}
+
+impl FrameFormat {
+    /// File extension (without the dot)
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Jpeg => "jpg",
+            Self::Png => "png",
+        }
+    }
+}
+
+impl ExportOptions {
+    /// Maximum clip duration in seconds (the default limit when unset)
+    pub fn max_duration(&self) -> f64 {
+        self.max_duration.unwrap_or(MAX_DURATION)
+    }
+
+    /// Speed factor to apply (1.0 when unset)
+    pub fn speed(&self) -> f64 {
+        self.speed.unwrap_or(1.0)
+    }
+
+    /// Whether the options require re-encoding (stream copy can't apply them)
+    pub fn needs_reencode(&self) -> bool {
+        self.changes_speed() || self.burn_timecode || !self.filters.is_empty()
+    }
+
+    /// Whether the options change playback speed
+    pub fn changes_speed(&self) -> bool {
+        (self.speed() - 1.0).abs() > f64::EPSILON
+    }
+
+    /// Duration of the exported file for a source range of `source_duration`
+    pub fn output_duration(&self, source_duration: f64) -> f64 {
+        source_duration / self.speed()
+    }
+
+    /// Validate the option values
+    pub fn validate(&self) -> ExportResult<()> {
+        let speed = self.speed();
+        if !is_valid_speed(speed) {
+            return Err(ExportError::InvalidSpeed(speed));
         }
 
+        self.filters.validate()?;
+
         Ok(())
     }
 }
 
 /// Progress callback type
-pub type ProgressCallback = Box<dyn Fn(f32, Option<String>) + Send + Sync>;
+pub type ProgressCallback = Box<dyn Fn(ProgressUpdate) + Send + Sync>;
 
 /// Smart exporter that chooses the best method based on the VOD
 pub struct SmartExporter {
@@ -73,37 +244,141 @@ impl Default for SmartExporter {
 
 impl SmartExporter {
     pub fn new() -> Self {
+        let ffmpeg_config = get_config().ffmpeg;
+
         Self {
-            ffmpeg: FfmpegExporter::new(),
+            ffmpeg: FfmpegExporter::new()
+                .with_max_size_mb(ffmpeg_config.max_size_mb)
+                .with_loudness_target(ffmpeg_config.loudness_lufs),
             ytdlp: YtDlpExporter::new(),
         }
     }
 
-    /// Export with optional progress callback
+    /// Export with optional progress and attempt callbacks
     pub async fn export_with_progress(
         &self,
         vod: &ResolvedVod,
         timing: &ClipTiming,
+        options: &ExportOptions,
         output_path: &Path,
         progress: Option<&ProgressCallback>,
+        attempts: Option<&AttemptCallback>,
     ) -> ExportResult<()> {
         // Use FFmpeg for HLS streams and direct URLs
         // Use yt-dlp for platform URLs that need extraction
         if vod.is_hls || is_direct_video(&vod.url) {
             log::info!("Using FFmpeg for export");
             self.ffmpeg
-                .export_with_retry(vod, timing, output_path, progress)
+                .export_with_retry(vod, timing, options, output_path, progress, attempts)
                 .await
         } else {
             log::info!("Using yt-dlp for export");
             self.ytdlp
-                .export_with_retry(vod, timing, output_path, progress)
+                .export_with_retry(vod, timing, options, output_path, progress, attempts)
+                .await?;
+
+            // yt-dlp can't target a size, filter or drop audio, so post-process locally
+            self.reencode_download(timing, options, output_path, progress, attempts)
                 .await
         }
     }
+
+    /// Cut a clip out of a local file downloaded earlier (see [`batch`])
+    pub async fn export_from_local(
+        &self,
+        source: &Path,
+        timing: &ClipTiming,
+        options: &ExportOptions,
+        output_path: &Path,
+        progress: Option<&ProgressCallback>,
+        attempts: Option<&AttemptCallback>,
+    ) -> ExportResult<()> {
+        self.ffmpeg
+            .trim_local(source, timing, options, output_path, progress, attempts)
+            .await
+    }
+
+    /// Re-process a downloaded clip with FFmpeg if it exceeds the size limit or
+    /// the options need processing yt-dlp can't apply
+    async fn reencode_download(
+        &self,
+        timing: &ClipTiming,
+        options: &ExportOptions,
+        output_path: &Path,
+        progress: Option<&ProgressCallback>,
+        attempts: Option<&AttemptCallback>,
+    ) -> ExportResult<()> {
+        let size = std::fs::metadata(output_path).map(|m| m.len()).unwrap_or(0);
+        let too_large = self.ffmpeg.exceeds_size_limit(size, options);
+
+        if !too_large && !self.ffmpeg.needs_reencode(options) && !options.strip_audio {
+            return Ok(());
+        }
+
+        log::info!(
+            "Post-processing download ({:.1}MB, {:?})",
+            size as f64 / 1024.0 / 1024.0,
+            options
+        );
+
+        let source = with_suffix(output_path, ".full");
+        std::fs::rename(output_path, &source)
+            .map_err(|e| ExportError::OutputDir(format!("Failed to move download: {}", e)))?;
+
+        let local_timing = ClipTiming::new(0.0, timing.duration);
+        let options = ExportOptions {
+            timecode_start: options.timecode_start.or(Some(timing.start)),
+            ..options.clone()
+        };
+        let result = self
+            .ffmpeg
+            .trim_local(
+                &source,
+                &local_timing,
+                &options,
+                output_path,
+                progress,
+                attempts,
+            )
+            .await;
+
+        let _ = std::fs::remove_file(&source);
+        result
+    }
+}
+
+/// Arguments of a command, without the program (for snapshot tests)
+#[cfg(test)]
+pub(crate) fn argv(cmd: &tokio::process::Command) -> Vec<String> {
+    cmd.as_std()
+        .get_args()
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Whether `speed` is in the allowed playback speed range
+pub fn is_valid_speed(speed: f64) -> bool {
+    (MIN_SPEED..=MAX_SPEED).contains(&speed)
+}
+
+/// Time allowed for a run producing `duration` seconds of output
+pub(crate) fn export_timeout(duration: f64) -> Duration {
+    Duration::try_from_secs_f64(duration * EXPORT_TIMEOUT_FACTOR)
+        .unwrap_or(MIN_EXPORT_TIMEOUT)
+        .max(MIN_EXPORT_TIMEOUT)
+}
+
+/// Append a suffix to a file name (clip.mp4 -> clip.mp4.bak)
+pub(crate) fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
 }
 
 fn is_direct_video(url: &str) -> bool {
+    if LocalFileResolver::is_local_path(url) {
+        return true;
+    }
     let lower = url.to_lowercase();
     lower.ends_with(".mp4")
         || lower.ends_with(".webm")
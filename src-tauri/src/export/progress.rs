@@ -1,26 +1,72 @@
 use regex::Regex;
 use serde::Serialize;
 
+use crate::error::ExportErrorKind;
+
 /// Progress information for export operations
 #[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ExportProgress {
     /// Export has started
     Started { total_clips: usize },
+    /// A shared download of overlapping clips has started
+    BatchStarted {
+        /// 1-based number of the batch
+        batch: usize,
+        total_batches: usize,
+        /// Indices of the clips cut from this download
+        clips: Vec<usize>,
+    },
+    /// Progress update for the current batch download
+    BatchProgress {
+        batch: usize,
+        percent: f32,
+        speed: Option<String>,
+    },
     /// A clip export has started
     ClipStarted {
         index: usize,
         action_name: String,
         streamer_name: String,
+        /// Seconds of the clip the platform muted
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "ts-bindings", ts(optional))]
+        muted_seconds: Option<f64>,
     },
     /// Progress update for current clip
     ClipProgress {
         index: usize,
         percent: f32,
         speed: Option<String>,
+        /// Output time reached so far (seconds)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "ts-bindings", ts(optional))]
+        out_time: Option<f64>,
+        /// Bytes written so far
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "ts-bindings", ts(type = "number", optional))]
+        bytes_written: Option<u64>,
+        /// Estimated seconds remaining
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "ts-bindings", ts(optional))]
+        eta_secs: Option<f64>,
+    },
+    /// A new export attempt started for the current clip (progress restarts at 0%)
+    ClipAttemptStarted {
+        index: usize,
+        attempt: u32,
+        method: ExportMethod,
     },
     /// A clip export has completed
-    ClipCompleted { index: usize, status: ClipResult },
+    ClipCompleted {
+        index: usize,
+        status: ClipResult,
+        /// Size of the output file in bytes (if it exists)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "ts-bindings", ts(type = "number", optional))]
+        size_bytes: Option<u64>,
+    },
     /// All exports have finished
     Finished {
         exported: usize,
@@ -31,18 +77,76 @@ pub enum ExportProgress {
 
 /// Result of a single clip export
 #[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
 #[serde(rename_all = "snake_case")]
 pub enum ClipResult {
     Success,
     Skipped,
-    Failed { error: String },
+    Failed {
+        error: String,
+        error_kind: ExportErrorKind,
+    },
+}
+
+/// How an export attempt fetches the clip
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum ExportMethod {
+    /// Stream copy without re-encoding
+    Copy,
+    /// Re-encode only the first GOP, copy the rest
+    SmartCut,
+    /// Full re-encode
+    Reencode,
+    /// Two-pass encode targeting a file size
+    TwoPass,
+    /// Download through yt-dlp
+    Download,
+}
+
+/// Start of a new export attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportAttempt {
+    pub attempt: u32,
+    pub method: ExportMethod,
+}
+
+/// Callback told when an export attempt starts (its progress restarts at 0%)
+pub type AttemptCallback = Box<dyn Fn(ExportAttempt) + Send + Sync>;
+
+/// A single progress report from FFmpeg or yt-dlp
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProgressUpdate {
+    pub percent: f32,
+    pub speed: Option<String>,
+    /// Output time reached so far (seconds)
+    pub out_time: Option<f64>,
+    /// Bytes written so far
+    pub total_size: Option<u64>,
+    /// Estimated seconds remaining for the current step
+    pub eta_secs: Option<f64>,
 }
 
 /// Parser for FFmpeg progress output
+///
+/// Handles both the classic stats line (`size=... time=... speed=...`) and
+/// `-progress pipe:2` output, where each value comes on its own `key=value`
+/// line and a block ends with `progress=continue|end`.
 pub struct FfmpegProgressParser {
     duration: f64,
     time_regex: Regex,
     speed_regex: Regex,
+    size_regex: Regex,
+    /// Values of the current `-progress` block
+    block: ProgressBlock,
+}
+
+#[derive(Default)]
+struct ProgressBlock {
+    out_time: Option<f64>,
+    total_size: Option<u64>,
+    speed: Option<f64>,
 }
 
 impl FfmpegProgressParser {
@@ -51,12 +155,35 @@ impl FfmpegProgressParser {
             duration,
             time_regex: Regex::new(r"time=(\d{2}):(\d{2}):(\d{2})\.(\d{2})").unwrap(),
             speed_regex: Regex::new(r"speed=\s*([0-9.]+)x").unwrap(),
+            size_regex: Regex::new(r"size=\s*(\d+)(?:kB|KiB)").unwrap(),
+            block: ProgressBlock::default(),
         }
     }
 
-    /// Parse FFmpeg stderr line and return progress percentage
-    pub fn parse_line(&self, line: &str) -> Option<(f32, Option<String>)> {
-        // Parse time=00:00:05.12 format
+    /// Parse an FFmpeg stderr line; returns an update once a full report is read
+    pub fn parse_line(&mut self, line: &str) -> Option<ProgressUpdate> {
+        let line = line.trim();
+
+        // -progress output: collect values until the end of the block
+        if let Some(value) = line.strip_prefix("out_time=") {
+            self.block.out_time = parse_clock(value);
+            return None;
+        }
+        if let Some(value) = line.strip_prefix("total_size=") {
+            self.block.total_size = value.parse().ok();
+            return None;
+        }
+        if let Some(value) = line.strip_prefix("speed=") {
+            self.block.speed = value.trim().trim_end_matches('x').parse().ok();
+            return None;
+        }
+        if line.starts_with("progress=") {
+            let block = std::mem::take(&mut self.block);
+            let out_time = block.out_time?;
+            return Some(self.update(out_time, block.speed, block.total_size));
+        }
+
+        // Classic stats line: frame=... size=1024kB time=00:00:05.12 ... speed=2.00x
         if let Some(caps) = self.time_regex.captures(line) {
             let hours: f64 = caps.get(1)?.as_str().parse().ok()?;
             let minutes: f64 = caps.get(2)?.as_str().parse().ok()?;
@@ -64,30 +191,81 @@ impl FfmpegProgressParser {
             let centis: f64 = caps.get(4)?.as_str().parse().ok()?;
 
             let current_time = hours * 3600.0 + minutes * 60.0 + seconds + centis / 100.0;
-            let percent = if self.duration > 0.0 {
-                ((current_time / self.duration) * 100.0).min(100.0) as f32
-            } else {
-                0.0
-            };
 
-            // Parse speed if available
             let speed = self
                 .speed_regex
                 .captures(line)
                 .and_then(|c| c.get(1))
-                .map(|m| format!("{}x", m.as_str()));
+                .and_then(|m| m.as_str().parse().ok());
+            let size = self
+                .size_regex
+                .captures(line)
+                .and_then(|c| c.get(1))
+                .and_then(|m| m.as_str().parse::<u64>().ok())
+                .map(|kb| kb * 1024);
 
-            return Some((percent, speed));
+            return Some(self.update(current_time, speed, size));
         }
 
         None
     }
+
+    /// Build an update for `out_time` seconds of output
+    fn update(&self, out_time: f64, speed: Option<f64>, total_size: Option<u64>) -> ProgressUpdate {
+        let percent = if self.duration > 0.0 {
+            ((out_time / self.duration) * 100.0).min(100.0) as f32
+        } else {
+            0.0
+        };
+
+        // speed is output seconds per wall-clock second
+        let eta_secs = speed
+            .filter(|s| *s > 0.0 && self.duration > 0.0)
+            .map(|s| (self.duration - out_time).max(0.0) / s);
+
+        ProgressUpdate {
+            percent,
+            speed: speed.map(|s| format!("{:.2}x", s)),
+            out_time: Some(out_time),
+            total_size,
+            eta_secs,
+        }
+    }
+}
+
+/// Parse an `HH:MM:SS.micros` timestamp (negative values at startup yield None)
+fn parse_clock(value: &str) -> Option<f64> {
+    let mut parts = value.trim().split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+
+    if hours < 0.0 {
+        return None;
+    }
+
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Parse a yt-dlp size like `100.0MiB` into bytes
+fn parse_size(value: &str, unit: &str) -> Option<u64> {
+    let value: f64 = value.parse().ok()?;
+    let multiplier = match unit {
+        "B" => 1.0,
+        "KiB" | "KB" => 1024.0,
+        "MiB" | "MB" => 1024.0 * 1024.0,
+        "GiB" | "GB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((value * multiplier) as u64)
 }
 
 /// Parser for yt-dlp progress output
 pub struct YtDlpProgressParser {
     percent_regex: Regex,
     speed_regex: Regex,
+    size_regex: Regex,
+    eta_regex: Regex,
 }
 
 impl Default for YtDlpProgressParser {
@@ -103,11 +281,15 @@ impl YtDlpProgressParser {
             percent_regex: Regex::new(r"\[download\]\s+(\d+\.?\d*)%").unwrap(),
             // Matches at 5.20MiB/s
             speed_regex: Regex::new(r"at\s+([0-9.]+\s*\w+/s)").unwrap(),
+            // Matches of ~100.0MiB
+            size_regex: Regex::new(r"of\s+~?\s*([0-9.]+)\s*([KMG]?i?B)").unwrap(),
+            // Matches ETA 01:05 or ETA 1:02:03
+            eta_regex: Regex::new(r"ETA\s+(\d+(?::\d{2}){1,2})").unwrap(),
         }
     }
 
-    /// Parse yt-dlp stdout line and return progress percentage
-    pub fn parse_line(&self, line: &str) -> Option<(f32, Option<String>)> {
+    /// Parse a yt-dlp stdout line into a progress update
+    pub fn parse_line(&self, line: &str) -> Option<ProgressUpdate> {
         if let Some(caps) = self.percent_regex.captures(line) {
             let percent: f32 = caps.get(1)?.as_str().parse().ok()?;
 
@@ -117,7 +299,26 @@ impl YtDlpProgressParser {
                 .and_then(|c| c.get(1))
                 .map(|m| m.as_str().to_string());
 
-            return Some((percent.min(100.0), speed));
+            // yt-dlp reports the total size; estimate bytes written from the percentage
+            let total_size = self
+                .size_regex
+                .captures(line)
+                .and_then(|c| parse_size(c.get(1)?.as_str(), c.get(2)?.as_str()))
+                .map(|total| (total as f64 * percent as f64 / 100.0) as u64);
+
+            let eta_secs = self.eta_regex.captures(line).and_then(|c| {
+                c[1].split(':').try_fold(0.0, |acc, part| {
+                    part.parse::<f64>().ok().map(|v| acc * 60.0 + v)
+                })
+            });
+
+            return Some(ProgressUpdate {
+                percent: percent.min(100.0),
+                speed,
+                out_time: None,
+                total_size,
+                eta_secs,
+            });
         }
 
         None
@@ -130,29 +331,64 @@ mod tests {
 
     #[test]
     fn test_ffmpeg_parser() {
-        let parser = FfmpegProgressParser::new(10.0);
+        let mut parser = FfmpegProgressParser::new(10.0);
 
-        let (percent, _) = parser.parse_line("frame=  150 fps= 50 q=28.0 size=    1024kB time=00:00:05.00 bitrate= 1677.7kbits/s speed=2.00x").unwrap();
-        assert!((percent - 50.0).abs() < 0.1);
+        let update = parser.parse_line("frame=  150 fps= 50 q=28.0 size=    1024kB time=00:00:05.00 bitrate= 1677.7kbits/s speed=2.00x").unwrap();
+        assert!((update.percent - 50.0).abs() < 0.1);
+        assert_eq!(update.total_size, Some(1024 * 1024));
+        assert_eq!(update.eta_secs, Some(2.5));
 
-        let (percent, speed) = parser.parse_line("time=00:00:10.00 speed=1.50x").unwrap();
-        assert!((percent - 100.0).abs() < 0.1);
-        assert_eq!(speed, Some("1.50x".to_string()));
+        let update = parser.parse_line("time=00:00:10.00 speed=1.50x").unwrap();
+        assert!((update.percent - 100.0).abs() < 0.1);
+        assert_eq!(update.speed, Some("1.50x".to_string()));
+    }
+
+    #[test]
+    fn test_ffmpeg_progress_pipe() {
+        let mut parser = FfmpegProgressParser::new(20.0);
+
+        for line in [
+            "frame=120",
+            "total_size=524288",
+            "out_time_us=4000000",
+            "out_time=00:00:04.000000",
+            "speed=2x",
+        ] {
+            assert_eq!(parser.parse_line(line), None);
+        }
+
+        let update = parser.parse_line("progress=continue").unwrap();
+        assert!((update.percent - 20.0).abs() < 0.1);
+        assert_eq!(update.out_time, Some(4.0));
+        assert_eq!(update.total_size, Some(524288));
+        assert_eq!(update.eta_secs, Some(8.0));
+        assert_eq!(update.speed, Some("2.00x".to_string()));
+
+        // Negative out_time at startup is ignored
+        parser.parse_line("out_time=-577014:32:22.775808");
+        assert_eq!(parser.parse_line("progress=continue"), None);
     }
 
     #[test]
     fn test_ytdlp_parser() {
         let parser = YtDlpProgressParser::new();
 
-        let (percent, _) = parser
-            .parse_line("[download]  45.2% of 100.0MiB at 5.20MiB/s")
+        let update = parser
+            .parse_line("[download]  45.2% of 100.0MiB at 5.20MiB/s ETA 01:05")
+            .unwrap();
+        assert!((update.percent - 45.2).abs() < 0.1);
+        assert_eq!(update.eta_secs, Some(65.0));
+
+        let update = parser
+            .parse_line("[download]  25.0% of ~ 100.00MiB at 5.20MiB/s ETA 1:02:03")
             .unwrap();
-        assert!((percent - 45.2).abs() < 0.1);
+        assert_eq!(update.total_size, Some(26_214_400));
+        assert_eq!(update.eta_secs, Some(3723.0));
 
-        let (percent, speed) = parser
+        let update = parser
             .parse_line("[download] 100.0% of 50.00MiB at 10.00MiB/s")
             .unwrap();
-        assert!((percent - 100.0).abs() < 0.1);
-        assert!(speed.is_some());
+        assert!((update.percent - 100.0).abs() < 0.1);
+        assert!(update.speed.is_some());
     }
 }
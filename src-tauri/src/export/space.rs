@@ -0,0 +1,125 @@
+//! Free disk space preflight for exports.
+//!
+//! There's no portable std API for free space, so it's read from `df` (Unix)
+//! or `dir` (Windows, which also works on UNC shares). If the query fails the
+//! check is skipped rather than blocking the export.
+
+use std::path::Path;
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use crate::error::{ExportError, ExportResult};
+
+/// Assumed bitrate for clips without a size limit (1080p60 source plus audio)
+pub const ESTIMATED_CLIP_KBPS: f64 = 9000.0;
+
+/// Extra space kept free on top of the estimate
+const HEADROOM_BYTES: u64 = 500 * 1024 * 1024;
+
+/// Estimated output size of a clip in bytes
+pub fn estimate_clip_bytes(duration: f64, max_size_mb: Option<f64>) -> u64 {
+    let estimate = duration.max(0.0) * ESTIMATED_CLIP_KBPS * 1000.0 / 8.0;
+    match max_size_mb {
+        Some(mb) => estimate.min(mb * 1024.0 * 1024.0) as u64,
+        None => estimate as u64,
+    }
+}
+
+/// Fail early if the volume holding `dir` can't fit `required` bytes plus headroom
+pub fn ensure_free_space(dir: &Path, required: u64) -> ExportResult<()> {
+    let Some(available) = available_space(dir) else {
+        log::debug!("Could not read free space for {}", dir.display());
+        return Ok(());
+    };
+
+    let needed = required.saturating_add(HEADROOM_BYTES);
+    if available < needed {
+        return Err(ExportError::OutputDir(format!(
+            "Not enough disk space in {}: about {} needed, {} free",
+            dir.display(),
+            format_bytes(needed),
+            format_bytes(available)
+        )));
+    }
+
+    Ok(())
+}
+
+/// Free bytes on the volume holding `dir`
+fn available_space(dir: &Path) -> Option<u64> {
+    #[cfg(target_os = "windows")]
+    {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "dir", "/-C"]).arg(dir);
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        let output = cmd.output().ok()?;
+        parse_dir_free(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let output = Command::new("df").arg("-Pk").arg(dir).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        parse_df_free(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+/// Parse the available column of `df -Pk` output (in KiB)
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+fn parse_df_free(output: &str) -> Option<u64> {
+    let line = output.lines().nth(1)?;
+    let kib: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Parse the trailing `N Dir(s)  123456 bytes free` line of `dir /-C` output
+///
+/// The wording is localized, so this takes the last number on that line.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_dir_free(output: &str) -> Option<u64> {
+    let line = output.lines().rev().find(|l| !l.trim().is_empty())?;
+    line.split(|c: char| !c.is_ascii_digit())
+        .rfind(|part| !part.is_empty())?
+        .parse()
+        .ok()
+}
+
+fn format_bytes(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / 1024.0 / 1024.0 / 1024.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_clip_bytes() {
+        assert_eq!(estimate_clip_bytes(10.0, None), 11_250_000);
+        assert_eq!(estimate_clip_bytes(10.0, Some(8.0)), 8 * 1024 * 1024);
+        assert_eq!(estimate_clip_bytes(-1.0, None), 0);
+        assert_eq!(estimate_clip_bytes(f64::INFINITY, None), u64::MAX);
+    }
+
+    #[test]
+    fn test_ensure_free_space_saturates() {
+        // An estimate at the top of the range must not overflow with the headroom
+        assert!(ensure_free_space(&std::env::temp_dir(), u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_parse_free_space() {
+        let df = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+                  /dev/sda1        102400000  51200000  40960000      56% /\n";
+        assert_eq!(parse_df_free(df), Some(40_960_000 * 1024));
+
+        let dir = " Directory of C:\\Nox\n\n\
+                   15/10/2026  12:00    <DIR>          .\n\
+                                  0 File(s)              0 bytes\n\
+                                  2 Dir(s)   52438953984 bytes free\n";
+        assert_eq!(parse_dir_free(dir), Some(52_438_953_984));
+    }
+}
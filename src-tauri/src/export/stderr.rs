@@ -0,0 +1,118 @@
+//! Bounded capture of FFmpeg / yt-dlp stderr for error reports.
+//!
+//! Long renders can print megabytes of output, so only the last
+//! `STDERR_TAIL_LINES` lines are kept and each line is capped in length.
+
+use std::collections::VecDeque;
+
+/// Number of stderr lines kept to explain a failed run
+const STDERR_TAIL_LINES: usize = 50;
+
+/// Longest line kept (FFmpeg occasionally dumps huge filter graphs on one line)
+const STDERR_MAX_LINE_LEN: usize = 1000;
+
+/// Most lines quoted in an error message
+const STDERR_REPORT_LINES: usize = 5;
+
+/// Ring buffer of the last non-progress stderr lines
+#[derive(Debug, Default)]
+pub(crate) struct StderrTail(VecDeque<String>);
+
+impl StderrTail {
+    pub(crate) fn push(&mut self, line: &str) {
+        let line = line.trim();
+        // Skip `key=value` progress output and stats lines
+        let is_progress = line
+            .split_whitespace()
+            .next()
+            .is_some_and(|token| token.contains('='));
+        if line.is_empty() || is_progress {
+            return;
+        }
+        if self.0.len() == STDERR_TAIL_LINES {
+            self.0.pop_front();
+        }
+        let line = match line.char_indices().nth(STDERR_MAX_LINE_LEN) {
+            Some((end, _)) => format!("{}...", &line[..end]),
+            None => line.to_string(),
+        };
+        self.0.push_back(line);
+    }
+
+    /// Lines that explain the failure: error lines if any, else the last few
+    pub(crate) fn relevant(&self) -> Vec<&str> {
+        let errors: Vec<&str> = self
+            .0
+            .iter()
+            .map(String::as_str)
+            .filter(|l| {
+                let lower = l.to_lowercase();
+                lower.contains("error")
+                    || lower.contains("invalid")
+                    || lower.contains("failed")
+                    || lower.contains("forbidden")
+                    || lower.contains("no space")
+            })
+            .collect();
+        let lines = if errors.is_empty() {
+            self.0.iter().map(String::as_str).collect()
+        } else {
+            errors
+        };
+        lines[lines.len().saturating_sub(STDERR_REPORT_LINES)..].to_vec()
+    }
+
+    /// `": line | line"` for appending to an error message, or empty
+    pub(crate) fn suffix(&self) -> String {
+        let lines = self.relevant();
+        if lines.is_empty() {
+            String::new()
+        } else {
+            format!(": {}", lines.join(" | "))
+        }
+    }
+
+    /// All kept lines, for the debug log
+    pub(crate) fn dump(&self) -> String {
+        self.0
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stderr_tail() {
+        let mut tail = StderrTail::default();
+        assert_eq!(tail.suffix(), "");
+
+        tail.push("Input #0, hls, from 'https://example.com/index.m3u8':");
+        tail.push("out_time=00:00:01.000000");
+        tail.push("frame=  120 fps= 60 q=-1.0 size=    1024kB time=00:00:02.00");
+        tail.push("[https @ 0x1] HTTP error 403 Forbidden");
+        tail.push("Error opening input files: Server returned 403 Forbidden (access denied)");
+        assert_eq!(
+            tail.suffix(),
+            ": [https @ 0x1] HTTP error 403 Forbidden | Error opening input files: Server returned 403 Forbidden (access denied)"
+        );
+
+        let mut tail = StderrTail::default();
+        for i in 0..60 {
+            tail.push(&format!("line {}", i));
+        }
+        assert_eq!(tail.0.len(), STDERR_TAIL_LINES);
+        assert_eq!(
+            tail.relevant(),
+            vec!["line 55", "line 56", "line 57", "line 58", "line 59"]
+        );
+
+        let mut tail = StderrTail::default();
+        tail.push(&"x".repeat(5000));
+        assert_eq!(tail.0[0].len(), STDERR_MAX_LINE_LEN + 3);
+    }
+}
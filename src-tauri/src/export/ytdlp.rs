@@ -1,5 +1,4 @@
 use std::path::Path;
-use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio::time::timeout;
@@ -7,19 +6,25 @@ use tokio::time::timeout;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
-use super::{ClipTiming, YtDlpProgressParser};
+use super::{
+    export_timeout, AttemptCallback, ClipTiming, ExportAttempt, ExportMethod, ExportOptions,
+    FfmpegExporter, ProgressUpdate, StderrTail, YtDlpProgressParser,
+};
 use crate::binaries::get_binary_manager;
 use crate::error::{ExportError, ExportResult};
 use crate::platform::ResolvedVod;
 
-/// Timeout for a single clip export (5 minutes)
-const EXPORT_TIMEOUT: Duration = Duration::from_secs(300);
-
 /// Maximum number of retry attempts
 const MAX_RETRIES: u32 = 2;
 
+/// Containers yt-dlp can merge/remux into directly
+const REMUX_FORMATS: [&str; 3] = ["mp4", "mkv", "mov"];
+
+/// Leftovers of an unfinished download that must not be picked as output
+const PARTIAL_SUFFIXES: [&str; 4] = [".part", ".ytdl", ".temp", ".frag"];
+
 /// Progress callback type
-pub type ProgressCallback = Box<dyn Fn(f32, Option<String>) + Send + Sync>;
+pub type ProgressCallback = Box<dyn Fn(ProgressUpdate) + Send + Sync>;
 
 pub struct YtDlpExporter {
     /// Max video height (e.g., 1080)
@@ -50,13 +55,14 @@ impl YtDlpExporter {
             .unwrap_or_else(|| "yt-dlp".to_string())
     }
 
-    /// Format seconds as HH:MM:SS for yt-dlp
+    /// Format seconds as HH:MM:SS.mmm for yt-dlp (millisecond precision, like the FFmpeg path)
     fn format_time(seconds: f64) -> String {
-        let total = seconds.abs() as u64;
+        let total_ms = (seconds.abs() * 1000.0).round() as u64;
+        let total = total_ms / 1000;
         let h = total / 3600;
         let m = (total % 3600) / 60;
         let s = total % 60;
-        format!("{:02}:{:02}:{:02}", h, m, s)
+        format!("{:02}:{:02}:{:02}.{:03}", h, m, s, total_ms % 1000)
     }
 
     fn build_command(
@@ -85,10 +91,19 @@ impl YtDlpExporter {
             cmd.arg("--force-keyframes-at-cuts");
         }
 
+        // Let yt-dlp pick the extension, then merge/remux into the requested container
+        let ext = output
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_else(|| "mp4".to_string());
+        if REMUX_FORMATS.contains(&ext.as_str()) {
+            cmd.args(["--merge-output-format", &ext, "--remux-video", &ext]);
+        }
+
         // Output with progress
         cmd.args([
             "-o",
-            output.to_str().unwrap_or("output.mp4"),
+            &output_template(output),
             "--no-playlist",
             "--progress",
             "--newline",
@@ -104,10 +119,12 @@ impl YtDlpExporter {
         cmd
     }
 
-    /// Run a command with timeout and optional progress callback
+    /// Run a command with a timeout scaled to the `duration` seconds it
+    /// downloads, and optional progress callback
     async fn run_command_with_progress(
         &self,
         mut cmd: Command,
+        duration: f64,
         progress: Option<&ProgressCallback>,
     ) -> ExportResult<()> {
         log::debug!("Running: {:?}", cmd);
@@ -121,22 +138,38 @@ impl YtDlpExporter {
             .take()
             .ok_or_else(|| ExportError::YtDlp("Failed to capture stdout".to_string()))?;
 
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| ExportError::YtDlp("Failed to capture stderr".to_string()))?;
+
         let parser = YtDlpProgressParser::new();
         let mut reader = BufReader::new(stdout).lines();
+        let mut stderr_reader = BufReader::new(stderr).lines();
+        let mut stderr_tail = StderrTail::default();
 
-        // Read progress in background
+        // Read progress in background; drain stderr alongside so the pipe never fills up
         let progress_task = async {
-            while let Ok(Some(line)) = reader.next_line().await {
-                if let Some((percent, speed)) = parser.parse_line(&line) {
-                    if let Some(cb) = progress {
-                        cb(percent, speed);
+            let stdout_task = async {
+                while let Ok(Some(line)) = reader.next_line().await {
+                    if let Some(update) = parser.parse_line(&line) {
+                        if let Some(cb) = progress {
+                            cb(update);
+                        }
                     }
                 }
-            }
+            };
+            let stderr_task = async {
+                while let Ok(Some(line)) = stderr_reader.next_line().await {
+                    stderr_tail.push(&line);
+                }
+            };
+            tokio::join!(stdout_task, stderr_task);
         };
 
         // Wait for process with timeout
-        let result = timeout(EXPORT_TIMEOUT, async {
+        let limit = export_timeout(duration);
+        let result = timeout(limit, async {
             tokio::select! {
                 _ = progress_task => {},
                 status = child.wait() => {
@@ -149,33 +182,53 @@ impl YtDlpExporter {
 
         match result {
             Ok(Ok(status)) if status.success() => Ok(()),
-            Ok(Ok(status)) => Err(ExportError::YtDlp(format!(
-                "yt-dlp exited with code: {}",
-                status
-            ))),
+            Ok(Ok(status)) => {
+                log::error!("[yt-dlp] Last output:\n{}", stderr_tail.dump());
+                // yt-dlp reports the cause on stderr as `ERROR: ...`
+                Err(ExportError::YtDlp(format!(
+                    "yt-dlp exited with code: {}{}",
+                    status,
+                    stderr_tail.suffix()
+                )))
+            }
             Ok(Err(e)) => Err(ExportError::YtDlp(format!("yt-dlp error: {}", e))),
             Err(_) => {
                 // Timeout - kill the process
                 let _ = child.kill().await;
                 Err(ExportError::Timeout(format!(
                     "Export timed out after {} seconds",
-                    EXPORT_TIMEOUT.as_secs()
+                    limit.as_secs()
                 )))
             }
         }
     }
 
+    /// Run yt-dlp, then make sure the result ends up at `output`
+    async fn run_and_finalize(
+        &self,
+        cmd: Command,
+        duration: f64,
+        output: &Path,
+        progress: Option<&ProgressCallback>,
+    ) -> ExportResult<()> {
+        self.run_command_with_progress(cmd, duration, progress)
+            .await?;
+        finalize_output(output, progress).await
+    }
+
     /// Export with retry logic
     #[allow(unused_assignments)]
     pub async fn export_with_retry(
         &self,
         vod: &ResolvedVod,
         timing: &ClipTiming,
+        options: &ExportOptions,
         output_path: &Path,
         progress: Option<&ProgressCallback>,
+        attempts: Option<&AttemptCallback>,
     ) -> ExportResult<()> {
         // Validate timing first
-        timing.validate()?;
+        timing.validate_with_max(options.max_duration())?;
 
         let mut last_error = None;
 
@@ -188,11 +241,21 @@ impl YtDlpExporter {
                 timing.duration
             );
 
+            if let Some(cb) = attempts {
+                cb(ExportAttempt {
+                    attempt,
+                    method: ExportMethod::Download,
+                });
+            }
+
             // Try with force keyframes first
             if self.force_keyframes && attempt == 1 {
                 let cmd = self.build_command(&vod.url, timing, output_path, true);
 
-                match self.run_command_with_progress(cmd, progress).await {
+                match self
+                    .run_and_finalize(cmd, timing.duration, output_path, progress)
+                    .await
+                {
                     Ok(()) => {
                         log::info!("[yt-dlp] Export successful (with keyframes)");
                         return Ok(());
@@ -207,7 +270,10 @@ impl YtDlpExporter {
             // Fallback without force keyframes
             let cmd = self.build_command(&vod.url, timing, output_path, false);
 
-            match self.run_command_with_progress(cmd, progress).await {
+            match self
+                .run_and_finalize(cmd, timing.duration, output_path, progress)
+                .await
+            {
                 Ok(()) => {
                     log::info!("[yt-dlp] Export successful");
                     return Ok(());
@@ -223,14 +289,118 @@ impl YtDlpExporter {
     }
 }
 
+/// Output template `<dir>/<stem>.%(ext)s` for the requested output path
+///
+/// `%` in the file name is escaped so yt-dlp doesn't read it as a field.
+fn output_template(output: &Path) -> String {
+    let stem = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().replace('%', "%%"))
+        .unwrap_or_else(|| "output".to_string());
+    match output.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir
+            .join(format!("{}.%(ext)s", stem))
+            .to_string_lossy()
+            .to_string(),
+        _ => format!("{}.%(ext)s", stem),
+    }
+}
+
+/// Convert the file yt-dlp actually produced to `output` if the extension differs
+///
+/// Happens when the source can't be remuxed into the requested container
+/// (e.g. `.ts`) or an older yt-dlp ignores `--remux-video`. FFmpeg copies the
+/// streams into the right container, or re-encodes them when they don't fit.
+async fn finalize_output(output: &Path, progress: Option<&ProgressCallback>) -> ExportResult<()> {
+    if output.exists() {
+        return Ok(());
+    }
+
+    let dir = output.parent().unwrap_or(Path::new("."));
+    let stem = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let names: Vec<String> = std::fs::read_dir(dir)
+        .map_err(|e| ExportError::OutputDir(format!("Failed to read output folder: {}", e)))?
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+
+    let Some(produced) = find_produced_file(&stem, &names) else {
+        return Err(ExportError::YtDlp(format!(
+            "yt-dlp finished but {} was not created",
+            output.display()
+        )));
+    };
+    let produced = dir.join(produced);
+
+    log::warn!(
+        "[yt-dlp] Output written as {}, converting to {}",
+        produced.display(),
+        output.display()
+    );
+    FfmpegExporter::new()
+        .remux_local(&produced, output, progress)
+        .await?;
+    let _ = std::fs::remove_file(&produced);
+    Ok(())
+}
+
+/// Pick the finished `<stem>.<ext>` file among a folder's entries
+fn find_produced_file<'a>(stem: &str, names: &'a [String]) -> Option<&'a str> {
+    names
+        .iter()
+        .map(String::as_str)
+        .filter(|name| {
+            name.strip_prefix(stem)
+                .and_then(|rest| rest.strip_prefix('.'))
+                .is_some_and(|ext| !ext.is_empty() && !ext.contains('.'))
+        })
+        .find(|name| !PARTIAL_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_format_time() {
-        assert_eq!(YtDlpExporter::format_time(0.0), "00:00:00");
-        assert_eq!(YtDlpExporter::format_time(61.0), "00:01:01");
-        assert_eq!(YtDlpExporter::format_time(3661.0), "01:01:01");
+        assert_eq!(YtDlpExporter::format_time(0.0), "00:00:00.000");
+        assert_eq!(YtDlpExporter::format_time(61.0), "00:01:01.000");
+        assert_eq!(YtDlpExporter::format_time(3661.0), "01:01:01.000");
+        assert_eq!(YtDlpExporter::format_time(97.25), "00:01:37.250");
+        assert_eq!(YtDlpExporter::format_time(59.9996), "00:01:00.000");
+    }
+
+    #[test]
+    fn test_section_matches_timing() {
+        // Same start/end as the FFmpeg path's -ss/-t for a marker at 100.4s
+        let timing = ClipTiming::from_points(100.4, -3.15, 7.0);
+        let end = timing.start + timing.duration;
+        assert_eq!(YtDlpExporter::format_time(timing.start), "00:01:37.250");
+        assert_eq!(YtDlpExporter::format_time(end), "00:01:47.400");
+    }
+
+    #[test]
+    fn test_output_template() {
+        assert_eq!(
+            output_template(Path::new("/clips/abc123_100%_Ace.mp4")),
+            "/clips/abc123_100%%_Ace.%(ext)s"
+        );
+        assert_eq!(output_template(Path::new("a.mkv")), "a.%(ext)s");
+    }
+
+    #[test]
+    fn test_find_produced_file() {
+        let names: Vec<String> = ["abc123_Ace.webm.part", "abc123_Ace.webm", "abc123_Ace2.mp4"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            find_produced_file("abc123_Ace", &names),
+            Some("abc123_Ace.webm")
+        );
+        assert_eq!(find_produced_file("abc123_Bo", &names), None);
     }
 }
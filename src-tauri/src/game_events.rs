@@ -0,0 +1,362 @@
+//! Kill and objective timelines pulled from game match APIs.
+//!
+//! Given a match id, the events of supported titles are fetched with their
+//! in-game time, so the frontend can create actions without timestamping them
+//! by hand. League of Legends uses Riot's match-v5 timeline (needs an API
+//! key), Dota 2 the public OpenDota API. Counter-Strike has no public match
+//! API and isn't supported. Kills close together are merged into one fight.
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PlatformError, PlatformResult};
+
+const OPENDOTA_URL: &str = "https://api.opendota.com/api/matches";
+
+/// Kills less than this apart belong to the same fight (seconds)
+const FIGHT_GAP: f64 = 10.0;
+
+/// Where a match's events come from
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum MatchSource {
+    /// League of Legends match id with its platform prefix (`EUW1_1234567890`)
+    Riot { match_id: String, api_key: String },
+    /// Dota 2 match id
+    #[serde(rename = "opendota")]
+    OpenDota { match_id: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameEventKind {
+    Kill,
+    Objective,
+}
+
+/// An event of the match, ready to become an action
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GameEvent {
+    /// Seconds from game start (can be negative before the Dota horn)
+    pub game_time: f64,
+    pub name: String,
+    pub kind: GameEventKind,
+}
+
+/// Fetch a match's events, in game time order
+pub async fn fetch_events(source: &MatchSource) -> PlatformResult<Vec<GameEvent>> {
+    let client = Client::new();
+    let events = match source {
+        MatchSource::Riot { match_id, api_key } => {
+            let region = riot_region(match_id).ok_or_else(|| {
+                PlatformError::ApiError(format!("Unknown Riot match id: {}", match_id))
+            })?;
+            if api_key.trim().is_empty() {
+                return Err(PlatformError::ApiError(
+                    "A Riot API key is required".to_string(),
+                ));
+            }
+            let url = format!(
+                "https://{region}.api.riotgames.com/lol/match/v5/matches/{match_id}/timeline"
+            );
+            let body = get_json(
+                client.get(url).header("X-Riot-Token", api_key.trim()),
+                match_id,
+            )
+            .await?;
+            parse_riot_timeline(&body)?
+        }
+        MatchSource::OpenDota { match_id } => {
+            if match_id.is_empty() || !match_id.chars().all(|c| c.is_ascii_digit()) {
+                return Err(PlatformError::ApiError(format!(
+                    "Invalid Dota match id: {}",
+                    match_id
+                )));
+            }
+            let url = format!("{OPENDOTA_URL}/{match_id}");
+            let body = get_json(client.get(url), match_id).await?;
+            parse_opendota_match(&body)?
+        }
+    };
+    Ok(merge_fights(events))
+}
+
+async fn get_json(request: reqwest::RequestBuilder, match_id: &str) -> PlatformResult<String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| PlatformError::ApiError(e.to_string()))?;
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND => Err(PlatformError::ApiError(format!(
+            "Match not found: {}",
+            match_id
+        ))),
+        status if !status.is_success() => Err(PlatformError::ApiError(format!(
+            "Match API returned {}",
+            status
+        ))),
+        _ => response
+            .text()
+            .await
+            .map_err(|e| PlatformError::ApiError(e.to_string())),
+    }
+}
+
+/// Regional routing of a match from its platform prefix
+fn riot_region(match_id: &str) -> Option<&'static str> {
+    let (platform, id) = match_id.split_once('_')?;
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    match platform.to_ascii_uppercase().as_str() {
+        "NA1" | "BR1" | "LA1" | "LA2" => Some("americas"),
+        "EUW1" | "EUN1" | "TR1" | "RU" | "ME1" => Some("europe"),
+        "KR" | "JP1" => Some("asia"),
+        "OC1" | "PH2" | "SG2" | "TH2" | "TW2" | "VN2" => Some("sea"),
+        _ => None,
+    }
+}
+
+#[derive(Deserialize)]
+struct RiotTimeline {
+    info: RiotInfo,
+}
+
+#[derive(Deserialize)]
+struct RiotInfo {
+    frames: Vec<RiotFrame>,
+}
+
+#[derive(Deserialize)]
+struct RiotFrame {
+    #[serde(default)]
+    events: Vec<RiotEvent>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RiotEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    /// Milliseconds from game start
+    timestamp: u64,
+    monster_type: Option<String>,
+    building_type: Option<String>,
+}
+
+fn parse_riot_timeline(body: &str) -> PlatformResult<Vec<GameEvent>> {
+    let timeline: RiotTimeline =
+        serde_json::from_str(body).map_err(|e| PlatformError::ParseError(e.to_string()))?;
+    Ok(timeline
+        .info
+        .frames
+        .iter()
+        .flat_map(|f| &f.events)
+        .filter_map(|e| {
+            let (name, kind) = match e.kind.as_str() {
+                "CHAMPION_KILL" => ("Kill".to_string(), GameEventKind::Kill),
+                "ELITE_MONSTER_KILL" => {
+                    let name = match e.monster_type.as_deref()? {
+                        "DRAGON" => "Dragon",
+                        "BARON_NASHOR" => "Baron Nashor",
+                        "RIFTHERALD" => "Héraut",
+                        "HORDE" => "Larves du Néant",
+                        "ATAKHAN" => "Atakhan",
+                        _ => return None,
+                    };
+                    (name.to_string(), GameEventKind::Objective)
+                }
+                "BUILDING_KILL" => {
+                    let name = match e.building_type.as_deref()? {
+                        "TOWER_BUILDING" => "Tour",
+                        "INHIBITOR_BUILDING" => "Inhibiteur",
+                        _ => return None,
+                    };
+                    (name.to_string(), GameEventKind::Objective)
+                }
+                _ => return None,
+            };
+            Some(GameEvent {
+                game_time: e.timestamp as f64 / 1000.0,
+                name,
+                kind,
+            })
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct DotaMatch {
+    #[serde(default)]
+    objectives: Option<Vec<DotaObjective>>,
+    #[serde(default)]
+    players: Vec<DotaPlayer>,
+}
+
+#[derive(Deserialize)]
+struct DotaObjective {
+    /// Seconds from the horn
+    time: f64,
+    #[serde(rename = "type")]
+    kind: String,
+    key: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct DotaPlayer {
+    /// Only present once the replay was parsed
+    #[serde(default)]
+    kills_log: Option<Vec<DotaKill>>,
+}
+
+#[derive(Deserialize)]
+struct DotaKill {
+    time: f64,
+}
+
+fn parse_opendota_match(body: &str) -> PlatformResult<Vec<GameEvent>> {
+    let dota: DotaMatch =
+        serde_json::from_str(body).map_err(|e| PlatformError::ParseError(e.to_string()))?;
+    // OpenDota only has timelines for parsed replays
+    let Some(objectives) = dota.objectives else {
+        return Err(PlatformError::ApiError(
+            "Match replay not parsed by OpenDota yet".to_string(),
+        ));
+    };
+
+    let kills = dota
+        .players
+        .iter()
+        .flat_map(|p| p.kills_log.iter().flatten())
+        .map(|k| GameEvent {
+            game_time: k.time,
+            name: "Kill".to_string(),
+            kind: GameEventKind::Kill,
+        });
+    let objectives = objectives.iter().filter_map(|o| {
+        let name = match o.kind.as_str() {
+            "CHAT_MESSAGE_ROSHAN_KILL" => "Roshan",
+            "building_kill" => {
+                let key = o.key.as_ref()?.as_str()?;
+                if key.contains("tower") {
+                    "Tour"
+                } else if key.contains("rax") {
+                    "Caserne"
+                } else if key.contains("fort") {
+                    "Ancient"
+                } else {
+                    return None;
+                }
+            }
+            _ => return None,
+        };
+        Some(GameEvent {
+            game_time: o.time,
+            name: name.to_string(),
+            kind: GameEventKind::Objective,
+        })
+    });
+    Ok(kills.chain(objectives).collect())
+}
+
+/// Sort events and merge kills less than `FIGHT_GAP` apart into one fight at
+/// the first kill
+fn merge_fights(mut events: Vec<GameEvent>) -> Vec<GameEvent> {
+    events.sort_by(|a, b| a.game_time.total_cmp(&b.game_time));
+
+    let mut merged: Vec<GameEvent> = Vec::new();
+    // Index in `merged` of the current fight, its kill count and last kill time
+    let mut fight: Option<(usize, usize, f64)> = None;
+    for event in events {
+        if event.kind == GameEventKind::Kill {
+            if let Some((index, count, last)) = fight.as_mut() {
+                if event.game_time - *last < FIGHT_GAP {
+                    *count += 1;
+                    *last = event.game_time;
+                    merged[*index].name = format!("Combat ({} kills)", count);
+                    continue;
+                }
+            }
+            fight = Some((merged.len(), 1, event.game_time));
+        }
+        merged.push(event);
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(game_time: f64, name: &str, kind: GameEventKind) -> GameEvent {
+        GameEvent {
+            game_time,
+            name: name.to_string(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_riot_timeline() {
+        let body = r#"{"metadata":{},"info":{"frames":[
+            {"events":[{"type":"ITEM_PURCHASED","timestamp":1000}]},
+            {"events":[
+                {"type":"CHAMPION_KILL","timestamp":62500,"killerId":3,"victimId":7},
+                {"type":"ELITE_MONSTER_KILL","timestamp":65000,"monsterType":"DRAGON"},
+                {"type":"BUILDING_KILL","timestamp":70000,"buildingType":"TOWER_BUILDING"},
+                {"type":"WARD_PLACED","timestamp":71000}
+            ]}
+        ]}}"#;
+        assert_eq!(
+            parse_riot_timeline(body).unwrap(),
+            vec![
+                event(62.5, "Kill", GameEventKind::Kill),
+                event(65.0, "Dragon", GameEventKind::Objective),
+                event(70.0, "Tour", GameEventKind::Objective),
+            ]
+        );
+        assert_eq!(riot_region("EUW1_6543210987"), Some("europe"));
+        assert_eq!(riot_region("na1_123"), Some("americas"));
+        assert_eq!(riot_region("XX1_123"), None);
+        assert_eq!(riot_region("EUW1_abc"), None);
+    }
+
+    #[test]
+    fn test_opendota_match() {
+        let body = r#"{"match_id":1,"objectives":[
+            {"time":-20,"type":"CHAT_MESSAGE_FIRSTBLOOD"},
+            {"time":600,"type":"building_kill","key":"npc_dota_goodguys_tower1_mid"},
+            {"time":1500,"type":"CHAT_MESSAGE_ROSHAN_KILL","team":2}
+        ],"players":[
+            {"kills_log":[{"time":300,"key":"npc_dota_hero_axe"}]},
+            {"kills_log":null}
+        ]}"#;
+        assert_eq!(
+            parse_opendota_match(body).unwrap(),
+            vec![
+                event(300.0, "Kill", GameEventKind::Kill),
+                event(600.0, "Tour", GameEventKind::Objective),
+                event(1500.0, "Roshan", GameEventKind::Objective),
+            ]
+        );
+        assert!(parse_opendota_match(r#"{"objectives":null,"players":[]}"#).is_err());
+    }
+
+    #[test]
+    fn test_merge_fights() {
+        let events = vec![
+            event(120.0, "Kill", GameEventKind::Kill),
+            event(100.0, "Kill", GameEventKind::Kill),
+            event(104.0, "Kill", GameEventKind::Kill),
+            event(110.0, "Dragon", GameEventKind::Objective),
+            event(113.0, "Kill", GameEventKind::Kill),
+        ];
+        assert_eq!(
+            merge_fights(events),
+            vec![
+                event(100.0, "Combat (4 kills)", GameEventKind::Kill),
+                event(110.0, "Dragon", GameEventKind::Objective),
+            ]
+        );
+    }
+}
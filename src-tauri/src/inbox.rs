@@ -0,0 +1,103 @@
+//! Watch-later inbox of VOD links saved outside any project.
+//!
+//! Items live in `inbox.json` in the config dir. Attaching an item to a
+//! project removes it from the inbox; the frontend adds it as a streamer.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::binaries::{ensure_config_dir, get_inbox_path};
+use crate::error::{NoxError, Result};
+use crate::platform::detect_platform;
+
+/// A saved VOD link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboxItem {
+    /// Unique item ID
+    pub id: String,
+    /// VOD URL
+    pub vod_url: String,
+    /// Platform (twitch, youtube, other)
+    pub platform: String,
+    /// Streamer name, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streamer_name: Option<String>,
+    /// Free-form note ("clutch round at 1:23:00")
+    #[serde(default)]
+    pub note: String,
+    /// When the link was saved
+    pub added_at: DateTime<Utc>,
+}
+
+/// Load all inbox items (empty list if the file doesn't exist yet)
+pub fn load_inbox() -> Result<Vec<InboxItem>> {
+    let path = get_inbox_path();
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| NoxError::Config(format!("Failed to parse inbox: {}", e)))
+}
+
+/// Write all inbox items to disk
+fn write_inbox(items: &[InboxItem]) -> Result<()> {
+    ensure_config_dir()?;
+    let content = serde_json::to_string_pretty(items)
+        .map_err(|e| NoxError::Config(format!("Failed to serialize inbox: {}", e)))?;
+    std::fs::write(get_inbox_path(), content)?;
+    Ok(())
+}
+
+/// Save a VOD link to the inbox
+pub fn add_item(vod_url: &str, streamer_name: Option<String>, note: String) -> Result<InboxItem> {
+    let mut items = load_inbox()?;
+    let added_at = Utc::now();
+
+    let item = InboxItem {
+        id: format!(
+            "inbox-{}",
+            added_at.timestamp_nanos_opt().unwrap_or_default()
+        ),
+        vod_url: vod_url.trim().to_string(),
+        platform: detect_platform(vod_url).to_string(),
+        streamer_name,
+        note,
+        added_at,
+    };
+
+    items.push(item.clone());
+    write_inbox(&items)?;
+    Ok(item)
+}
+
+/// Update the note and streamer name of an item
+pub fn update_item(id: &str, streamer_name: Option<String>, note: String) -> Result<InboxItem> {
+    let mut items = load_inbox()?;
+
+    let item = items
+        .iter_mut()
+        .find(|i| i.id == id)
+        .ok_or_else(|| NoxError::Config(format!("Inbox item not found: {}", id)))?;
+    item.streamer_name = streamer_name;
+    item.note = note;
+    let updated = item.clone();
+
+    write_inbox(&items)?;
+    Ok(updated)
+}
+
+/// Remove an item and return it (None if it doesn't exist)
+pub fn take_item(id: &str) -> Result<Option<InboxItem>> {
+    let mut items = load_inbox()?;
+
+    let Some(index) = items.iter().position(|i| i.id == id) else {
+        return Ok(None);
+    };
+    let item = items.remove(index);
+
+    write_inbox(&items)?;
+    Ok(Some(item))
+}
@@ -1,30 +1,64 @@
+mod api;
 mod binaries;
 mod commands;
 mod config;
 mod error;
 mod export;
+mod game_events;
+mod inbox;
+mod localtime;
+mod logging;
+mod manifest;
+mod markers;
 mod montage;
 mod platform;
 mod project;
 mod proxy;
+mod scratch;
+mod thumbnails;
+mod workdir;
 
 use commands::{
-    check_binaries, check_clips_status, delete_project_files, download_binary, export_clips,
-    export_montage, get_clips_dir, get_proxy_url, get_work_dir, list_project_clips, list_projects,
-    load_project, open_clips_folder, open_montages_folder, pick_work_dir, resolve_vod_url,
-    save_project, set_work_dir,
+    add_inbox_item, add_live_marker, analyze_music_beats, attach_inbox_item, benchmark_encoders,
+    cancel_montage, check_binaries, check_clips_status, check_muted_segments, clear_segment_cache,
+    convert_marker_session, delete_clip_file, delete_marker_session, delete_montage,
+    delete_overlay_preset, delete_project_files, download_binary, export_action_compilation,
+    export_action_grid, export_clips, export_montage, export_montage_config, export_montages,
+    export_project_archive, export_streamer_reels, extract_sync_audio, fetch_vod_recorded_at,
+    generate_manifest, generate_proxies, get_api_settings, get_app_paths, get_clips_dir, get_frame,
+    get_hls_reconnect, get_loudness_target, get_marker_hotkey, get_max_clip_size,
+    get_migration_report, get_montage_timeout_factor, get_proxy_url, get_segment_cache_enabled,
+    get_thumbnails, get_work_dir, import_match_events, import_montage_config,
+    import_project_archive, list_inbox, list_marker_sessions, list_overlay_presets,
+    list_project_clips, list_project_montages, list_projects, load_montage, load_project,
+    normalize_clip, open_app_path, open_clips_folder, open_montages_folder, pick_image_file,
+    pick_music_file, pick_video_file, pick_work_dir, prepare_project, preview_montage,
+    regenerate_api_token, register_marker_hotkey, relink_montage_clips, remove_inbox_item,
+    rename_action_clips, render_saved_montage, resolve_vod_url, save_montage, save_overlay_preset,
+    save_project, set_api_enabled, set_hls_reconnect, set_loudness_target, set_marker_hotkey,
+    set_max_clip_size, set_montage_timeout_factor, set_segment_cache_enabled, set_work_dir,
+    smoke_test_vod, start_marker_session, stop_marker_session, trim_exported_clip, trim_local_clip,
+    update_inbox_item, validate_montage_inputs, verify_manifest, verify_project_clips,
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize logging
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    // Initialize logging (stderr and a rotated log file)
+    logging::init();
 
     // Initialize config (detects best encoder)
     config::init_config();
 
     log::info!("Starting Nox v{}", env!("CARGO_PKG_VERSION"));
 
+    // Clear scratch folders of jobs a previous run never finished
+    scratch::remove_stale(&scratch::temp_root());
+
+    // Move clips from legacy layouts into streamer folders (slow on network drives)
+    std::thread::spawn(|| {
+        project::migrate::migrate_all();
+    });
+
     // Start HLS proxy server in background
     // First, find an available port synchronously
     if proxy::init_proxy_port().is_some() {
@@ -38,25 +72,103 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .setup(|app| {
+            // Record live markers from the saved global shortcut
+            register_marker_hotkey(app.handle());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             export_clips,
             check_clips_status,
+            check_muted_segments,
             get_clips_dir,
             open_clips_folder,
             resolve_vod_url,
+            smoke_test_vod,
+            prepare_project,
             get_proxy_url,
             check_binaries,
             download_binary,
             get_work_dir,
             set_work_dir,
             pick_work_dir,
+            get_max_clip_size,
+            set_max_clip_size,
+            get_loudness_target,
+            set_loudness_target,
+            get_hls_reconnect,
+            set_hls_reconnect,
+            get_montage_timeout_factor,
+            set_montage_timeout_factor,
+            benchmark_encoders,
+            get_segment_cache_enabled,
+            set_segment_cache_enabled,
+            clear_segment_cache,
+            get_api_settings,
+            set_api_enabled,
+            regenerate_api_token,
+            get_app_paths,
+            open_app_path,
             save_project,
             load_project,
             list_projects,
             delete_project_files,
+            export_project_archive,
+            import_project_archive,
+            delete_clip_file,
+            get_migration_report,
             export_montage,
+            export_montages,
+            preview_montage,
+            cancel_montage,
+            export_action_compilation,
+            export_action_grid,
+            export_streamer_reels,
             list_project_clips,
             open_montages_folder,
+            pick_music_file,
+            analyze_music_beats,
+            pick_video_file,
+            pick_image_file,
+            trim_exported_clip,
+            trim_local_clip,
+            verify_project_clips,
+            rename_action_clips,
+            generate_manifest,
+            verify_manifest,
+            normalize_clip,
+            generate_proxies,
+            get_frame,
+            extract_sync_audio,
+            get_thumbnails,
+            list_overlay_presets,
+            save_overlay_preset,
+            delete_overlay_preset,
+            export_montage_config,
+            import_montage_config,
+            relink_montage_clips,
+            validate_montage_inputs,
+            list_project_montages,
+            save_montage,
+            load_montage,
+            render_saved_montage,
+            delete_montage,
+            list_inbox,
+            add_inbox_item,
+            update_inbox_item,
+            remove_inbox_item,
+            attach_inbox_item,
+            list_marker_sessions,
+            start_marker_session,
+            stop_marker_session,
+            add_live_marker,
+            get_marker_hotkey,
+            set_marker_hotkey,
+            delete_marker_session,
+            fetch_vod_recorded_at,
+            convert_marker_session,
+            import_match_events,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,53 @@
+//! Local-time formatting for reports and file names.
+//!
+//! Timestamps are stored in UTC. A project may name the IANA timezone of its
+//! event (e.g. "Europe/Berlin"); times shown to users follow that zone, or the
+//! system zone when the project has none.
+
+use chrono::{DateTime, Local, Utc};
+use chrono_tz::Tz;
+
+/// Parse an IANA timezone name
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.trim().parse().ok()
+}
+
+/// Readable time for reports: `2026-10-16 17:34:25 CEST`
+pub fn format_report(time: DateTime<Utc>, timezone: Option<&str>) -> String {
+    format_in(time, timezone, "%Y-%m-%d %H:%M:%S %Z")
+}
+
+/// Sortable time for file names: `20261016_173425`
+pub fn format_filename(time: DateTime<Utc>, timezone: Option<&str>) -> String {
+    format_in(time, timezone, "%Y%m%d_%H%M%S")
+}
+
+/// Format `time` in `timezone`, falling back to the system zone
+fn format_in(time: DateTime<Utc>, timezone: Option<&str>, fmt: &str) -> String {
+    match timezone.and_then(parse_timezone) {
+        Some(tz) => time.with_timezone(&tz).format(fmt).to_string(),
+        None => time.with_timezone(&Local).format(fmt).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_in_timezone() {
+        let time = DateTime::parse_from_rfc3339("2026-07-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            format_report(time, Some("Europe/Berlin")),
+            "2026-07-01 14:00:00 CEST"
+        );
+        assert_eq!(
+            format_filename(time, Some("America/Los_Angeles")),
+            "20260701_050000"
+        );
+        assert!(parse_timezone("Mars/Olympus").is_none());
+    }
+}
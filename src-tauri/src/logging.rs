@@ -0,0 +1,144 @@
+//! Log output to stderr and a size-capped log file.
+//!
+//! The log is written to `nox.log` in the log dir. Once it reaches
+//! `LOG_MAX_BYTES` it is rotated to `nox.1.log` (older files shift up) and
+//! only the newest `LOG_KEEP_FILES` rotated files are kept, so the config dir
+//! doesn't grow without bound.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::binaries::get_log_dir;
+
+/// Size at which the log file is rotated
+const LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated log files kept next to the current one
+const LOG_KEEP_FILES: usize = 3;
+
+/// Name of the current log file
+const LOG_FILE_NAME: &str = "nox.log";
+
+/// Set up `log` output (level from `RUST_LOG`, info by default)
+pub fn init() {
+    let mut builder =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"));
+
+    match RotatingFile::open(&get_log_dir(), LOG_MAX_BYTES, LOG_KEEP_FILES) {
+        Ok(file) => {
+            builder.target(env_logger::Target::Pipe(Box::new(Tee(file))));
+        }
+        Err(e) => eprintln!("Failed to open log file, logging to stderr only: {}", e),
+    }
+    builder.init();
+}
+
+/// Writes to stderr and a log file
+struct Tee(RotatingFile);
+
+impl Write for Tee {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let _ = io::stderr().write_all(buf);
+        self.0.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let _ = io::stderr().flush();
+        self.0.flush()
+    }
+}
+
+/// Log file that rotates itself once it reaches `max_bytes`
+struct RotatingFile {
+    dir: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    keep: usize,
+}
+
+impl RotatingFile {
+    fn open(dir: &Path, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(LOG_FILE_NAME);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            file,
+            written,
+            max_bytes,
+            keep,
+        })
+    }
+
+    /// `nox.N.log` (N = 0 is the current file)
+    fn path(&self, n: usize) -> PathBuf {
+        match n {
+            0 => self.dir.join(LOG_FILE_NAME),
+            n => self.dir.join(format!("nox.{}.log", n)),
+        }
+    }
+
+    /// Shift `nox.log` -> `nox.1.log` -> ... dropping the oldest, then start
+    /// a new `nox.log`
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let _ = std::fs::remove_file(self.path(self.keep));
+        for n in (0..self.keep).rev() {
+            let from = self.path(n);
+            if from.exists() {
+                std::fs::rename(&from, self.path(n + 1))?;
+            }
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(0))?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let n = self.file.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotating_file() {
+        let dir = std::env::temp_dir().join(format!("nox-log-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut log = RotatingFile::open(&dir, 10, 2).unwrap();
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            log.write_all(line.as_bytes()).unwrap();
+        }
+        log.flush().unwrap();
+
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).ok();
+        assert_eq!(read("nox.log").as_deref(), Some("fourth\n"));
+        assert_eq!(read("nox.1.log").as_deref(), Some("third\n"));
+        assert_eq!(read("nox.2.log").as_deref(), Some("second\n"));
+        // Only two rotated files are kept
+        assert_eq!(read("nox.3.log"), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
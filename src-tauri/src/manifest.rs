@@ -0,0 +1,283 @@
+//! Checksum manifest of a project's exported files, for delivery.
+//!
+//! `checksums.json` (sizes, durations, SHA-256) and a `sha256sum`-compatible
+//! `checksums.txt` are written to the project folder. They list every media
+//! file under `clips/` and `montages/` by its path relative to that folder, so
+//! a copied folder can be checked against the manifest it was sent with.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::error::{NoxError, Result};
+use crate::export::Container;
+use crate::montage::MontageContainer;
+
+/// Current version of the manifest format
+const MANIFEST_VERSION: u32 = 1;
+
+pub const MANIFEST_FILE: &str = "checksums.json";
+pub const CHECKSUMS_FILE: &str = "checksums.txt";
+
+/// Folders of a project that are delivered
+const DELIVERY_DIRS: [&str; 2] = ["clips", "montages"];
+
+/// One delivered file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Path relative to the manifest, with `/` separators
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    /// Media duration in seconds (None if ffprobe couldn't read it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+}
+
+/// Manifest file contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub project: String,
+    pub generated_at: DateTime<Utc>,
+    pub files: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    pub fn new(project: &str, files: Vec<ManifestEntry>) -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            project: project.to_string(),
+            generated_at: Utc::now(),
+            files,
+        }
+    }
+
+    /// `sha256sum -c` compatible listing
+    pub fn checksums_text(&self) -> String {
+        self.files
+            .iter()
+            .map(|f| format!("{}  {}\n", f.sha256, f.path))
+            .collect()
+    }
+}
+
+/// Outcome of checking one file against the manifest
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum FileCheck {
+    Ok,
+    Missing,
+    SizeMismatch { expected: u64, actual: u64 },
+    HashMismatch,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestCheck {
+    pub path: String,
+    #[serde(flatten)]
+    pub result: FileCheck,
+}
+
+/// Media files to deliver from a project folder (hidden entries skipped)
+pub fn delivery_files(project_dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in DELIVERY_DIRS {
+        collect_media(&project_dir.join(dir), &mut files);
+    }
+    files.sort();
+    files
+}
+
+fn collect_media(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            collect_media(&path, files);
+        } else if Container::from_path(&path).is_some()
+            || MontageContainer::from_path(&path).is_some()
+        {
+            files.push(path);
+        }
+    }
+}
+
+/// Manifest entry for a file (duration left unset)
+pub fn entry_for(base: &Path, path: &Path) -> Result<ManifestEntry> {
+    Ok(ManifestEntry {
+        path: relative_path(base, path),
+        size: std::fs::metadata(path)?.len(),
+        sha256: sha256_file(path)?,
+        duration: None,
+    })
+}
+
+/// Hex SHA-256 of a file, read in chunks
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn relative_path(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Write `checksums.json` and `checksums.txt` into `dir`, returning the JSON path
+pub fn write(dir: &Path, manifest: &Manifest) -> Result<PathBuf> {
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| NoxError::Config(format!("Failed to serialize manifest: {}", e)))?;
+    let path = dir.join(MANIFEST_FILE);
+    std::fs::write(&path, json)?;
+    std::fs::write(dir.join(CHECKSUMS_FILE), manifest.checksums_text())?;
+    Ok(path)
+}
+
+fn read(manifest_path: &Path) -> Result<Manifest> {
+    let content = std::fs::read_to_string(manifest_path)?;
+    let manifest: Manifest = serde_json::from_str(&content)
+        .map_err(|e| NoxError::Config(format!("Invalid manifest: {}", e)))?;
+    if manifest.version > MANIFEST_VERSION {
+        return Err(NoxError::Config(format!(
+            "Manifest version {} is newer than supported ({})",
+            manifest.version, MANIFEST_VERSION
+        )));
+    }
+    Ok(manifest)
+}
+
+/// Point the manifest in `dir` (if any) at files that were renamed
+pub fn rename_entries(dir: &Path, renames: &[(PathBuf, PathBuf)]) -> Result<()> {
+    let manifest_path = dir.join(MANIFEST_FILE);
+    if !manifest_path.is_file() {
+        return Ok(());
+    }
+
+    let mut manifest = read(&manifest_path)?;
+    let mut changed = false;
+    for (from, to) in renames {
+        let from = relative_path(dir, from);
+        for entry in manifest.files.iter_mut().filter(|e| e.path == from) {
+            entry.path = relative_path(dir, to);
+            changed = true;
+        }
+    }
+
+    if changed {
+        write(dir, &manifest)?;
+    }
+    Ok(())
+}
+
+/// Check the files next to a manifest against it
+pub fn verify(manifest_path: &Path) -> Result<Vec<ManifestCheck>> {
+    let manifest = read(manifest_path)?;
+
+    let base = manifest_path.parent().unwrap_or(Path::new("."));
+    manifest
+        .files
+        .iter()
+        .map(|entry| {
+            Ok(ManifestCheck {
+                path: entry.path.clone(),
+                result: check_file(base, entry)?,
+            })
+        })
+        .collect()
+}
+
+fn check_file(base: &Path, entry: &ManifestEntry) -> Result<FileCheck> {
+    // Manifest paths come from another machine; never follow them outside `base`
+    let relative = Path::new(&entry.path);
+    if relative
+        .components()
+        .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        return Ok(FileCheck::Missing);
+    }
+
+    let path = base.join(relative);
+    let Ok(meta) = std::fs::metadata(&path) else {
+        return Ok(FileCheck::Missing);
+    };
+    if meta.len() != entry.size {
+        return Ok(FileCheck::SizeMismatch {
+            expected: entry.size,
+            actual: meta.len(),
+        });
+    }
+    if sha256_file(&path)? != entry.sha256 {
+        return Ok(FileCheck::HashMismatch);
+    }
+    Ok(FileCheck::Ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let dir = std::env::temp_dir().join("nox-manifest-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("clips").join("A")).unwrap();
+        std::fs::create_dir_all(dir.join("montages")).unwrap();
+        std::fs::write(dir.join("clips").join("A").join("abc_ace.mp4"), b"clip").unwrap();
+        std::fs::write(dir.join("montages").join("m.mp4"), b"montage").unwrap();
+        std::fs::write(dir.join("montages").join("m.webm"), b"web").unwrap();
+        std::fs::write(dir.join("clips").join("notes.txt"), b"skip").unwrap();
+
+        let files = delivery_files(&dir);
+        let entries: Vec<ManifestEntry> =
+            files.iter().map(|f| entry_for(&dir, f).unwrap()).collect();
+        assert_eq!(entries[0].path, "clips/A/abc_ace.mp4");
+        assert_eq!(entries.len(), 3);
+        // echo -n clip | sha256sum
+        assert_eq!(
+            entries[0].sha256,
+            "67905ad3cc2dd52b1f5f6a6d2814de0396618b29b4238b9af5207aeb69936e6d"
+        );
+
+        let manifest_path = write(&dir, &Manifest::new("p", entries)).unwrap();
+        assert!(std::fs::read_to_string(dir.join(CHECKSUMS_FILE))
+            .unwrap()
+            .contains("  montages/m.mp4\n"));
+
+        let clip = dir.join("clips").join("A").join("abc_ace.mp4");
+        let renamed = dir.join("clips").join("A").join("abc_clutch.mp4");
+        rename_entries(&dir, &[(clip, renamed)]).unwrap();
+        assert_eq!(
+            read(&manifest_path).unwrap().files[0].path,
+            "clips/A/abc_clutch.mp4"
+        );
+
+        std::fs::write(dir.join("montages").join("m.mp4"), b"montagE").unwrap();
+        std::fs::remove_file(dir.join("clips").join("A").join("abc_ace.mp4")).unwrap();
+        let checks = verify(&manifest_path).unwrap();
+        assert_eq!(checks[0].result, FileCheck::Missing);
+        assert_eq!(checks[1].result, FileCheck::HashMismatch);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
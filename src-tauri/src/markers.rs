@@ -0,0 +1,170 @@
+//! Live action markers recorded during a match.
+//!
+//! Markers store wall-clock time only; once the VODs are available they are
+//! converted into per-streamer VOD offsets and project actions. Markers come
+//! from the `add_live_marker` command, the global marker shortcut set with
+//! `set_marker_hotkey` (works while a game has focus) or the local scripting
+//! API (`POST /api/markers`).
+//!
+//! Sessions live in `live_markers.json` in the config dir.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::binaries::{ensure_config_dir, get_markers_path};
+use crate::error::{NoxError, Result};
+
+/// A single "action at T" marker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveMarker {
+    /// Unique marker ID
+    pub id: String,
+    /// Optional label ("ace", "clutch", ...)
+    #[serde(default)]
+    pub label: String,
+    /// Wall-clock time the marker was recorded
+    pub at: DateTime<Utc>,
+}
+
+/// A recording session (usually one live match)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkerSession {
+    /// Unique session ID
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// When the session was started
+    pub started_at: DateTime<Utc>,
+    /// When the session was stopped (None = still active)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Markers in recording order
+    #[serde(default)]
+    pub markers: Vec<LiveMarker>,
+}
+
+/// Held while `live_markers.json` is read or rewritten: markers come from
+/// the Tauri runtime, the shortcut handler and the API runtime at once, and
+/// an unguarded read-modify-write would drop one of two quick markers
+static SESSIONS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Load all sessions (empty list if the file doesn't exist yet)
+pub fn load_sessions() -> Result<Vec<MarkerSession>> {
+    let _guard = SESSIONS_LOCK.lock().unwrap();
+    read_sessions()
+}
+
+fn read_sessions() -> Result<Vec<MarkerSession>> {
+    let path = get_markers_path();
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| NoxError::Config(format!("Failed to parse live markers: {}", e)))
+}
+
+/// Write all sessions to disk
+fn write_sessions(sessions: &[MarkerSession]) -> Result<()> {
+    ensure_config_dir()?;
+    let content = serde_json::to_string_pretty(sessions)
+        .map_err(|e| NoxError::Config(format!("Failed to serialize live markers: {}", e)))?;
+    std::fs::write(get_markers_path(), content)?;
+    Ok(())
+}
+
+fn new_id(prefix: &str, at: DateTime<Utc>) -> String {
+    format!(
+        "{}-{}",
+        prefix,
+        at.timestamp_nanos_opt().unwrap_or_default()
+    )
+}
+
+/// Close any active session and start a new one
+pub fn start_session(name: String) -> Result<MarkerSession> {
+    let _guard = SESSIONS_LOCK.lock().unwrap();
+    let mut sessions = read_sessions()?;
+    let now = Utc::now();
+
+    for session in sessions.iter_mut().filter(|s| s.ended_at.is_none()) {
+        session.ended_at = Some(now);
+    }
+
+    let session = MarkerSession {
+        id: new_id("session", now),
+        name,
+        started_at: now,
+        ended_at: None,
+        markers: Vec::new(),
+    };
+
+    sessions.push(session.clone());
+    write_sessions(&sessions)?;
+    Ok(session)
+}
+
+/// Stop the active session, if any
+pub fn stop_session() -> Result<Option<MarkerSession>> {
+    let _guard = SESSIONS_LOCK.lock().unwrap();
+    let mut sessions = read_sessions()?;
+
+    let Some(session) = sessions.iter_mut().find(|s| s.ended_at.is_none()) else {
+        return Ok(None);
+    };
+    session.ended_at = Some(Utc::now());
+    let stopped = session.clone();
+
+    write_sessions(&sessions)?;
+    Ok(Some(stopped))
+}
+
+/// Record a marker at the current time in the active session
+///
+/// A session is started automatically so a marker is never lost.
+pub fn add_marker(label: String) -> Result<LiveMarker> {
+    let _guard = SESSIONS_LOCK.lock().unwrap();
+    let now = Utc::now();
+    let mut sessions = read_sessions()?;
+
+    if !sessions.iter().any(|s| s.ended_at.is_none()) {
+        sessions.push(MarkerSession {
+            id: new_id("session", now),
+            name: format!("Live {}", now.format("%Y-%m-%d %H:%M")),
+            started_at: now,
+            ended_at: None,
+            markers: Vec::new(),
+        });
+    }
+
+    let marker = LiveMarker {
+        id: new_id("marker", now),
+        label,
+        at: now,
+    };
+
+    if let Some(session) = sessions.iter_mut().find(|s| s.ended_at.is_none()) {
+        session.markers.push(marker.clone());
+    }
+
+    write_sessions(&sessions)?;
+    log::info!("[Markers] Recorded marker at {}", now.to_rfc3339());
+    Ok(marker)
+}
+
+/// Delete a session and its markers (no-op if it doesn't exist)
+pub fn delete_session(id: &str) -> Result<()> {
+    let _guard = SESSIONS_LOCK.lock().unwrap();
+    let mut sessions = read_sessions()?;
+    let before = sessions.len();
+    sessions.retain(|s| s.id != id);
+
+    if sessions.len() != before {
+        write_sessions(&sessions)?;
+    }
+
+    Ok(())
+}
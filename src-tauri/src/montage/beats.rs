@@ -0,0 +1,305 @@
+//! Beat detection on a music track, for cutting clips on the beat.
+//!
+//! The track is decoded by ffmpeg to mono PCM at a low rate. Onsets are the
+//! peaks of the rise in short-term energy (log energy per frame, half-wave
+//! rectified difference) that stand out from their neighbourhood. The tempo
+//! is the strongest period of that onset envelope between 60 and 180 BPM.
+
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use crate::binaries::get_binary_manager;
+use crate::error::{ExportError, ExportResult};
+
+/// Decoding a whole track is quick, but music files can be long mixes
+const ANALYSIS_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Sample rate the track is decoded at (drums and bass stay well below Nyquist)
+const ANALYSIS_RATE: u32 = 11025;
+
+/// Samples per energy frame (~23 ms)
+const FRAME: usize = 256;
+
+/// Floor of the frame energy, so near-silence doesn't make large jumps (-60 dB)
+const ENERGY_FLOOR: f64 = 1e-6;
+
+/// Shortest time between two suggested cuts (seconds)
+const MIN_BEAT_GAP: f64 = 0.25;
+
+/// Frames on each side an onset is compared with
+const LOCAL_WINDOW: usize = 16;
+
+/// Tempo range searched, in BPM
+const MIN_BPM: f64 = 60.0;
+const MAX_BPM: f64 = 180.0;
+
+/// Onsets of a music track, as suggested cut points
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BeatAnalysis {
+    /// Length of the track (seconds); the montage loops it
+    pub duration: f64,
+    /// Estimated tempo (None when the track has no clear beat)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bpm: Option<f64>,
+    /// Onset times in seconds, ascending
+    pub beats: Vec<f64>,
+}
+
+impl BeatAnalysis {
+    /// Latest beat at or before `t` in the montage, where the track loops
+    pub fn beat_before(&self, t: f64) -> Option<f64> {
+        if self.duration <= 0.0 || t < 0.0 {
+            return None;
+        }
+        let lap = (t / self.duration).floor();
+        // The previous lap's last beat if none is before `t` in this one
+        [lap, lap - 1.0]
+            .into_iter()
+            .filter(|lap| *lap >= 0.0)
+            .find_map(|lap| {
+                let start = lap * self.duration;
+                self.beats
+                    .iter()
+                    .rev()
+                    .map(|b| start + b)
+                    .find(|b| *b <= t + 1e-6)
+            })
+    }
+}
+
+/// Decode a music file and find its beats
+pub async fn analyze(path: &Path) -> ExportResult<BeatAnalysis> {
+    let mut cmd = build_decode_command(&ffmpeg_path(), path);
+    cmd.kill_on_drop(true);
+
+    let output = timeout(ANALYSIS_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| {
+            ExportError::Timeout(format!(
+                "Beat analysis timed out after {} seconds",
+                ANALYSIS_TIMEOUT.as_secs()
+            ))
+        })?
+        .map_err(|e| ExportError::Ffmpeg(format!("Failed to run FFmpeg: {}", e)))?;
+
+    if !output.status.success() || output.stdout.is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::warn!("[Beats] FFmpeg failed: {}", stderr.trim());
+        return Err(ExportError::Ffmpeg(format!(
+            "Failed to decode music file {}",
+            path.display()
+        )));
+    }
+
+    let samples: Vec<f32> = output
+        .stdout
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    let analysis = analyze_samples(&samples, ANALYSIS_RATE);
+    log::info!(
+        "[Beats] {} onsets in {:.1}s of {} ({:?} BPM)",
+        analysis.beats.len(),
+        analysis.duration,
+        path.display(),
+        analysis.bpm.map(|b| b.round())
+    );
+    Ok(analysis)
+}
+
+fn build_decode_command(ffmpeg: &str, path: &Path) -> Command {
+    let mut cmd = Command::new(ffmpeg);
+    cmd.args(["-nostdin", "-i"]);
+    cmd.arg(path);
+    cmd.args([
+        "-vn",
+        "-sn",
+        "-dn",
+        "-ac",
+        "1",
+        "-ar",
+        &ANALYSIS_RATE.to_string(),
+        "-f",
+        "f32le",
+        "-",
+    ]);
+    cmd.stdin(std::process::Stdio::null());
+    cmd.stderr(std::process::Stdio::piped());
+    cmd.stdout(std::process::Stdio::piped());
+    #[cfg(target_os = "windows")]
+    cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    cmd
+}
+
+fn ffmpeg_path() -> String {
+    get_binary_manager()
+        .ffmpeg_path()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|| "ffmpeg".to_string())
+}
+
+fn analyze_samples(samples: &[f32], rate: u32) -> BeatAnalysis {
+    let frame_secs = FRAME as f64 / rate as f64;
+    let envelope = onset_envelope(samples);
+    BeatAnalysis {
+        duration: samples.len() as f64 / rate as f64,
+        bpm: estimate_bpm(&envelope, frame_secs),
+        beats: pick_onsets(&envelope, frame_secs)
+            .into_iter()
+            .map(|i| i as f64 * frame_secs)
+            .collect(),
+    }
+}
+
+/// Rise in log energy from each frame to the next (0 when it falls)
+fn onset_envelope(samples: &[f32]) -> Vec<f64> {
+    let log_energy: Vec<f64> = samples
+        .chunks(FRAME)
+        .map(|frame| {
+            let energy =
+                frame.iter().map(|s| (*s as f64).powi(2)).sum::<f64>() / frame.len() as f64;
+            energy.max(ENERGY_FLOOR).ln()
+        })
+        .collect();
+    std::iter::once(0.0)
+        .chain(log_energy.windows(2).map(|w| (w[1] - w[0]).max(0.0)))
+        .collect()
+}
+
+/// Frames whose rise is the largest around them and well above the local average
+fn pick_onsets(envelope: &[f64], frame_secs: f64) -> Vec<usize> {
+    let peak = envelope.iter().copied().fold(0.0, f64::max);
+    if peak <= 0.0 {
+        return Vec::new();
+    }
+    let gap = (MIN_BEAT_GAP / frame_secs).ceil() as usize;
+
+    let mut onsets: Vec<usize> = Vec::new();
+    for (i, &value) in envelope.iter().enumerate() {
+        let local =
+            &envelope[i.saturating_sub(LOCAL_WINDOW)..(i + LOCAL_WINDOW + 1).min(envelope.len())];
+        let mean = local.iter().sum::<f64>() / local.len() as f64;
+        let neighbours =
+            &envelope[i.saturating_sub(gap / 2)..(i + gap / 2 + 1).min(envelope.len())];
+        let is_peak = neighbours.iter().all(|v| *v <= value);
+        if is_peak
+            && value > mean * 1.5
+            && value > peak * 0.1
+            && onsets.last().is_none_or(|last| i - last >= gap)
+        {
+            onsets.push(i);
+        }
+    }
+    onsets
+}
+
+/// Tempo from the autocorrelation of the onset envelope
+fn estimate_bpm(envelope: &[f64], frame_secs: f64) -> Option<f64> {
+    let min_lag = (60.0 / MAX_BPM / frame_secs).round() as usize;
+    let max_lag = (60.0 / MIN_BPM / frame_secs).round() as usize;
+    if envelope.len() <= max_lag * 2 {
+        return None;
+    }
+
+    // Spread each onset over 3 frames, so beats that don't fall on a whole
+    // number of frames still line up
+    let smoothed: Vec<f64> = (0..envelope.len())
+        .map(|i| {
+            envelope[i.saturating_sub(1)..(i + 2).min(envelope.len())]
+                .iter()
+                .sum()
+        })
+        .collect();
+    let score = |lag: usize| -> f64 {
+        smoothed
+            .iter()
+            .zip(&smoothed[lag..])
+            .map(|(a, b)| a * b)
+            .sum()
+    };
+    let best = |lags: std::ops::RangeInclusive<usize>| {
+        lags.map(|lag| (lag, score(lag)))
+            .fold((0, 0.0), |best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    };
+
+    let (mut lag, score) = best(min_lag..=max_lag);
+    if score <= 0.0 {
+        return None;
+    }
+    // A beat also repeats every two beats; prefer the faster tempo when it
+    // scores nearly as well
+    let half = lag / 2;
+    if half > min_lag {
+        let (half_lag, half_score) = best(half - 1..=half + 1);
+        if half_score > score * 0.5 {
+            lag = half_lag;
+        }
+    }
+    Some(60.0 / (lag as f64 * frame_secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Short noise bursts every `period` seconds over silence
+    fn clicks(period: f64, seconds: f64) -> Vec<f32> {
+        let rate = ANALYSIS_RATE as f64;
+        let mut samples = vec![0.0f32; (seconds * rate) as usize];
+        let mut t = period;
+        while t < seconds {
+            let start = (t * rate) as usize;
+            for (k, s) in samples[start..].iter_mut().take(400).enumerate() {
+                *s = if k % 2 == 0 { 0.8 } else { -0.8 };
+            }
+            t += period;
+        }
+        samples
+    }
+
+    #[test]
+    fn test_beats_of_clicks() {
+        let analysis = analyze_samples(&clicks(0.5, 10.0), ANALYSIS_RATE);
+        assert_eq!(analysis.duration, 10.0);
+        assert_eq!(analysis.beats.len(), 19);
+        for (k, beat) in analysis.beats.iter().enumerate() {
+            let expected = (k + 1) as f64 * 0.5;
+            assert!((beat - expected).abs() < 0.05, "{beat} vs {expected}");
+        }
+        let bpm = analysis.bpm.unwrap();
+        assert!((bpm - 120.0).abs() < 3.0, "{bpm}");
+
+        // Silence has no beat
+        let silent = analyze_samples(&vec![0.0; 44100], ANALYSIS_RATE);
+        assert!(silent.beats.is_empty());
+        assert_eq!(silent.bpm, None);
+    }
+
+    #[test]
+    fn test_beat_before_loops() {
+        let analysis = BeatAnalysis {
+            duration: 10.0,
+            bpm: None,
+            beats: vec![1.0, 4.0, 8.0],
+        };
+        assert_eq!(analysis.beat_before(5.0), Some(4.0));
+        assert_eq!(analysis.beat_before(8.0), Some(8.0));
+        assert_eq!(analysis.beat_before(0.5), None);
+        // Second time through the track
+        assert_eq!(analysis.beat_before(10.5), Some(8.0));
+        assert_eq!(analysis.beat_before(14.5), Some(14.0));
+    }
+}
@@ -0,0 +1,138 @@
+//! Cancelling the montage exports in progress.
+//!
+//! Each export (or batch of them) is a job with its own [`CancelToken`],
+//! registered while a [`CancelScope`] for it is held. [`request_cancel`]
+//! cancels the jobs registered at that moment: every montage FFmpeg step of
+//! a cancelled job checks its token before it starts, and the step that is
+//! running is woken so it can kill its FFmpeg process. Jobs started later
+//! get a fresh token and are not affected.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::futures::Notified;
+use tokio::sync::Notify;
+
+use crate::error::{ExportError, ExportResult};
+
+/// Jobs that [`request_cancel`] reaches
+static JOBS: Mutex<Vec<CancelToken>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Default)]
+struct Job {
+    requested: AtomicBool,
+    notify: Notify,
+}
+
+/// Cancellation state of one montage job, shared by its steps
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<Job>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop the job: steps not started yet won't run, the running one is woken
+    pub fn cancel(&self) {
+        self.0.requested.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    /// Whether the job was asked to stop
+    pub fn is_cancelled(&self) -> bool {
+        self.0.requested.load(Ordering::SeqCst)
+    }
+
+    /// Future completing on the next cancel request
+    ///
+    /// Check [`Self::is_cancelled`] after enabling it, or a request made in
+    /// between is missed.
+    pub(super) fn cancelled(&self) -> Notified<'_> {
+        self.0.notify.notified()
+    }
+
+    /// Run `step` unless the job is cancelled, dropping it when a cancel
+    /// request arrives while it runs
+    ///
+    /// FFmpeg commands run by `step` must be `kill_on_drop` so dropping it
+    /// stops them.
+    pub(super) async fn guard<T>(
+        &self,
+        step: impl Future<Output = ExportResult<T>>,
+    ) -> ExportResult<T> {
+        let cancelled = self.cancelled();
+        tokio::pin!(cancelled);
+        cancelled.as_mut().enable();
+        if self.is_cancelled() {
+            return Err(ExportError::Cancelled);
+        }
+        tokio::select! {
+            result = step => result,
+            _ = cancelled => Err(ExportError::Cancelled),
+        }
+    }
+}
+
+/// Keeps a job reachable by [`request_cancel`] while it runs
+pub struct CancelScope(CancelToken);
+
+impl CancelScope {
+    pub fn enter(token: &CancelToken) -> Self {
+        JOBS.lock().unwrap().push(token.clone());
+        Self(token.clone())
+    }
+}
+
+impl Drop for CancelScope {
+    fn drop(&mut self) {
+        let mut jobs = JOBS.lock().unwrap();
+        if let Some(i) = jobs.iter().position(|job| Arc::ptr_eq(&job.0, &(self.0).0)) {
+            jobs.remove(i);
+        }
+    }
+}
+
+/// Stop the running montage exports; false if none is running
+pub fn request_cancel() -> bool {
+    let jobs = JOBS.lock().unwrap();
+    for job in jobs.iter() {
+        job.cancel();
+    }
+    !jobs.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancel_scope() {
+        assert!(!request_cancel());
+
+        let batch = CancelToken::new();
+        let batch_scope = CancelScope::enter(&batch);
+        let export_scope = CancelScope::enter(&batch);
+        assert!(request_cancel());
+        drop(export_scope);
+        // Still set for the rest of the batch, and its next steps don't run
+        assert!(batch.is_cancelled());
+        assert!(matches!(
+            batch.guard(async { Ok(()) }).await,
+            Err(ExportError::Cancelled)
+        ));
+
+        // A job started afterwards is not affected
+        let next = CancelToken::new();
+        let _next_scope = CancelScope::enter(&next);
+        assert!(!next.is_cancelled());
+        assert!(next.guard(async { Ok(()) }).await.is_ok());
+        drop(batch_scope);
+
+        // A request stops the step that is running
+        let step = next.guard(std::future::pending::<ExportResult<()>>());
+        let (result, cancelled) = tokio::join!(step, async { request_cancel() });
+        assert!(cancelled);
+        assert!(matches!(result, Err(ExportError::Cancelled)));
+    }
+}
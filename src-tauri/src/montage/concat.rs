@@ -9,14 +9,98 @@ use tokio::time::timeout;
 use std::os::windows::process::CommandExt;
 
 use crate::binaries::get_binary_manager;
+use crate::config::{get_config, FfmpegConfig, VideoEncoder};
 use crate::error::{ExportError, ExportResult};
-use crate::export::FfmpegProgressParser;
+use crate::export::cache::vod_key;
+use crate::export::{
+    concat_list_entry, loudnorm_filter, CropRect, FfmpegExporter, FfmpegProgressParser,
+    NormalizeFormat, ProgressUpdate, StderrTail, StreamParams, LOUDNESS_TARGET_RANGE,
+};
+use crate::scratch::ScratchDir;
 
-/// Timeout for montage export (15 minutes for longer videos)
-const MONTAGE_TIMEOUT: Duration = Duration::from_secs(900);
+use super::beats::{self, BeatAnalysis};
+use super::cancel::{CancelScope, CancelToken};
+use super::probe::check_inputs;
+
+/// Shortest montage timeout, for short montages with slow startup (15 minutes)
+const MIN_MONTAGE_TIMEOUT: Duration = Duration::from_secs(900);
+
+/// Timeout for rendering one title card
+const CARD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Title card text size (pixels, in the 1080p normalized frame)
+const CARD_FONT_SIZE: u32 = 96;
+
+/// Line height of multi-line cards, relative to the font size
+const CARD_LINE_SPACING: f64 = 1.3;
+
+/// Allowed title card durations (seconds)
+const CARD_DURATION_RANGE: std::ops::RangeInclusive<f64> = 0.5..=30.0;
+
+/// Duration of the overlay slide-in and its fade-out (seconds)
+const OVERLAY_ANIMATION_DURATION: f64 = 0.5;
+
+/// Duration of the overlay fade in/out (seconds)
+const OVERLAY_FADE_DURATION: f64 = 1.0;
+
+/// Margin between overlay text and the frame edge (pixels)
+const OVERLAY_MARGIN: u32 = 20;
+
+/// Safe area of 9:16 output: platform UI covers the bottom and right edges
+const PORTRAIT_SAFE_MARGIN_X: u32 = 60;
+const PORTRAIT_SAFE_MARGIN_Y: u32 = 260;
+
+/// Accepted per-clip zoom factors
+const CLIP_ZOOM_RANGE: std::ops::RangeInclusive<f64> = 1.0..=4.0;
+
+/// Accepted slow-motion factors (2 = half speed); atempo chains go down to 1/4
+const SLOWMO_FACTOR_RANGE: std::ops::RangeInclusive<f64> = 1.0..=4.0;
+
+/// Longest hold of a clip's last frame (seconds)
+const MAX_FREEZE: f64 = 10.0;
+
+/// Shortest a clip is cut to when snapped to a beat (seconds)
+const MIN_SNAPPED_LENGTH: f64 = 1.0;
+
+/// Frame height of preview renders
+const PREVIEW_HEIGHT: u32 = 480;
+
+/// x264 settings of preview renders: blocky, but quick to encode
+const PREVIEW_PRESET: &str = "ultrafast";
+const PREVIEW_CRF: u8 = 32;
+const PREVIEW_AUDIO_BITRATE: &str = "96k";
+
+/// Highest frame rate of preview renders
+const PREVIEW_FPS: u32 = 30;
+
+/// Accepted per-clip volume multipliers
+const CLIP_VOLUME_RANGE: std::ops::RangeInclusive<f64> = 0.0..=4.0;
+
+/// Accepted watermark scale, relative to the image file
+const IMAGE_SCALE_RANGE: std::ops::RangeInclusive<f64> = 0.05..=4.0;
+
+/// Sidechain compressor settings for ducking music under the clip audio:
+/// clip audio above the threshold pulls the music down by up to `ratio`
+const DUCK_THRESHOLD: f64 = 0.05;
+const DUCK_RATIO: u32 = 8;
+const DUCK_ATTACK_MS: u32 = 20;
+const DUCK_RELEASE_MS: u32 = 400;
+
+/// Frame rate clips are conformed to before `xfade` transitions
+const XFADE_FPS: u32 = 60;
+
+/// Folder (next to the montage) holding the staged mode's rendered clips
+const STAGE_DIR: &str = ".stages";
+
+/// Renders left by a montage that never finished are deleted after a week
+const STAGE_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// Extension of a render in progress (keeps ffmpeg's MP4 muxer)
+const PARTIAL_EXTENSION: &str = "part.mp4";
 
 /// Position for overlay text
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
 #[serde(rename_all = "kebab-case")]
 pub enum OverlayPosition {
     TopLeft,
@@ -27,290 +111,2475 @@ pub enum OverlayPosition {
 
 impl OverlayPosition {
     /// Get FFmpeg drawtext position coordinates
-    fn to_ffmpeg_coords(&self, margin: u32) -> String {
-        match self {
-            OverlayPosition::TopLeft => format!("x={}:y={}", margin, margin),
-            OverlayPosition::TopRight => format!("x=w-tw-{}:y={}", margin, margin),
-            OverlayPosition::BottomLeft => format!("x={}:y=h-th-{}", margin, margin),
-            OverlayPosition::BottomRight => format!("x=w-tw-{}:y=h-th-{}", margin, margin),
+    fn to_ffmpeg_coords(&self, margin_x: u32, margin_y: u32) -> String {
+        format!("x={}:y={}", self.x_expr(margin_x), self.y_expr(margin_y))
+    }
+
+    /// overlay filter coordinates of a picture (`W`/`H` = frame, `w`/`h` = picture)
+    pub(super) fn overlay_filter_coords(&self, margin_x: u32, margin_y: u32) -> String {
+        let x = if self.is_left() {
+            margin_x.to_string()
+        } else {
+            format!("W-w-{margin_x}")
+        };
+        let y = if self.is_top() {
+            margin_y.to_string()
+        } else {
+            format!("H-h-{margin_y}")
+        };
+        format!("x={x}:y={y}")
+    }
+
+    fn is_left(&self) -> bool {
+        matches!(self, OverlayPosition::TopLeft | OverlayPosition::BottomLeft)
+    }
+
+    fn is_top(&self) -> bool {
+        matches!(self, OverlayPosition::TopLeft | OverlayPosition::TopRight)
+    }
+
+    /// Resting x coordinate expression
+    fn x_expr(&self, margin: u32) -> String {
+        if self.is_left() {
+            margin.to_string()
+        } else {
+            format!("w-tw-{}", margin)
+        }
+    }
+
+    /// Resting y coordinate expression
+    fn y_expr(&self, margin: u32) -> String {
+        if self.is_top() {
+            margin.to_string()
+        } else {
+            format!("h-th-{}", margin)
+        }
+    }
+
+    /// x expression that slides in from the nearest side edge over `duration` seconds
+    fn slide_in_x_expr(&self, margin: u32, duration: f64) -> String {
+        if self.is_left() {
+            format!("if(lt(t,{duration:.2}),-tw+(tw+{margin})*t/{duration:.2},{margin})")
+        } else {
+            format!("if(lt(t,{duration:.2}),w-(tw+{margin})*t/{duration:.2},w-tw-{margin})")
         }
     }
 }
 
-/// Overlay configuration for text display
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OverlayConfig {
-    /// Text to display (supports {streamer} placeholder)
-    pub text: String,
-    /// Position on screen
-    pub position: OverlayPosition,
-    /// Font size in pixels
-    pub font_size: u32,
-    /// Text color in hex format (e.g., "FFFFFF")
-    pub color: String,
-    /// Background box color (optional, e.g., "000000@0.5" for 50% black)
-    pub box_color: Option<String>,
+/// Free overlay position in percent of the room around the text
+/// (0,0 = flush top-left, 100,100 = flush bottom-right), so the text never
+/// leaves the frame
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct OverlayPoint {
+    pub x: f64,
+    pub y: f64,
 }
 
-/// A clip in the montage sequence
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MontageClip {
-    /// Path to the clip file
-    pub path: PathBuf,
-    /// Duration of the clip in seconds
-    pub duration: f64,
-    /// Streamer name (for overlay placeholder)
-    pub streamer_name: String,
+impl OverlayPoint {
+    fn x_expr(&self) -> String {
+        format!("(w-tw)*{:.3}", self.x.clamp(0.0, 100.0) / 100.0)
+    }
+
+    fn y_expr(&self) -> String {
+        format!("(h-th)*{:.3}", self.y.clamp(0.0, 100.0) / 100.0)
+    }
+
+    /// x expression that slides in from the nearer side edge over `duration` seconds
+    fn slide_in_x_expr(&self, duration: f64) -> String {
+        let x = self.x_expr();
+        if self.x < 50.0 {
+            format!("if(lt(t,{duration:.2}),-tw+(tw+{x})*t/{duration:.2},{x})")
+        } else {
+            format!("if(lt(t,{duration:.2}),w-(w-{x})*t/{duration:.2},{x})")
+        }
+    }
 }
 
-/// Configuration for montage export
+/// Outline drawn around each glyph
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MontageConfig {
-    /// Ordered list of clips to concatenate
-    pub clips: Vec<MontageClip>,
-    /// Transition duration in seconds (0 = no transition)
-    pub transition_duration: f64,
-    /// Overlay configuration (optional)
-    pub overlay: Option<OverlayConfig>,
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct OverlayOutline {
+    /// Width in pixels
+    pub width: u32,
+    /// Hex color or name (e.g., "000000", "black")
+    pub color: String,
 }
 
-impl MontageConfig {
-    /// Calculate total duration including transitions
-    pub fn total_duration(&self) -> f64 {
-        if self.clips.is_empty() {
-            return 0.0;
-        }
+/// Drop shadow behind the text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct OverlayShadow {
+    /// Offset in pixels (positive = right/down)
+    pub x: i32,
+    pub y: i32,
+    /// Hex color or name, optionally with opacity (e.g., "000000@0.6")
+    pub color: String,
+}
 
-        let clips_duration: f64 = self.clips.iter().map(|c| c.duration).sum();
-        let transition_count = (self.clips.len() - 1) as f64;
+/// Color names accepted for overlay outlines and shadows
+const COLOR_NAMES: &[&str] = &[
+    "black", "white", "gray", "grey", "red", "green", "blue", "yellow", "orange", "purple", "pink",
+    "cyan", "magenta",
+];
 
-        // Transitions overlap clips, so we subtract their duration
-        clips_duration - (transition_count * self.transition_duration)
-    }
+/// `color` without its `@opacity` is RRGGBB or RRGGBBAA
+fn is_hex_color(color: &str) -> bool {
+    let base = color.split('@').next().unwrap_or_default();
+    matches!(base.len(), 6 | 8) && base.chars().all(|c| c.is_ascii_hexdigit())
 }
 
-/// Progress callback type
-pub type ProgressCallback = Box<dyn Fn(f32, Option<String>) + Send + Sync>;
-
-/// Montage exporter using FFmpeg
-pub struct MontageExporter;
+/// `color` is hex or a known name, optionally with an `@opacity` between 0
+/// and 1
+fn is_valid_color(color: &str) -> bool {
+    let (base, opacity) = match color.split_once('@') {
+        Some((base, opacity)) => (base, Some(opacity)),
+        None => (color, None),
+    };
+    (is_hex_color(base) || COLOR_NAMES.contains(&base.to_ascii_lowercase().as_str()))
+        && opacity.is_none_or(|o| o.parse::<f64>().is_ok_and(|o| (0.0..=1.0).contains(&o)))
+}
 
-impl Default for MontageExporter {
-    fn default() -> Self {
-        Self::new()
+/// `color` as ffmpeg reads it: hex colors get their `#`
+fn ffmpeg_color(color: &str) -> String {
+    if is_hex_color(color) {
+        format!("#{color}")
+    } else {
+        color.to_string()
     }
 }
 
-impl MontageExporter {
-    pub fn new() -> Self {
-        Self
+/// Entrance/exit animation for overlay text
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "kebab-case")]
+pub enum OverlayAnimation {
+    /// Static text for the whole clip
+    #[default]
+    None,
+    /// Fade in at the start of the clip and out before it ends
+    Fade,
+    /// Slide in from the nearest side edge, fade out before the clip ends
+    SlideIn,
+}
+
+impl OverlayAnimation {
+    /// Alpha expression fading in over `d` seconds and out over the last `d` seconds
+    fn fade_alpha_expr(clip_duration: f64, d: f64) -> String {
+        let fade_out_start = (clip_duration - d).max(d);
+        format!("if(lt(t,{d:.2}),t/{d:.2},if(gt(t,{fade_out_start:.2}),max(0,({clip_duration:.2}-t)/{d:.2}),1))")
     }
 
-    /// Get the ffmpeg binary path
-    fn ffmpeg_path(&self) -> String {
-        get_binary_manager()
-            .ffmpeg_path()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|| "ffmpeg".to_string())
+    /// Alpha expression fading out over the last `d` seconds only
+    fn fade_out_alpha_expr(clip_duration: f64, d: f64) -> String {
+        let fade_out_start = (clip_duration - d).max(0.0);
+        format!("if(gt(t,{fade_out_start:.2}),max(0,({clip_duration:.2}-t)/{d:.2}),1)")
     }
+}
 
-    /// Build the FFmpeg filter_complex string for concatenation with fades
-    fn build_filter_complex(&self, config: &MontageConfig) -> String {
-        let n = config.clips.len();
-        let fade_duration = config.transition_duration;
-        let overlay = &config.overlay;
+/// How one clip hands over to the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum TransitionStyle {
+    /// Hard cut, whatever the transition duration
+    None,
+    /// Fade through black (clips play back to back)
+    #[default]
+    Fade,
+    /// Cross-dissolve into the next clip
+    Dissolve,
+    /// Next clip wipes in from the right
+    Wipe,
+    /// Next clip pushes the current one out to the left
+    Slide,
+}
 
-        if n == 0 {
-            return String::new();
+impl TransitionStyle {
+    /// FFmpeg `xfade` transition (None = no overlap between clips)
+    fn xfade_name(self) -> Option<&'static str> {
+        match self {
+            Self::None | Self::Fade => None,
+            Self::Dissolve => Some("fade"),
+            Self::Wipe => Some("wipeleft"),
+            Self::Slide => Some("slideleft"),
         }
+    }
+}
 
-        let mut filters = Vec::new();
-
-        // Helper to get overlay filter for a specific clip index
-        let get_clip_filters = |i: usize| -> String {
-            let mut clip_filters = Vec::new();
-
-            // 1. Overlay (if configured)
-            if let Some(ov) = overlay {
-                let streamer_name = &config.clips[i].streamer_name;
-                let overlay_filter = self.build_overlay_filter(ov, streamer_name);
-                clip_filters.push(overlay_filter);
-            }
-
-            // 2. Fades (if transition configured)
-            if fade_duration > 0.0 {
-                let clip_duration = config.clips[i].duration;
-                let fade_out_start = (clip_duration - fade_duration).max(0.0);
+/// Background music mixed under the clip audio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MusicTrack {
+    pub path: PathBuf,
+    /// Linear gain applied to the track (1.0 = unchanged)
+    pub volume: f64,
+    /// Shorten each clip so the cut lands on a beat of the track
+    #[serde(default)]
+    pub snap_to_beats: bool,
+}
 
-                if n == 1 {
-                    // Single clip with transition: just fade in/out? Or no transition?
-                    // Usually transition is between clips. If single clip, maybe fade in/out is nice?
-                    // Logic below handles n > 1. For n=1, let's just fade in/out provided duration is small?
-                    // Standard logic usually entails no transition for single clip.
-                    // But let's stick to existing logic structure.
-                    // Existing logic handled n=1 separately returning null.
-                } else if i == 0 {
-                    // First clip: only fade out
-                    clip_filters.push(format!(
-                        "fade=t=out:st={fade_out_start:.2}:d={fade_duration:.2}"
-                    ));
-                } else if i == n - 1 {
-                    // Last clip: only fade in
-                    clip_filters.push(format!("fade=t=in:st=0:d={fade_duration:.2}"));
-                } else {
-                    // Middle clips: both fade in and out
-                    clip_filters.push(format!("fade=t=in:st=0:d={fade_duration:.2}"));
-                    clip_filters.push(format!(
-                        "fade=t=out:st={fade_out_start:.2}:d={fade_duration:.2}"
-                    ));
-                }
-            }
+/// Image (e.g., a logo) drawn over the whole montage, for watermarking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts-bindings",
+    derive(ts_rs::TS),
+    ts(export, optional_fields)
+)]
+pub struct ImageOverlay {
+    pub path: PathBuf,
+    #[serde(default = "default_image_position")]
+    pub position: OverlayPosition,
+    /// Free position (overrides the corner in `position`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_position: Option<OverlayPoint>,
+    /// Size relative to the image file (1.0 = native size)
+    #[serde(default = "default_image_scale")]
+    pub scale: f64,
+    /// 0.0 (invisible) to 1.0 (opaque)
+    #[serde(default = "default_image_opacity")]
+    pub opacity: f64,
+}
 
-            if clip_filters.is_empty() {
-                "null".to_string()
-            } else {
-                clip_filters.join(",")
-            }
-        };
+fn default_image_position() -> OverlayPosition {
+    OverlayPosition::TopRight
+}
 
-        if n == 1 {
-            // Single clip
-            let v_filter = get_clip_filters(0);
+fn default_image_scale() -> f64 {
+    1.0
+}
 
-            // Audio fade?
-            let a_filter = if fade_duration > 0.0 {
-                // If single clip, maybe fade in/out?
-                // Existing logic returned null for n=1. I should check if overlay exists.
-                // If overlay exists, we MUST return a filter chain.
-                // If fade > 0, we might want fades.
-                // But let's assume if n=1, we just do overlay.
-                "anull".to_string()
-            } else {
-                "anull".to_string()
-            };
+fn default_image_opacity() -> f64 {
+    0.8
+}
 
-            return format!("[0:v]{}[vout];[0:a]{}[aout]", v_filter, a_filter);
+impl ImageOverlay {
+    /// overlay filter coordinates (`W`/`H` = frame, `w`/`h` = image)
+    fn coords(&self, (margin_x, margin_y): (u32, u32)) -> String {
+        if let Some(point) = self.custom_position {
+            return format!(
+                "x=(W-w)*{:.3}:y=(H-h)*{:.3}",
+                point.x.clamp(0.0, 100.0) / 100.0,
+                point.y.clamp(0.0, 100.0) / 100.0
+            );
         }
+        self.position.overlay_filter_coords(margin_x, margin_y)
+    }
 
-        // Multiple clips
-        for i in 0..n {
-            let v_filter = get_clip_filters(i);
+    /// Filter drawing input `input` over `[vout]`, ending in `[vmark]`
+    fn filter(&self, input: usize, margins: (u32, u32)) -> String {
+        format!(
+            "[{input}:v]scale=iw*{:.3}:-1,format=rgba,colorchannelmixer=aa={:.2}[logo];\
+             [vout][logo]overlay={}[vmark]",
+            self.scale
+                .clamp(*IMAGE_SCALE_RANGE.start(), *IMAGE_SCALE_RANGE.end()),
+            self.opacity.clamp(0.0, 1.0),
+            self.coords(margins)
+        )
+    }
+}
 
-            // Audio fades
-            let mut a_filters = Vec::new();
-            if fade_duration > 0.0 {
-                let clip_duration = config.clips[i].duration;
-                let fade_out_start = (clip_duration - fade_duration).max(0.0);
+/// Generated full-frame title card: centered white text, silent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts-bindings",
+    derive(ts_rs::TS),
+    ts(export, optional_fields)
+)]
+pub struct TitleCard {
+    pub text: String,
+    /// Seconds on screen
+    pub duration: f64,
+    /// Hex background color without # (black when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<String>,
+    /// Background image, scaled to fill the frame (wins over the color)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background_image: Option<PathBuf>,
+}
 
-                if i == 0 {
-                    a_filters.push(format!(
-                        "afade=t=out:st={fade_out_start:.2}:d={fade_duration:.2}"
-                    ));
-                } else if i == n - 1 {
-                    a_filters.push(format!("afade=t=in:st=0:d={fade_duration:.2}"));
-                } else {
-                    a_filters.push(format!("afade=t=in:st=0:d={fade_duration:.2}"));
-                    a_filters.push(format!(
-                        "afade=t=out:st={fade_out_start:.2}:d={fade_duration:.2}"
-                    ));
-                }
+impl TitleCard {
+    fn validate(&self) -> Result<(), String> {
+        if !CARD_DURATION_RANGE.contains(&self.duration) {
+            return Err(format!(
+                "Title cards must last between {} and {} seconds",
+                CARD_DURATION_RANGE.start(),
+                CARD_DURATION_RANGE.end()
+            ));
+        }
+        if let Some(color) = &self.background_color {
+            if !matches!(color.len(), 6 | 8) || !color.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("Invalid card background color: {}", color));
             }
-
-            let a_filter_str = if a_filters.is_empty() {
-                "anull".to_string()
-            } else {
-                a_filters.join(",")
-            };
-
-            filters.push(format!("[{i}:v]{}[v{i}]", v_filter));
-            filters.push(format!("[{i}:a]{}[a{i}]", a_filter_str));
         }
+        if let Some(image) = &self.background_image {
+            if !image.exists() {
+                return Err(format!("Card background not found: {}", image.display()));
+            }
+        }
+        Ok(())
+    }
+}
 
-        // Concat all processed streams - must be in order [v0][a0][v1][a1]...
-        let concat_inputs: Vec<String> = (0..n).map(|i| format!("[v{i}][a{i}]")).collect();
+/// Video played before or after the clips
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts-bindings",
+    derive(ts_rs::TS),
+    ts(export, optional_fields)
+)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Bookend {
+    /// Existing video file (needs an audio track)
+    File { path: PathBuf },
+    /// Generated title card
+    Card(TitleCard),
+    /// Generated credits naming the montage's streamers
+    Credits(CreditsCard),
+}
 
-        filters.push(format!(
-            "{}concat=n={n}:v=1:a=1[vout][aout]",
-            concat_inputs.join("")
-        ));
+/// End card listing every streamer of the montage under a heading
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts-bindings",
+    derive(ts_rs::TS),
+    ts(export, optional_fields)
+)]
+pub struct CreditsCard {
+    /// Heading, e.g. the project name
+    #[serde(default)]
+    pub title: String,
+    /// Seconds on screen
+    pub duration: f64,
+    /// Hex background color without # (black when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<String>,
+}
 
-        filters.join(";")
+impl CreditsCard {
+    /// The title card: the heading, a blank line, then each streamer of
+    /// `clips` once, in order of appearance
+    fn card(&self, clips: &[MontageClip]) -> TitleCard {
+        let mut names: Vec<&str> = Vec::new();
+        for clip in clips {
+            let name = clip.streamer_name.as_str();
+            if !clip.is_bookend() && !name.is_empty() && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        let mut lines = vec![self.title.as_str(), ""];
+        lines.extend(names);
+        TitleCard {
+            text: lines.join("\n"),
+            duration: self.duration,
+            background_color: self.background_color.clone(),
+            background_image: None,
+        }
     }
+}
 
-    /// Build drawtext filter for overlay
-    fn build_overlay_filter(&self, overlay: &OverlayConfig, streamer_name: &str) -> String {
-        // Escape special characters for FFmpeg
-        let text = overlay
-            .text
-            .replace("{streamer}", streamer_name)
-            .replace(":", "\\:")
-            .replace("'", "\\'");
+/// Title card shown between clips, e.g. "Round 13 – Ace"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts-bindings",
+    derive(ts_rs::TS),
+    ts(export, optional_fields)
+)]
+pub struct SectionBreak {
+    /// Index of the clip the card is shown before
+    pub before: usize,
+    #[serde(flatten)]
+    pub card: TitleCard,
+}
 
-        let position = overlay.position.to_ffmpeg_coords(20);
+/// Output frame shape of a montage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum MontageAspect {
+    /// 16:9, clips used as they are
+    #[default]
+    Landscape,
+    /// 9:16 for shorts / reels
+    Portrait,
+    /// 1:1
+    Square,
+}
 
-        // Use bundled Roboto font
-        // In dev: use path relative to Cargo manifest
-        // In prod: font is bundled with the app
-        #[cfg(debug_assertions)]
-        let font_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .join("assets")
-            .join("fonts")
-            .join("Roboto.ttf");
+/// How a 16:9 clip is fitted into a portrait or square frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum MontageFit {
+    /// Fill the frame, cutting off the sides
+    #[default]
+    Crop,
+    /// Keep the whole clip, with black bars above and below
+    Pad,
+}
 
-        #[cfg(not(debug_assertions))]
-        let font_path = std::env::current_exe()
-            .ok()
-            .and_then(|p| p.parent().map(|p| p.to_path_buf()))
-            .map(|p| p.join("assets").join("fonts").join("Roboto.ttf"))
-            .unwrap_or_else(|| PathBuf::from("Roboto.ttf"));
+/// How a montage is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum RenderMode {
+    /// One FFmpeg run over all clips
+    #[default]
+    Single,
+    /// Render each clip (reframe, overlay, loudness) to its own file, then
+    /// join them; rendered clips are kept until the join succeeds, so an
+    /// export retried after a failure only renders what's missing
+    Staged,
+    /// Clips are intermediates from the staged mode, already processed
+    #[serde(skip)]
+    Prerendered,
+}
 
-        // Convert path to FFmpeg format (forward slashes, escaped colon)
-        let font_path_str = font_path
-            .to_string_lossy()
-            .replace('\\', "/")
-            .replace(":/", "\\:/");
+/// File type of a montage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "lowercase")]
+pub enum MontageContainer {
+    /// H.264 + AAC
+    #[default]
+    Mp4,
+    /// H.264 + AAC in Matroska, for archiving
+    Mkv,
+    /// VP9 + Opus, for embedding on the web
+    Webm,
+    /// Audio only, MP3
+    Mp3,
+    /// Audio only, AAC
+    M4a,
+}
 
-        let mut filter = format!(
-            "drawtext=fontfile='{}':text='{}':{}:fontsize={}:fontcolor=#{}",
-            font_path_str, text, position, overlay.font_size, overlay.color
-        );
+/// Encoder used for WebM montages
+const VP9_ENCODER: &str = "libvpx-vp9";
 
-        if let Some(ref box_color) = overlay.box_color {
-            filter.push_str(&format!(":box=1:boxcolor={}:boxborderw=10", box_color));
+impl MontageContainer {
+    pub const ALL: [Self; 5] = [Self::Mp4, Self::Mkv, Self::Webm, Self::Mp3, Self::M4a];
+
+    /// File extension (without the dot)
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Mp4 => "mp4",
+            Self::Mkv => "mkv",
+            Self::Webm => "webm",
+            Self::Mp3 => "mp3",
+            Self::M4a => "m4a",
         }
+    }
 
-        filter
+    /// Audio encoder of audio-only containers (None for video ones)
+    pub fn audio_codec(self) -> Option<&'static str> {
+        match self {
+            Self::Mp3 => Some("libmp3lame"),
+            Self::M4a => Some("aac"),
+            Self::Mp4 | Self::Mkv | Self::Webm => None,
+        }
     }
 
-    /// Build the complete FFmpeg command
-    fn build_command(&self, config: &MontageConfig, output_path: &Path) -> Command {
-        let mut cmd = Command::new(self.ffmpeg_path());
-        cmd.arg("-y"); // Overwrite output
+    /// Container of a montage file, from its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        Self::ALL.into_iter().find(|c| c.extension() == ext)
+    }
+}
 
-        // Add all input files
-        for clip in &config.clips {
-            cmd.args(["-i", clip.path.to_string_lossy().as_ref()]);
-        }
+/// Encoding overrides for a montage; unset fields use the app's FFmpeg settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts-bindings",
+    derive(ts_rs::TS),
+    ts(export, optional_fields)
+)]
+pub struct MontageEncoding {
+    /// Quality (lower is better, 0-51)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crf: Option<u8>,
+    /// libx264 speed preset; hardware encoders use their own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+    /// AAC bitrate, e.g. "192k"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_bitrate: Option<String>,
+    /// Peak video bitrate in kbit/s, for platforms with upload limits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bitrate_kbps: Option<u32>,
+}
 
-        // Build filter complex
-        let filter = self.build_filter_complex(config);
+/// Presets libx264 accepts
+const X264_PRESETS: [&str; 10] = [
+    "ultrafast",
+    "superfast",
+    "veryfast",
+    "faster",
+    "fast",
+    "medium",
+    "slow",
+    "slower",
+    "veryslow",
+    "placebo",
+];
+
+impl MontageEncoding {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(crf) = self.crf.filter(|crf| *crf > 51) {
+            return Err(format!("Invalid CRF: {} (0-51)", crf));
+        }
+        if let Some(preset) = self.preset.as_deref().filter(|p| !X264_PRESETS.contains(p)) {
+            return Err(format!("Invalid encoder preset: {}", preset));
+        }
+        if let Some(bitrate) = self.audio_bitrate.as_deref().filter(|b| {
+            let digits = b.strip_suffix('k').unwrap_or(b);
+            digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit())
+        }) {
+            return Err(format!("Invalid audio bitrate: {}", bitrate));
+        }
+        if self.max_bitrate_kbps == Some(0) {
+            return Err("Invalid max bitrate: 0 kbit/s".to_string());
+        }
+        Ok(())
+    }
+
+    /// `base` with these overrides applied
+    fn apply(&self, base: &FfmpegConfig) -> FfmpegConfig {
+        FfmpegConfig {
+            preset: self.preset.clone().unwrap_or_else(|| base.preset.clone()),
+            crf: self.crf.unwrap_or(base.crf),
+            audio_bitrate: self
+                .audio_bitrate
+                .clone()
+                .unwrap_or_else(|| base.audio_bitrate.clone()),
+            ..base.clone()
+        }
+    }
+}
+
+/// Frame size and rate every clip is converted to before joining, so clips
+/// from different sources (e.g. 720p30 next to 1080p60) line up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct OutputFormat {
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+}
+
+impl OutputFormat {
+    fn validate(&self) -> Result<(), String> {
+        if !(2..=7680).contains(&self.width)
+            || !(2..=4320).contains(&self.height)
+            || !self.width.is_multiple_of(2)
+            || !self.height.is_multiple_of(2)
+        {
+            return Err(format!(
+                "Invalid output size {}x{} (even sizes up to 7680x4320)",
+                self.width, self.height
+            ));
+        }
+        if !(1..=120).contains(&self.fps) {
+            return Err(format!("Invalid output frame rate: {}", self.fps));
+        }
+        Ok(())
+    }
+}
+
+impl MontageAspect {
+    /// Fixed output frame size (None = the clips' own)
+    fn frame_size(self) -> Option<(u32, u32)> {
+        match self {
+            Self::Landscape => None,
+            Self::Portrait => Some((1080, 1920)),
+            Self::Square => Some((1080, 1080)),
+        }
+    }
+
+    /// Crop/scale filters that reframe a clip to `w`x`h` (None = keep the
+    /// clip's frame)
+    fn reframe_filter(self, fit: MontageFit, (w, h): (u32, u32)) -> Option<String> {
+        self.frame_size()?;
+        Some(match (self, fit) {
+            (Self::Portrait, MontageFit::Crop) => {
+                format!("crop='min(iw,ih*9/16)':ih,scale={w}:{h},setsar=1")
+            }
+            (_, MontageFit::Crop) => {
+                format!("crop='min(iw,ih)':'min(iw,ih)',scale={w}:{h},setsar=1")
+            }
+            (_, MontageFit::Pad) => format!(
+                "scale={w}:{h}:force_original_aspect_ratio=decrease,\
+                 pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1"
+            ),
+        })
+    }
+
+    /// Horizontal and vertical overlay margins. Vertical videos keep overlays
+    /// clear of the captions and buttons Shorts/TikTok/Reels draw on top
+    fn overlay_margins(self) -> (u32, u32) {
+        match self {
+            Self::Landscape => (OVERLAY_MARGIN, OVERLAY_MARGIN),
+            Self::Portrait => (PORTRAIT_SAFE_MARGIN_X, PORTRAIT_SAFE_MARGIN_Y),
+            Self::Square => (OVERLAY_MARGIN * 2, OVERLAY_MARGIN * 2),
+        }
+    }
+
+    /// Short name for file names
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Landscape => "landscape",
+            Self::Portrait => "portrait",
+            Self::Square => "square",
+        }
+    }
+}
+
+/// Per-clip overlay override; unset fields fall back to the montage overlay
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts-bindings",
+    derive(ts_rs::TS),
+    ts(export, optional_fields)
+)]
+pub struct ClipOverlay {
+    /// Show or hide the overlay on this clip (unset: shown if the montage has
+    /// an overlay or this clip sets its own text)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Text for this clip (placeholders as in [`OverlayConfig::text`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<OverlayPosition>,
+}
+
+/// Overlay configuration for text display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlayConfig {
+    /// Text to display. Placeholders: {streamer}, {action}, {index} and
+    /// {total} (the clip's place among the streamer clips), {count} (clips so
+    /// far with this action name, ticking up when the action happens)
+    pub text: String,
+    /// Position on screen
+    pub position: OverlayPosition,
+    /// Font size in pixels
+    pub font_size: u32,
+    /// Text color in hex format (e.g., "FFFFFF")
+    pub color: String,
+    /// Background box color (optional, e.g., "000000@0.5" for 50% black)
+    pub box_color: Option<String>,
+    /// Font file (bundled Roboto when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font: Option<PathBuf>,
+    /// Entrance/exit animation
+    #[serde(default)]
+    pub animation: OverlayAnimation,
+    /// Free position (overrides the corner in `position`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_position: Option<OverlayPoint>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outline: Option<OverlayOutline>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow: Option<OverlayShadow>,
+}
+
+impl OverlayConfig {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(outline) = &self.outline {
+            if !is_valid_color(&outline.color) {
+                return Err(format!("Invalid outline color: {}", outline.color));
+            }
+        }
+        if let Some(shadow) = &self.shadow {
+            if !is_valid_color(&shadow.color) {
+                return Err(format!("Invalid shadow color: {}", shadow.color));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for OverlayConfig {
+    /// Style for clips that set their own overlay in a montage without one
+    fn default() -> Self {
+        Self {
+            text: "{streamer}".to_string(),
+            position: OverlayPosition::BottomLeft,
+            font_size: 32,
+            color: "FFFFFF".to_string(),
+            box_color: Some("000000@0.5".to_string()),
+            font: None,
+            animation: OverlayAnimation::None,
+            custom_position: None,
+            outline: None,
+            shadow: None,
+        }
+    }
+}
+
+/// Part of a clip played slower, for impact moments
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct SlowMotion {
+    /// Seconds into the (trimmed) clip
+    pub start: f64,
+    pub end: f64,
+    /// How many times slower, e.g. 2 = half speed
+    pub factor: f64,
+}
+
+impl SlowMotion {
+    /// Seconds the slowed part adds to the clip
+    fn added(&self) -> f64 {
+        (self.end - self.start) * (self.factor - 1.0)
+    }
+}
+
+/// `atempo` filters slowing audio down `factor` times (one instance goes down to 0.5)
+fn atempo_chain(factor: f64) -> String {
+    let mut tempo = 1.0 / factor;
+    let mut filters = Vec::new();
+    while tempo < 0.5 {
+        filters.push("atempo=0.5".to_string());
+        tempo /= 0.5;
+    }
+    filters.push(format!("atempo={tempo:.4}"));
+    filters.join(",")
+}
+
+/// A clip in the montage sequence
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MontageClip {
+    /// Path to the clip file
+    pub path: PathBuf,
+    /// Duration of the clip in seconds
+    pub duration: f64,
+    /// Streamer name (for overlay placeholder)
+    pub streamer_name: String,
+    /// Action name (for the chapter title)
+    #[serde(default)]
+    pub action_name: String,
+    /// Overlay override for this clip
+    #[serde(default)]
+    pub overlay: Option<ClipOverlay>,
+    /// Volume multiplier, applied after loudness normalization (None = unchanged)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f64>,
+    /// Seconds cut from the start of the clip file (None = from the start)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim_start: Option<f64>,
+    /// Where the clip ends, in seconds into the file (None = its end)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim_end: Option<f64>,
+    /// Part of the frame to keep, in source pixels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crop: Option<CropRect>,
+    /// Punch-in on the center of the (cropped) frame, e.g. 1.5
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoom: Option<f64>,
+    /// Part of the clip played slower
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slowmo: Option<SlowMotion>,
+    /// Seconds the last frame is held after the clip ends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub freeze: Option<f64>,
+    /// Where the clip's action happens, in seconds into the clip file (from
+    /// the project)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_at: Option<f64>,
+    /// The file has no audio track (set by export from its probe); silence
+    /// is played in its place
+    #[serde(default)]
+    pub silent: bool,
+    /// Rendered by the montage itself (title card, intro/outro) rather than
+    /// cut from a VOD
+    #[serde(default)]
+    pub generated: bool,
+}
+
+impl MontageClip {
+    /// Title card or intro/outro: no streamer, so no overlay
+    fn is_bookend(&self) -> bool {
+        self.generated
+    }
+
+    /// Seconds of the clip file that are played
+    fn source_length(&self) -> f64 {
+        let start = self.trim_start.unwrap_or(0.0);
+        let end = self.trim_end.unwrap_or(self.duration).min(self.duration);
+        (end - start).max(0.0)
+    }
+
+    /// Seconds of the clip that end up in the montage, slow motion and
+    /// freeze included
+    pub fn length(&self) -> f64 {
+        self.source_length() + self.slowmo.map_or(0.0, |s| s.added()) + self.freeze.unwrap_or(0.0)
+    }
+
+    fn is_trimmed(&self) -> bool {
+        self.trim_start.is_some() || self.trim_end.is_some()
+    }
+
+    /// `trim`/`atrim` arguments keeping the trimmed part (None = untrimmed)
+    fn trim_args(&self) -> Option<String> {
+        if !self.is_trimmed() {
+            return None;
+        }
+        let start = self.trim_start.map(|t| format!("start={t:.3}"));
+        let end = self.trim_end.map(|t| format!("end={t:.3}"));
+        Some(start.into_iter().chain(end).collect::<Vec<_>>().join(":"))
+    }
+
+    /// `crop` filters for the clip's region and zoom (None = whole frame)
+    fn crop_filter(&self) -> Option<String> {
+        let region = self
+            .crop
+            .map(|c| format!("crop={}:{}:{}:{}", c.width, c.height, c.x, c.y));
+        let zoom = self
+            .zoom
+            .filter(|z| *z != 1.0)
+            .map(|z| format!("crop=iw/{z:.2}:ih/{z:.2}"));
+        let filters: Vec<String> = region.into_iter().chain(zoom).collect();
+        (!filters.is_empty()).then(|| filters.join(","))
+    }
+
+    fn validate_crop(&self) -> Result<(), String> {
+        if let Some(crop) = self.crop.filter(|c| c.width < 2 || c.height < 2) {
+            return Err(format!(
+                "Invalid crop {}x{} for {}",
+                crop.width,
+                crop.height,
+                self.path.display()
+            ));
+        }
+        if let Some(zoom) = self.zoom.filter(|z| !CLIP_ZOOM_RANGE.contains(z)) {
+            return Err(format!("Invalid zoom {} (1-4)", zoom));
+        }
+        Ok(())
+    }
+
+    /// When the action happens in the montage clip: after the trim, slowed
+    /// down with it (None = unknown or cut away)
+    fn action_time(&self) -> Option<f64> {
+        let mut at = self.action_at? - self.trim_start.unwrap_or(0.0);
+        if !(0.0..=self.source_length()).contains(&at) {
+            return None;
+        }
+        if let Some(s) = self.slowmo.filter(|s| at > s.start) {
+            at += (at.min(s.end) - s.start) * (s.factor - 1.0);
+        }
+        Some(at)
+    }
+
+    /// Video filters for the slowed part and the held last frame (None = none)
+    fn effects_filter(&self) -> Option<String> {
+        // Stretch the timestamps of the slowed part and push back the rest
+        let slowmo = self.slowmo.map(|s| {
+            format!(
+                "setpts='if(lt(T,{start:.3}),T,if(lt(T,{end:.3}),{start:.3}+(T-{start:.3})*{factor:.2},T+{added:.3}))/TB'",
+                start = s.start,
+                end = s.end,
+                factor = s.factor,
+                added = s.added()
+            )
+        });
+        let freeze = self
+            .freeze
+            .map(|d| format!("tpad=stop_mode=clone:stop_duration={d:.3}"));
+        let filters: Vec<String> = slowmo.into_iter().chain(freeze).collect();
+        (!filters.is_empty()).then(|| filters.join(","))
+    }
+
+    /// Audio filters matching [`Self::effects_filter`]: the slowed part is
+    /// split out and stretched, the freeze is silent. `i` keeps the split
+    /// labels unique in the graph
+    fn audio_effects_filter(&self, i: usize) -> Option<String> {
+        let slowmo = self.slowmo.map(|s| {
+            let slowed = atempo_chain(s.factor);
+            let mut parts = Vec::new();
+            if s.start > 0.0 {
+                parts.push(format!("end={:.3}", s.start));
+            }
+            let middle = parts.len();
+            parts.push(format!("start={:.3}:end={:.3}", s.start, s.end));
+            if s.end < self.source_length() {
+                parts.push(format!("start={:.3}", s.end));
+            }
+            if parts.len() == 1 {
+                return slowed;
+            }
+
+            let n = parts.len();
+            let outputs: String = (0..n).map(|k| format!("[slow{i}_{k}]")).collect();
+            let mut chains = vec![format!("asplit={n}{outputs}")];
+            for (k, part) in parts.iter().enumerate() {
+                let tempo = if k == middle {
+                    format!(",{slowed}")
+                } else {
+                    String::new()
+                };
+                chains.push(format!(
+                    "[slow{i}_{k}]atrim={part},asetpts=PTS-STARTPTS{tempo}[slowed{i}_{k}]"
+                ));
+            }
+            let inputs: String = (0..n).map(|k| format!("[slowed{i}_{k}]")).collect();
+            chains.push(format!("{inputs}concat=n={n}:v=0:a=1"));
+            chains.join(";")
+        });
+        let freeze = self.freeze.map(|d| format!("apad=pad_dur={d:.3}"));
+        let filters: Vec<String> = slowmo.into_iter().chain(freeze).collect();
+        (!filters.is_empty()).then(|| filters.join(","))
+    }
+
+    fn validate_effects(&self) -> Result<(), String> {
+        if let Some(s) = self.slowmo {
+            let valid = s.start >= 0.0
+                && s.end > s.start
+                && s.end <= self.source_length() + 0.001
+                && SLOWMO_FACTOR_RANGE.contains(&s.factor);
+            if !valid {
+                return Err(format!(
+                    "Invalid slow motion for {}: x{} over {:.2}-{:.2}s of {:.2}s",
+                    self.path.display(),
+                    s.factor,
+                    s.start,
+                    s.end,
+                    self.source_length()
+                ));
+            }
+        }
+        if let Some(freeze) = self.freeze.filter(|d| !(0.0..=MAX_FREEZE).contains(d)) {
+            return Err(format!("Invalid freeze: {}s (0-{})", freeze, MAX_FREEZE));
+        }
+        Ok(())
+    }
+
+    fn validate_trim(&self) -> Result<(), String> {
+        let valid = self.trim_start.is_none_or(|t| t >= 0.0)
+            && self.trim_end.is_none_or(|t| t > 0.0)
+            && self.source_length() > 0.0;
+        if valid {
+            Ok(())
+        } else {
+            Err(format!(
+                "Invalid trim for {}: {:?}-{:?} of {:.2}s",
+                self.path.display(),
+                self.trim_start,
+                self.trim_end,
+                self.duration
+            ))
+        }
+    }
+
+    /// Chapter title: "{streamer} – {action}", or whichever of them is set
+    fn chapter_title(&self) -> String {
+        if self.action_name.is_empty() {
+            self.streamer_name.clone()
+        } else if self.is_bookend() {
+            self.action_name.clone()
+        } else {
+            format!("{} – {}", self.streamer_name, self.action_name)
+        }
+    }
+}
+
+/// Configuration for montage export
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MontageConfig {
+    /// Ordered list of clips to concatenate
+    pub clips: Vec<MontageClip>,
+    /// Transition duration in seconds (0 = no transition)
+    pub transition_duration: f64,
+    /// Transition look
+    #[serde(default)]
+    pub transition: TransitionStyle,
+    /// Played before the first clip, with the same transitions (optional)
+    #[serde(default)]
+    pub intro: Option<Bookend>,
+    /// Played after the last clip (optional)
+    #[serde(default)]
+    pub outro: Option<Bookend>,
+    /// Title cards inserted between clips
+    #[serde(default)]
+    pub sections: Vec<SectionBreak>,
+    /// Background music, ducked under the clip audio (optional)
+    #[serde(default)]
+    pub music: Option<MusicTrack>,
+    /// Overlay configuration (optional)
+    pub overlay: Option<OverlayConfig>,
+    /// Watermark drawn over the finished montage, across transitions (optional)
+    #[serde(default)]
+    pub image_overlay: Option<ImageOverlay>,
+    /// Output frame shape
+    #[serde(default)]
+    pub aspect: MontageAspect,
+    /// How clips fill a portrait or square frame
+    #[serde(default)]
+    pub fit: MontageFit,
+    /// Common frame size and rate (None = keep the clips' own). Portrait and
+    /// square output have a fixed size, so only the rate applies to them
+    #[serde(default)]
+    pub format: Option<OutputFormat>,
+    /// Bring every clip to this loudness (LUFS) before joining, so the
+    /// volume doesn't jump between streamers (None = keep their levels)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loudness_lufs: Option<f64>,
+    /// In one pass, or clip by clip
+    #[serde(default)]
+    pub render: RenderMode,
+    /// Quality, speed and bitrate cap (unset = app settings)
+    #[serde(default)]
+    pub encoding: MontageEncoding,
+    /// File type, which also picks the codecs
+    #[serde(default)]
+    pub container: MontageContainer,
+}
+
+impl MontageConfig {
+    /// Overlay drawn on clip `i`: its override applied to the montage
+    /// overlay, with the counters filled in
+    fn clip_overlay(&self, i: usize) -> Option<OverlayConfig> {
+        let clip = &self.clips[i];
+        if clip.is_bookend() || self.render == RenderMode::Prerendered {
+            return None;
+        }
+        let mut overlay = self.clip_overlay_style(i)?;
+        overlay.text = self.counter_text(i, &overlay.text);
+        Some(overlay)
+    }
+
+    /// `text` with {index}, {total} and {count} of clip `i`
+    ///
+    /// {count} becomes a drawtext expansion when the clip knows its action
+    /// time, so the counter goes up on the action rather than at the cut.
+    fn counter_text(&self, i: usize, text: &str) -> String {
+        if !["{index}", "{total}", "{count}"]
+            .iter()
+            .any(|p| text.contains(p))
+        {
+            return text.to_string();
+        }
+        let clip = &self.clips[i];
+        let before = self.clips[..i].iter().filter(|c| !c.is_bookend());
+        let index = before.clone().count() + 1;
+        let total = self.clips.iter().filter(|c| !c.is_bookend()).count();
+        let count = before.filter(|c| c.action_name == clip.action_name).count() + 1;
+        let count = match clip.action_time() {
+            Some(at) => format!("%{{eif:{}+gte(t,{at:.3}):d}}", count - 1),
+            None => count.to_string(),
+        };
+        text.replace("{index}", &index.to_string())
+            .replace("{total}", &total.to_string())
+            .replace("{count}", &count)
+    }
+
+    /// Overlay style of clip `i` before its placeholders are filled in
+    fn clip_overlay_style(&self, i: usize) -> Option<OverlayConfig> {
+        let Some(own) = &self.clips[i].overlay else {
+            return self.overlay.clone();
+        };
+
+        let enabled = own
+            .enabled
+            .unwrap_or(self.overlay.is_some() || own.text.is_some());
+        if !enabled {
+            return None;
+        }
+
+        let mut overlay = self.overlay.clone().unwrap_or_default();
+        if let Some(text) = &own.text {
+            overlay.text = text.clone();
+        }
+        if let Some(position) = &own.position {
+            overlay.position = position.clone();
+        }
+        Some(overlay)
+    }
+
+    /// Whether export has to render title cards or an intro/outro first
+    fn has_generated_clips(&self) -> bool {
+        self.intro.is_some() || self.outro.is_some() || !self.sections.is_empty()
+    }
+
+    /// Whether the clips go into the output untouched, so they can be joined
+    /// by stream copy instead of a re-encode (if their codecs also match)
+    fn is_plain_concat(&self) -> bool {
+        let has_fades = self.fade_duration() > 0.0 && self.clips.len() > 1;
+        self.xfade().is_none()
+            && !has_fades
+            && self.music.is_none()
+            && self.image_overlay.is_none()
+            && !self.has_generated_clips()
+            && self.frame_filter().is_none()
+            && (self.format.is_none() || self.render == RenderMode::Prerendered)
+            && (self.encoding.max_bitrate_kbps.is_none() || self.render == RenderMode::Prerendered)
+            && self.container != MontageContainer::Webm
+            && (0..self.clips.len()).all(|i| self.clip_audio_filter(i).is_none())
+            && (0..self.clips.len()).all(|i| self.clip_trim(i).is_none())
+            && (0..self.clips.len()).all(|i| self.clip_crop(i).is_none())
+            && (0..self.clips.len()).all(|i| self.clip_effects(i).is_none())
+            && (0..self.clips.len()).all(|i| self.clip_overlay(i).is_none())
+            && (0..self.clips.len()).all(|i| self.clip_silence(i).is_none())
+    }
+
+    /// `xfade` transition between clips, if they overlap
+    fn xfade(&self) -> Option<&'static str> {
+        self.transition
+            .xfade_name()
+            .filter(|_| self.transition_duration > 0.0 && self.clips.len() > 1)
+    }
+
+    /// Output frame size, when fixed (None = the clips' own)
+    ///
+    /// Portrait and square montages take the output format's size when it
+    /// has their shape.
+    fn frame_size(&self) -> Option<(u32, u32)> {
+        let format = self.format.map(|f| (f.width, f.height));
+        match self.aspect.frame_size() {
+            None => format,
+            Some((w, h)) => Some(
+                format
+                    .filter(|&(fw, fh)| fw * h == fh * w)
+                    .unwrap_or((w, h)),
+            ),
+        }
+    }
+
+    /// Overlay margins, in proportion to the output frame
+    fn overlay_margins(&self) -> (u32, u32) {
+        let (x, y) = self.aspect.overlay_margins();
+        let reference = self.aspect.frame_size().map_or(1080, |(_, h)| h);
+        match self.frame_size() {
+            Some((_, h)) if h != reference => {
+                let scale = |m: u32| (m as f64 * h as f64 / reference as f64).round() as u32;
+                (scale(x), scale(y))
+            }
+            _ => (x, y),
+        }
+    }
+
+    /// Filters giving a clip the output frame (None = keep the clip's frame)
+    pub(super) fn frame_filter(&self) -> Option<String> {
+        if self.render == RenderMode::Prerendered {
+            return None;
+        }
+        match (self.aspect, self.frame_size()?) {
+            (MontageAspect::Landscape, (w, h)) => Some(format!(
+                "scale={w}:{h}:force_original_aspect_ratio=decrease,\
+                 pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1"
+            )),
+            (aspect, size) => aspect.reframe_filter(self.fit, size),
+        }
+    }
+
+    /// Crop/zoom filters for clip `i` (None = whole frame)
+    fn clip_crop(&self, i: usize) -> Option<String> {
+        if self.render == RenderMode::Prerendered {
+            return None;
+        }
+        self.clips[i].crop_filter()
+    }
+
+    /// Slow motion and freeze filters for clip `i` (None = played as is)
+    fn clip_effects(&self, i: usize) -> Option<String> {
+        if self.render == RenderMode::Prerendered {
+            return None;
+        }
+        self.clips[i].effects_filter()
+    }
+
+    /// `trim` arguments for clip `i` (None = played whole)
+    fn clip_trim(&self, i: usize) -> Option<String> {
+        if self.render == RenderMode::Prerendered {
+            return None;
+        }
+        self.clips[i].trim_args()
+    }
+
+    /// Audio filters applied to clip `i` before joining (None = untouched)
+    fn clip_audio_filter(&self, i: usize) -> Option<String> {
+        if self.render == RenderMode::Prerendered {
+            return None;
+        }
+        let trim = self
+            .clip_trim(i)
+            .map(|trim| format!("atrim={trim},asetpts=PTS-STARTPTS"));
+        let effects = self.clips[i].audio_effects_filter(i);
+        // loudnorm resamples to 192 kHz internally
+        let loudness = self
+            .loudness_lufs
+            .map(|lufs| format!("{},aresample=48000", loudnorm_filter(lufs)));
+        // After loudnorm, so a quieter streamer stays quieter
+        let volume = self.clips[i]
+            .volume
+            .filter(|v| *v != 1.0)
+            .map(|v| format!("volume={:.2}", v));
+        let filters: Vec<String> = trim
+            .into_iter()
+            .chain(effects)
+            .chain(loudness)
+            .chain(volume)
+            .collect();
+        (!filters.is_empty()).then(|| filters.join(","))
+    }
+
+    /// Silent source `[sil<i>]` standing in for clip `i`'s missing audio
+    /// (None = the clip has audio)
+    fn clip_silence(&self, i: usize) -> Option<String> {
+        if self.render == RenderMode::Prerendered || !self.clips[i].silent {
+            return None;
+        }
+        // As long as the whole file, so trims and effects apply as on audio
+        Some(format!(
+            "anullsrc=r=48000:cl=stereo,atrim=end={:.3}[sil{i}]",
+            self.clips[i].duration
+        ))
+    }
+
+    /// Filter graph label of clip `i`'s audio
+    fn clip_audio_input(&self, i: usize) -> String {
+        match self.clip_silence(i) {
+            Some(_) => format!("sil{i}"),
+            None => format!("{i}:a"),
+        }
+    }
+
+    /// Silent sources of all clips without audio
+    fn silence_sources(&self) -> Vec<String> {
+        (0..self.clips.len())
+            .filter_map(|i| self.clip_silence(i))
+            .collect()
+    }
+
+    /// Frame rate of the joined clips (None = keep the clips' own)
+    fn output_fps(&self) -> Option<u32> {
+        match (self.format, self.xfade()) {
+            (Some(format), _) => Some(format.fps),
+            // xfade can't mix rates
+            (None, Some(_)) => Some(XFADE_FPS),
+            (None, None) => None,
+        }
+    }
+
+    /// Clip `i` alone with its own processing, for the staged mode's first pass
+    ///
+    /// Its overlay is resolved against the whole montage first: the counters
+    /// depend on the clips around it.
+    fn stage(&self, i: usize) -> MontageConfig {
+        let clip = MontageClip {
+            overlay: None,
+            ..self.clips[i].clone()
+        };
+        MontageConfig {
+            clips: vec![clip],
+            overlay: self.clip_overlay(i),
+            transition_duration: 0.0,
+            transition: TransitionStyle::None,
+            intro: None,
+            outro: None,
+            sections: Vec::new(),
+            music: None,
+            image_overlay: None,
+            render: RenderMode::Single,
+            // Rendered clips are H.264 intermediates whatever the output
+            container: MontageContainer::Mp4,
+            ..self.clone()
+        }
+    }
+
+    /// The montage drawn on a [`PREVIEW_HEIGHT`] frame in one pass to MP4
+    ///
+    /// Clips are scaled down before anything else is done to them; text,
+    /// outlines, shadows and the logo shrink with the frame.
+    fn preview(&self) -> MontageConfig {
+        let (width, height) = self.frame_size().unwrap_or((1920, 1080));
+        let scale = (PREVIEW_HEIGHT as f64 / height as f64).min(1.0);
+        let even = |v: u32| ((v as f64 * scale / 2.0).round() as u32 * 2).max(2);
+        let pixels = |v: u32| ((v as f64 * scale).round() as u32).max(1);
+        let offset = |v: i32| (v as f64 * scale).round() as i32;
+        let overlay = self.overlay.clone().map(|overlay| OverlayConfig {
+            font_size: pixels(overlay.font_size),
+            outline: overlay.outline.map(|outline| OverlayOutline {
+                width: pixels(outline.width),
+                ..outline
+            }),
+            shadow: overlay.shadow.map(|shadow| OverlayShadow {
+                x: offset(shadow.x),
+                y: offset(shadow.y),
+                ..shadow
+            }),
+            ..overlay
+        });
+        MontageConfig {
+            format: Some(OutputFormat {
+                width: even(width),
+                height: even(height),
+                fps: self.format.map_or(PREVIEW_FPS, |f| f.fps.min(PREVIEW_FPS)),
+            }),
+            overlay,
+            image_overlay: self.image_overlay.clone().map(|image| ImageOverlay {
+                scale: image.scale * scale,
+                ..image
+            }),
+            render: match self.render {
+                RenderMode::Staged => RenderMode::Single,
+                render => render,
+            },
+            container: MontageContainer::Mp4,
+            encoding: MontageEncoding::default(),
+            ..self.clone()
+        }
+    }
+
+    /// The montage as sound only: no cards, intro/outro or framing, and
+    /// fades through black become crossfades
+    fn audio_only(&self) -> MontageConfig {
+        MontageConfig {
+            clips: self
+                .clips
+                .iter()
+                .map(|clip| MontageClip {
+                    crop: None,
+                    zoom: None,
+                    overlay: None,
+                    ..clip.clone()
+                })
+                .collect(),
+            transition: match self.transition {
+                TransitionStyle::Fade => TransitionStyle::Dissolve,
+                transition => transition,
+            },
+            intro: None,
+            outro: None,
+            sections: Vec::new(),
+            overlay: None,
+            image_overlay: None,
+            format: None,
+            render: match self.render {
+                RenderMode::Staged => RenderMode::Single,
+                render => render,
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Duration of the fade-through-black at each clip boundary (0 = none)
+    fn fade_duration(&self) -> f64 {
+        if self.transition == TransitionStyle::Fade {
+            self.transition_duration
+        } else {
+            0.0
+        }
+    }
+
+    /// Start of each clip in the output; `xfade` transitions overlap clips
+    fn clip_offsets(&self) -> Vec<f64> {
+        let overlap = if self.xfade().is_some() {
+            self.transition_duration
+        } else {
+            0.0
+        };
+        let mut start = 0.0;
+        self.clips
+            .iter()
+            .map(|clip| {
+                let offset = start;
+                start += clip.length() - overlap;
+                offset
+            })
+            .collect()
+    }
+
+    /// Clips shortened so each cut lands on the last beat before it
+    ///
+    /// Only the played part of a streamer clip is cut (never a title card, a
+    /// freeze or a slowed part), and never below [`MIN_SNAPPED_LENGTH`].
+    fn snapped_to_beats(&self, beats: &BeatAnalysis) -> MontageConfig {
+        let mut snapped = self.clone();
+        // The last clip ends the montage, the music fades with it
+        for i in 0..self.clips.len().saturating_sub(1) {
+            let clip = &snapped.clips[i];
+            if clip.is_bookend() {
+                continue;
+            }
+            let end = snapped.clip_offsets()[i] + clip.length();
+            let Some(beat) = beats.beat_before(end) else {
+                continue;
+            };
+            let kept = clip.source_length() - (end - beat);
+            let slowed_until = clip.slowmo.map_or(0.0, |s| s.end);
+            if kept < MIN_SNAPPED_LENGTH.max(slowed_until) || end - beat < 0.001 {
+                continue;
+            }
+            let trim_end = clip.trim_start.unwrap_or(0.0) + kept;
+            snapped.clips[i].trim_end = Some(trim_end);
+        }
+        snapped
+    }
+
+    /// FFMETADATA with one chapter per clip, for `-map_chapters`
+    ///
+    /// A chapter runs from its clip's start in the output to the next one's.
+    fn chapters_metadata(&self) -> String {
+        let offsets = self.clip_offsets();
+        let mut metadata = String::from(";FFMETADATA1\n");
+        for (i, clip) in self.clips.iter().enumerate() {
+            let start = offsets[i];
+            let end = offsets.get(i + 1).copied().unwrap_or(start + clip.length());
+            metadata.push_str(&format!(
+                "[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+                (start * 1000.0).round() as u64,
+                (end * 1000.0).round() as u64,
+                escape_metadata(&clip.chapter_title())
+            ));
+        }
+        metadata
+    }
+
+    /// Calculate total duration including transitions
+    ///
+    /// Only crossfades overlap clips; a fade through black plays inside each
+    /// clip and keeps the full length.
+    pub fn total_duration(&self) -> f64 {
+        match (self.clip_offsets().last(), self.clips.last()) {
+            (Some(offset), Some(last)) => offset + last.length(),
+            _ => 0.0,
+        }
+    }
+}
+
+/// Clip rendered from a title card or bookend (no streamer, so no overlay)
+fn generated_clip(path: PathBuf, duration: f64, title: &str) -> MontageClip {
+    MontageClip {
+        path,
+        duration,
+        streamer_name: String::new(),
+        action_name: title.to_string(),
+        generated: true,
+        ..Default::default()
+    }
+}
+
+/// Insert `(before, clip)` pairs, `before` being an index into the original
+/// list; cards for the same clip keep their order
+fn insert_sections(clips: &mut Vec<MontageClip>, mut sections: Vec<(usize, MontageClip)>) {
+    sections.sort_by_key(|(before, _)| *before);
+    for (before, clip) in sections.into_iter().rev() {
+        clips.insert(before, clip);
+    }
+}
+
+/// Name of a staged clip render: its settings and the source file's
+/// modification time, so a changed clip or setting renders again
+fn stage_key(stage: &MontageConfig) -> String {
+    let modified: u128 = stage.clips.iter().map(|c| modified_millis(&c.path)).sum();
+    vod_key(&format!("{:?}@{}", stage, modified))
+}
+
+/// drawtext filters centering `text` on a card `height` pixels high
+///
+/// Each line of a multi-line text is drawn on its own, centered, and the
+/// font shrinks when the lines wouldn't fit the frame.
+fn card_text_filter(text: &str, width: u32, height: u32) -> String {
+    // Sized for a 1080-pixel frame
+    let base_size = (CARD_FONT_SIZE * width.min(height) / 1080).max(1);
+    let drawtext = |line: &str, size: u32, y: &str| {
+        format!(
+            "drawtext=fontfile='{}':expansion=none:text={}:fontsize={}:fontcolor=white:x=(w-tw)/2:y={}",
+            ffmpeg_font_path(),
+            escape_drawtext(line),
+            size,
+            y
+        )
+    };
+
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() <= 1 {
+        return drawtext(text, base_size, "(h-th)/2");
+    }
+    let fit = height as f64 * 0.8 / (lines.len() as f64 * CARD_LINE_SPACING);
+    let size = base_size.min(fit as u32);
+    let line_height = (size as f64 * CARD_LINE_SPACING).round() as u32;
+    let top = (height - line_height * lines.len() as u32) / 2;
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(k, line)| drawtext(line, size, &(top + k as u32 * line_height).to_string()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Escape `text` for an unquoted drawtext `text=` value in a filtergraph
+///
+/// The value is unescaped twice, by the filtergraph parser then by the
+/// filter's option parser, so each level's special characters get a
+/// backslash. Used with `expansion=none`, which keeps `%` literal.
+fn escape_drawtext(text: &str) -> String {
+    let escape = |text: &str, special: &[char]| {
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            if special.contains(&c) {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+        out
+    };
+    let option = escape(text, &['\\', '\'', ':']);
+    escape(&option, &['\\', '\'', '[', ']', ',', ';'])
+}
+
+/// Name of a rendered title card or intro/outro, from what it's made of
+fn generated_name(kind: &str, source: &impl std::fmt::Debug) -> String {
+    format!(
+        "{}_{}.mp4",
+        kind.to_lowercase(),
+        vod_key(&format!("{:?}", source))
+    )
+}
+
+/// Modification time of a file in milliseconds (0 if unreadable)
+fn modified_millis(path: &Path) -> u128 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+/// Move a finished render from its partial name to its final one, so an
+/// interrupted render is never mistaken for a finished one
+fn publish(partial: &Path, output: &Path) -> ExportResult<()> {
+    std::fs::rename(partial, output)
+        .map_err(|e| ExportError::OutputDir(format!("Failed to store render: {}", e)))
+}
+
+/// Delete stage files (and partial renders) of montages abandoned for
+/// longer than `max_age`
+fn prune_stages(dir: &Path, max_age: Duration) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age > max_age);
+        if expired && std::fs::remove_file(entry.path()).is_ok() {
+            log::info!("[Montage] Pruned stale render {}", entry.path().display());
+        }
+    }
+}
+
+/// Time allowed to render `duration` seconds of montage at `factor` seconds
+/// per second (None = no timeout)
+fn montage_timeout(duration: f64, factor: f64) -> Option<Duration> {
+    if factor <= 0.0 {
+        return None;
+    }
+    let scaled = Duration::try_from_secs_f64(duration * factor).unwrap_or(Duration::MAX);
+    Some(scaled.max(MIN_MONTAGE_TIMEOUT))
+}
+
+/// Escape a value for an FFMETADATA file
+fn escape_metadata(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Path of the bundled Roboto font, escaped for use in an FFmpeg drawtext filter
+pub(crate) fn ffmpeg_font_path() -> String {
+    // In dev: use path relative to Cargo manifest
+    // In prod: font is bundled with the app
+    #[cfg(debug_assertions)]
+    let font_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("assets")
+        .join("fonts")
+        .join("Roboto.ttf");
+
+    #[cfg(not(debug_assertions))]
+    let font_path = std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+        .map(|p| p.join("assets").join("fonts").join("Roboto.ttf"))
+        .unwrap_or_else(|| PathBuf::from("Roboto.ttf"));
+
+    ffmpeg_filter_path(&font_path)
+}
+
+/// Path in FFmpeg filter format (forward slashes, escaped colon)
+fn ffmpeg_filter_path(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('\\', "/")
+        .replace(":/", "\\:/")
+}
+
+/// Progress function type (borrowed form of ProgressCallback)
+pub(super) type ProgressFn<'a> = dyn Fn(ProgressUpdate) + Send + Sync + 'a;
+
+/// Progress callback type
+pub type ProgressCallback = Box<ProgressFn<'static>>;
+
+/// Montage exporter using FFmpeg
+pub struct MontageExporter {
+    /// Render a low-resolution preview instead of the montage
+    preview: bool,
+    /// The job the exporter's steps belong to
+    cancel: CancelToken,
+}
+
+impl Default for MontageExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MontageExporter {
+    pub fn new() -> Self {
+        Self {
+            preview: false,
+            cancel: CancelToken::new(),
+        }
+    }
+
+    /// Exporter for previews: the same filter graph on a frame scaled down
+    /// to [`PREVIEW_HEIGHT`], encoded to MP4 in one quick x264 pass
+    pub fn preview() -> Self {
+        Self {
+            preview: true,
+            cancel: CancelToken::new(),
+        }
+    }
+
+    /// The job this exporter's exports belong to, for holding a
+    /// [`CancelScope`] across several of them
+    pub fn cancel_token(&self) -> &CancelToken {
+        &self.cancel
+    }
+
+    /// Get the ffmpeg binary path
+    pub(super) fn ffmpeg_path(&self) -> String {
+        get_binary_manager()
+            .ffmpeg_path()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| "ffmpeg".to_string())
+    }
+
+    /// Build the FFmpeg filter_complex string for concatenation with fades
+    fn build_filter_complex(&self, config: &MontageConfig) -> String {
+        let graph = self.build_clips_filter(config);
+        let mut filters = config.silence_sources();
+        if filters.is_empty() || graph.is_empty() {
+            return graph;
+        }
+        filters.push(graph);
+        filters.join(";")
+    }
+
+    /// The clips' processing and joins, reading silent clips' audio from
+    /// their `[sil<i>]` source
+    fn build_clips_filter(&self, config: &MontageConfig) -> String {
+        let n = config.clips.len();
+        let fade_duration = config.fade_duration();
+
+        if n == 0 {
+            return String::new();
+        }
+
+        let mut filters = Vec::new();
+
+        // Helper to get overlay filter for a specific clip index
+        let get_clip_filters = |i: usize| -> String {
+            let mut clip_filters = Vec::new();
+
+            // 0. Cut the clip to its trim, restarting its timestamps at 0
+            if let Some(trim) = config.clip_trim(i) {
+                clip_filters.push(format!("trim={trim},setpts=PTS-STARTPTS"));
+            }
+            // ...then slow it down and hold its last frame
+            if let Some(effects) = config.clip_effects(i) {
+                clip_filters.push(effects);
+            }
+
+            // 1. Punch in, then reframe/resize so the overlay is placed in the output frame,
+            //    and match frame rates so concat keeps its timing (xfade sets
+            //    the rate after the clip filters)
+            if let Some(crop) = config.clip_crop(i) {
+                clip_filters.push(crop);
+            }
+            if let Some(frame) = config.frame_filter() {
+                clip_filters.push(frame);
+            }
+            if let (Some(fps), None) = (config.output_fps(), config.xfade()) {
+                clip_filters.push(format!("fps={fps}"));
+            }
+
+            // 2. Overlay (if configured; intro/outro have no streamer to name)
+            if let Some(ov) = config.clip_overlay(i) {
+                clip_filters.push(self.build_overlay_filter(
+                    &ov,
+                    &config.clips[i],
+                    config.overlay_margins(),
+                ));
+            }
+
+            // 3. Fades (if transition configured)
+            if fade_duration > 0.0 {
+                let clip_duration = config.clips[i].length();
+                let fade_out_start = (clip_duration - fade_duration).max(0.0);
+
+                if n == 1 {
+                    // Single clip with transition: just fade in/out? Or no transition?
+                    // Usually transition is between clips. If single clip, maybe fade in/out is nice?
+                    // Logic below handles n > 1. For n=1, let's just fade in/out provided duration is small?
+                    // Standard logic usually entails no transition for single clip.
+                    // But let's stick to existing logic structure.
+                    // Existing logic handled n=1 separately returning null.
+                } else if i == 0 {
+                    // First clip: only fade out
+                    clip_filters.push(format!(
+                        "fade=t=out:st={fade_out_start:.2}:d={fade_duration:.2}"
+                    ));
+                } else if i == n - 1 {
+                    // Last clip: only fade in
+                    clip_filters.push(format!("fade=t=in:st=0:d={fade_duration:.2}"));
+                } else {
+                    // Middle clips: both fade in and out
+                    clip_filters.push(format!("fade=t=in:st=0:d={fade_duration:.2}"));
+                    clip_filters.push(format!(
+                        "fade=t=out:st={fade_out_start:.2}:d={fade_duration:.2}"
+                    ));
+                }
+            }
+
+            if clip_filters.is_empty() {
+                "null".to_string()
+            } else {
+                clip_filters.join(",")
+            }
+        };
+
+        if n == 1 {
+            // Single clip
+            let v_filter = get_clip_filters(0);
+
+            // No transitions on a single clip
+            let a_filter = config
+                .clip_audio_filter(0)
+                .unwrap_or_else(|| "anull".to_string());
+
+            return format!(
+                "[0:v]{}[vout];[{}]{}[aout]",
+                v_filter,
+                config.clip_audio_input(0),
+                a_filter
+            );
+        }
+
+        if let Some(transition) = config.xfade() {
+            return Self::build_xfade_filter_complex(config, transition, get_clip_filters);
+        }
+
+        // Multiple clips
+        for i in 0..n {
+            let v_filter = get_clip_filters(i);
+
+            // Loudness and volume, then fades
+            let mut a_filters: Vec<String> = config.clip_audio_filter(i).into_iter().collect();
+            if fade_duration > 0.0 {
+                let clip_duration = config.clips[i].length();
+                let fade_out_start = (clip_duration - fade_duration).max(0.0);
+
+                if i == 0 {
+                    a_filters.push(format!(
+                        "afade=t=out:st={fade_out_start:.2}:d={fade_duration:.2}"
+                    ));
+                } else if i == n - 1 {
+                    a_filters.push(format!("afade=t=in:st=0:d={fade_duration:.2}"));
+                } else {
+                    a_filters.push(format!("afade=t=in:st=0:d={fade_duration:.2}"));
+                    a_filters.push(format!(
+                        "afade=t=out:st={fade_out_start:.2}:d={fade_duration:.2}"
+                    ));
+                }
+            }
+
+            let a_filter_str = if a_filters.is_empty() {
+                "anull".to_string()
+            } else {
+                a_filters.join(",")
+            };
+
+            filters.push(format!("[{i}:v]{}[v{i}]", v_filter));
+            filters.push(format!(
+                "[{}]{}[a{i}]",
+                config.clip_audio_input(i),
+                a_filter_str
+            ));
+        }
+
+        // Concat all processed streams - must be in order [v0][a0][v1][a1]...
+        let concat_inputs: Vec<String> = (0..n).map(|i| format!("[v{i}][a{i}]")).collect();
+
+        filters.push(format!(
+            "{}concat=n={n}:v=1:a=1[vout][aout]",
+            concat_inputs.join("")
+        ));
+
+        filters.join(";")
+    }
+
+    /// Chain clips with overlapping `xfade` / `acrossfade` transitions
+    fn build_xfade_filter_complex(
+        config: &MontageConfig,
+        transition: &str,
+        clip_filters: impl Fn(usize) -> String,
+    ) -> String {
+        let n = config.clips.len();
+        let d = config.transition_duration;
+        let offsets = config.clip_offsets();
+        let mut filters = Vec::new();
+        let fps = config.output_fps().unwrap_or(XFADE_FPS);
+
+        // xfade needs matching frame rates and timebases on both inputs
+        for i in 0..n {
+            let chain = match clip_filters(i).as_str() {
+                "null" => String::new(),
+                f => format!("{f},"),
+            };
+            filters.push(format!("[{i}:v]{chain}fps={fps},settb=AVTB[v{i}]"));
+        }
+
+        // Normalized or adjusted audio gets its own labels
+        let audio_in: Vec<String> = (0..n)
+            .map(|i| match config.clip_audio_filter(i) {
+                Some(chain) => {
+                    filters.push(format!("[{}]{chain}[an{i}]", config.clip_audio_input(i)));
+                    format!("an{i}")
+                }
+                None => config.clip_audio_input(i),
+            })
+            .collect();
+
+        let mut video = "v0".to_string();
+        let mut audio = audio_in[0].clone();
+        for (i, offset) in offsets.iter().enumerate().skip(1) {
+            let (v_out, a_out) = if i == n - 1 {
+                ("vout".to_string(), "aout".to_string())
+            } else {
+                (format!("xv{i}"), format!("xa{i}"))
+            };
+            filters.push(format!(
+                "[{video}][v{i}]xfade=transition={transition}:duration={d:.2}:offset={offset:.3}[{v_out}]"
+            ));
+            filters.push(format!(
+                "[{audio}][{}]acrossfade=d={d:.2}[{a_out}]",
+                audio_in[i]
+            ));
+            video = v_out;
+            audio = a_out;
+        }
+
+        filters.join(";")
+    }
+
+    /// Join the clips' audio into `[aout]`, crossfaded when the montage has
+    /// transitions
+    fn build_audio_filter_complex(config: &MontageConfig) -> String {
+        let n = config.clips.len();
+        let mut filters = config.silence_sources();
+        let audio_in: Vec<String> = (0..n)
+            .map(|i| match config.clip_audio_filter(i) {
+                Some(chain) => {
+                    filters.push(format!("[{}]{chain}[an{i}]", config.clip_audio_input(i)));
+                    format!("an{i}")
+                }
+                None => config.clip_audio_input(i),
+            })
+            .collect();
+
+        if n == 1 {
+            filters.push(format!("[{}]anull[aout]", audio_in[0]));
+        } else if config.xfade().is_some() {
+            let d = config.transition_duration;
+            let mut audio = audio_in[0].clone();
+            for (i, input) in audio_in.iter().enumerate().skip(1) {
+                let out = if i == n - 1 {
+                    "aout".to_string()
+                } else {
+                    format!("xa{i}")
+                };
+                filters.push(format!("[{audio}][{input}]acrossfade=d={d:.2}[{out}]"));
+                audio = out;
+            }
+        } else {
+            let inputs: String = audio_in.iter().map(|a| format!("[{a}]")).collect();
+            filters.push(format!("{inputs}concat=n={n}:v=0:a=1[aout]"));
+        }
+        filters.join(";")
+    }
+
+    /// FFmpeg command for an audio-only montage (`codec` from its container)
+    fn build_audio_command(
+        &self,
+        config: &MontageConfig,
+        output_path: &Path,
+        chapters: Option<&Path>,
+        ffmpeg: &FfmpegConfig,
+        codec: &str,
+    ) -> Command {
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.arg("-y");
+        for clip in &config.clips {
+            cmd.args(["-i", clip.path.to_string_lossy().as_ref()]);
+        }
+        let mut next_input = config.clips.len();
+        if let Some(music) = &config.music {
+            cmd.args(["-stream_loop", "-1", "-i"]);
+            cmd.arg(&music.path);
+            next_input += 1;
+        }
+        if let Some(chapters) = chapters {
+            cmd.args(["-f", "ffmetadata", "-i"]);
+            cmd.arg(chapters);
+        }
+
+        let mut filter = Self::build_audio_filter_complex(config);
+        let audio_out = match &config.music {
+            Some(music) => {
+                filter.push(';');
+                filter.push_str(&Self::build_music_filter(config, music, config.clips.len()));
+                "[amix]"
+            }
+            None => "[aout]",
+        };
+        cmd.args(["-filter_complex", &filter, "-map", audio_out]);
+        if chapters.is_some() {
+            cmd.args(["-map_chapters", &next_input.to_string()]);
+        }
+        cmd.args(["-c:a", codec, "-b:a", &ffmpeg.audio_bitrate]);
+        if config.container == MontageContainer::M4a {
+            cmd.args(["-movflags", "+faststart"]);
+        }
+        cmd.args(["-progress", "pipe:2"]);
+        cmd.arg(output_path);
+
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdout(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+        cmd
+    }
+
+    /// Mix the music input under `[aout]` into `[amix]`
+    ///
+    /// The clip audio drives a sidechain compressor on the music, so the
+    /// track dips whenever someone talks. The looped track is cut to the
+    /// montage length and faded out over its last second.
+    fn build_music_filter(config: &MontageConfig, music: &MusicTrack, input: usize) -> String {
+        // Where the last clip ends (fades through black don't shorten the output)
+        let total = match (config.clip_offsets().last(), config.clips.last()) {
+            (Some(start), Some(last)) => start + last.length(),
+            _ => 0.0,
+        };
+        let fade = total.min(1.0);
+        [
+            "[aout]asplit=2[aclip][akey]".to_string(),
+            format!(
+                "[{input}:a]volume={:.2},atrim=end={total:.3},asetpts=PTS-STARTPTS,\
+                 afade=t=out:st={:.3}:d={fade:.3}[bgm]",
+                music.volume.max(0.0),
+                total - fade,
+            ),
+            format!(
+                "[bgm][akey]sidechaincompress=threshold={DUCK_THRESHOLD}:ratio={DUCK_RATIO}:\
+                 attack={DUCK_ATTACK_MS}:release={DUCK_RELEASE_MS}[ducked]"
+            ),
+            "[aclip][ducked]amix=inputs=2:duration=first:normalize=0[amix]".to_string(),
+        ]
+        .join(";")
+    }
+
+    /// Build drawtext filter for overlay
+    fn build_overlay_filter(
+        &self,
+        overlay: &OverlayConfig,
+        clip: &MontageClip,
+        (margin_x, margin_y): (u32, u32),
+    ) -> String {
+        let clip_duration = clip.length();
+
+        // Escape special characters for FFmpeg
+        let text = overlay
+            .text
+            .replace("{streamer}", &clip.streamer_name)
+            .replace("{action}", &clip.action_name)
+            .replace(":", "\\:")
+            .replace("'", "\\'");
+
+        let d = OVERLAY_ANIMATION_DURATION;
+        let position = match (overlay.animation, &overlay.custom_position) {
+            (OverlayAnimation::SlideIn, Some(point)) => {
+                format!("x='{}':y={}", point.slide_in_x_expr(d), point.y_expr())
+            }
+            (OverlayAnimation::SlideIn, None) => format!(
+                "x='{}':y={}",
+                overlay.position.slide_in_x_expr(margin_x, d),
+                overlay.position.y_expr(margin_y)
+            ),
+            (_, Some(point)) => format!("x={}:y={}", point.x_expr(), point.y_expr()),
+            (_, None) => overlay.position.to_ffmpeg_coords(margin_x, margin_y),
+        };
+
+        let font_path_str = match &overlay.font {
+            Some(font) => ffmpeg_filter_path(font),
+            None => ffmpeg_font_path(),
+        };
+
+        let mut filter = format!(
+            "drawtext=fontfile='{}':text='{}':{}:fontsize={}:fontcolor=#{}",
+            font_path_str, text, position, overlay.font_size, overlay.color
+        );
+
+        if let Some(ref box_color) = overlay.box_color {
+            filter.push_str(&format!(":box=1:boxcolor={}:boxborderw=10", box_color));
+        }
+
+        if let Some(outline) = &overlay.outline {
+            filter.push_str(&format!(
+                ":borderw={}:bordercolor={}",
+                outline.width,
+                ffmpeg_color(&outline.color)
+            ));
+        }
+
+        if let Some(shadow) = &overlay.shadow {
+            filter.push_str(&format!(
+                ":shadowx={}:shadowy={}:shadowcolor={}",
+                shadow.x,
+                shadow.y,
+                ffmpeg_color(&shadow.color)
+            ));
+        }
+
+        match overlay.animation {
+            OverlayAnimation::None => {}
+            OverlayAnimation::Fade => filter.push_str(&format!(
+                ":alpha='{}'",
+                OverlayAnimation::fade_alpha_expr(clip_duration, OVERLAY_FADE_DURATION)
+            )),
+            OverlayAnimation::SlideIn => filter.push_str(&format!(
+                ":alpha='{}'",
+                OverlayAnimation::fade_out_alpha_expr(clip_duration, d)
+            )),
+        }
+
+        filter
+    }
+
+    /// Title card video in `format`
+    fn build_card_command(
+        &self,
+        card: &TitleCard,
+        format: &NormalizeFormat,
+        output: &Path,
+    ) -> Command {
+        let (w, h, fps) = (format.width, format.height, format.fps);
+        let duration = format!("{:.3}", card.duration);
+
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.arg("-y");
+
+        // Background: a looped still filling the frame, or a solid color
+        let mut video_filter = String::new();
+        match &card.background_image {
+            Some(image) => {
+                cmd.args([
+                    "-loop",
+                    "1",
+                    "-framerate",
+                    &fps.to_string(),
+                    "-t",
+                    &duration,
+                    "-i",
+                ]);
+                cmd.arg(image);
+                video_filter.push_str(&format!(
+                    "scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h},setsar=1,"
+                ));
+            }
+            None => {
+                let color = card
+                    .background_color
+                    .as_deref()
+                    .map(|c| format!("0x{}", c))
+                    .unwrap_or_else(|| "black".to_string());
+                cmd.args([
+                    "-f",
+                    "lavfi",
+                    "-i",
+                    &format!("color=c={color}:s={w}x{h}:r={fps}:d={duration}"),
+                ]);
+            }
+        }
+        video_filter.push_str(&card_text_filter(&card.text, w, h));
+
+        cmd.args([
+            "-f",
+            "lavfi",
+            "-i",
+            &format!("anullsrc=r={}:cl=stereo", format.sample_rate),
+            "-vf",
+            &video_filter,
+            "-t",
+            &duration,
+            "-c:v",
+            "libx264",
+            "-preset",
+            "fast",
+            "-crf",
+            "23",
+            "-pix_fmt",
+            "yuv420p",
+            "-c:a",
+            "aac",
+            "-b:a",
+            "128k",
+        ]);
+        cmd.arg(output);
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdout(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+        cmd
+    }
+
+    /// Render a title card to `output`
+    async fn render_card(
+        &self,
+        card: &TitleCard,
+        format: &NormalizeFormat,
+        output: &Path,
+    ) -> ExportResult<()> {
+        card.validate().map_err(ExportError::Ffmpeg)?;
+
+        let mut cmd = self.build_card_command(card, format, output);
+        cmd.kill_on_drop(true);
+        let result = self
+            .cancel
+            .guard(async { Ok(timeout(CARD_TIMEOUT, cmd.output()).await) })
+            .await?
+            .map_err(|_| ExportError::Timeout(format!("Title card \"{}\" timed out", card.text)))?
+            .map_err(|e| ExportError::Ffmpeg(format!("Failed to run FFmpeg: {}", e)))?;
+
+        if !result.status.success() {
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            log::warn!("[Montage] Card rendering failed: {}", stderr.trim());
+            return Err(ExportError::Ffmpeg(format!(
+                "Failed to render title card \"{}\"",
+                card.text
+            )));
+        }
+        Ok(())
+    }
+
+    /// Render a title card to `output`, unless a previous staged export
+    /// already did
+    async fn render_card_once(
+        &self,
+        card: &TitleCard,
+        format: &NormalizeFormat,
+        output: &Path,
+    ) -> ExportResult<()> {
+        if output.is_file() {
+            return Ok(());
+        }
+        let partial = output.with_extension(PARTIAL_EXTENSION);
+        self.render_card(card, format, &partial).await?;
+        publish(&partial, output)
+    }
+
+    /// Render a bookend into `dir` as a clip in `format`
+    async fn render_bookend(
+        &self,
+        bookend: &Bookend,
+        name: &str,
+        clips: &[MontageClip],
+        format: &NormalizeFormat,
+        dir: &Path,
+    ) -> ExportResult<MontageClip> {
+        match bookend {
+            Bookend::File { path } => {
+                if !path.exists() {
+                    return Err(ExportError::Ffmpeg(format!(
+                        "{} file not found: {}",
+                        name,
+                        path.display()
+                    )));
+                }
+                let output = dir.join(generated_name(name, &(path, modified_millis(path), format)));
+                let ffmpeg = FfmpegExporter::new();
+                if !output.is_file() {
+                    let partial = output.with_extension(PARTIAL_EXTENSION);
+                    self.cancel
+                        .guard(ffmpeg.normalize_to(path, &partial, Some(format), None))
+                        .await
+                        .inspect_err(|_| {
+                            let _ = std::fs::remove_file(&partial);
+                        })?;
+                    publish(&partial, &output)?;
+                }
+                let duration = ffmpeg.probe_duration(&output).await?;
+                Ok(generated_clip(output, duration, name))
+            }
+            Bookend::Card(card) => {
+                let output = dir.join(generated_name(name, &(card, format)));
+                self.render_card_once(card, format, &output).await?;
+                Ok(generated_clip(output, card.duration, name))
+            }
+            Bookend::Credits(credits) => {
+                let card = credits.card(clips);
+                let output = dir.join(generated_name(name, &(&card, format)));
+                self.render_card_once(&card, format, &output).await?;
+                Ok(generated_clip(output, card.duration, name))
+            }
+        }
+    }
+
+    /// `config` with its section cards and intro/outro rendered into `dir`
+    /// and added as clips
+    async fn with_generated_clips(
+        &self,
+        config: &MontageConfig,
+        dir: &Path,
+    ) -> ExportResult<MontageConfig> {
+        let mut expanded = config.clone();
+        expanded.intro = None;
+        expanded.outro = None;
+        expanded.sections.clear();
+
+        let format = self.generated_format(config).await;
+        let mut sections = Vec::with_capacity(config.sections.len());
+        for section in &config.sections {
+            if section.before > config.clips.len() {
+                return Err(ExportError::Ffmpeg(format!(
+                    "Section \"{}\" is placed after the last clip",
+                    section.card.text
+                )));
+            }
+            let output = dir.join(generated_name("Section", &(&section.card, &format)));
+            self.render_card_once(&section.card, &format, &output)
+                .await?;
+            sections.push((
+                section.before,
+                generated_clip(output, section.card.duration, &section.card.text),
+            ));
+        }
+        insert_sections(&mut expanded.clips, sections);
+
+        if let Some(intro) = &config.intro {
+            let clip = self
+                .render_bookend(intro, "Intro", &config.clips, &format, dir)
+                .await?;
+            expanded.clips.insert(0, clip);
+        }
+        if let Some(outro) = &config.outro {
+            let clip = self
+                .render_bookend(outro, "Outro", &config.clips, &format, dir)
+                .await?;
+            expanded.clips.push(clip);
+        }
+        Ok(expanded)
+    }
+
+    /// Format title cards and intro/outro files are rendered in: the montage's
+    /// output size and rate where set, else those of the first clip
+    async fn generated_format(&self, config: &MontageConfig) -> NormalizeFormat {
+        let source = match config.clips.first() {
+            Some(clip) => match FfmpegExporter::new().probe_stream_params(&clip.path).await {
+                Ok(params) => NormalizeFormat::of_source(&params),
+                Err(e) => {
+                    log::warn!("[Montage] {}", e);
+                    NormalizeFormat::new(1920, 1080, 60)
+                }
+            },
+            None => NormalizeFormat::new(1920, 1080, 60),
+        };
+        let (width, height) = config.frame_size().unwrap_or((source.width, source.height));
+        let fps = config.format.map_or(source.fps, |f| f.fps);
+        NormalizeFormat::new(width, height, fps)
+    }
+
+    /// Whether every clip has audio and the same codec parameters as the first
+    async fn clips_share_codecs(&self, config: &MontageConfig) -> bool {
+        let ffmpeg = FfmpegExporter::new();
+        let mut first: Option<StreamParams> = None;
+        for clip in &config.clips {
+            let params = match ffmpeg.probe_stream_params(&clip.path).await {
+                Ok(params) if params.has_video_and_audio() => params,
+                Ok(_) => return false,
+                Err(e) => {
+                    log::warn!("[Montage] {}", e);
+                    return false;
+                }
+            };
+            match &first {
+                Some(first) if *first != params => {
+                    log::info!(
+                        "[Montage] {} differs from the first clip, re-encoding",
+                        clip.path.display()
+                    );
+                    return false;
+                }
+                Some(_) => {}
+                None => first = Some(params),
+            }
+        }
+        true
+    }
+
+    /// Build the FFmpeg command joining clips with the concat demuxer (stream copy)
+    fn build_stream_copy_command(
+        &self,
+        list_path: &Path,
+        output_path: &Path,
+        chapters: Option<&Path>,
+        container: MontageContainer,
+    ) -> Command {
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.args(["-y", "-f", "concat", "-safe", "0", "-i"]);
+        cmd.arg(list_path);
+        if let Some(chapters) = chapters {
+            cmd.args(["-f", "ffmetadata", "-i"]);
+            cmd.arg(chapters);
+        }
+
+        cmd.args(["-map", "0:v:0", "-map", "0:a:0"]);
+        if chapters.is_some() {
+            cmd.args(["-map_chapters", "1"]);
+        }
+        cmd.args(["-c", "copy"]);
+        if container == MontageContainer::Mp4 {
+            cmd.args(["-movflags", "+faststart"]);
+        }
+        cmd.args(["-progress", "pipe:2"]);
+        cmd.arg(output_path);
+
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdout(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+        cmd
+    }
+
+    /// Build the complete FFmpeg command
+    fn build_command(
+        &self,
+        config: &MontageConfig,
+        output_path: &Path,
+        chapters: Option<&Path>,
+        ffmpeg: &FfmpegConfig,
+    ) -> Command {
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.arg("-y"); // Overwrite output
+
+        // Add all input files
+        for clip in &config.clips {
+            cmd.args(["-i", clip.path.to_string_lossy().as_ref()]);
+        }
+
+        // Music follows the clips, looped so it's never shorter than the montage
+        let mut next_input = config.clips.len();
+        if let Some(music) = &config.music {
+            cmd.args(["-stream_loop", "-1", "-i"]);
+            cmd.arg(&music.path);
+            next_input += 1;
+        }
+
+        // The watermark image follows; it's a single frame the overlay filter repeats
+        let image_input = next_input;
+        if let Some(image) = &config.image_overlay {
+            cmd.arg("-i");
+            cmd.arg(&image.path);
+            next_input += 1;
+        }
+
+        // Chapter metadata goes last so the clip input indices stay 0..n
+        if let Some(chapters) = chapters {
+            cmd.args(["-f", "ffmetadata", "-i"]);
+            cmd.arg(chapters);
+        }
+
+        // Build filter complex
+        let mut filter = self.build_filter_complex(config);
+        let video_out = match &config.image_overlay {
+            Some(image) => {
+                filter.push(';');
+                filter.push_str(&image.filter(image_input, config.overlay_margins()));
+                "[vmark]"
+            }
+            None => "[vout]",
+        };
+        let audio_out = match &config.music {
+            Some(music) => {
+                filter.push(';');
+                filter.push_str(&Self::build_music_filter(config, music, config.clips.len()));
+                "[amix]"
+            }
+            None => "[aout]",
+        };
 
         cmd.args(["-filter_complex", &filter]);
 
         // Map outputs
-        cmd.args(["-map", "[vout]", "-map", "[aout]"]);
+        cmd.args(["-map", video_out, "-map", audio_out]);
+        if chapters.is_some() {
+            cmd.args(["-map_chapters", &next_input.to_string()]);
+        }
 
-        // Video encoding - always use libx264 for montage (filter_complex + hw encoders can be unreliable)
-        // Hardware encoders like NVENC require CUDA which may not be available
-        cmd.args([
-            "-c:v", "libx264", "-preset", "fast", "-crf", "23", "-pix_fmt", "yuv420p",
-        ]);
+        if config.container == MontageContainer::Webm {
+            Self::add_webm_codec_args(&mut cmd, config, ffmpeg);
+        } else {
+            // Video encoding; frames stay in system memory for the filters, so
+            // hardware encoders get NV12, which all of them accept
+            FfmpegExporter::add_video_encoder_args(&mut cmd, ffmpeg);
+            if let Some(kbps) = config.encoding.max_bitrate_kbps {
+                cmd.args([
+                    "-maxrate",
+                    &format!("{}k", kbps),
+                    "-bufsize",
+                    &format!("{}k", kbps * 2),
+                ]);
+            }
+            let pix_fmt = match ffmpeg.encoder {
+                VideoEncoder::Libx264 => "yuv420p",
+                _ => "nv12",
+            };
+            cmd.args(["-pix_fmt", pix_fmt]);
 
-        // Audio encoding
-        cmd.args(["-c:a", "aac", "-b:a", "128k"]);
+            // Audio encoding
+            cmd.args(["-c:a", "aac", "-b:a", &ffmpeg.audio_bitrate]);
+        }
 
         // Output optimization + progress
-        cmd.args(["-movflags", "+faststart", "-progress", "pipe:2"]);
+        if config.container == MontageContainer::Mp4 {
+            cmd.args(["-movflags", "+faststart"]);
+        }
+        cmd.args(["-progress", "pipe:2"]);
         cmd.arg(output_path);
 
         cmd.stdin(std::process::Stdio::null());
@@ -322,19 +2591,95 @@ impl MontageExporter {
         cmd
     }
 
+    /// VP9 + Opus arguments for WebM output
+    ///
+    /// VP9's CRF scale runs to 63; x264 CRF + 10 gives a similar quality. A
+    /// bitrate cap turns constant quality into constrained quality.
+    fn add_webm_codec_args(cmd: &mut Command, config: &MontageConfig, ffmpeg: &FfmpegConfig) {
+        let crf = (ffmpeg.crf + 10).min(63).to_string();
+        let bitrate = config
+            .encoding
+            .max_bitrate_kbps
+            .map_or("0".to_string(), |kbps| format!("{}k", kbps));
+        cmd.args([
+            "-c:v",
+            VP9_ENCODER,
+            "-crf",
+            &crf,
+            "-b:v",
+            &bitrate,
+            "-deadline",
+            "good",
+            "-cpu-used",
+            "4",
+            "-row-mt",
+            "1",
+            "-pix_fmt",
+            "yuv420p",
+        ]);
+        cmd.args(["-c:a", "libopus", "-b:a", &ffmpeg.audio_bitrate]);
+    }
+
     /// Export montage with progress callback
     pub async fn export_with_progress(
         &self,
         config: &MontageConfig,
         output_path: &Path,
         progress: Option<&ProgressCallback>,
-    ) -> ExportResult<()> {
+    ) -> ExportResult<Option<&'static str>> {
         if config.clips.is_empty() {
             return Err(ExportError::Ffmpeg("No clips to export".to_string()));
         }
+        let preview_config;
+        let config = if self.preview {
+            preview_config = config.preview();
+            &preview_config
+        } else {
+            config
+        };
+        // Audio-only montages skip everything that is only seen
+        let audio_config;
+        let config = if config.container.audio_codec().is_some() {
+            audio_config = config.audio_only();
+            &audio_config
+        } else {
+            config
+        };
+        // Previews are replaced by the next one rather than cancelled
+        let _scope = (!self.preview).then(|| CancelScope::enter(&self.cancel));
+        if let Some(format) = &config.format {
+            format.validate().map_err(ExportError::Ffmpeg)?;
+        }
+        config.encoding.validate().map_err(ExportError::Ffmpeg)?;
+        if let Some(overlay) = &config.overlay {
+            overlay.validate().map_err(ExportError::Ffmpeg)?;
+        }
+        if let Some(lufs) = config.loudness_lufs {
+            if !LOUDNESS_TARGET_RANGE.contains(&lufs) {
+                return Err(ExportError::Ffmpeg(format!(
+                    "Invalid loudness target: {} LUFS",
+                    lufs
+                )));
+            }
+        }
 
         // Verify all input files exist
         for clip in &config.clips {
+            clip.validate_trim().map_err(ExportError::Ffmpeg)?;
+            clip.validate_crop().map_err(ExportError::Ffmpeg)?;
+            clip.validate_effects().map_err(ExportError::Ffmpeg)?;
+            // The cropped frame is scaled back by the output format or aspect
+            if clip.crop_filter().is_some() && config.frame_filter().is_none() {
+                return Err(ExportError::Ffmpeg(
+                    "Cropped or zoomed clips need an output resolution".to_string(),
+                ));
+            }
+            if let Some(volume) = clip.volume.filter(|v| !CLIP_VOLUME_RANGE.contains(v)) {
+                return Err(ExportError::Ffmpeg(format!(
+                    "Invalid clip volume: {} (0-4)",
+                    volume
+                )));
+            }
             if !clip.path.exists() {
                 return Err(ExportError::Ffmpeg(format!(
                     "Clip file not found: {}",
@@ -342,6 +2687,91 @@ impl MontageExporter {
                 )));
             }
         }
+        if let Some(music) = &config.music {
+            if !music.path.exists() {
+                return Err(ExportError::Ffmpeg(format!(
+                    "Music file not found: {}",
+                    music.path.display()
+                )));
+            }
+        }
+        if let Some(font) = config.overlay.as_ref().and_then(|o| o.font.as_ref()) {
+            if !font.is_file() {
+                return Err(ExportError::Ffmpeg(format!(
+                    "Overlay font not found: {}",
+                    font.display()
+                )));
+            }
+        }
+        if let Some(image) = &config.image_overlay {
+            if !image.path.exists() {
+                return Err(ExportError::Ffmpeg(format!(
+                    "Overlay image not found: {}",
+                    image.path.display()
+                )));
+            }
+        }
+        // A clip without video or of another size breaks the filter graph
+        // with an error that doesn't say which clip it is
+        let report = check_inputs(config).await;
+        if let Some(problem) = report.first_problem() {
+            return Err(ExportError::Ffmpeg(problem));
+        }
+        let silent = report.silent_clips();
+        let silenced;
+        let config = if silent.is_empty() {
+            config
+        } else {
+            log::info!(
+                "[Montage] {} clip(s) without audio, using silence",
+                silent.len()
+            );
+            let mut with_silence = config.clone();
+            for i in silent {
+                with_silence.clips[i].silent = true;
+            }
+            silenced = with_silence;
+            &silenced
+        };
+
+        let scratch = ScratchDir::new("montage")
+            .map_err(|e| ExportError::OutputDir(format!("Failed to create scratch dir: {}", e)))?;
+
+        // Staged exports keep their renders next to the montage for a retry
+        let stage_dir = (config.render == RenderMode::Staged).then(|| {
+            output_path
+                .parent()
+                .unwrap_or(Path::new("."))
+                .join(STAGE_DIR)
+        });
+        if let Some(dir) = &stage_dir {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                ExportError::OutputDir(format!("Failed to create stage dir: {}", e))
+            })?;
+            prune_stages(dir, STAGE_MAX_AGE);
+        }
+
+        // Title cards and intro/outro become ordinary clips, so they get the
+        // same reframing, transitions and chapters
+        let expanded;
+        let config = if config.has_generated_clips() {
+            let dir = stage_dir.as_deref().unwrap_or(scratch.path());
+            expanded = self.with_generated_clips(config, dir).await?;
+            &expanded
+        } else {
+            config
+        };
+
+        // Prerendered clips can't be cut
+        let snapped;
+        let config = match &config.music {
+            Some(music) if music.snap_to_beats && config.render != RenderMode::Prerendered => {
+                let beats = beats::analyze(&music.path).await?;
+                snapped = config.snapped_to_beats(&beats);
+                &snapped
+            }
+            _ => config,
+        };
 
         let total_duration = config.total_duration();
         log::info!(
@@ -350,146 +2780,1765 @@ impl MontageExporter {
             total_duration
         );
 
-        let mut cmd = self.build_command(config, output_path);
+        // One chapter per clip so players (and YouTube) can jump between them
+        let chapters_path = scratch.join("chapters.txt");
+        std::fs::write(&chapters_path, config.chapters_metadata())
+            .map_err(|e| ExportError::OutputDir(format!("Failed to write chapters: {}", e)))?;
+
+        let progress = progress.map(|cb| cb.as_ref());
+        if let Some(stage_dir) = &stage_dir {
+            return self
+                .export_staged(
+                    config,
+                    output_path,
+                    stage_dir,
+                    &chapters_path,
+                    scratch.path(),
+                    progress,
+                )
+                .await;
+        }
+
+        self.join(
+            config,
+            output_path,
+            &chapters_path,
+            scratch.path(),
+            progress,
+        )
+        .await
+    }
+
+    /// Join the clips of `config` into `output_path`, returning the encoder
+    /// used (None when joined by stream copy)
+    async fn join(
+        &self,
+        config: &MontageConfig,
+        output_path: &Path,
+        chapters: &Path,
+        scratch: &Path,
+        progress: Option<&ProgressFn<'_>>,
+    ) -> ExportResult<Option<&'static str>> {
+        let total_duration = config.total_duration();
+
+        if let Some(codec) = config.container.audio_codec() {
+            let ffmpeg = config.encoding.apply(&get_config().ffmpeg);
+            let cmd = self.build_audio_command(config, output_path, Some(chapters), &ffmpeg, codec);
+            self.run(cmd, output_path, total_duration, progress).await?;
+            return Ok(Some(codec));
+        }
+
+        // Nothing to draw or blend: join the clips as they are when their
+        // codecs match, which takes seconds instead of a full re-encode
+        if self.preview || !config.is_plain_concat() || !self.clips_share_codecs(config).await {
+            let build = |ffmpeg: &FfmpegConfig| {
+                self.build_command(config, output_path, Some(chapters), ffmpeg)
+            };
+            return self
+                .run_encode(config, build, output_path, total_duration, progress)
+                .await
+                .map(Some);
+        }
+
+        log::info!("[Montage] Clips share codec parameters, joining by stream copy");
+        let list: String = config
+            .clips
+            .iter()
+            .map(|clip| concat_list_entry(&clip.path) + "\n")
+            .collect();
+        let list_path = scratch.join("concat.txt");
+        std::fs::write(&list_path, list)
+            .map_err(|e| ExportError::OutputDir(format!("Failed to write concat list: {}", e)))?;
+        let cmd = self.build_stream_copy_command(
+            &list_path,
+            output_path,
+            Some(chapters),
+            config.container,
+        );
+        self.run(cmd, output_path, total_duration, progress).await?;
+        Ok(None)
+    }
+
+    /// Run an encode with the app's FFmpeg settings and the montage's
+    /// overrides, retrying with libx264 if a hardware encoder fails (missing
+    /// driver, unsupported input); returns the encoder that wrote the file.
+    /// WebM is always encoded in software
+    async fn run_encode(
+        &self,
+        config: &MontageConfig,
+        build: impl Fn(&FfmpegConfig) -> Command,
+        output_path: &Path,
+        total_duration: f64,
+        progress: Option<&ProgressFn<'_>>,
+    ) -> ExportResult<&'static str> {
+        let ffmpeg = if self.preview {
+            FfmpegConfig {
+                encoder: VideoEncoder::Libx264,
+                preset: PREVIEW_PRESET.to_string(),
+                crf: PREVIEW_CRF,
+                audio_bitrate: PREVIEW_AUDIO_BITRATE.to_string(),
+                ..get_config().ffmpeg
+            }
+        } else {
+            config.encoding.apply(&get_config().ffmpeg)
+        };
+        if config.container == MontageContainer::Webm {
+            self.run(build(&ffmpeg), output_path, total_duration, progress)
+                .await?;
+            return Ok(VP9_ENCODER);
+        }
+        let encoder = ffmpeg.encoder;
+        let result = self
+            .run(build(&ffmpeg), output_path, total_duration, progress)
+            .await;
+        match result {
+            Ok(()) => Ok(encoder.as_str()),
+            Err(e @ (ExportError::Cancelled | ExportError::Timeout(_))) => Err(e),
+            Err(e) if encoder != VideoEncoder::Libx264 => {
+                log::warn!(
+                    "[Montage] {} failed, falling back to libx264: {}",
+                    encoder.as_str(),
+                    e
+                );
+                let software = FfmpegConfig {
+                    encoder: VideoEncoder::Libx264,
+                    ..ffmpeg
+                };
+                self.run(build(&software), output_path, total_duration, progress)
+                    .await?;
+                Ok(VideoEncoder::Libx264.as_str())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Render clip by clip into `stage_dir`, then join the rendered clips
+    ///
+    /// Each rendered clip is named after its inputs, so exporting again after
+    /// a failed stage (or a failed join) skips the clips that already
+    /// finished. They are removed once the montage is written.
+    async fn export_staged(
+        &self,
+        config: &MontageConfig,
+        output_path: &Path,
+        stage_dir: &Path,
+        chapters: &Path,
+        scratch: &Path,
+        progress: Option<&ProgressFn<'_>>,
+    ) -> ExportResult<Option<&'static str>> {
+        // Progress is weighted by seconds rendered: every clip, then the join
+        let clips_duration: f64 = config.clips.iter().map(|c| c.length()).sum();
+        let total_duration = config.total_duration();
+        let work = (clips_duration + total_duration).max(f64::EPSILON);
+
+        let mut joined = config.clone();
+        joined.render = RenderMode::Prerendered;
+        let mut done = 0.0;
+        // libx264 once any clip needed the fallback
+        let mut stage_encoder = None;
+        for (i, clip) in config.clips.iter().enumerate() {
+            let stage = config.stage(i);
+            let output = stage_dir.join(format!("{}.mp4", stage_key(&stage)));
+            if output.is_file() {
+                log::info!(
+                    "[Montage] Clip {}/{} already rendered",
+                    i + 1,
+                    config.clips.len()
+                );
+            } else {
+                log::info!("[Montage] Rendering clip {}/{}", i + 1, config.clips.len());
+                let report = |update: ProgressUpdate| {
+                    if let Some(cb) = progress {
+                        let rendered = done + clip.length() * update.percent as f64 / 100.0;
+                        cb(ProgressUpdate {
+                            percent: (rendered / work * 100.0) as f32,
+                            ..update
+                        });
+                    }
+                };
+                // Only a finished render gets the final name
+                let partial = output.with_extension(PARTIAL_EXTENSION);
+                let build =
+                    |ffmpeg: &FfmpegConfig| self.build_command(&stage, &partial, None, ffmpeg);
+                let encoder = self
+                    .run_encode(&stage, build, &partial, clip.length(), Some(&report))
+                    .await
+                    .inspect_err(|_| {
+                        let _ = std::fs::remove_file(&partial);
+                    })?;
+                publish(&partial, &output)?;
+                if stage_encoder != Some(VideoEncoder::Libx264.as_str()) {
+                    stage_encoder = Some(encoder);
+                }
+            }
+            done += clip.length();
+            // The render is already cut, slowed and frozen
+            joined.clips[i] = MontageClip {
+                path: output,
+                duration: clip.length(),
+                overlay: None,
+                trim_start: None,
+                trim_end: None,
+                crop: None,
+                zoom: None,
+                slowmo: None,
+                freeze: None,
+                action_at: None,
+                silent: false,
+                ..clip.clone()
+            };
+        }
+
+        let joined_from = (done / work * 100.0) as f32;
+        let report = |update: ProgressUpdate| {
+            if let Some(cb) = progress {
+                cb(ProgressUpdate {
+                    percent: joined_from + update.percent * (100.0 - joined_from) / 100.0,
+                    ..update
+                });
+            }
+        };
+        let join_encoder = self
+            .join(&joined, output_path, chapters, scratch, Some(&report))
+            .await?;
+
+        // Clip renders and the title cards they were made from
+        for clip in config.clips.iter().chain(&joined.clips) {
+            if clip.path.starts_with(stage_dir) {
+                let _ = std::fs::remove_file(&clip.path);
+            }
+        }
+        let _ = std::fs::remove_dir(stage_dir);
+        Ok(join_encoder.or(stage_encoder))
+    }
+
+    /// Run a montage ffmpeg command, reporting its progress
+    pub(super) async fn run(
+        &self,
+        mut cmd: Command,
+        output_path: &Path,
+        total_duration: f64,
+        progress: Option<&ProgressFn<'_>>,
+    ) -> ExportResult<()> {
         log::debug!("[Montage] Command: {:?}", cmd);
 
-        let mut child = cmd
-            .spawn()
-            .map_err(|e| ExportError::Ffmpeg(format!("Failed to start FFmpeg: {}", e)))?;
+        let cancelled = self.cancel.cancelled();
+        tokio::pin!(cancelled);
+        cancelled.as_mut().enable();
+        if self.cancel.is_cancelled() {
+            return Err(ExportError::Cancelled);
+        }
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| ExportError::Ffmpeg(format!("Failed to start FFmpeg: {}", e)))?;
+
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| ExportError::Ffmpeg("Failed to capture stderr".to_string()))?;
+
+        let mut parser = FfmpegProgressParser::new(total_duration);
+        let mut reader = BufReader::new(stderr).lines();
+
+        // Keep the stderr tail for error reporting
+        let mut stderr_tail = StderrTail::default();
+
+        // Read progress and collect output, until FFmpeg exits or the export
+        // is cancelled
+        let read_output = async {
+            while let Ok(Some(line)) = reader.next_line().await {
+                log::debug!("[FFmpeg] {}", line);
+
+                if let Some(update) = parser.parse_line(&line) {
+                    if let Some(cb) = progress {
+                        cb(update);
+                    }
+                } else {
+                    stderr_tail.push(&line);
+                }
+            }
+        };
+        let limit = montage_timeout(total_duration, get_config().ffmpeg.montage_timeout_factor);
+        let deadline = async {
+            match limit {
+                Some(limit) => tokio::time::sleep(limit).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            _ = read_output => {}
+            _ = cancelled => {
+                let _ = child.kill().await;
+                let _ = std::fs::remove_file(output_path);
+                log::info!("[Montage] Cancelled, removed {}", output_path.display());
+                return Err(ExportError::Cancelled);
+            }
+            _ = deadline => {
+                let _ = child.kill().await;
+                let _ = std::fs::remove_file(output_path);
+                return Err(ExportError::Timeout(format!(
+                    "Montage export timed out after {} seconds",
+                    limit.unwrap_or_default().as_secs()
+                )));
+            }
+        }
+
+        // stderr closed, so FFmpeg is exiting
+        match child.wait().await {
+            Ok(status) if status.success() => {
+                log::info!("[Montage] Export successful: {}", output_path.display());
+                Ok(())
+            }
+            Ok(status) => {
+                let error_msg = format!(
+                    "FFmpeg exited with code: {}{}",
+                    status,
+                    stderr_tail.suffix()
+                );
+
+                log::error!("[Montage] FFmpeg failed: {}", error_msg);
+                log::error!("[Montage] Last output:\n{}", stderr_tail.dump());
+
+                Err(ExportError::Ffmpeg(error_msg))
+            }
+            Err(e) => Err(ExportError::Ffmpeg(format!("FFmpeg error: {}", e))),
+        }
+    }
+
+    /// Simple export without progress callback
+    pub async fn export(
+        &self,
+        config: &MontageConfig,
+        output_path: &Path,
+    ) -> ExportResult<Option<&'static str>> {
+        self.export_with_progress(config, output_path, None).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_total_duration_no_transition() {
+        let config = MontageConfig {
+            clips: vec![
+                MontageClip {
+                    path: PathBuf::new(),
+                    duration: 10.0,
+                    streamer_name: "A".into(),
+                    ..Default::default()
+                },
+                MontageClip {
+                    path: PathBuf::new(),
+                    duration: 15.0,
+                    streamer_name: "B".into(),
+                    ..Default::default()
+                },
+            ],
+            transition_duration: 0.0,
+            transition: TransitionStyle::Fade,
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        assert_eq!(config.total_duration(), 25.0);
+    }
+
+    #[test]
+    fn test_total_duration_with_transition() {
+        let mut config = MontageConfig {
+            clips: vec![
+                MontageClip {
+                    path: PathBuf::new(),
+                    duration: 10.0,
+                    streamer_name: "A".into(),
+                    ..Default::default()
+                },
+                MontageClip {
+                    path: PathBuf::new(),
+                    duration: 15.0,
+                    streamer_name: "B".into(),
+                    ..Default::default()
+                },
+                MontageClip {
+                    path: PathBuf::new(),
+                    duration: 20.0,
+                    streamer_name: "C".into(),
+                    ..Default::default()
+                },
+            ],
+            transition_duration: 0.5,
+            transition: TransitionStyle::Dissolve,
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        // Crossfades overlap: 45 - 2*0.5 = 44
+        assert_eq!(config.total_duration(), 44.0);
+
+        // Fades through black play inside each clip
+        config.transition = TransitionStyle::Fade;
+        assert_eq!(config.total_duration(), 45.0);
+    }
+
+    #[test]
+    fn test_portrait_filter_complex() {
+        let config = MontageConfig {
+            clips: vec![MontageClip {
+                path: PathBuf::from("/clips/a.mp4"),
+                duration: 10.0,
+                streamer_name: "A".into(),
+                ..Default::default()
+            }],
+            transition_duration: 0.0,
+            transition: TransitionStyle::Fade,
+            aspect: MontageAspect::Portrait,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        assert_eq!(
+            MontageExporter::new().build_filter_complex(&config),
+            "[0:v]crop='min(iw,ih*9/16)':ih,scale=1080:1920,setsar=1[vout];[0:a]anull[aout]"
+        );
+    }
+
+    #[test]
+    fn test_output_format_normalization() {
+        let clip = |name: &str| MontageClip {
+            path: PathBuf::from(format!("/clips/{name}.mp4")),
+            duration: 10.0,
+            streamer_name: name.into(),
+            ..Default::default()
+        };
+        let mut config = MontageConfig {
+            clips: vec![clip("a"), clip("b")],
+            transition_duration: 0.0,
+            transition: TransitionStyle::None,
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            format: Some(OutputFormat {
+                width: 1280,
+                height: 720,
+                fps: 30,
+            }),
+            ..Default::default()
+        };
+        let exporter = MontageExporter::new();
+        assert!(exporter.build_filter_complex(&config).starts_with(concat!(
+            "[0:v]scale=1280:720:force_original_aspect_ratio=decrease,",
+            "pad=1280:720:(ow-iw)/2:(oh-ih)/2,setsar=1,fps=30[v0];"
+        )));
+
+        // xfade sets the rate once, after the clip filters
+        config.transition = TransitionStyle::Dissolve;
+        config.transition_duration = 0.5;
+        assert!(exporter
+            .build_filter_complex(&config)
+            .starts_with("[0:v]scale=1280:720:force_original_aspect_ratio=decrease,pad=1280:720:(ow-iw)/2:(oh-ih)/2,setsar=1,fps=30,settb=AVTB[v0];"));
+
+        // Portrait keeps its own size; only the rate applies
+        config.transition = TransitionStyle::None;
+        config.aspect = MontageAspect::Portrait;
+        assert!(exporter
+            .build_filter_complex(&config)
+            .starts_with("[0:v]crop='min(iw,ih*9/16)':ih,scale=1080:1920,setsar=1,fps=30[v0];"));
+
+        assert!(OutputFormat {
+            width: 1281,
+            height: 720,
+            fps: 30
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_loudness_normalization() {
+        let clip = |name: &str| MontageClip {
+            path: PathBuf::from(format!("/clips/{name}.mp4")),
+            duration: 10.0,
+            streamer_name: name.into(),
+            ..Default::default()
+        };
+        let mut config = MontageConfig {
+            clips: vec![clip("a"), clip("b")],
+            transition_duration: 0.5,
+            transition: TransitionStyle::Fade,
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            loudness_lufs: Some(-14.0),
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        let exporter = MontageExporter::new();
+        assert!(exporter.build_filter_complex(&config).contains(
+            "[1:a]loudnorm=I=-14:TP=-1.5:LRA=11,aresample=48000,afade=t=in:st=0:d=0.50[a1]"
+        ));
+
+        config.transition = TransitionStyle::Wipe;
+        let filter = exporter.build_filter_complex(&config);
+        assert!(filter.contains("[0:a]loudnorm=I=-14:TP=-1.5:LRA=11,aresample=48000[an0];"));
+        assert!(filter.ends_with("[an0][an1]acrossfade=d=0.50[aout]"));
+
+        // A clip's volume goes after normalization, and alone it still gets a label
+        config.clips[1].volume = Some(0.5);
+        let filter = exporter.build_filter_complex(&config);
+        assert!(filter.contains("aresample=48000,volume=0.50[an1];"));
+        config.loudness_lufs = None;
+        let filter = exporter.build_filter_complex(&config);
+        assert!(filter.contains("[1:a]volume=0.50[an1];"));
+        assert!(filter.ends_with("[0:a][an1]acrossfade=d=0.50[aout]"));
+        assert!(!config.is_plain_concat());
+    }
+
+    #[test]
+    fn test_clip_trim() {
+        let clip = |name: &str| MontageClip {
+            path: PathBuf::from(format!("/clips/{name}.mp4")),
+            duration: 10.0,
+            streamer_name: name.into(),
+            ..Default::default()
+        };
+        let mut config = MontageConfig {
+            clips: vec![clip("a"), clip("b")],
+            transition_duration: 0.5,
+            transition: TransitionStyle::Fade,
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        config.clips[0].trim_start = Some(2.0);
+        config.clips[0].trim_end = Some(8.0);
+        config.clips[1].trim_end = Some(20.0);
+
+        // An end past the file is clamped to it
+        assert_eq!(config.clips[0].length(), 6.0);
+        assert_eq!(config.clips[1].length(), 10.0);
+        assert_eq!(config.total_duration(), 16.0);
+        assert!(!config.is_plain_concat());
+
+        // Fades are placed on the trimmed length
+        let filter = MontageExporter::new().build_filter_complex(&config);
+        assert!(filter.starts_with(concat!(
+            "[0:v]trim=start=2.000:end=8.000,setpts=PTS-STARTPTS,",
+            "fade=t=out:st=5.50:d=0.50[v0];",
+            "[0:a]atrim=start=2.000:end=8.000,asetpts=PTS-STARTPTS,",
+            "afade=t=out:st=5.50:d=0.50[a0];"
+        )));
+
+        config.clips[1].trim_start = Some(10.0);
+        assert!(config.clips[1].validate_trim().is_err());
+        assert!(config.clips[0].validate_trim().is_ok());
+    }
+
+    #[test]
+    fn test_clip_crop_and_zoom() {
+        let mut clip = MontageClip {
+            path: PathBuf::from("/clips/a.mp4"),
+            duration: 10.0,
+            streamer_name: "A".into(),
+            crop: Some(CropRect {
+                x: 640,
+                y: 0,
+                width: 1280,
+                height: 1080,
+            }),
+            zoom: Some(1.5),
+            ..Default::default()
+        };
+        assert_eq!(
+            clip.crop_filter().unwrap(),
+            "crop=1280:1080:640:0,crop=iw/1.50:ih/1.50"
+        );
+        assert!(clip.validate_crop().is_ok());
+
+        // Punched in before the vertical reframe
+        let config = MontageConfig {
+            clips: vec![clip.clone()],
+            transition_duration: 0.0,
+            transition: TransitionStyle::None,
+            aspect: MontageAspect::Portrait,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        assert!(MontageExporter::new()
+            .build_filter_complex(&config)
+            .starts_with(
+                "[0:v]crop=1280:1080:640:0,crop=iw/1.50:ih/1.50,crop='min(iw,ih*9/16)':ih,"
+            ));
+
+        clip.zoom = Some(0.5);
+        assert!(clip.validate_crop().is_err());
+    }
+
+    #[test]
+    fn test_clip_slowmo_and_freeze() {
+        let mut clip = MontageClip {
+            path: PathBuf::from("/clips/a.mp4"),
+            duration: 10.0,
+            streamer_name: "A".into(),
+            slowmo: Some(SlowMotion {
+                start: 2.0,
+                end: 4.0,
+                factor: 4.0,
+            }),
+            freeze: Some(1.5),
+            ..Default::default()
+        };
+        // 2s at quarter speed add 6s, plus the freeze
+        assert_eq!(clip.length(), 17.5);
+        assert!(clip.validate_effects().is_ok());
+
+        let config = MontageConfig {
+            clips: vec![clip.clone()],
+            transition_duration: 0.0,
+            transition: TransitionStyle::None,
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        assert!(!config.is_plain_concat());
+        assert_eq!(
+            MontageExporter::new().build_filter_complex(&config),
+            concat!(
+                "[0:v]setpts='if(lt(T,2.000),T,if(lt(T,4.000),2.000+(T-2.000)*4.00,T+6.000))/TB',",
+                "tpad=stop_mode=clone:stop_duration=1.500[vout];",
+                "[0:a]asplit=3[slow0_0][slow0_1][slow0_2];",
+                "[slow0_0]atrim=end=2.000,asetpts=PTS-STARTPTS[slowed0_0];",
+                "[slow0_1]atrim=start=2.000:end=4.000,asetpts=PTS-STARTPTS,",
+                "atempo=0.5,atempo=0.5000[slowed0_1];",
+                "[slow0_2]atrim=start=4.000,asetpts=PTS-STARTPTS[slowed0_2];",
+                "[slowed0_0][slowed0_1][slowed0_2]concat=n=3:v=0:a=1,apad=pad_dur=1.500[aout]"
+            )
+        );
+
+        // The whole clip slowed needs no split
+        clip.slowmo = Some(SlowMotion {
+            start: 0.0,
+            end: 10.0,
+            factor: 2.0,
+        });
+        assert_eq!(
+            clip.audio_effects_filter(0).unwrap(),
+            "atempo=0.5000,apad=pad_dur=1.500"
+        );
+
+        clip.trim_end = Some(8.0);
+        assert!(clip.validate_effects().is_err());
+    }
+
+    #[test]
+    fn test_snap_to_beats() {
+        let clip = |name: &str| MontageClip {
+            path: PathBuf::from("/clips/a.mp4"),
+            duration: 10.0,
+            streamer_name: name.into(),
+            generated: name.is_empty(),
+            ..Default::default()
+        };
+        let mut config = MontageConfig {
+            clips: vec![clip("A"), clip(""), clip("B"), clip("C")],
+            transition_duration: 0.0,
+            transition: TransitionStyle::None,
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        config.clips[2].trim_start = Some(2.5);
+        // A 12s loop with a beat every 0.75s
+        let beats = BeatAnalysis {
+            duration: 12.0,
+            bpm: Some(80.0),
+            beats: (0..16).map(|k| k as f64 * 0.75).collect(),
+        };
+
+        let snapped = config.snapped_to_beats(&beats);
+        // Ends at 10 -> 9.75
+        assert_eq!(snapped.clips[0].trim_end, Some(9.75));
+        // Title cards are left alone
+        assert_eq!(snapped.clips[1].trim_end, None);
+        // Starts at 19.75, ends at 27.25 -> 27.0 (3.0 into the third loop)
+        assert_eq!(snapped.clips[2].trim_end, Some(9.75));
+        // The last clip ends the montage
+        assert_eq!(snapped.clips[3].trim_end, None);
+        assert_eq!(snapped.clip_offsets()[3], 27.0);
+    }
 
-        let stderr = child
-            .stderr
-            .take()
-            .ok_or_else(|| ExportError::Ffmpeg("Failed to capture stderr".to_string()))?;
+    #[test]
+    fn test_reframe_fit_and_safe_area() {
+        assert_eq!(
+            MontageAspect::Portrait
+                .reframe_filter(MontageFit::Pad, (1080, 1920))
+                .unwrap(),
+            "scale=1080:1920:force_original_aspect_ratio=decrease,\
+             pad=1080:1920:(ow-iw)/2:(oh-ih)/2,setsar=1"
+        );
+        assert!(MontageAspect::Landscape
+            .reframe_filter(MontageFit::Pad, (1920, 1080))
+            .is_none());
 
-        let parser = FfmpegProgressParser::new(total_duration);
-        let mut reader = BufReader::new(stderr).lines();
+        // Vertical overlays stay above the platform captions
+        let clip = MontageClip {
+            path: PathBuf::from("/clips/a.mp4"),
+            duration: 8.0,
+            streamer_name: "A".into(),
+            ..Default::default()
+        };
+        let filter = MontageExporter::new().build_overlay_filter(
+            &OverlayConfig::default(),
+            &clip,
+            MontageAspect::Portrait.overlay_margins(),
+        );
+        assert!(filter.contains(":x=60:y=h-th-260:"));
+    }
 
-        // Collect stderr lines for error reporting
-        let mut stderr_lines: Vec<String> = Vec::new();
+    #[test]
+    fn test_overlay_animation_exprs() {
+        let fade = OverlayAnimation::fade_alpha_expr(10.0, 0.5);
+        assert!(fade.starts_with("if(lt(t,0.50),t/0.50"));
+        assert!(fade.contains("gt(t,9.50)"));
 
-        // Read progress and collect output
-        while let Ok(Some(line)) = reader.next_line().await {
-            log::debug!("[FFmpeg] {}", line);
-            stderr_lines.push(line.clone());
+        let slide = OverlayPosition::TopRight.slide_in_x_expr(20, 0.5);
+        assert!(slide.ends_with("w-tw-20)"));
 
-            // Keep only last 50 lines to avoid memory issues
-            if stderr_lines.len() > 50 {
-                stderr_lines.remove(0);
-            }
+        // Free position, outline and shadow
+        let styled = OverlayConfig {
+            custom_position: Some(OverlayPoint { x: 50.0, y: 90.0 }),
+            outline: Some(OverlayOutline {
+                width: 3,
+                color: "000000".into(),
+            }),
+            shadow: Some(OverlayShadow {
+                x: 2,
+                y: 4,
+                color: "000000@0.6".into(),
+            }),
+            box_color: None,
+            ..Default::default()
+        };
+        let clip_a = MontageClip {
+            path: PathBuf::from("/clips/a.mp4"),
+            duration: 8.0,
+            streamer_name: "A".into(),
+            ..Default::default()
+        };
+        let filter = MontageExporter::new().build_overlay_filter(
+            &styled,
+            &clip_a,
+            MontageAspect::Landscape.overlay_margins(),
+        );
+        assert!(filter.contains(":x=(w-tw)*0.500:y=(h-th)*0.900:fontsize=32:"));
+        assert!(filter.ends_with(
+            ":borderw=3:bordercolor=#000000:shadowx=2:shadowy=4:shadowcolor=#000000@0.6"
+        ));
+        assert!(styled.validate().is_ok());
 
-            if let Some((percent, speed)) = parser.parse_line(&line) {
-                if let Some(cb) = progress {
-                    cb(percent, speed);
-                }
-            }
+        // Named colors pass through, anything else is refused
+        let mut named = styled.clone();
+        named.outline.as_mut().unwrap().color = "black".into();
+        named.shadow.as_mut().unwrap().color = "Black@0.5".into();
+        assert!(named.validate().is_ok());
+        let filter = MontageExporter::new().build_overlay_filter(
+            &named,
+            &clip_a,
+            MontageAspect::Landscape.overlay_margins(),
+        );
+        assert!(filter.ends_with(":bordercolor=black:shadowx=2:shadowy=4:shadowcolor=Black@0.5"));
+        for color in ["00000", "black:x=1", "000000@2", "000000@", "nope"] {
+            named.outline.as_mut().unwrap().color = color.into();
+            assert!(named.validate().is_err(), "{color}");
         }
+        let slide = OverlayPoint { x: 80.0, y: 10.0 }.slide_in_x_expr(0.5);
+        assert_eq!(
+            slide,
+            "if(lt(t,0.50),w-(w-(w-tw)*0.800)*t/0.50,(w-tw)*0.800)"
+        );
 
-        // Wait for process
-        let result = timeout(MONTAGE_TIMEOUT, child.wait()).await;
+        // Fade overlays take a full second to appear and to go
+        let overlay = OverlayConfig {
+            animation: OverlayAnimation::Fade,
+            ..Default::default()
+        };
+        let clip = MontageClip {
+            path: PathBuf::from("/clips/a.mp4"),
+            duration: 8.0,
+            streamer_name: "A".into(),
+            ..Default::default()
+        };
+        let filter = MontageExporter::new().build_overlay_filter(
+            &overlay,
+            &clip,
+            MontageAspect::Landscape.overlay_margins(),
+        );
+        assert!(filter
+            .ends_with(":alpha='if(lt(t,1.00),t/1.00,if(gt(t,7.00),max(0,(8.00-t)/1.00),1))'"));
+    }
 
-        match result {
-            Ok(Ok(status)) if status.success() => {
-                log::info!("[Montage] Export successful: {}", output_path.display());
-                Ok(())
-            }
-            Ok(Ok(status)) => {
-                // Get the last error lines for the message
-                let error_context = stderr_lines
-                    .iter()
-                    .filter(|l| l.contains("Error") || l.contains("error") || l.contains("Invalid"))
-                    .take(5)
-                    .cloned()
-                    .collect::<Vec<_>>()
-                    .join("\n");
-
-                let error_msg = if error_context.is_empty() {
-                    format!("FFmpeg exited with code: {}", status)
-                } else {
-                    format!("FFmpeg error: {}", error_context)
-                };
+    #[test]
+    fn test_overlay_font() {
+        let clip = MontageClip {
+            path: PathBuf::from("/clips/a.mp4"),
+            duration: 8.0,
+            streamer_name: "Nox".into(),
+            ..Default::default()
+        };
+        let exporter = MontageExporter::new();
 
-                log::error!("[Montage] FFmpeg failed: {}", error_msg);
-                log::error!("[Montage] Last output:\n{}", stderr_lines.join("\n"));
+        // Bundled Roboto by default
+        let filter = exporter.build_overlay_filter(
+            &OverlayConfig::default(),
+            &clip,
+            MontageAspect::Landscape.overlay_margins(),
+        );
+        assert!(filter.starts_with(&format!("drawtext=fontfile='{}':", ffmpeg_font_path())));
+        assert!(filter.contains("Roboto.ttf"));
 
-                Err(ExportError::Ffmpeg(error_msg))
-            }
-            Ok(Err(e)) => Err(ExportError::Ffmpeg(format!("FFmpeg error: {}", e))),
-            Err(_) => {
-                let _ = child.kill().await;
-                Err(ExportError::Timeout(format!(
-                    "Montage export timed out after {} seconds",
-                    MONTAGE_TIMEOUT.as_secs()
-                )))
-            }
-        }
+        // A chosen font file, with a Windows drive letter escaped
+        let overlay = OverlayConfig {
+            font: Some(PathBuf::from("C:\\Fonts\\Bebas Neue.ttf")),
+            ..Default::default()
+        };
+        let filter = exporter.build_overlay_filter(
+            &overlay,
+            &clip,
+            MontageAspect::Landscape.overlay_margins(),
+        );
+        assert!(filter.starts_with(
+            "drawtext=fontfile='C\\:/Fonts/Bebas Neue.ttf':text='Nox':x=20:y=h-th-20:fontsize=32:"
+        ));
+        assert!(!filter.contains("Roboto"));
     }
 
-    /// Simple export without progress callback
-    pub async fn export(&self, config: &MontageConfig, output_path: &Path) -> ExportResult<()> {
-        self.export_with_progress(config, output_path, None).await
+    #[test]
+    fn test_overlay_position_coords() {
+        assert!(OverlayPosition::TopLeft
+            .to_ffmpeg_coords(20, 20)
+            .contains("x=20"));
+        assert!(OverlayPosition::BottomRight
+            .to_ffmpeg_coords(20, 20)
+            .contains("w-tw-20"));
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn test_total_duration_no_transition() {
+    fn test_build_command_snapshot() {
         let config = MontageConfig {
             clips: vec![
                 MontageClip {
-                    path: PathBuf::new(),
+                    path: PathBuf::from("/clips/a.mp4"),
                     duration: 10.0,
                     streamer_name: "A".into(),
+                    ..Default::default()
                 },
                 MontageClip {
-                    path: PathBuf::new(),
+                    path: PathBuf::from("/clips/b.mp4"),
                     duration: 15.0,
                     streamer_name: "B".into(),
+                    ..Default::default()
                 },
             ],
+            transition_duration: 0.5,
+            transition: TransitionStyle::Fade,
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        let cmd = MontageExporter::new().build_command(
+            &config,
+            Path::new("/montages/out.mp4"),
+            None,
+            &FfmpegConfig::with_encoder(VideoEncoder::Libx264),
+        );
+        assert_eq!(
+            crate::export::argv(&cmd).join(" "),
+            concat!(
+                "-y -i /clips/a.mp4 -i /clips/b.mp4 -filter_complex ",
+                "[0:v]fade=t=out:st=9.50:d=0.50[v0];[0:a]afade=t=out:st=9.50:d=0.50[a0];",
+                "[1:v]fade=t=in:st=0:d=0.50[v1];[1:a]afade=t=in:st=0:d=0.50[a1];",
+                "[v0][a0][v1][a1]concat=n=2:v=1:a=1[vout][aout] ",
+                "-map [vout] -map [aout] -c:v libx264 -preset fast -crf 23 -pix_fmt yuv420p ",
+                "-c:a aac -b:a 128k -movflags +faststart -progress pipe:2 /montages/out.mp4"
+            )
+        );
+
+        // Hardware encoders get their own rate control and NV12 frames
+        let cmd = MontageExporter::new().build_command(
+            &config,
+            Path::new("/montages/out.mp4"),
+            None,
+            &FfmpegConfig::with_encoder(VideoEncoder::H264Nvenc),
+        );
+        assert!(crate::export::argv(&cmd)
+            .join(" ")
+            .contains("-c:v h264_nvenc -preset p4 -cq 23 -pix_fmt nv12 "));
+
+        // Overrides replace the app settings; a bitrate cap rules out stream copy
+        let mut config = config;
+        config.encoding = MontageEncoding {
+            crf: Some(18),
+            preset: Some("slow".to_string()),
+            audio_bitrate: Some("192k".to_string()),
+            max_bitrate_kbps: Some(8000),
+        };
+        assert!(config.encoding.validate().is_ok());
+        let ffmpeg = config
+            .encoding
+            .apply(&FfmpegConfig::with_encoder(VideoEncoder::Libx264));
+        let cmd = MontageExporter::new().build_command(
+            &config,
+            Path::new("/montages/out.mp4"),
+            None,
+            &ffmpeg,
+        );
+        assert!(crate::export::argv(&cmd).join(" ").contains(concat!(
+            "-c:v libx264 -preset slow -crf 18 -maxrate 8000k -bufsize 16000k ",
+            "-pix_fmt yuv420p -c:a aac -b:a 192k "
+        )));
+
+        // WebM switches to VP9 + Opus and drops the MP4-only faststart
+        config.container = MontageContainer::Webm;
+        let args = crate::export::argv(&MontageExporter::new().build_command(
+            &config,
+            Path::new("/montages/out.webm"),
+            None,
+            &ffmpeg,
+        ))
+        .join(" ");
+        assert!(args.contains(concat!(
+            "-c:v libvpx-vp9 -crf 28 -b:v 8000k -deadline good -cpu-used 4 -row-mt 1 ",
+            "-pix_fmt yuv420p -c:a libopus -b:a 192k -progress pipe:2 /montages/out.webm"
+        )));
+        assert_eq!(
+            MontageContainer::from_path(Path::new("/montages/a.WEBM")),
+            Some(MontageContainer::Webm)
+        );
+
+        for encoding in [
+            MontageEncoding {
+                crf: Some(52),
+                ..Default::default()
+            },
+            MontageEncoding {
+                preset: Some("turbo".to_string()),
+                ..Default::default()
+            },
+            MontageEncoding {
+                audio_bitrate: Some("lots".to_string()),
+                ..Default::default()
+            },
+        ] {
+            assert!(encoding.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_preview_command() {
+        let config = MontageConfig {
+            clips: vec![MontageClip {
+                path: PathBuf::from("/clips/a.mp4"),
+                duration: 10.0,
+                streamer_name: "A".into(),
+                ..Default::default()
+            }],
             transition_duration: 0.0,
-            overlay: None,
+            transition: TransitionStyle::None,
+            overlay: Some(OverlayConfig::default()),
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Staged,
+            container: MontageContainer::Mp4,
+            ..Default::default()
         };
-        assert_eq!(config.total_duration(), 25.0);
+        // Clips are scaled down first, and the overlay shrinks with the frame
+        let preview = config.preview();
+        assert_eq!(preview.render, RenderMode::Single);
+        let filter = MontageExporter::preview().build_filter_complex(&preview);
+        assert!(filter.starts_with(concat!(
+            "[0:v]scale=854:480:force_original_aspect_ratio=decrease,",
+            "pad=854:480:(ow-iw)/2:(oh-ih)/2,setsar=1,fps=30,drawtext="
+        )));
+        assert!(filter.contains(":x=9:y=h-th-9:fontsize=14:"));
+
+        let portrait = MontageConfig {
+            aspect: MontageAspect::Portrait,
+            ..config.clone()
+        };
+        let filter = MontageExporter::preview().build_filter_complex(&portrait.preview());
+        assert!(filter.contains("crop='min(iw,ih*9/16)':ih,scale=270:480,setsar=1,fps=30,"));
+        assert!(filter.contains(":x=15:y=h-th-65:fontsize=8:"));
+
+        // The export itself keeps the clips' frame
+        let filter = MontageExporter::new().build_filter_complex(&config);
+        assert!(!filter.contains("scale="));
+        assert!(filter.contains(":x=20:y=h-th-20:fontsize=32:"));
     }
 
     #[test]
-    fn test_total_duration_with_transition() {
+    fn test_audio_only_command() {
+        let clip = |path: &str| MontageClip {
+            path: PathBuf::from(path),
+            duration: 10.0,
+            streamer_name: "A".into(),
+            zoom: Some(2.0),
+            ..Default::default()
+        };
+        let config = MontageConfig {
+            clips: vec![
+                clip("/clips/a.mp4"),
+                clip("/clips/b.mp4"),
+                clip("/clips/c.mp4"),
+            ],
+            transition_duration: 0.5,
+            transition: TransitionStyle::Fade,
+            aspect: MontageAspect::Portrait,
+            fit: MontageFit::Crop,
+            render: RenderMode::Staged,
+            container: MontageContainer::Mp3,
+            ..Default::default()
+        }
+        .audio_only();
+        assert_eq!(config.render, RenderMode::Single);
+        assert_eq!(config.clips[0].zoom, None);
+
+        let args = crate::export::argv(&MontageExporter::new().build_audio_command(
+            &config,
+            Path::new("/montages/out.mp3"),
+            None,
+            &FfmpegConfig::with_encoder(VideoEncoder::Libx264),
+            "libmp3lame",
+        ))
+        .join(" ");
+        // Fades through black become crossfades; no video stream at all
+        assert_eq!(
+            args,
+            concat!(
+                "-y -i /clips/a.mp4 -i /clips/b.mp4 -i /clips/c.mp4 -filter_complex ",
+                "[0:a][1:a]acrossfade=d=0.50[xa1];[xa1][2:a]acrossfade=d=0.50[aout] ",
+                "-map [aout] -c:a libmp3lame -b:a 128k -progress pipe:2 /montages/out.mp3"
+            )
+        );
+        assert_eq!(config.total_duration(), 29.0);
+
+        // Hard cuts are a plain concat
+        let cut = MontageConfig {
+            transition: TransitionStyle::None,
+            ..config
+        };
+        assert_eq!(
+            MontageExporter::build_audio_filter_complex(&cut),
+            "[0:a][1:a][2:a]concat=n=3:v=0:a=1[aout]"
+        );
+        assert_eq!(
+            MontageContainer::from_path(Path::new("/montages/a.m4a")),
+            Some(MontageContainer::M4a)
+        );
+    }
+
+    #[test]
+    fn test_silent_clip() {
+        let clip = |path: &str, silent: bool| MontageClip {
+            path: PathBuf::from(path),
+            duration: 10.0,
+            streamer_name: "A".into(),
+            silent,
+            ..Default::default()
+        };
+        let mut config = MontageConfig {
+            clips: vec![clip("/clips/a.mp4", false), clip("/clips/b.mp4", true)],
+            transition_duration: 0.0,
+            transition: TransitionStyle::None,
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        config.clips[1].trim_start = Some(2.0);
+        // Silence as long as the file, trimmed like its audio would be
+        assert_eq!(
+            MontageExporter::new().build_filter_complex(&config),
+            concat!(
+                "anullsrc=r=48000:cl=stereo,atrim=end=10.000[sil1];",
+                "[0:v]null[v0];[0:a]anull[a0];",
+                "[1:v]trim=start=2.000,setpts=PTS-STARTPTS[v1];",
+                "[sil1]atrim=start=2.000,asetpts=PTS-STARTPTS[a1];",
+                "[v0][a0][v1][a1]concat=n=2:v=1:a=1[vout][aout]"
+            )
+        );
+
+        config.clips[1].trim_start = None;
+        assert!(!config.is_plain_concat());
+        config.transition = TransitionStyle::Dissolve;
+        config.transition_duration = 0.5;
+        assert!(MontageExporter::new()
+            .build_filter_complex(&config)
+            .ends_with("[0:a][sil1]acrossfade=d=0.50[aout]"));
+
+        // Staged renders already have audio
+        config.render = RenderMode::Prerendered;
+        assert!(!MontageExporter::new()
+            .build_filter_complex(&config)
+            .contains("anullsrc"));
+    }
+
+    #[test]
+    fn test_montage_timeout() {
+        // Short montages keep the floor, an hour at 4x gets four hours
+        assert_eq!(montage_timeout(60.0, 4.0), Some(MIN_MONTAGE_TIMEOUT));
+        assert_eq!(
+            montage_timeout(3600.0, 4.0),
+            Some(Duration::from_secs(14_400))
+        );
+        assert_eq!(montage_timeout(3600.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_chapters_metadata() {
         let config = MontageConfig {
             clips: vec![
                 MontageClip {
-                    path: PathBuf::new(),
+                    path: PathBuf::from("/clips/a.mp4"),
                     duration: 10.0,
                     streamer_name: "A".into(),
+                    action_name: "Ace; 1v5".into(),
+                    ..Default::default()
                 },
                 MontageClip {
-                    path: PathBuf::new(),
-                    duration: 15.0,
+                    path: PathBuf::from("/clips/b.mp4"),
+                    duration: 15.25,
                     streamer_name: "B".into(),
-                },
-                MontageClip {
-                    path: PathBuf::new(),
-                    duration: 20.0,
-                    streamer_name: "C".into(),
+                    ..Default::default()
                 },
             ],
             transition_duration: 0.5,
-            overlay: None,
+            transition: TransitionStyle::Fade,
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
         };
-        // 45 - 2*0.5 = 44
+        assert_eq!(
+            config.chapters_metadata(),
+            concat!(
+                ";FFMETADATA1\n",
+                "[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=10000\ntitle=A – Ace\\; 1v5\n",
+                "[CHAPTER]\nTIMEBASE=1/1000\nSTART=10000\nEND=25250\ntitle=B\n"
+            )
+        );
+
+        let args = crate::export::argv(&MontageExporter::new().build_command(
+            &config,
+            Path::new("/montages/out.mp4"),
+            Some(Path::new("/tmp/chapters.txt")),
+            &FfmpegConfig::with_encoder(VideoEncoder::Libx264),
+        ))
+        .join(" ");
+        assert!(args.starts_with(
+            "-y -i /clips/a.mp4 -i /clips/b.mp4 -f ffmetadata -i /tmp/chapters.txt -filter_complex "
+        ));
+        assert!(args.contains("-map [vout] -map [aout] -map_chapters 2 -c:v libx264"));
+    }
+
+    #[test]
+    fn test_bookend_clips() {
+        let clip = |streamer: &str, action: &str| MontageClip {
+            path: PathBuf::from("/clips/x.mp4"),
+            duration: 5.0,
+            streamer_name: streamer.into(),
+            action_name: action.into(),
+            generated: streamer.is_empty(),
+            ..Default::default()
+        };
+        let config = MontageConfig {
+            clips: vec![clip("", "Intro"), clip("A", "Ace")],
+            transition_duration: 0.0,
+            transition: TransitionStyle::None,
+            overlay: Some(OverlayConfig {
+                text: "{streamer}".into(),
+                position: OverlayPosition::TopLeft,
+                font_size: 48,
+                color: "FFFFFF".into(),
+                box_color: None,
+                font: None,
+                animation: OverlayAnimation::None,
+                custom_position: None,
+                outline: None,
+                shadow: None,
+            }),
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+
+        // Only the streamer clip gets the overlay
+        let filter = MontageExporter::new().build_filter_complex(&config);
+        assert!(filter.starts_with("[0:v]null[v0]"));
+        assert_eq!(filter.matches("drawtext").count(), 1);
+        assert!(config.chapters_metadata().contains("title=Intro\n"));
+
+        let mut card = TitleCard {
+            text: "GRAND FINAL: Day 2".into(),
+            duration: 3.0,
+            background_color: None,
+            background_image: None,
+        };
+        let exporter = MontageExporter::new();
+        let args = crate::export::argv(&exporter.build_card_command(
+            &card,
+            &NormalizeFormat::new(1920, 1080, 60),
+            Path::new("/tmp/intro.mp4"),
+        ))
+        .join(" ");
+        assert!(args.starts_with(concat!(
+            "-y -f lavfi -i color=c=black:s=1920x1080:r=60:d=3.000 ",
+            "-f lavfi -i anullsrc=r=48000:cl=stereo -vf drawtext="
+        )));
+        assert!(args.contains(":expansion=none:text=GRAND FINAL\\\\: Day 2:fontsize=96:"));
+        assert!(args.ends_with("-c:a aac -b:a 128k /tmp/intro.mp4"));
+
+        card.background_color = Some("1A1A2E".into());
+        assert!(card.validate().is_ok());
+        let args = crate::export::argv(&exporter.build_card_command(
+            &card,
+            &NormalizeFormat::new(1920, 1080, 60),
+            Path::new("/tmp/intro.mp4"),
+        ))
+        .join(" ");
+        assert!(args.starts_with("-y -f lavfi -i color=c=0x1A1A2E:s=1920x1080"));
+
+        card.background_image = Some(PathBuf::from("/art/bg.png"));
+        let args = crate::export::argv(&exporter.build_card_command(
+            &card,
+            &NormalizeFormat::new(1920, 1080, 60),
+            Path::new("/tmp/intro.mp4"),
+        ))
+        .join(" ");
+        assert!(args.starts_with(concat!(
+            "-y -loop 1 -framerate 60 -t 3.000 -i /art/bg.png -f lavfi -i anullsrc=r=48000:cl=stereo ",
+            "-vf scale=1920:1080:force_original_aspect_ratio=increase,crop=1920:1080,setsar=1,drawtext="
+        )));
+
+        card.background_color = Some("red;x".into());
+        assert!(card.validate().is_err());
+    }
+
+    #[test]
+    fn test_escape_drawtext() {
+        // Escaped once for the option parser, then again for the filtergraph
+        assert_eq!(escape_drawtext("Round 2"), "Round 2");
+        assert_eq!(escape_drawtext("It's 100%"), r"It\\\'s 100%");
+        assert_eq!(escape_drawtext(r"a:b\c"), r"a\\:b\\\\c");
+        assert_eq!(escape_drawtext("[x], y; z"), r"\[x\]\, y\; z");
+    }
+
+    #[test]
+    fn test_credits_card() {
+        let clip = |streamer: &str| MontageClip {
+            path: PathBuf::from("/clips/x.mp4"),
+            duration: 5.0,
+            streamer_name: streamer.into(),
+            ..Default::default()
+        };
+        let credits = CreditsCard {
+            title: "Finals".into(),
+            duration: 5.0,
+            background_color: None,
+        };
+        // Title cards are left out; each streamer is named once
+        let section = generated_clip(PathBuf::from("/cards/s.mp4"), 2.0, "Round 2");
+        let card = credits.card(&[clip("Bravo"), section, clip("Alpha"), clip("Bravo")]);
+        assert_eq!(card.text, "Finals\n\nBravo\nAlpha");
+
+        // One drawtext per line, stacked around the middle of the frame
+        let filter = card_text_filter(&card.text, 1920, 1080);
+        assert_eq!(filter.matches("drawtext").count(), 3);
+        assert!(filter.contains(":text=Finals:fontsize=96:fontcolor=white:x=(w-tw)/2:y=290"));
+        assert!(filter.ends_with(":text=Alpha:fontsize=96:fontcolor=white:x=(w-tw)/2:y=665"));
+
+        // Long credits shrink to fit
+        let many: Vec<MontageClip> = (0..20).map(|i| clip(&format!("S{i}"))).collect();
+        let filter = card_text_filter(&credits.card(&many).text, 1920, 1080);
+        assert!(filter.contains(":fontsize=30:"));
+
+        let bookend: Bookend =
+            serde_json::from_str(r#"{"type":"credits","duration":4.0}"#).unwrap();
+        assert!(matches!(bookend, Bookend::Credits(CreditsCard { title, .. }) if title.is_empty()));
+    }
+
+    #[test]
+    fn test_clip_overlay_overrides() {
+        let clip = |overlay: Option<ClipOverlay>| MontageClip {
+            path: PathBuf::from("/clips/x.mp4"),
+            duration: 5.0,
+            streamer_name: "A".into(),
+            action_name: "Ace".into(),
+            overlay,
+            ..Default::default()
+        };
+        let mut config = MontageConfig {
+            clips: vec![
+                clip(None),
+                clip(Some(ClipOverlay {
+                    enabled: Some(false),
+                    ..Default::default()
+                })),
+                clip(Some(ClipOverlay {
+                    text: Some("{streamer} – {action} (13-11)".into()),
+                    position: Some(OverlayPosition::TopRight),
+                    ..Default::default()
+                })),
+            ],
+            transition_duration: 0.0,
+            transition: TransitionStyle::None,
+            overlay: Some(OverlayConfig {
+                text: "{streamer}".into(),
+                position: OverlayPosition::TopLeft,
+                font_size: 48,
+                color: "FFFFFF".into(),
+                box_color: None,
+                font: None,
+                animation: OverlayAnimation::None,
+                custom_position: None,
+                outline: None,
+                shadow: None,
+            }),
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+
+        assert_eq!(config.clip_overlay(0).unwrap().text, "{streamer}");
+        assert!(config.clip_overlay(1).is_none());
+        let own = config.clip_overlay(2).unwrap();
+        assert!(matches!(own.position, OverlayPosition::TopRight));
+        assert_eq!(own.font_size, 48);
+
+        let filter = MontageExporter::new().build_filter_complex(&config);
+        assert!(filter.contains(":text='A – Ace (13-11)':"));
+
+        // Without a montage overlay only clips with their own text get one
+        config.overlay = None;
+        assert!(config.clip_overlay(0).is_none());
+        assert_eq!(config.clip_overlay(2).unwrap().font_size, 32);
+    }
+
+    #[test]
+    fn test_overlay_counters() {
+        let clip = |streamer: &str, action: &str| MontageClip {
+            path: PathBuf::from("/clips/x.mp4"),
+            duration: 8.0,
+            streamer_name: streamer.into(),
+            action_name: action.into(),
+            generated: streamer.is_empty(),
+            ..Default::default()
+        };
+        let mut config = MontageConfig {
+            clips: vec![
+                clip("", "Intro"),
+                clip("A", "Kill"),
+                clip("B", "Ace"),
+                clip("A", "Kill"),
+            ],
+            transition_duration: 0.0,
+            transition: TransitionStyle::None,
+            overlay: Some(OverlayConfig {
+                text: "Highlight {index}/{total} – kills {count}".into(),
+                ..Default::default()
+            }),
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        // The kill happens 4s into the file, 1s of which is trimmed
+        config.clips[1].action_at = Some(4.0);
+        config.clips[1].trim_start = Some(1.0);
+
+        assert!(config.clip_overlay(0).is_none());
+        assert_eq!(
+            config.clip_overlay(1).unwrap().text,
+            "Highlight 1/3 – kills %{eif:0+gte(t,3.000):d}"
+        );
+        assert_eq!(
+            config.clip_overlay(2).unwrap().text,
+            "Highlight 2/3 – kills 1"
+        );
+        assert_eq!(
+            config.clip_overlay(3).unwrap().text,
+            "Highlight 3/3 – kills 2"
+        );
+        assert!(MontageExporter::new()
+            .build_filter_complex(&config)
+            .contains(":text='Highlight 1/3 – kills %{eif\\:0+gte(t,3.000)\\:d}':"));
+
+        // Staged clips are rendered alone but keep their place in the count
+        config.render = RenderMode::Staged;
+        assert_eq!(
+            config.stage(3).clip_overlay(0).unwrap().text,
+            "Highlight 3/3 – kills 2"
+        );
+        assert!(config.stage(0).clip_overlay(0).is_none());
+        config.render = RenderMode::Single;
+
+        // Slowed down before the action, it happens later in the clip
+        config.clips[1].slowmo = Some(SlowMotion {
+            start: 1.0,
+            end: 2.0,
+            factor: 2.0,
+        });
+        assert_eq!(config.clips[1].action_time(), Some(4.0));
+        // Trimmed away: the counter is simply the count
+        config.clips[1].trim_end = Some(3.0);
+        assert_eq!(config.clips[1].action_time(), None);
+    }
+
+    #[test]
+    fn test_insert_sections() {
+        let clip = |title: &str| generated_clip(PathBuf::from("/x.mp4"), 1.0, title);
+        let mut clips = vec![clip("a"), clip("b"), clip("c")];
+        insert_sections(
+            &mut clips,
+            vec![
+                (3, clip("end")),
+                (1, clip("round 2")),
+                (0, clip("round 1")),
+                (1, clip("ace")),
+            ],
+        );
+        let titles: Vec<_> = clips.iter().map(|c| c.action_name.as_str()).collect();
+        assert_eq!(titles, ["round 1", "a", "round 2", "ace", "b", "c", "end"]);
+    }
+
+    #[test]
+    fn test_music_ducking() {
+        let config = MontageConfig {
+            clips: vec![MontageClip {
+                path: PathBuf::from("/clips/a.mp4"),
+                duration: 12.0,
+                streamer_name: "A".into(),
+                ..Default::default()
+            }],
+            transition_duration: 0.0,
+            transition: TransitionStyle::Fade,
+            music: Some(MusicTrack {
+                path: PathBuf::from("/music/bed.mp3"),
+                volume: 0.3,
+                snap_to_beats: false,
+            }),
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        let args = crate::export::argv(&MontageExporter::new().build_command(
+            &config,
+            Path::new("/montages/out.mp4"),
+            Some(Path::new("/tmp/chapters.txt")),
+            &FfmpegConfig::with_encoder(VideoEncoder::Libx264),
+        ))
+        .join(" ");
+        assert!(args.contains(
+            "-i /clips/a.mp4 -stream_loop -1 -i /music/bed.mp3 -f ffmetadata -i /tmp/chapters.txt"
+        ));
+        assert!(args.contains(concat!(
+            "[aout]asplit=2[aclip][akey];",
+            "[1:a]volume=0.30,atrim=end=12.000,asetpts=PTS-STARTPTS,",
+            "afade=t=out:st=11.000:d=1.000[bgm];",
+            "[bgm][akey]sidechaincompress=threshold=0.05:ratio=8:attack=20:release=400[ducked];",
+            "[aclip][ducked]amix=inputs=2:duration=first:normalize=0[amix]"
+        )));
+        assert!(args.contains("-map [vout] -map [amix] -map_chapters 2 "));
+    }
+
+    #[test]
+    fn test_image_overlay() {
+        let config = MontageConfig {
+            clips: vec![MontageClip {
+                path: PathBuf::from("/clips/a.mp4"),
+                duration: 12.0,
+                streamer_name: "A".into(),
+                ..Default::default()
+            }],
+            transition_duration: 0.0,
+            transition: TransitionStyle::Fade,
+            image_overlay: Some(ImageOverlay {
+                path: PathBuf::from("/logos/team.png"),
+                position: OverlayPosition::TopRight,
+                custom_position: None,
+                scale: 0.5,
+                opacity: 0.8,
+            }),
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        let args = crate::export::argv(&MontageExporter::new().build_command(
+            &config,
+            Path::new("/montages/out.mp4"),
+            Some(Path::new("/tmp/chapters.txt")),
+            &FfmpegConfig::with_encoder(VideoEncoder::Libx264),
+        ))
+        .join(" ");
+        assert!(args.contains("-i /clips/a.mp4 -i /logos/team.png -f ffmetadata"));
+        assert!(args.contains(concat!(
+            "[1:v]scale=iw*0.500:-1,format=rgba,colorchannelmixer=aa=0.80[logo];",
+            "[vout][logo]overlay=x=W-w-20:y=20[vmark] "
+        )));
+        assert!(args.contains("-map [vmark] -map [aout] -map_chapters 2 "));
+
+        let free = ImageOverlay {
+            custom_position: Some(OverlayPoint { x: 0.0, y: 100.0 }),
+            scale: 100.0,
+            ..config.image_overlay.unwrap()
+        };
+        assert!(free
+            .filter(3, MontageAspect::Landscape.overlay_margins())
+            .starts_with("[3:v]scale=iw*4.000:-1,"));
+        assert_eq!(
+            free.coords(MontageAspect::Landscape.overlay_margins()),
+            "x=(W-w)*0.000:y=(H-h)*1.000"
+        );
+    }
+
+    #[test]
+    fn test_xfade_filter_complex() {
+        let clip = |name: &str, duration: f64| MontageClip {
+            path: PathBuf::from(format!("/clips/{name}.mp4")),
+            duration,
+            streamer_name: name.into(),
+            ..Default::default()
+        };
+        let config = MontageConfig {
+            clips: vec![clip("a", 10.0), clip("b", 15.0), clip("c", 20.0)],
+            transition_duration: 0.5,
+            transition: TransitionStyle::Wipe,
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        assert_eq!(
+            MontageExporter::new().build_filter_complex(&config),
+            concat!(
+                "[0:v]fps=60,settb=AVTB[v0];[1:v]fps=60,settb=AVTB[v1];[2:v]fps=60,settb=AVTB[v2];",
+                "[v0][v1]xfade=transition=wipeleft:duration=0.50:offset=9.500[xv1];",
+                "[0:a][1:a]acrossfade=d=0.50[xa1];",
+                "[xv1][v2]xfade=transition=wipeleft:duration=0.50:offset=24.000[vout];",
+                "[xa1][2:a]acrossfade=d=0.50[aout]"
+            )
+        );
+        assert_eq!(config.clip_offsets(), vec![0.0, 9.5, 24.0]);
         assert_eq!(config.total_duration(), 44.0);
+
+        let cut = MontageConfig {
+            transition: TransitionStyle::None,
+            ..config
+        };
+        assert!(!MontageExporter::new()
+            .build_filter_complex(&cut)
+            .contains("fade"));
+        assert_eq!(cut.total_duration(), 45.0);
     }
 
     #[test]
-    fn test_overlay_position_coords() {
-        assert!(OverlayPosition::TopLeft
-            .to_ffmpeg_coords(20)
-            .contains("x=20"));
-        assert!(OverlayPosition::BottomRight
-            .to_ffmpeg_coords(20)
-            .contains("w-tw-20"));
+    fn test_stream_copy_eligibility() {
+        let clip = |name: &str| MontageClip {
+            path: PathBuf::from(format!("/clips/{name}.mp4")),
+            duration: 10.0,
+            streamer_name: name.into(),
+            ..Default::default()
+        };
+        let config = MontageConfig {
+            clips: vec![clip("a"), clip("b")],
+            transition_duration: 0.0,
+            transition: TransitionStyle::Fade,
+            aspect: MontageAspect::Landscape,
+            fit: MontageFit::Crop,
+            render: RenderMode::Single,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        assert!(config.is_plain_concat());
+
+        // A single clip has no fade to draw
+        let single = MontageConfig {
+            clips: vec![clip("a")],
+            transition_duration: 0.5,
+            ..config.clone()
+        };
+        assert!(single.is_plain_concat());
+
+        let faded = MontageConfig {
+            transition_duration: 0.5,
+            ..config.clone()
+        };
+        assert!(!faded.is_plain_concat());
+        let overlaid = MontageConfig {
+            overlay: Some(OverlayConfig::default()),
+            ..config.clone()
+        };
+        assert!(!overlaid.is_plain_concat());
+        let portrait = MontageConfig {
+            aspect: MontageAspect::Portrait,
+            ..config.clone()
+        };
+        assert!(!portrait.is_plain_concat());
+        let capped = MontageConfig {
+            encoding: MontageEncoding {
+                max_bitrate_kbps: Some(8000),
+                ..Default::default()
+            },
+            ..config.clone()
+        };
+        assert!(!capped.is_plain_concat());
+        let normalized = MontageConfig {
+            loudness_lufs: Some(-14.0),
+            render: RenderMode::Single,
+            ..config
+        };
+        assert!(!normalized.is_plain_concat());
+    }
+
+    #[test]
+    fn test_stream_copy_command() {
+        let cmd = MontageExporter::new().build_stream_copy_command(
+            Path::new("/tmp/concat.txt"),
+            Path::new("/montages/out.mp4"),
+            Some(Path::new("/tmp/chapters.txt")),
+            MontageContainer::Mp4,
+        );
+        assert_eq!(
+            crate::export::argv(&cmd).join(" "),
+            concat!(
+                "-y -f concat -safe 0 -i /tmp/concat.txt -f ffmetadata -i /tmp/chapters.txt ",
+                "-map 0:v:0 -map 0:a:0 -map_chapters 1 -c copy -movflags +faststart ",
+                "-progress pipe:2 /montages/out.mp4"
+            )
+        );
+    }
+
+    #[test]
+    fn test_staged_render() {
+        let clip = |name: &str| MontageClip {
+            path: PathBuf::from(format!("/clips/{name}.mp4")),
+            duration: 10.0,
+            streamer_name: name.into(),
+            ..Default::default()
+        };
+        let config = MontageConfig {
+            clips: vec![clip("a"), clip("b")],
+            transition_duration: 0.5,
+            transition: TransitionStyle::Fade,
+            overlay: Some(OverlayConfig::default()),
+            aspect: MontageAspect::Portrait,
+            fit: MontageFit::Crop,
+            loudness_lufs: Some(-14.0),
+            render: RenderMode::Staged,
+            container: MontageContainer::Mp4,
+            ..Default::default()
+        };
+        let exporter = MontageExporter::new();
+
+        // First pass: the clip's own processing, no transitions
+        let stage = config.stage(1);
+        assert_eq!(stage.clips.len(), 1);
+        let filter = exporter.build_filter_complex(&stage);
+        assert!(filter.starts_with("[0:v]crop="));
+        assert!(filter.contains("drawtext="));
+        assert!(filter.contains("[0:a]loudnorm=I=-14"));
+        assert!(!filter.contains("fade="));
+        assert_ne!(stage_key(&stage), stage_key(&config.stage(0)));
+
+        // Second pass only adds the transitions
+        let joined = MontageConfig {
+            render: RenderMode::Prerendered,
+            container: MontageContainer::Mp4,
+            ..config.clone()
+        };
+        let filter = exporter.build_filter_complex(&joined);
+        assert!(filter.contains("fade=t=out:st=9.50:d=0.50"));
+        assert!(!filter.contains("crop="));
+        assert!(!filter.contains("drawtext="));
+        assert!(!filter.contains("loudnorm"));
+
+        let cut = MontageConfig {
+            transition: TransitionStyle::None,
+            ..joined
+        };
+        assert!(cut.is_plain_concat());
+        assert!(!MontageConfig {
+            render: RenderMode::Staged,
+            container: MontageContainer::Mp4,
+            ..cut
+        }
+        .is_plain_concat());
+    }
+
+    #[test]
+    fn test_stage_files() {
+        let card = TitleCard {
+            text: "Round 13".into(),
+            duration: 2.0,
+            background_color: None,
+            background_image: None,
+        };
+        let name = generated_name("Section", &card);
+        assert!(name.starts_with("section_") && name.ends_with(".mp4"));
+        assert_eq!(name, generated_name("Section", &card.clone()));
+        let other = TitleCard {
+            text: "Round 14".into(),
+            ..card
+        };
+        assert_ne!(name, generated_name("Section", &other));
+
+        let dir = std::env::temp_dir().join("nox-stage-prune-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let stale = dir.join("old.mp4");
+        let fresh = dir.join("new.mp4");
+        std::fs::write(&stale, b"old").unwrap();
+        std::fs::write(&fresh, b"new").unwrap();
+        let week_ago = std::time::SystemTime::now() - Duration::from_secs(8 * 24 * 3600);
+        std::fs::File::options()
+            .write(true)
+            .open(&stale)
+            .unwrap()
+            .set_modified(week_ago)
+            .unwrap();
+
+        prune_stages(&dir, STAGE_MAX_AGE);
+        assert!(!stale.exists());
+        assert!(fresh.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
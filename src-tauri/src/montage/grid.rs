@@ -0,0 +1,414 @@
+//! Multi-POV grid montages.
+//!
+//! Synchronized clips of one action (one per streamer, same time window) are
+//! scaled into 960x540 cells, labelled with their streamer and stacked on a
+//! 1080p canvas, side by side or 2x2. In picture-in-picture the second clip
+//! is inset in a corner of the first instead. The output ends with the
+//! shortest clip.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use super::cancel::CancelScope;
+use super::concat::{
+    ffmpeg_font_path, MontageClip, MontageExporter, OverlayPosition, ProgressCallback,
+};
+use crate::error::{ExportError, ExportResult};
+
+/// One cell: a quarter of the canvas
+const CELL_WIDTH: u32 = 960;
+const CELL_HEIGHT: u32 = 540;
+
+const CANVAS_WIDTH: u32 = 1920;
+const CANVAS_HEIGHT: u32 = 1080;
+
+/// Cells are resampled to a common rate so the stack filters can line them up
+const GRID_FPS: u32 = 60;
+
+/// Streamer label size (pixels)
+const LABEL_FONT_SIZE: u32 = 28;
+
+/// Accepted inset widths, as a fraction of the canvas width
+const PIP_SIZE_RANGE: std::ops::RangeInclusive<f64> = 0.15..=0.5;
+
+/// Gap between the inset and the frame edge (pixels)
+const PIP_MARGIN: u32 = 32;
+
+/// How the cells are arranged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GridLayout {
+    /// Two clips next to each other, letterboxed
+    #[default]
+    SideBySide,
+    /// Up to four clips, two per row
+    #[serde(rename = "grid_2x2")]
+    Grid2x2,
+    /// Second clip inset in a corner of the first
+    PictureInPicture,
+}
+
+/// Look of the inset clip in picture-in-picture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipStyle {
+    pub position: OverlayPosition,
+    /// Inset width as a fraction of the frame width
+    pub size: f64,
+    /// Border around the inset (pixels, 0 = none)
+    pub border: u32,
+    /// Hex border color (e.g., "FFFFFF")
+    pub border_color: String,
+}
+
+impl Default for PipStyle {
+    fn default() -> Self {
+        Self {
+            position: OverlayPosition::BottomRight,
+            size: 0.3,
+            border: 4,
+            border_color: "FFFFFF".to_string(),
+        }
+    }
+}
+
+impl PipStyle {
+    /// Inset width in pixels (even, for yuv420p)
+    fn width(&self) -> u32 {
+        let size = self
+            .size
+            .clamp(*PIP_SIZE_RANGE.start(), *PIP_SIZE_RANGE.end());
+        (CANVAS_WIDTH as f64 * size / 2.0).round() as u32 * 2
+    }
+}
+
+impl GridLayout {
+    /// Most clips the layout has room for
+    pub fn max_clips(self) -> usize {
+        match self {
+            Self::SideBySide | Self::PictureInPicture => 2,
+            Self::Grid2x2 => 4,
+        }
+    }
+
+    /// Stack the `n` cells `[c0]..` into `[grid]`
+    fn stack_filter(self, n: usize) -> String {
+        let cells: String = (0..n).map(|i| format!("[c{i}]")).collect();
+        match self {
+            Self::SideBySide | Self::PictureInPicture => {
+                format!("{cells}hstack=inputs={n}:shortest=1[grid]")
+            }
+            Self::Grid2x2 => {
+                // A lone cell on the second row is centered
+                let positions: &[&str] = match n {
+                    3 => &["0_0", "960_0", "480_540"],
+                    _ => &["0_0", "960_0", "0_540", "960_540"],
+                };
+                format!(
+                    "{cells}xstack=inputs={n}:layout={}:fill=black:shortest=1[grid]",
+                    positions[..n].join("|")
+                )
+            }
+        }
+    }
+}
+
+/// Clips to compose into one grid video
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridConfig {
+    /// Synchronized clips, one cell each, in reading order
+    pub clips: Vec<MontageClip>,
+    pub layout: GridLayout,
+    /// Clip whose audio is kept (None = mix all of them)
+    #[serde(default)]
+    pub audio_from: Option<usize>,
+    /// Inset look (picture-in-picture only)
+    #[serde(default)]
+    pub pip: PipStyle,
+}
+
+impl GridConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        let max = self.layout.max_clips();
+        if !(2..=max).contains(&self.clips.len()) {
+            return Err(format!(
+                "This layout takes 2 to {} clips (got {})",
+                max,
+                self.clips.len()
+            ));
+        }
+        if self.audio_from.is_some_and(|i| i >= self.clips.len()) {
+            return Err("Audio source is not one of the grid clips".to_string());
+        }
+        Ok(())
+    }
+
+    /// Output length: the grid stops with its shortest clip
+    pub fn duration(&self) -> f64 {
+        self.clips
+            .iter()
+            .map(|c| c.duration)
+            .reduce(f64::min)
+            .unwrap_or(0.0)
+    }
+}
+
+impl MontageExporter {
+    fn build_grid_filter(config: &GridConfig) -> String {
+        let n = config.clips.len();
+        let mut parts = match config.layout {
+            GridLayout::PictureInPicture => Self::pip_video_filter(&config.pip),
+            layout => Self::stack_video_filter(config, layout),
+        };
+
+        if config.audio_from.is_none() {
+            let inputs: String = (0..n).map(|i| format!("[{i}:a]")).collect();
+            parts.push(format!("{inputs}amix=inputs={n}:duration=shortest[aout]"));
+        }
+
+        parts.join(";")
+    }
+
+    /// Full-frame first clip with the second one inset, ending in `[vout]`
+    fn pip_video_filter(pip: &PipStyle) -> Vec<String> {
+        let mut inset = format!(
+            "[1:v]scale={}:-2,setsar=1,fps={GRID_FPS},format=yuv420p",
+            pip.width()
+        );
+        if pip.border > 0 {
+            inset.push_str(&format!(
+                ",pad=iw+{b2}:ih+{b2}:{b}:{b}:color=#{}",
+                pip.border_color,
+                b = pip.border,
+                b2 = pip.border * 2
+            ));
+        }
+        inset.push_str("[inset]");
+
+        vec![
+            format!(
+                "[0:v]scale={CANVAS_WIDTH}:{CANVAS_HEIGHT}:force_original_aspect_ratio=decrease,\
+                 pad={CANVAS_WIDTH}:{CANVAS_HEIGHT}:(ow-iw)/2:(oh-ih)/2,setsar=1,fps={GRID_FPS},\
+                 format=yuv420p[main]"
+            ),
+            inset,
+            format!(
+                "[main][inset]overlay={}:shortest=1[vout]",
+                pip.position.overlay_filter_coords(PIP_MARGIN, PIP_MARGIN)
+            ),
+        ]
+    }
+
+    /// Labelled cells stacked on the canvas, ending in `[vout]`
+    fn stack_video_filter(config: &GridConfig, layout: GridLayout) -> Vec<String> {
+        let n = config.clips.len();
+        let font = ffmpeg_font_path();
+        let mut parts: Vec<String> = config
+            .clips
+            .iter()
+            .enumerate()
+            .map(|(i, clip)| {
+                let label = clip.streamer_name.replace(':', "\\:").replace('\'', "\\'");
+                format!(
+                    "[{i}:v]scale={CELL_WIDTH}:{CELL_HEIGHT}:force_original_aspect_ratio=decrease,\
+                     pad={CELL_WIDTH}:{CELL_HEIGHT}:(ow-iw)/2:(oh-ih)/2,setsar=1,fps={GRID_FPS},\
+                     format=yuv420p,drawtext=fontfile='{font}':text='{label}':x=16:y=h-th-16:\
+                     fontsize={LABEL_FONT_SIZE}:fontcolor=white:box=1:boxcolor=black@0.5:\
+                     boxborderw=8[c{i}]"
+                )
+            })
+            .collect();
+
+        parts.push(layout.stack_filter(n));
+        parts.push(format!(
+            "[grid]pad={CANVAS_WIDTH}:{CANVAS_HEIGHT}:(ow-iw)/2:(oh-ih)/2[vout]"
+        ));
+        parts
+    }
+
+    fn build_grid_command(&self, config: &GridConfig, output_path: &Path) -> Command {
+        let mut cmd = Command::new(self.ffmpeg_path());
+        cmd.arg("-y");
+
+        for clip in &config.clips {
+            cmd.args(["-i", clip.path.to_string_lossy().as_ref()]);
+        }
+
+        cmd.args(["-filter_complex", &Self::build_grid_filter(config)]);
+
+        let audio_out = match config.audio_from {
+            Some(i) => format!("{i}:a"),
+            None => "[aout]".to_string(),
+        };
+        cmd.args(["-map", "[vout]", "-map", &audio_out, "-shortest"]);
+
+        cmd.args([
+            "-c:v", "libx264", "-preset", "fast", "-crf", "23", "-pix_fmt", "yuv420p",
+        ]);
+        cmd.args(["-c:a", "aac", "-b:a", "128k"]);
+        cmd.args(["-movflags", "+faststart", "-progress", "pipe:2"]);
+        cmd.arg(output_path);
+
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stderr(std::process::Stdio::piped());
+        cmd.stdout(std::process::Stdio::null());
+        #[cfg(target_os = "windows")]
+        cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+        cmd
+    }
+
+    /// Compose synchronized clips into one grid video
+    pub async fn export_grid(
+        &self,
+        config: &GridConfig,
+        output_path: &Path,
+        progress: Option<&ProgressCallback>,
+    ) -> ExportResult<()> {
+        config.validate().map_err(ExportError::Ffmpeg)?;
+        let _scope = CancelScope::enter(self.cancel_token());
+        for clip in &config.clips {
+            if !clip.path.exists() {
+                return Err(ExportError::Ffmpeg(format!(
+                    "Clip file not found: {}",
+                    clip.path.display()
+                )));
+            }
+        }
+
+        log::info!(
+            "[Montage] Grid of {} clips ({:?}), duration: {:.2}s",
+            config.clips.len(),
+            config.layout,
+            config.duration()
+        );
+
+        let cmd = self.build_grid_command(config, output_path);
+        self.run(
+            cmd,
+            output_path,
+            config.duration(),
+            progress.map(|cb| cb.as_ref()),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn clip(name: &str, duration: f64) -> MontageClip {
+        MontageClip {
+            path: PathBuf::from(format!("/clips/{name}.mp4")),
+            duration,
+            streamer_name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_grid_command() {
+        let config = GridConfig {
+            clips: vec![clip("a", 10.0), clip("b", 9.5), clip("c", 10.0)],
+            layout: GridLayout::Grid2x2,
+            audio_from: None,
+            pip: PipStyle::default(),
+        };
+        assert_eq!(config.duration(), 9.5);
+
+        let args = crate::export::argv(
+            &MontageExporter::new().build_grid_command(&config, Path::new("/montages/g.mp4")),
+        )
+        .join(" ");
+        assert!(args.starts_with("-y -i /clips/a.mp4 -i /clips/b.mp4 -i /clips/c.mp4 "));
+        assert!(args.contains(
+            "[2:v]scale=960:540:force_original_aspect_ratio=decrease,pad=960:540:(ow-iw)/2:(oh-ih)/2"
+        ));
+        assert!(args.contains(":text='c':x=16:y=h-th-16:"));
+        assert!(args.contains(concat!(
+            "[c0][c1][c2]xstack=inputs=3:layout=0_0|960_0|480_540:fill=black:shortest=1[grid];",
+            "[grid]pad=1920:1080:(ow-iw)/2:(oh-ih)/2[vout];",
+            "[0:a][1:a][2:a]amix=inputs=3:duration=shortest[aout] ",
+            "-map [vout] -map [aout] -shortest "
+        )));
+
+        let side = GridConfig {
+            clips: vec![clip("a", 10.0), clip("b", 10.0)],
+            layout: GridLayout::SideBySide,
+            audio_from: Some(1),
+            pip: PipStyle::default(),
+        };
+        let args = crate::export::argv(
+            &MontageExporter::new().build_grid_command(&side, Path::new("/montages/g.mp4")),
+        )
+        .join(" ");
+        assert!(args.contains("[c0][c1]hstack=inputs=2:shortest=1[grid]"));
+        assert!(args.contains("-map [vout] -map 1:a -shortest "));
+        assert!(!args.contains("amix"));
+    }
+
+    #[test]
+    fn test_pip_command() {
+        let config = GridConfig {
+            clips: vec![clip("a", 10.0), clip("b", 10.0)],
+            layout: GridLayout::PictureInPicture,
+            audio_from: Some(0),
+            pip: PipStyle {
+                position: OverlayPosition::TopLeft,
+                size: 0.25,
+                border: 6,
+                border_color: "FF0000".into(),
+            },
+        };
+        let args = crate::export::argv(
+            &MontageExporter::new().build_grid_command(&config, Path::new("/montages/p.mp4")),
+        )
+        .join(" ");
+        assert!(args.contains(concat!(
+            "[0:v]scale=1920:1080:force_original_aspect_ratio=decrease,",
+            "pad=1920:1080:(ow-iw)/2:(oh-ih)/2,setsar=1,fps=60,format=yuv420p[main];",
+            "[1:v]scale=480:-2,setsar=1,fps=60,format=yuv420p,",
+            "pad=iw+12:ih+12:6:6:color=#FF0000[inset];",
+            "[main][inset]overlay=x=32:y=32:shortest=1[vout] ",
+            "-map [vout] -map 0:a -shortest "
+        )));
+
+        // Default: bottom-right, 30% wide, clamped sizes
+        let pip = PipStyle::default();
+        assert_eq!(pip.width(), 576);
+        assert_eq!(
+            pip.position.overlay_filter_coords(PIP_MARGIN, PIP_MARGIN),
+            "x=W-w-32:y=H-h-32"
+        );
+        let huge = PipStyle {
+            size: 2.0,
+            ..PipStyle::default()
+        };
+        assert_eq!(huge.width(), 960);
+    }
+
+    #[test]
+    fn test_grid_validate() {
+        let grid = |n: usize, layout| GridConfig {
+            clips: (0..n).map(|i| clip(&i.to_string(), 5.0)).collect(),
+            layout,
+            audio_from: None,
+            pip: PipStyle::default(),
+        };
+        assert!(grid(1, GridLayout::SideBySide).validate().is_err());
+        assert!(grid(3, GridLayout::SideBySide).validate().is_err());
+        assert!(grid(4, GridLayout::Grid2x2).validate().is_ok());
+        assert!(grid(5, GridLayout::Grid2x2).validate().is_err());
+        assert!(grid(3, GridLayout::PictureInPicture).validate().is_err());
+
+        let mut config = grid(2, GridLayout::SideBySide);
+        config.audio_from = Some(2);
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! What the frontend sends to export a montage.
+//!
+//! The same input is stored with a project's saved montages, so it lives
+//! here rather than with the commands.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::{
+    Bookend, ClipOverlay, ImageOverlay, MontageAspect, MontageContainer, MontageEncoding,
+    MontageFit, MontageOrder, OutputFormat, OverlayAnimation, OverlayOutline, OverlayPoint,
+    OverlayPosition, OverlayShadow, RenderMode, SectionBreak, SlowMotion, TransitionStyle,
+};
+use crate::config::get_config;
+use crate::export::CropRect;
+
+/// Input for a single clip in the montage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts-bindings",
+    derive(ts_rs::TS),
+    ts(export, optional_fields)
+)]
+pub struct MontageClipInput {
+    /// Clip filename (for display)
+    pub filename: String,
+    /// Full path to the clip file
+    pub path: String,
+    /// Duration in seconds
+    pub duration: f64,
+    /// Streamer name for overlay
+    pub streamer_name: String,
+    /// Action name for the chapter title
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(as = "Option<_>", optional))]
+    pub action_name: String,
+    /// Project action the clip was cut from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    /// Path relative to the work dir, so saved configs survive moving it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative_path: Option<String>,
+    /// Overlay text/position for this clip instead of the montage overlay
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overlay: Option<ClipOverlay>,
+    /// Volume multiplier for this clip (1.0 = unchanged)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f64>,
+    /// Seconds cut from the start of the clip
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim_start: Option<f64>,
+    /// Where the clip ends, in seconds into the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim_end: Option<f64>,
+    /// Part of the frame to keep, in source pixels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crop: Option<CropRect>,
+    /// Punch-in factor on the center of the frame
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoom: Option<f64>,
+    /// Part of the clip played slower
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slowmo: Option<SlowMotion>,
+    /// Seconds the last frame is held
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub freeze: Option<f64>,
+}
+
+impl MontageClipInput {
+    /// Where the clip file is now: its path, or its work-dir-relative path
+    /// under the current work dir (the original path if neither exists)
+    pub(crate) fn resolved_path(&self) -> PathBuf {
+        let path = PathBuf::from(&self.path);
+        if path.exists() {
+            return path;
+        }
+        self.relative_path
+            .as_ref()
+            .map(|rel| get_config().output_dir.join(rel))
+            .filter(|p| p.exists())
+            .unwrap_or(path)
+    }
+}
+
+/// Overlay position for the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+#[serde(rename_all = "kebab-case")]
+pub enum OverlayPositionInput {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl From<OverlayPositionInput> for OverlayPosition {
+    fn from(pos: OverlayPositionInput) -> Self {
+        match pos {
+            OverlayPositionInput::TopLeft => OverlayPosition::TopLeft,
+            OverlayPositionInput::TopRight => OverlayPosition::TopRight,
+            OverlayPositionInput::BottomLeft => OverlayPosition::BottomLeft,
+            OverlayPositionInput::BottomRight => OverlayPosition::BottomRight,
+        }
+    }
+}
+
+/// Overlay configuration input from frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts-bindings",
+    derive(ts_rs::TS),
+    ts(export, optional_fields)
+)]
+pub struct OverlayInput {
+    pub text: String,
+    pub position: OverlayPositionInput,
+    pub font_size: u32,
+    pub color: String,
+    pub box_color: Option<String>,
+    /// Font file (bundled Roboto when unset)
+    #[serde(default)]
+    pub font: Option<String>,
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(as = "Option<_>", optional))]
+    pub animation: OverlayAnimation,
+    #[serde(default)]
+    pub custom_position: Option<OverlayPoint>,
+    #[serde(default)]
+    pub outline: Option<OverlayOutline>,
+    #[serde(default)]
+    pub shadow: Option<OverlayShadow>,
+}
+
+/// Background music input from frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts-bindings",
+    derive(ts_rs::TS),
+    ts(export, optional_fields)
+)]
+pub struct MusicInput {
+    pub path: String,
+    /// Linear gain (1.0 = unchanged)
+    #[serde(default = "default_music_volume")]
+    pub volume: f64,
+    /// Cut clips on the beats of the track
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(as = "Option<_>", optional))]
+    pub snap_to_beats: bool,
+}
+
+fn default_music_volume() -> f64 {
+    0.3
+}
+
+/// Export configuration from frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts-bindings",
+    derive(ts_rs::TS),
+    ts(export, optional_fields)
+)]
+pub struct MontageExportInput {
+    pub clips: Vec<MontageClipInput>,
+    pub transition_duration: f64,
+    /// Transition look (fade through black when unset)
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(as = "Option<_>", optional))]
+    pub transition: TransitionStyle,
+    /// Background music, ducked under the clip audio
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub music: Option<MusicInput>,
+    /// Video or title card before the first clip
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intro: Option<Bookend>,
+    /// Video or title card after the last clip
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outro: Option<Bookend>,
+    /// Title cards between clips
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "ts-bindings", ts(as = "Option<_>", optional))]
+    pub sections: Vec<SectionBreak>,
+    pub overlay: Option<OverlayInput>,
+    /// Watermark image over the whole montage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_overlay: Option<ImageOverlay>,
+    pub output_filename: Option<String>,
+    /// Output frame shape (16:9 when unset)
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(as = "Option<_>", optional))]
+    pub aspect: MontageAspect,
+    /// Crop or letterbox clips into a portrait/square frame
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(as = "Option<_>", optional))]
+    pub fit: MontageFit,
+    /// Common frame size and rate for all clips (keeps the clips' own when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<OutputFormat>,
+    /// Loudness every clip is normalized to before joining (LUFS)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loudness_lufs: Option<f64>,
+    /// Render clip by clip so a failed export can resume
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(as = "Option<_>", optional))]
+    pub render: RenderMode,
+    /// CRF, preset, audio bitrate and bitrate cap overrides
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(as = "Option<_>", optional))]
+    pub encoding: MontageEncoding,
+    /// MP4, MKV or WebM (VP9 + Opus)
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(as = "Option<_>", optional))]
+    pub container: MontageContainer,
+    /// Rearrange the clips (as sent when unset)
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(as = "Option<_>", optional))]
+    pub order: MontageOrder,
+}
@@ -1,3 +1,23 @@
+pub mod beats;
+mod cancel;
 mod concat;
+mod grid;
+mod input;
+mod order;
+pub mod presets;
+mod probe;
 
-pub use concat::{MontageClip, MontageConfig, MontageExporter, OverlayConfig, OverlayPosition};
+pub use cancel::{request_cancel, CancelScope};
+pub use concat::{
+    Bookend, ClipOverlay, ImageOverlay, MontageAspect, MontageClip, MontageConfig,
+    MontageContainer, MontageEncoding, MontageExporter, MontageFit, MusicTrack, OutputFormat,
+    OverlayAnimation, OverlayConfig, OverlayOutline, OverlayPoint, OverlayPosition, OverlayShadow,
+    RenderMode, SectionBreak, SlowMotion, TransitionStyle,
+};
+pub use grid::{GridConfig, GridLayout, PipStyle};
+pub use input::{MontageClipInput, MontageExportInput};
+pub use order::{MontageOrder, OrderKey};
+pub use presets::OverlayStylePreset;
+pub use probe::{check_inputs, InputReport};
+
+pub(crate) use concat::ffmpeg_font_path;
@@ -0,0 +1,135 @@
+//! Ordering of a montage's clips.
+//!
+//! The frontend sends clips in timeline order; an ordering mode can rearrange
+//! them from what the project knows about each clip (the streamer it's from
+//! and when its action happened in the game). Clips the project doesn't know
+//! keep their relative order, after the known ones.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// How a montage's clips are ordered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum MontageOrder {
+    /// As sent
+    #[default]
+    AsGiven,
+    /// By game time of the clip's start
+    Chronological,
+    /// All clips of a streamer together, streamers in project order, each
+    /// streamer's clips chronological
+    ByStreamer,
+    /// Random, but the same for the same seed
+    Shuffle {
+        #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+        seed: u64,
+    },
+}
+
+/// What the project knows about a clip
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OrderKey {
+    /// Position of the clip's streamer in the project
+    pub streamer: Option<usize>,
+    /// Game time the clip starts at (seconds)
+    pub game_time: Option<f64>,
+}
+
+impl MontageOrder {
+    /// Indices of `keys` in montage order
+    pub fn sequence(self, keys: &[OrderKey]) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..keys.len()).collect();
+        match self {
+            Self::AsGiven => {}
+            // Stable sorts keep unknown clips in their given order
+            Self::Chronological => {
+                indices.sort_by(|a, b| by_time(&keys[*a], &keys[*b]));
+            }
+            Self::ByStreamer => {
+                indices.sort_by(|a, b| {
+                    known_first(keys[*a].streamer, keys[*b].streamer)
+                        .then_with(|| by_time(&keys[*a], &keys[*b]))
+                });
+            }
+            Self::Shuffle { seed } => shuffle(&mut indices, seed),
+        }
+        indices
+    }
+}
+
+fn by_time(a: &OrderKey, b: &OrderKey) -> Ordering {
+    match (a.game_time, b.game_time) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (a, b) => a.is_none().cmp(&b.is_none()),
+    }
+}
+
+fn known_first(a: Option<usize>, b: Option<usize>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (a, b) => a.is_none().cmp(&b.is_none()),
+    }
+}
+
+/// Fisher-Yates with a SplitMix64 generator, so a seed gives the same order
+/// on every machine and version
+fn shuffle(items: &mut [usize], seed: u64) {
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    };
+    for i in (1..items.len()).rev() {
+        let j = (next() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(streamer: Option<usize>, game_time: Option<f64>) -> OrderKey {
+        OrderKey {
+            streamer,
+            game_time,
+        }
+    }
+
+    #[test]
+    fn test_sequence() {
+        let keys = [
+            key(Some(1), Some(300.0)),
+            key(None, None),
+            key(Some(0), Some(500.0)),
+            key(Some(1), Some(100.0)),
+        ];
+        assert_eq!(MontageOrder::AsGiven.sequence(&keys), vec![0, 1, 2, 3]);
+        assert_eq!(
+            MontageOrder::Chronological.sequence(&keys),
+            vec![3, 0, 2, 1]
+        );
+        assert_eq!(MontageOrder::ByStreamer.sequence(&keys), vec![2, 3, 0, 1]);
+
+        let shuffled = MontageOrder::Shuffle { seed: 7 }.sequence(&keys);
+        assert_eq!(shuffled, MontageOrder::Shuffle { seed: 7 }.sequence(&keys));
+        let mut sorted = shuffled.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_order_serde() {
+        let order: MontageOrder = serde_json::from_str(r#"{"mode":"shuffle","seed":42}"#).unwrap();
+        assert_eq!(order, MontageOrder::Shuffle { seed: 42 });
+        assert_eq!(
+            serde_json::to_string(&MontageOrder::ByStreamer).unwrap(),
+            r#"{"mode":"by_streamer"}"#
+        );
+    }
+}
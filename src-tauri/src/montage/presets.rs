@@ -0,0 +1,81 @@
+//! Overlay style presets shared across projects.
+//!
+//! Presets live in `overlay_presets.json` in the config dir and are re-read on
+//! every access, so edits made on disk are picked up without restarting.
+
+use serde::{Deserialize, Serialize};
+
+use super::{OverlayAnimation, OverlayPosition};
+use crate::binaries::{ensure_config_dir, get_overlay_presets_path};
+use crate::error::{NoxError, Result};
+
+/// A reusable overlay style
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlayStylePreset {
+    /// Unique preset ID
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Position on screen
+    pub position: OverlayPosition,
+    /// Font size in pixels
+    pub font_size: u32,
+    /// Text color in hex format (e.g., "FFFFFF")
+    pub color: String,
+    /// Background box color (optional, e.g., "000000@0.5")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub box_color: Option<String>,
+    /// Font file (bundled Roboto when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font: Option<String>,
+    /// Entrance/exit animation
+    #[serde(default)]
+    pub animation: OverlayAnimation,
+}
+
+/// Load all presets from disk (empty list if the file doesn't exist yet)
+pub fn load_presets() -> Result<Vec<OverlayStylePreset>> {
+    let path = get_overlay_presets_path();
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| NoxError::Config(format!("Failed to parse overlay presets: {}", e)))
+}
+
+/// Write all presets to disk
+fn write_presets(presets: &[OverlayStylePreset]) -> Result<()> {
+    ensure_config_dir()?;
+    let content = serde_json::to_string_pretty(presets)
+        .map_err(|e| NoxError::Config(format!("Failed to serialize overlay presets: {}", e)))?;
+    std::fs::write(get_overlay_presets_path(), content)?;
+    Ok(())
+}
+
+/// Insert a preset, or replace the existing one with the same ID
+pub fn save_preset(preset: OverlayStylePreset) -> Result<()> {
+    let mut presets = load_presets()?;
+
+    match presets.iter_mut().find(|p| p.id == preset.id) {
+        Some(existing) => *existing = preset,
+        None => presets.push(preset),
+    }
+
+    write_presets(&presets)
+}
+
+/// Delete a preset by ID (no-op if it doesn't exist)
+pub fn delete_preset(id: &str) -> Result<()> {
+    let mut presets = load_presets()?;
+    let before = presets.len();
+    presets.retain(|p| p.id != id);
+
+    if presets.len() != before {
+        write_presets(&presets)?;
+    }
+
+    Ok(())
+}
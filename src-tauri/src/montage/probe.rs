@@ -0,0 +1,288 @@
+//! Up-front checks of a montage's clips with ffprobe.
+//!
+//! The filter graph maps `[i:v]` of every clip and joins them at one frame
+//! size. A clip without video, or a different size with nothing to rescale
+//! it, makes FFmpeg fail with an error that doesn't say which clip is at
+//! fault; probing first names the clip and the problem. Clips without audio
+//! (muted VOD segments) are fine: export puts silence in their place.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::{MontageConfig, RenderMode};
+use crate::error::ExportResult;
+use crate::export::{FfmpegExporter, StreamParams};
+
+/// Why a clip can't go into the montage as it is
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ClipIssue {
+    Missing,
+    /// ffprobe couldn't read the file
+    Unreadable {
+        error: String,
+    },
+    NoVideo,
+    /// Played with silence
+    NoAudio,
+    /// Another frame size than the first clip, and no output format to
+    /// rescale the clips to
+    SizeMismatch {
+        width: u32,
+        height: u32,
+        expected_width: u32,
+        expected_height: u32,
+    },
+}
+
+impl ClipIssue {
+    /// Whether the export can't go ahead with the clip
+    fn is_fatal(&self) -> bool {
+        !matches!(self, Self::NoAudio)
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Missing => "file not found".to_string(),
+            Self::Unreadable { error } => format!("unreadable ({})", error),
+            Self::NoVideo => "no video track".to_string(),
+            Self::NoAudio => "no audio track".to_string(),
+            Self::SizeMismatch {
+                width,
+                height,
+                expected_width,
+                expected_height,
+            } => format!(
+                "{}x{} while the first clip is {}x{}; pick an output resolution",
+                width, height, expected_width, expected_height
+            ),
+        }
+    }
+}
+
+/// What ffprobe found in one clip
+#[derive(Debug, Clone, Serialize)]
+pub struct ClipCheck {
+    /// Position in the montage
+    pub index: usize,
+    pub path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video_codec: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_codec: Option<String>,
+    /// Empty when the clip is fine
+    pub issues: Vec<ClipIssue>,
+}
+
+/// Checks of all clips of a montage
+#[derive(Debug, Clone, Serialize)]
+pub struct InputReport {
+    pub clips: Vec<ClipCheck>,
+}
+
+impl InputReport {
+    /// The first problem that stops the export, as an error message naming
+    /// the clip
+    pub fn first_problem(&self) -> Option<String> {
+        self.clips.iter().find_map(|clip| {
+            clip.issues.iter().find(|i| i.is_fatal()).map(|issue| {
+                format!(
+                    "Clip {} ({}): {}",
+                    clip.index + 1,
+                    clip.path.display(),
+                    issue.describe()
+                )
+            })
+        })
+    }
+
+    /// Positions of the clips without an audio track
+    pub fn silent_clips(&self) -> Vec<usize> {
+        self.clips
+            .iter()
+            .filter(|c| c.issues.contains(&ClipIssue::NoAudio))
+            .map(|c| c.index)
+            .collect()
+    }
+}
+
+/// Probe every clip of `config`
+///
+/// Prerendered clips are the exporter's own intermediates and aren't probed.
+pub async fn check_inputs(config: &MontageConfig) -> InputReport {
+    let mut report = InputReport { clips: Vec::new() };
+    if config.render == RenderMode::Prerendered {
+        return report;
+    }
+
+    let ffmpeg = FfmpegExporter::new();
+    let rules = Rules {
+        needs_video: config.container.audio_codec().is_none(),
+        rescaled: config.frame_filter().is_some(),
+    };
+    let mut first_size = None;
+    for (index, clip) in config.clips.iter().enumerate() {
+        let probe = match clip.path.exists() {
+            true => Some(ffmpeg.probe_stream_params(&clip.path).await),
+            false => None,
+        };
+        report.clips.push(check_clip(
+            index,
+            &clip.path,
+            probe,
+            &rules,
+            &mut first_size,
+        ));
+    }
+    report
+}
+
+/// What the montage needs from its clips
+struct Rules {
+    needs_video: bool,
+    /// Clips are scaled to the output format, so their sizes may differ
+    rescaled: bool,
+}
+
+/// Check one clip from its probe (None when the file doesn't exist);
+/// `first_size` is the frame size of the first clip that had one
+fn check_clip(
+    index: usize,
+    path: &Path,
+    probe: Option<ExportResult<StreamParams>>,
+    rules: &Rules,
+    first_size: &mut Option<(u32, u32)>,
+) -> ClipCheck {
+    let mut check = ClipCheck {
+        index,
+        path: path.to_path_buf(),
+        video_codec: None,
+        width: None,
+        height: None,
+        audio_codec: None,
+        issues: Vec::new(),
+    };
+    let params = match probe {
+        None => {
+            check.issues.push(ClipIssue::Missing);
+            return check;
+        }
+        Some(Err(e)) => {
+            check.issues.push(ClipIssue::Unreadable {
+                error: e.to_string(),
+            });
+            return check;
+        }
+        Some(Ok(params)) => params,
+    };
+
+    if let Some((codec, width, height)) = params.video() {
+        check.video_codec = Some(codec);
+        check.width = width;
+        check.height = height;
+    } else if rules.needs_video {
+        check.issues.push(ClipIssue::NoVideo);
+    }
+    check.audio_codec = params.audio_codec();
+    if check.audio_codec.is_none() {
+        check.issues.push(ClipIssue::NoAudio);
+    }
+
+    if let (Some(width), Some(height)) = (check.width, check.height) {
+        match *first_size {
+            None => *first_size = Some((width, height)),
+            Some((expected_width, expected_height))
+                if rules.needs_video
+                    && !rules.rescaled
+                    && (width, height) != (expected_width, expected_height) =>
+            {
+                check.issues.push(ClipIssue::SizeMismatch {
+                    width,
+                    height,
+                    expected_width,
+                    expected_height,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    check
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ExportError;
+
+    fn params(json: &str) -> Option<ExportResult<StreamParams>> {
+        Some(Ok(serde_json::from_str(json).unwrap()))
+    }
+
+    const FULL_HD: &str = r#"{"streams": [
+        {"codec_type": "video", "codec_name": "h264", "width": 1920, "height": 1080},
+        {"codec_type": "audio", "codec_name": "aac"}]}"#;
+
+    #[test]
+    fn test_check_clip() {
+        let rules = Rules {
+            needs_video: true,
+            rescaled: false,
+        };
+        let mut first = None;
+        let path = Path::new("/clips/a.mp4");
+
+        let check = check_clip(0, path, params(FULL_HD), &rules, &mut first);
+        assert!(check.issues.is_empty());
+        assert_eq!(check.video_codec.as_deref(), Some("h264"));
+        assert_eq!(first, Some((1920, 1080)));
+
+        let silent = r#"{"streams": [
+            {"codec_type": "video", "codec_name": "h264", "width": 1280, "height": 720}]}"#;
+        let check = check_clip(1, path, params(silent), &rules, &mut first);
+        assert_eq!(
+            check.issues,
+            vec![
+                ClipIssue::NoAudio,
+                ClipIssue::SizeMismatch {
+                    width: 1280,
+                    height: 720,
+                    expected_width: 1920,
+                    expected_height: 1080
+                }
+            ]
+        );
+
+        // An output format rescales every clip
+        let rescaled = Rules {
+            needs_video: true,
+            rescaled: true,
+        };
+        let check = check_clip(1, path, params(silent), &rescaled, &mut first);
+        assert_eq!(check.issues, vec![ClipIssue::NoAudio]);
+
+        let check = check_clip(2, path, None, &rules, &mut first);
+        assert_eq!(check.issues, vec![ClipIssue::Missing]);
+        let unreadable = Some(Err(ExportError::Ffmpeg("ffprobe failed".into())));
+        let check = check_clip(3, path, unreadable, &rules, &mut first);
+        assert!(matches!(check.issues[0], ClipIssue::Unreadable { .. }));
+
+        // A clip without audio is played with silence
+        let mut report = InputReport {
+            clips: vec![check_clip(4, path, params(silent), &rescaled, &mut first)],
+        };
+        assert_eq!(report.first_problem(), None);
+        assert_eq!(report.silent_clips(), vec![4]);
+        report
+            .clips
+            .push(check_clip(5, path, params(silent), &rules, &mut first));
+        assert_eq!(
+            report.first_problem().unwrap(),
+            "Clip 6 (/clips/a.mp4): 1280x720 while the first clip is 1920x1080; \
+             pick an output resolution"
+        );
+    }
+}
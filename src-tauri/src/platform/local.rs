@@ -0,0 +1,100 @@
+//! Local recordings (`file://` URLs or plain paths) used as a streamer's VOD.
+//!
+//! Nothing is fetched: the file is checked with ffprobe and FFmpeg cuts clips
+//! from it directly.
+
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+
+use super::{ResolvedVod, VodResolver};
+use crate::error::{PlatformError, PlatformResult};
+use crate::export::FfmpegExporter;
+
+pub struct LocalFileResolver;
+
+impl LocalFileResolver {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Check whether the URL is a local file rather than a web address
+    pub(crate) fn is_local_path(url: &str) -> bool {
+        Self::to_path(url).is_some()
+    }
+
+    /// Filesystem path of a `file://` URL or an absolute path
+    fn to_path(url: &str) -> Option<PathBuf> {
+        let url = url.trim();
+        if url.starts_with("file://") {
+            return reqwest::Url::parse(url).ok()?.to_file_path().ok();
+        }
+        let path = Path::new(url);
+        path.is_absolute().then(|| path.to_path_buf())
+    }
+}
+
+impl Default for LocalFileResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl VodResolver for LocalFileResolver {
+    fn can_handle(&self, url: &str) -> bool {
+        Self::is_local_path(url)
+    }
+
+    async fn resolve(&self, url: &str) -> PlatformResult<ResolvedVod> {
+        let path = Self::to_path(url).ok_or_else(|| PlatformError::InvalidUrl(url.to_string()))?;
+        if !path.is_file() {
+            return Err(PlatformError::VodNotFound(path.display().to_string()));
+        }
+
+        let params = FfmpegExporter::new()
+            .probe_stream_params(&path)
+            .await
+            .map_err(|e| {
+                PlatformError::ParseError(format!("Unreadable video {}: {}", path.display(), e))
+            })?;
+        if params.video().is_none() {
+            return Err(PlatformError::ParseError(format!(
+                "No video track in {}",
+                path.display()
+            )));
+        }
+
+        log::info!("[Local] Using recording {}", path.display());
+        Ok(ResolvedVod {
+            url: path.to_string_lossy().to_string(),
+            is_hls: false,
+            recorded_until: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_path() {
+        let (file_url, path) = if cfg!(windows) {
+            (
+                "file:///C:/Recordings/vod%201.mkv",
+                r"C:\Recordings\vod 1.mkv",
+            )
+        } else {
+            ("file:///recordings/vod%201.mkv", "/recordings/vod 1.mkv")
+        };
+        assert_eq!(
+            LocalFileResolver::to_path(file_url),
+            Some(PathBuf::from(path))
+        );
+        assert_eq!(LocalFileResolver::to_path(path), Some(PathBuf::from(path)));
+        assert!(!LocalFileResolver::is_local_path(
+            "https://www.twitch.tv/videos/123"
+        ));
+        assert!(!LocalFileResolver::is_local_path("recordings/vod.mkv"));
+    }
+}
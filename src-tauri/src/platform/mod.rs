@@ -1,11 +1,34 @@
+mod local;
 mod twitch;
+mod twitch_clip;
 mod youtube;
 
+pub use local::LocalFileResolver;
 pub use twitch::TwitchResolver;
+pub use twitch_clip::TwitchClipResolver;
 pub use youtube::YoutubeResolver;
 
-use crate::error::PlatformResult;
+use crate::error::{PlatformError, PlatformResult};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// How long a resolved stream URL is reused. Twitch playlist URLs stay valid
+/// for hours; exports re-resolve on a 403 anyway.
+const RESOLVE_CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// How long a VOD that is still recording is reused (its length grows)
+const LIVE_RESOLVE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Recently resolved VODs, keyed by VOD URL
+static RESOLVE_CACHE: OnceLock<Mutex<HashMap<String, (Instant, ResolvedVod)>>> = OnceLock::new();
+
+fn resolve_cache() -> &'static Mutex<HashMap<String, (Instant, ResolvedVod)>> {
+    RESOLVE_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
 
 /// Information about a resolved VOD
 #[derive(Debug, Clone)]
@@ -14,6 +37,43 @@ pub struct ResolvedVod {
     pub url: String,
     /// Whether this is an HLS stream
     pub is_hls: bool,
+    /// Seconds recorded so far, while the stream is still live (None = the
+    /// VOD is complete)
+    pub recorded_until: Option<f64>,
+}
+
+impl ResolvedVod {
+    /// Fail if a clip ending at `end` goes past what a live VOD has recorded
+    /// so far
+    pub fn check_available(&self, end: f64) -> PlatformResult<()> {
+        match self.recorded_until {
+            Some(available) if end > available => Err(PlatformError::StillProcessing { available }),
+            _ => Ok(()),
+        }
+    }
+
+    fn cache_ttl(&self) -> Duration {
+        match self.recorded_until {
+            Some(_) => LIVE_RESOLVE_CACHE_TTL,
+            None => RESOLVE_CACHE_TTL,
+        }
+    }
+}
+
+/// Part of a VOD whose audio the platform muted (DMCA'd music on Twitch)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MutedSegment {
+    /// Start in the VOD (seconds)
+    pub offset: f64,
+    pub duration: f64,
+}
+
+/// Seconds of `start..end` that fall into muted segments
+pub fn muted_overlap(segments: &[MutedSegment], start: f64, end: f64) -> f64 {
+    segments
+        .iter()
+        .map(|s| (end.min(s.offset + s.duration) - start.max(s.offset)).max(0.0))
+        .sum()
 }
 
 /// Trait for resolving VOD URLs to direct stream URLs
@@ -24,6 +84,33 @@ pub trait VodResolver: Send + Sync {
 
     /// Resolve a VOD URL to a direct stream URL
     async fn resolve(&self, url: &str) -> PlatformResult<ResolvedVod>;
+
+    /// Resolve a VOD URL to its audio-only rendition, for cheap audio sync.
+    /// Platforms without one fall back to the full stream.
+    async fn resolve_audio(&self, url: &str) -> PlatformResult<ResolvedVod> {
+        self.resolve(url).await
+    }
+
+    /// Wall-clock time the VOD recording started, if the platform exposes it
+    async fn recorded_at(&self, _url: &str) -> PlatformResult<Option<DateTime<Utc>>> {
+        Ok(None)
+    }
+
+    /// Parts of the VOD with muted audio, if the platform mutes any
+    async fn muted_segments(&self, _url: &str) -> PlatformResult<Vec<MutedSegment>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Detect the platform of a VOD URL ("twitch", "youtube" or "other")
+pub fn detect_platform(url: &str) -> &'static str {
+    if TwitchResolver::is_twitch_url(url) || TwitchClipResolver::is_clip_url(url) {
+        "twitch"
+    } else if YoutubeResolver::is_youtube_url(url) {
+        "youtube"
+    } else {
+        "other"
+    }
 }
 
 /// Main resolver that delegates to platform-specific resolvers
@@ -41,14 +128,40 @@ impl VodResolverChain {
     pub fn new() -> Self {
         Self {
             resolvers: vec![
+                Box::new(TwitchClipResolver::new()),
                 Box::new(TwitchResolver::new()),
                 Box::new(YoutubeResolver::new()),
+                Box::new(LocalFileResolver::new()),
             ],
         }
     }
 
     /// Resolve a VOD URL using the appropriate resolver
+    ///
+    /// Always asks the platform; the result replaces any cached one.
     pub async fn resolve(&self, url: &str) -> PlatformResult<ResolvedVod> {
+        let resolved = self.resolve_uncached(url).await?;
+        if let Ok(mut cache) = resolve_cache().lock() {
+            cache.insert(url.to_string(), (Instant::now(), resolved.clone()));
+        }
+        Ok(resolved)
+    }
+
+    /// Like [`resolve`](Self::resolve), but reuses a recent resolution of the same URL
+    pub async fn resolve_cached(&self, url: &str) -> PlatformResult<ResolvedVod> {
+        let cached = resolve_cache().lock().ok().and_then(|cache| {
+            cache
+                .get(url)
+                .filter(|(at, vod)| at.elapsed() < vod.cache_ttl())
+                .map(|(_, vod)| vod.clone())
+        });
+        match cached {
+            Some(vod) => Ok(vod),
+            None => self.resolve(url).await,
+        }
+    }
+
+    async fn resolve_uncached(&self, url: &str) -> PlatformResult<ResolvedVod> {
         for resolver in &self.resolvers {
             if resolver.can_handle(url) {
                 return resolver.resolve(url).await;
@@ -59,6 +172,98 @@ impl VodResolverChain {
         Ok(ResolvedVod {
             url: url.to_string(),
             is_hls: url.contains(".m3u8"),
+            recorded_until: None,
         })
     }
+
+    /// Resolve a VOD URL to its audio-only stream (full stream if none exists)
+    pub async fn resolve_audio(&self, url: &str) -> PlatformResult<ResolvedVod> {
+        for resolver in &self.resolvers {
+            if resolver.can_handle(url) {
+                return resolver.resolve_audio(url).await;
+            }
+        }
+
+        Ok(ResolvedVod {
+            url: url.to_string(),
+            is_hls: url.contains(".m3u8"),
+            recorded_until: None,
+        })
+    }
+
+    /// Look up when a VOD recording started (None if unknown)
+    pub async fn recorded_at(&self, url: &str) -> PlatformResult<Option<DateTime<Utc>>> {
+        for resolver in &self.resolvers {
+            if resolver.can_handle(url) {
+                return resolver.recorded_at(url).await;
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Look up the muted parts of a VOD (empty if none or unknown)
+    pub async fn muted_segments(&self, url: &str) -> PlatformResult<Vec<MutedSegment>> {
+        for resolver in &self.resolvers {
+            if resolver.can_handle(url) {
+                return resolver.muted_segments(url).await;
+            }
+        }
+
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_platform() {
+        assert_eq!(
+            detect_platform("https://www.twitch.tv/videos/123456"),
+            "twitch"
+        );
+        assert_eq!(
+            detect_platform("https://clips.twitch.tv/FunnyClipSlug-abc123"),
+            "twitch"
+        );
+        assert_eq!(detect_platform("https://youtu.be/dQw4w9WgXcQ"), "youtube");
+        assert_eq!(detect_platform("https://example.com/vod.mp4"), "other");
+    }
+
+    #[test]
+    fn test_check_available() {
+        let live = ResolvedVod {
+            url: "https://example.com/index-dvr.m3u8".to_string(),
+            is_hls: true,
+            recorded_until: Some(5400.0),
+        };
+        assert!(live.check_available(5000.0).is_ok());
+        let err = live.check_available(5460.0).unwrap_err();
+        assert_eq!(err.to_string(), "VOD still processing beyond 1:30:00");
+        assert!(ResolvedVod {
+            recorded_until: None,
+            ..live
+        }
+        .check_available(9000.0)
+        .is_ok());
+    }
+
+    #[test]
+    fn test_muted_overlap() {
+        let segments = [
+            MutedSegment {
+                offset: 360.0,
+                duration: 180.0,
+            },
+            MutedSegment {
+                offset: 900.0,
+                duration: 360.0,
+            },
+        ];
+        assert_eq!(muted_overlap(&segments, 500.0, 560.0), 40.0);
+        assert_eq!(muted_overlap(&segments, 600.0, 800.0), 0.0);
+        assert_eq!(muted_overlap(&segments, 300.0, 1000.0), 280.0);
+    }
 }
@@ -1,16 +1,26 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
-use super::{ResolvedVod, VodResolver};
+use super::{MutedSegment, ResolvedVod, VodResolver};
 use crate::error::{PlatformError, PlatformResult};
 
-const CLIENT_ID: &str = "kimne78kx3ncx6brgo4mv6wki5h1ko";
-const GQL_URL: &str = "https://gql.twitch.tv/gql";
+pub(super) const CLIENT_ID: &str = "kimne78kx3ncx6brgo4mv6wki5h1ko";
+pub(super) const GQL_URL: &str = "https://gql.twitch.tv/gql";
+
+/// Master playlists of VODs, listing every rendition
+const USHER_URL: &str = "https://usher.ttvnw.net/vod";
 
 /// Available video qualities in order of preference
 const QUALITIES: &[&str] = &["chunked", "1080p60", "720p60", "480p30", "360p30"];
 
+/// Uploads older than this keep their playlists under the owner's login
+const UPLOAD_PATH_CHANGE_DAYS: i64 = 7;
+
+/// Rendition with only the audio track (~160 kbps instead of several Mbps)
+const AUDIO_QUALITY: &str = "audio_only";
+
 pub struct TwitchResolver {
     client: Client,
 }
@@ -22,17 +32,33 @@ impl TwitchResolver {
         }
     }
 
+    /// Check whether the URL points to a Twitch VOD
+    pub(crate) fn is_twitch_url(url: &str) -> bool {
+        Self::extract_vod_id(url).is_some()
+    }
+
     /// Extract VOD ID from URL
+    ///
+    /// Accepts `twitch.tv/videos/<id>`, `twitch.tv/<channel>/v/<id>`,
+    /// `twitch.tv/<channel>/video/<id>` (any subdomain) and embedded player
+    /// links (`player.twitch.tv/?video=v<id>`).
     fn extract_vod_id(url: &str) -> Option<String> {
-        let re = regex::Regex::new(r"twitch\.tv/videos?/(\d+)").ok()?;
-        re.captures(url).map(|c| c[1].to_string())
+        let re = regex::Regex::new(
+            r"twitch\.tv/(?:[^/?#]+/)?(?:videos?|v)/(\d+)|player\.twitch\.tv/\?(?:[^#]*&)?video=v?(\d+)",
+        )
+        .ok()?;
+        let captures = re.captures(url)?;
+        captures
+            .get(1)
+            .or_else(|| captures.get(2))
+            .map(|m| m.as_str().to_string())
     }
 
     /// Fetch VOD metadata from Twitch GraphQL API
     async fn fetch_metadata(&self, vod_id: &str) -> PlatformResult<VodMetadata> {
         let query = GqlQuery {
             query: format!(
-                r#"query {{ video(id: "{vod_id}") {{ broadcastType, seekPreviewsURL }} }}"#
+                r#"query {{ video(id: "{vod_id}") {{ broadcastType, seekPreviewsURL, createdAt, status, lengthSeconds, owner {{ login }} }} }}"#
             ),
         };
 
@@ -55,19 +81,191 @@ impl TwitchResolver {
             .ok_or_else(|| PlatformError::VodNotFound(vod_id.to_string()))
     }
 
+    /// Fetch the parts of the VOD Twitch muted
+    async fn fetch_muted_segments(&self, vod_id: &str) -> PlatformResult<Vec<MutedSegment>> {
+        let query = GqlQuery {
+            query: format!(
+                r#"query {{ video(id: "{vod_id}") {{ muteInfo {{ mutedSegmentConnection {{ nodes {{ offset, duration }} }} }} }} }}"#
+            ),
+        };
+
+        let response = self
+            .client
+            .post(GQL_URL)
+            .header("Client-Id", CLIENT_ID)
+            .json(&query)
+            .send()
+            .await
+            .map_err(|e| PlatformError::ApiError(e.to_string()))?;
+
+        let body: MuteResponse = response
+            .json()
+            .await
+            .map_err(|e| PlatformError::ParseError(e.to_string()))?;
+
+        let video = body
+            .data
+            .video
+            .ok_or_else(|| PlatformError::VodNotFound(vod_id.to_string()))?;
+        Ok(video.muted_segments())
+    }
+
+    /// Fetch a signed token allowing playback of the VOD
+    async fn fetch_access_token(&self, vod_id: &str) -> PlatformResult<AccessToken> {
+        let query = GqlQuery {
+            query: format!(
+                r#"query {{ videoPlaybackAccessToken(id: "{vod_id}", params: {{ platform: "web", playerBackend: "mediaplayer", playerType: "embed" }}) {{ value, signature }} }}"#
+            ),
+        };
+
+        let response = self
+            .client
+            .post(GQL_URL)
+            .header("Client-Id", CLIENT_ID)
+            .json(&query)
+            .send()
+            .await
+            .map_err(|e| PlatformError::ApiError(e.to_string()))?;
+
+        let body: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| PlatformError::ParseError(e.to_string()))?;
+
+        body.data
+            .video_playback_access_token
+            .ok_or_else(|| PlatformError::VodNotFound(vod_id.to_string()))
+    }
+
+    /// Fetch the VOD's master playlist from usher with its access token
+    async fn fetch_master_playlist(
+        &self,
+        vod_id: &str,
+        token: &AccessToken,
+    ) -> PlatformResult<String> {
+        let response = self
+            .client
+            .get(format!("{USHER_URL}/{vod_id}.m3u8"))
+            .query(&[
+                ("sig", token.signature.as_str()),
+                ("token", token.value.as_str()),
+                ("allow_source", "true"),
+                ("allow_audio_only", "true"),
+                ("playlist_include_framerate", "true"),
+                ("player", "twitchweb"),
+            ])
+            .send()
+            .await
+            .map_err(|e| PlatformError::ApiError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(PlatformError::ApiError(format!(
+                "Usher returned {} for VOD {}",
+                response.status(),
+                vod_id
+            )));
+        }
+        response
+            .text()
+            .await
+            .map_err(|e| PlatformError::ParseError(e.to_string()))
+    }
+
+    /// `(quality, playlist URL)` of each rendition in a master playlist, as
+    /// listed (best first)
+    fn parse_master_playlist(playlist: &str) -> Vec<(String, String)> {
+        let mut variants = Vec::new();
+        let mut quality = None;
+        for line in playlist.lines().map(str::trim) {
+            if let Some(attributes) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+                quality = Self::playlist_attribute(attributes, "VIDEO").map(str::to_string);
+            } else if !line.is_empty() && !line.starts_with('#') {
+                if let Some(quality) = quality.take() {
+                    variants.push((quality, line.to_string()));
+                }
+            }
+        }
+        variants
+    }
+
+    /// Value of the `name` attribute in an M3U8 attribute list, unquoted
+    ///
+    /// Quoted values may hold commas (`CODECS="avc1.64002A,mp4a.40.2"`), so
+    /// the list is split on the commas outside quotes only.
+    fn playlist_attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+        let mut rest = attributes;
+        while !rest.is_empty() {
+            let (key, after_key) = rest.split_once('=')?;
+            let (value, next) = match after_key.strip_prefix('"') {
+                Some(quoted) => {
+                    let (value, after) = quoted.split_once('"')?;
+                    (value, after.strip_prefix(',').unwrap_or(after))
+                }
+                None => after_key.split_once(',').unwrap_or((after_key, "")),
+            };
+            if key.trim() == name {
+                return Some(value);
+            }
+            rest = next;
+        }
+        None
+    }
+
+    /// Playlist URL of the first of `qualities` the VOD has, from its master
+    /// playlist
+    async fn resolve_with_token(
+        &self,
+        vod_id: &str,
+        qualities: &[&str],
+    ) -> PlatformResult<ResolvedVod> {
+        let token = self.fetch_access_token(vod_id).await?;
+        let playlist = self.fetch_master_playlist(vod_id, &token).await?;
+        let variants = Self::parse_master_playlist(&playlist);
+
+        let (quality, url) = qualities
+            .iter()
+            .find_map(|wanted| variants.iter().find(|(quality, _)| quality == wanted))
+            .ok_or(PlatformError::NoValidQuality)?;
+
+        log::info!("[Twitch] Found quality: {}", quality);
+        Ok(ResolvedVod {
+            url: url.clone(),
+            is_hls: true,
+            recorded_until: None,
+        })
+    }
+
     /// Build the direct m3u8 URL for a VOD
+    ///
+    /// Highlights have their own playlist name. Uploads (and premieres, which
+    /// are uploads) move under the owner's login once they are a week old;
+    /// archives keep the plain layout.
     fn build_playlist_url(
-        &self,
         domain: &str,
         vod_special_id: &str,
         vod_id: &str,
         quality: &str,
         metadata: &VodMetadata,
+        now: DateTime<Utc>,
     ) -> String {
         let broadcast_type = metadata.broadcast_type.to_lowercase();
+        let is_upload = matches!(
+            broadcast_type.as_str(),
+            "upload" | "premiere" | "past_premiere"
+        );
+        let old_upload_owner = metadata.owner.as_ref().filter(|_| is_upload).filter(|_| {
+            metadata
+                .created_at
+                .is_some_and(|at| (now - at).num_days() > UPLOAD_PATH_CHANGE_DAYS)
+        });
 
         if broadcast_type == "highlight" {
             format!("https://{domain}/{vod_special_id}/{quality}/highlight-{vod_id}.m3u8")
+        } else if let Some(owner) = old_upload_owner {
+            format!(
+                "https://{domain}/{}/{vod_id}/{vod_special_id}/{quality}/index-dvr.m3u8",
+                owner.login
+            )
         } else {
             format!("https://{domain}/{vod_special_id}/{quality}/index-dvr.m3u8")
         }
@@ -110,28 +308,49 @@ impl TwitchResolver {
 
         Ok((domain, vod_special_id))
     }
-}
-
-impl Default for TwitchResolver {
-    fn default() -> Self {
-        Self::new()
-    }
-}
 
-#[async_trait]
-impl VodResolver for TwitchResolver {
-    fn can_handle(&self, url: &str) -> bool {
-        url.contains("twitch.tv/video")
-    }
-
-    async fn resolve(&self, url: &str) -> PlatformResult<ResolvedVod> {
+    /// Playlist URL of the first of `qualities` that exists for the VOD
+    ///
+    /// Asks usher with a playback access token; the playlist URL is guessed
+    /// from the seek previews when that fails (sub-only VODs are refused a
+    /// token but their playlists can still be read). A VOD whose stream is
+    /// still live resolves to its growing playlist, with the length recorded
+    /// so far.
+    async fn resolve_first_valid(
+        &self,
+        url: &str,
+        qualities: &[&str],
+    ) -> PlatformResult<ResolvedVod> {
         let vod_id =
             Self::extract_vod_id(url).ok_or_else(|| PlatformError::InvalidUrl(url.to_string()))?;
 
         log::info!("[Twitch] Resolving VOD {}", vod_id);
 
-        // Fetch metadata
-        let metadata = self.fetch_metadata(&vod_id).await?;
+        let metadata = self.fetch_metadata(&vod_id).await;
+        let recorded_until = metadata.as_ref().ok().and_then(VodMetadata::recorded_until);
+        if let Some(length) = recorded_until {
+            log::info!(
+                "[Twitch] VOD {} is still recording ({:.0}s so far)",
+                vod_id,
+                length
+            );
+        }
+
+        match self.resolve_with_token(&vod_id, qualities).await {
+            Ok(resolved) => {
+                return Ok(ResolvedVod {
+                    recorded_until,
+                    ..resolved
+                })
+            }
+            Err(e) => log::warn!(
+                "[Twitch] Access token flow failed for {}, trying seek previews: {}",
+                vod_id,
+                e
+            ),
+        }
+
+        let metadata = metadata?;
 
         let seek_url = metadata
             .seek_previews_url
@@ -148,9 +367,16 @@ impl VodResolver for TwitchResolver {
         );
 
         // Try each quality
-        for quality in QUALITIES {
-            let playlist_url =
-                self.build_playlist_url(&domain, &vod_special_id, &vod_id, quality, &metadata);
+        let now = Utc::now();
+        for quality in qualities {
+            let playlist_url = Self::build_playlist_url(
+                &domain,
+                &vod_special_id,
+                &vod_id,
+                quality,
+                &metadata,
+                now,
+            );
 
             log::debug!("[Twitch] Trying quality {}: {}", quality, playlist_url);
 
@@ -159,6 +385,7 @@ impl VodResolver for TwitchResolver {
                 return Ok(ResolvedVod {
                     url: playlist_url,
                     is_hls: true,
+                    recorded_until,
                 });
             }
         }
@@ -167,6 +394,54 @@ impl VodResolver for TwitchResolver {
     }
 }
 
+impl Default for TwitchResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl VodResolver for TwitchResolver {
+    fn can_handle(&self, url: &str) -> bool {
+        Self::is_twitch_url(url)
+    }
+
+    async fn resolve(&self, url: &str) -> PlatformResult<ResolvedVod> {
+        self.resolve_first_valid(url, QUALITIES).await
+    }
+
+    async fn resolve_audio(&self, url: &str) -> PlatformResult<ResolvedVod> {
+        // VODs without an audio-only rendition fall back to the full stream
+        let qualities: Vec<&str> = std::iter::once(AUDIO_QUALITY)
+            .chain(QUALITIES.iter().copied())
+            .collect();
+        self.resolve_first_valid(url, &qualities).await
+    }
+
+    async fn recorded_at(&self, url: &str) -> PlatformResult<Option<DateTime<Utc>>> {
+        let vod_id =
+            Self::extract_vod_id(url).ok_or_else(|| PlatformError::InvalidUrl(url.to_string()))?;
+
+        // For archives, createdAt is when the broadcast (and its recording) started
+        Ok(self.fetch_metadata(&vod_id).await?.created_at)
+    }
+
+    async fn muted_segments(&self, url: &str) -> PlatformResult<Vec<MutedSegment>> {
+        let vod_id =
+            Self::extract_vod_id(url).ok_or_else(|| PlatformError::InvalidUrl(url.to_string()))?;
+
+        let segments = self.fetch_muted_segments(&vod_id).await?;
+        if !segments.is_empty() {
+            log::info!(
+                "[Twitch] VOD {} has {} muted segments",
+                vod_id,
+                segments.len()
+            );
+        }
+        Ok(segments)
+    }
+}
+
 // ============ GraphQL Types ============
 
 #[derive(Serialize)]
@@ -184,12 +459,105 @@ struct GqlData {
     video: Option<VodMetadata>,
 }
 
+#[derive(Deserialize)]
+struct MuteResponse {
+    data: MuteData,
+}
+
+#[derive(Deserialize)]
+struct MuteData {
+    video: Option<MuteVideo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MuteVideo {
+    mute_info: Option<MuteInfo>,
+}
+
+impl MuteVideo {
+    fn muted_segments(self) -> Vec<MutedSegment> {
+        self.mute_info
+            .and_then(|info| info.muted_segment_connection)
+            .map(|connection| {
+                connection
+                    .nodes
+                    .into_iter()
+                    .map(|node| MutedSegment {
+                        offset: node.offset,
+                        duration: node.duration,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MuteInfo {
+    muted_segment_connection: Option<MutedSegmentConnection>,
+}
+
+#[derive(Deserialize)]
+struct MutedSegmentConnection {
+    #[serde(default)]
+    nodes: Vec<MutedSegmentNode>,
+}
+
+#[derive(Deserialize)]
+struct MutedSegmentNode {
+    offset: f64,
+    duration: f64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    data: TokenData,
+}
+
+#[derive(Deserialize)]
+struct TokenData {
+    #[serde(rename = "videoPlaybackAccessToken")]
+    video_playback_access_token: Option<AccessToken>,
+}
+
+#[derive(Deserialize)]
+struct AccessToken {
+    value: String,
+    signature: String,
+}
+
 #[derive(Deserialize)]
 struct VodMetadata {
     #[serde(rename = "broadcastType")]
     broadcast_type: String,
     #[serde(rename = "seekPreviewsURL")]
     seek_previews_url: Option<String>,
+    #[serde(rename = "createdAt", default)]
+    created_at: Option<DateTime<Utc>>,
+    /// "RECORDING" while the stream is live
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(rename = "lengthSeconds", default)]
+    length_seconds: Option<f64>,
+    #[serde(default)]
+    owner: Option<VodOwner>,
+}
+
+#[derive(Deserialize)]
+struct VodOwner {
+    login: String,
+}
+
+impl VodMetadata {
+    /// Seconds recorded so far if the stream is still live
+    fn recorded_until(&self) -> Option<f64> {
+        if self.status.as_deref() != Some("RECORDING") {
+            return None;
+        }
+        Some(self.length_seconds.unwrap_or(0.0))
+    }
 }
 
 #[cfg(test)]
@@ -206,10 +574,144 @@ mod tests {
             TwitchResolver::extract_vod_id("https://twitch.tv/video/987654"),
             Some("987654".to_string())
         );
+        assert_eq!(
+            TwitchResolver::extract_vod_id("https://www.twitch.tv/streamer/v/555"),
+            Some("555".to_string())
+        );
+        assert_eq!(
+            TwitchResolver::extract_vod_id("https://m.twitch.tv/streamer/video/556?t=1h2m"),
+            Some("556".to_string())
+        );
+        assert_eq!(
+            TwitchResolver::extract_vod_id(
+                "https://player.twitch.tv/?parent=example.com&video=v557&autoplay=false"
+            ),
+            Some("557".to_string())
+        );
         assert_eq!(
             TwitchResolver::extract_vod_id("https://youtube.com/watch?v=abc"),
             None
         );
+        assert_eq!(
+            TwitchResolver::extract_vod_id("https://www.twitch.tv/streamer/clip/Funny-123"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_build_playlist_url() {
+        let now: DateTime<Utc> = "2026-03-20T12:00:00Z".parse().unwrap();
+        let url = |broadcast_type: &str, created_at: &str| {
+            let metadata: VodMetadata = serde_json::from_str(&format!(
+                r#"{{"broadcastType": "{broadcast_type}", "seekPreviewsURL": null,
+                    "createdAt": "{created_at}", "owner": {{"login": "streamer"}}}}"#
+            ))
+            .unwrap();
+            TwitchResolver::build_playlist_url(
+                "d2.cloudfront.net",
+                "abc_42",
+                "42",
+                "720p60",
+                &metadata,
+                now,
+            )
+        };
+        let recent = "2026-03-18T20:00:00Z";
+        let old = "2026-01-05T20:00:00Z";
+
+        assert_eq!(
+            url("ARCHIVE", old),
+            "https://d2.cloudfront.net/abc_42/720p60/index-dvr.m3u8"
+        );
+        assert_eq!(
+            url("HIGHLIGHT", old),
+            "https://d2.cloudfront.net/abc_42/720p60/highlight-42.m3u8"
+        );
+        assert_eq!(
+            url("UPLOAD", recent),
+            "https://d2.cloudfront.net/abc_42/720p60/index-dvr.m3u8"
+        );
+        let moved = "https://d2.cloudfront.net/streamer/42/abc_42/720p60/index-dvr.m3u8";
+        assert_eq!(url("UPLOAD", old), moved);
+        assert_eq!(url("PAST_PREMIERE", old), moved);
+        assert_eq!(url("PREMIERE", old), moved);
+    }
+
+    #[test]
+    fn test_parse_master_playlist() {
+        let playlist = r#"#EXTM3U
+#EXT-X-TWITCH-INFO:ORIGIN="s3",B="false",REGION="EU"
+#EXT-X-MEDIA:TYPE=VIDEO,GROUP-ID="chunked",NAME="1080p60 (source)",AUTOSELECT=YES,DEFAULT=YES
+#EXT-X-STREAM-INF:BANDWIDTH=6000000,CODECS="avc1.64002A,mp4a.40.2",RESOLUTION=1920x1080,VIDEO="chunked",FRAME-RATE=60.000
+https://d1m7jfoe9zdc1j.cloudfront.net/abc_123/chunked/index-dvr.m3u8
+#EXT-X-MEDIA:TYPE=VIDEO,GROUP-ID="720p60",NAME="720p60",AUTOSELECT=YES,DEFAULT=YES
+#EXT-X-STREAM-INF:BANDWIDTH=3000000,CODECS="avc1.4D401F,mp4a.40.2",RESOLUTION=1280x720,VIDEO="720p60",FRAME-RATE=60.000
+https://d1m7jfoe9zdc1j.cloudfront.net/abc_123/720p60/index-dvr.m3u8
+#EXT-X-MEDIA:TYPE=VIDEO,GROUP-ID="audio_only",NAME="Audio Only",AUTOSELECT=NO,DEFAULT=NO
+#EXT-X-STREAM-INF:BANDWIDTH=160000,CODECS="mp4a.40.2",VIDEO="audio_only"
+https://d1m7jfoe9zdc1j.cloudfront.net/abc_123/audio_only/index-dvr.m3u8
+"#;
+        // Commas inside quoted values don't split attributes
+        assert_eq!(
+            TwitchResolver::playlist_attribute(
+                r#"CODECS="mp4a.40.2,VIDEO=x",VIDEO="480p30""#,
+                "VIDEO"
+            ),
+            Some("480p30")
+        );
+        assert_eq!(
+            TwitchResolver::playlist_attribute("BANDWIDTH=160000,VIDEO=audio_only", "VIDEO"),
+            Some("audio_only")
+        );
+        assert_eq!(
+            TwitchResolver::playlist_attribute(r#"CODECS="avc1,VIDEO=x""#, "VIDEO"),
+            None
+        );
+        let variants = TwitchResolver::parse_master_playlist(playlist);
+        let qualities: Vec<&str> = variants.iter().map(|(q, _)| q.as_str()).collect();
+        assert_eq!(qualities, ["chunked", "720p60", "audio_only"]);
+        assert_eq!(
+            variants[2].1,
+            "https://d1m7jfoe9zdc1j.cloudfront.net/abc_123/audio_only/index-dvr.m3u8"
+        );
+    }
+
+    #[test]
+    fn test_muted_segments() {
+        let body: MuteResponse = serde_json::from_str(
+            r#"{"data": {"video": {"muteInfo": {"mutedSegmentConnection": {"nodes": [
+                {"offset": 360, "duration": 180},
+                {"offset": 1260, "duration": 360}
+            ]}}}}}"#,
+        )
+        .unwrap();
+        let segments = body.data.video.unwrap().muted_segments();
+        assert_eq!(
+            segments[1],
+            MutedSegment {
+                offset: 1260.0,
+                duration: 360.0
+            }
+        );
+
+        let clean: MuteResponse =
+            serde_json::from_str(r#"{"data": {"video": {"muteInfo": null}}}"#).unwrap();
+        assert!(clean.data.video.unwrap().muted_segments().is_empty());
+    }
+
+    #[test]
+    fn test_recorded_until() {
+        let metadata = |json: &str| serde_json::from_str::<VodMetadata>(json).unwrap();
+        let live = metadata(
+            r#"{"broadcastType": "ARCHIVE", "seekPreviewsURL": null,
+                "status": "RECORDING", "lengthSeconds": 5400}"#,
+        );
+        assert_eq!(live.recorded_until(), Some(5400.0));
+        let done = metadata(
+            r#"{"broadcastType": "ARCHIVE", "seekPreviewsURL": null,
+                "status": "RECORDED", "lengthSeconds": 9000}"#,
+        );
+        assert_eq!(done.recorded_until(), None);
     }
 
     #[test]
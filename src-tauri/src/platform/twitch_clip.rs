@@ -0,0 +1,232 @@
+//! Twitch clips (`clips.twitch.tv/<slug>` or `twitch.tv/<channel>/clip/<slug>`).
+//!
+//! A clip is a short MP4 rather than an HLS playlist. Its file URLs come from
+//! the same GraphQL endpoint as VOD metadata, and only play with the signed
+//! access token appended.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::twitch::{CLIENT_ID, GQL_URL};
+use super::{ResolvedVod, VodResolver};
+use crate::error::{PlatformError, PlatformResult};
+
+/// Persisted query the Twitch web player uses for a clip's access token
+const CLIP_ACCESS_QUERY: &str = "VideoAccessToken_Clip";
+const CLIP_ACCESS_HASH: &str = "36b89d2507fce29e5ca551df756d27c1cfe079e2609642b4390aa4c35796eb11";
+
+pub struct TwitchClipResolver {
+    client: Client,
+}
+
+impl TwitchClipResolver {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+        }
+    }
+
+    /// Check whether the URL points to a Twitch clip
+    pub(crate) fn is_clip_url(url: &str) -> bool {
+        Self::extract_slug(url).is_some()
+    }
+
+    /// Extract the clip slug from URL
+    fn extract_slug(url: &str) -> Option<String> {
+        let re =
+            regex::Regex::new(r"(?:clips\.twitch\.tv/|twitch\.tv/[^/?#]+/clip/)([\w-]+)").ok()?;
+        re.captures(url)
+            .map(|c| c[1].to_string())
+            .filter(|slug| slug != "embed")
+    }
+
+    /// Fetch the clip's renditions and access token from Twitch GraphQL API
+    async fn fetch_clip(&self, slug: &str) -> PlatformResult<ClipData> {
+        let query = ClipQuery {
+            operation_name: CLIP_ACCESS_QUERY,
+            variables: ClipVariables { slug },
+            extensions: QueryExtensions {
+                persisted_query: PersistedQuery {
+                    version: 1,
+                    sha256_hash: CLIP_ACCESS_HASH,
+                },
+            },
+        };
+
+        let response = self
+            .client
+            .post(GQL_URL)
+            .header("Client-Id", CLIENT_ID)
+            .json(&query)
+            .send()
+            .await
+            .map_err(|e| PlatformError::ApiError(e.to_string()))?;
+
+        let body: ClipResponse = response
+            .json()
+            .await
+            .map_err(|e| PlatformError::ParseError(e.to_string()))?;
+
+        body.data
+            .clip
+            .ok_or_else(|| PlatformError::VodNotFound(slug.to_string()))
+    }
+
+    /// Signed URL of the clip's best rendition
+    fn direct_url(clip: &ClipData) -> PlatformResult<String> {
+        let token = clip
+            .playback_access_token
+            .as_ref()
+            .ok_or_else(|| PlatformError::ParseError("No clip access token".to_string()))?;
+
+        // Height, then frame rate
+        let best = clip
+            .video_qualities
+            .iter()
+            .max_by(|a, b| {
+                let height = |q: &ClipQuality| q.quality.parse::<u32>().unwrap_or(0);
+                height(a)
+                    .cmp(&height(b))
+                    .then(a.frame_rate.total_cmp(&b.frame_rate))
+            })
+            .ok_or(PlatformError::NoValidQuality)?;
+
+        log::info!("[Twitch] Found clip quality: {}p", best.quality);
+        Ok(format!(
+            "{}?sig={}&token={}",
+            best.source_url,
+            urlencoding::encode(&token.signature),
+            urlencoding::encode(&token.value)
+        ))
+    }
+}
+
+impl Default for TwitchClipResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl VodResolver for TwitchClipResolver {
+    fn can_handle(&self, url: &str) -> bool {
+        Self::is_clip_url(url)
+    }
+
+    async fn resolve(&self, url: &str) -> PlatformResult<ResolvedVod> {
+        let slug =
+            Self::extract_slug(url).ok_or_else(|| PlatformError::InvalidUrl(url.to_string()))?;
+
+        log::info!("[Twitch] Resolving clip {}", slug);
+
+        let clip = self.fetch_clip(&slug).await?;
+        Ok(ResolvedVod {
+            url: Self::direct_url(&clip)?,
+            is_hls: false,
+            recorded_until: None,
+        })
+    }
+}
+
+// ============ GraphQL Types ============
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClipQuery<'a> {
+    operation_name: &'a str,
+    variables: ClipVariables<'a>,
+    extensions: QueryExtensions<'a>,
+}
+
+#[derive(Serialize)]
+struct ClipVariables<'a> {
+    slug: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryExtensions<'a> {
+    persisted_query: PersistedQuery<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedQuery<'a> {
+    version: u32,
+    sha256_hash: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ClipResponse {
+    data: ClipResponseData,
+}
+
+#[derive(Deserialize)]
+struct ClipResponseData {
+    clip: Option<ClipData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClipData {
+    playback_access_token: Option<AccessToken>,
+    #[serde(default)]
+    video_qualities: Vec<ClipQuality>,
+}
+
+#[derive(Deserialize)]
+struct AccessToken {
+    signature: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClipQuality {
+    #[serde(default)]
+    frame_rate: f64,
+    quality: String,
+    #[serde(rename = "sourceURL")]
+    source_url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_slug() {
+        assert_eq!(
+            TwitchClipResolver::extract_slug("https://clips.twitch.tv/FunnyClip-Ab_12"),
+            Some("FunnyClip-Ab_12".to_string())
+        );
+        assert_eq!(
+            TwitchClipResolver::extract_slug("https://www.twitch.tv/streamer/clip/FunnyClip?x=1"),
+            Some("FunnyClip".to_string())
+        );
+        assert_eq!(
+            TwitchClipResolver::extract_slug("https://www.twitch.tv/videos/123456789"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_direct_url() {
+        let clip: ClipData = serde_json::from_str(
+            r#"{
+                "playbackAccessToken": {"signature": "abc", "value": "{\"clip\":1}"},
+                "videoQualities": [
+                    {"frameRate": 30, "quality": "480", "sourceURL": "https://cdn/480.mp4"},
+                    {"frameRate": 60, "quality": "1080", "sourceURL": "https://cdn/1080.mp4"},
+                    {"frameRate": 30, "quality": "1080", "sourceURL": "https://cdn/1080-30.mp4"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            TwitchClipResolver::direct_url(&clip).unwrap(),
+            "https://cdn/1080.mp4?sig=abc&token=%7B%22clip%22%3A1%7D"
+        );
+    }
+}
@@ -1,7 +1,13 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
 
 use super::{ResolvedVod, VodResolver};
-use crate::error::PlatformResult;
+use crate::binaries::get_binary_manager;
+use crate::error::{PlatformError, PlatformResult};
 
 /// YouTube resolver - delegates to yt-dlp for actual resolution
 /// We just pass through the URL since yt-dlp handles YouTube natively
@@ -12,7 +18,7 @@ impl YoutubeResolver {
         Self
     }
 
-    fn is_youtube_url(url: &str) -> bool {
+    pub(crate) fn is_youtube_url(url: &str) -> bool {
         url.contains("youtube.com") || url.contains("youtu.be")
     }
 }
@@ -37,8 +43,77 @@ impl VodResolver for YoutubeResolver {
         Ok(ResolvedVod {
             url: url.to_string(),
             is_hls: false, // yt-dlp will handle the format
+            recorded_until: None,
+        })
+    }
+
+    async fn resolve_audio(&self, url: &str) -> PlatformResult<ResolvedVod> {
+        // Direct URL of the best audio-only format, so ffmpeg can seek into it
+        let stdout = run_ytdlp(&["--no-playlist", "-f", "bestaudio", "-g", url]).await?;
+        let audio_url = stdout
+            .lines()
+            .next()
+            .map(str::trim)
+            .filter(|line| line.starts_with("http"))
+            .ok_or_else(|| PlatformError::ParseError("No audio stream URL".to_string()))?;
+
+        log::info!("[YouTube] Resolved audio-only stream for {}", url);
+        Ok(ResolvedVod {
+            url: audio_url.to_string(),
+            is_hls: audio_url.contains(".m3u8"),
+            recorded_until: None,
         })
     }
+
+    async fn recorded_at(&self, url: &str) -> PlatformResult<Option<DateTime<Utc>>> {
+        // release_timestamp is the actual start of a livestream; uploads only have timestamp
+        let stdout = run_ytdlp(&[
+            "--skip-download",
+            "--no-warnings",
+            "--print",
+            "%(release_timestamp,timestamp)s",
+            url,
+        ])
+        .await?;
+
+        Ok(parse_timestamp(&stdout))
+    }
+}
+
+/// Run yt-dlp and return its stdout
+async fn run_ytdlp(args: &[&str]) -> PlatformResult<String> {
+    let ytdlp_path = get_binary_manager()
+        .ytdlp_path()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|| "yt-dlp".to_string());
+
+    let mut cmd = Command::new(ytdlp_path);
+    cmd.args(args);
+    cmd.stdin(std::process::Stdio::null());
+    #[cfg(target_os = "windows")]
+    cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| PlatformError::ApiError(format!("Failed to run yt-dlp: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(PlatformError::ApiError(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parse a unix timestamp printed by yt-dlp ("NA" when unavailable)
+fn parse_timestamp(output: &str) -> Option<DateTime<Utc>> {
+    output
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs as i64, 0))
 }
 
 #[cfg(test)]
@@ -53,4 +128,13 @@ mod tests {
         assert!(resolver.can_handle("https://youtu.be/abc123"));
         assert!(!resolver.can_handle("https://twitch.tv/videos/123"));
     }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(
+            parse_timestamp("1700000000\n").map(|t| t.timestamp()),
+            Some(1700000000)
+        );
+        assert_eq!(parse_timestamp("NA\n"), None);
+    }
 }
@@ -0,0 +1,253 @@
+//! Project archives for moving or sharing a project.
+//!
+//! An archive is a zip of the project folder: `project.json` and, optionally,
+//! the exported clips and montages. With a password every entry is AES-256
+//! encrypted (WinZip AE-2, which 7-Zip and most unzip tools open), so archives
+//! of scrim footage can be sent over untrusted channels. Entry names stay
+//! readable; only the contents are encrypted.
+
+use serde::Serialize;
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+
+use zip::result::ZipError;
+use zip::write::SimpleFileOptions;
+use zip::{AesMode, CompressionMethod, ZipArchive, ZipWriter};
+
+use super::ProjectFile;
+use crate::config::get_config;
+use crate::error::{NoxError, Result};
+use crate::manifest;
+use crate::scratch::ScratchDir;
+
+const PROJECT_FILE: &str = "project.json";
+
+/// What an archive export wrote
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveSummary {
+    pub path: String,
+    pub files: usize,
+    pub encrypted: bool,
+}
+
+/// Zip a project to `dest`, encrypting it when a password is given
+pub fn export_archive(
+    project_name: &str,
+    dest: &Path,
+    password: Option<&str>,
+    include_media: bool,
+) -> Result<ArchiveSummary> {
+    let project_dir = get_config().project_dir(project_name);
+    if !project_dir.join(PROJECT_FILE).is_file() {
+        return Err(NoxError::Config(format!(
+            "Project not found: {}",
+            project_name
+        )));
+    }
+
+    let mut files = vec![project_dir.join(PROJECT_FILE)];
+    if include_media {
+        files.extend(manifest::delivery_files(&project_dir));
+    }
+
+    // Write next to the destination and rename, so a failed export leaves nothing behind
+    let partial = dest.with_extension("zip.part");
+    let result = write_zip(&partial, &project_dir, &files, password);
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+    std::fs::rename(&partial, dest)?;
+
+    log::info!(
+        "Archived project {} ({} files{}) to {:?}",
+        project_name,
+        files.len(),
+        if password.is_some() {
+            ", encrypted"
+        } else {
+            ""
+        },
+        dest
+    );
+
+    Ok(ArchiveSummary {
+        path: dest.to_string_lossy().to_string(),
+        files: files.len(),
+        encrypted: password.is_some(),
+    })
+}
+
+fn write_zip(dest: &Path, base: &Path, files: &[PathBuf], password: Option<&str>) -> Result<()> {
+    let mut zip = ZipWriter::new(File::create(dest)?);
+
+    for path in files {
+        let name = path
+            .strip_prefix(base)
+            .unwrap_or(path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let size = std::fs::metadata(path)?.len();
+
+        // Media is already compressed; deflating it only costs time
+        let method = if name == PROJECT_FILE {
+            CompressionMethod::Deflated
+        } else {
+            CompressionMethod::Stored
+        };
+        let options = SimpleFileOptions::default()
+            .compression_method(method)
+            .large_file(size >= u32::MAX as u64);
+        let options = match password {
+            Some(password) => options.with_aes_encryption(AesMode::Aes256, password),
+            None => options,
+        };
+
+        zip.start_file(name, options).map_err(zip_error)?;
+        std::io::copy(&mut File::open(path)?, &mut zip)?;
+    }
+
+    zip.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+/// Unpack an archive into a new project folder, returning the project name
+pub fn import_archive(archive_path: &Path, password: Option<&str>) -> Result<String> {
+    let mut archive = ZipArchive::new(File::open(archive_path)?).map_err(zip_error)?;
+
+    let project: ProjectFile = {
+        let index = archive
+            .index_for_name(PROJECT_FILE)
+            .ok_or_else(|| NoxError::Config("Archive has no project.json".to_string()))?;
+        let entry = open_entry(&mut archive, index, password)?;
+        serde_json::from_reader(entry)
+            .map_err(|e| NoxError::Config(format!("Failed to parse project.json: {}", e)))?
+    };
+
+    let name = project.name.clone();
+    if !is_folder_name(&name) {
+        return Err(NoxError::Config(format!(
+            "Invalid project name in archive: {}",
+            name
+        )));
+    }
+
+    let config = get_config();
+    let project_dir = config.project_dir(&name);
+    if project_dir.exists() {
+        return Err(NoxError::Config(format!(
+            "A project named {} already exists",
+            name
+        )));
+    }
+
+    // Unpack into scratch first so a wrong password or truncated file can't
+    // leave a half-imported project
+    std::fs::create_dir_all(&config.output_dir)?;
+    let scratch = ScratchDir::new_in(&config.output_dir, "import")?;
+    let unpacked = scratch.join("project");
+
+    for index in 0..archive.len() {
+        let mut entry = open_entry(&mut archive, index, password)?;
+        // Reject absolute and `..` entry names
+        let Some(relative) = entry.enclosed_name() else {
+            log::warn!("Skipping unsafe archive entry {}", entry.name());
+            continue;
+        };
+
+        let target = unpacked.join(relative);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::io::copy(&mut entry, &mut File::create(&target)?)?;
+    }
+
+    std::fs::rename(&unpacked, &project_dir)?;
+    log::info!("Imported project {} from {:?}", name, archive_path);
+
+    Ok(name)
+}
+
+fn open_entry<'a>(
+    archive: &'a mut ZipArchive<File>,
+    index: usize,
+    password: Option<&str>,
+) -> Result<zip::read::ZipFile<'a>> {
+    match password {
+        Some(password) => archive.by_index_decrypt(index, password.as_bytes()),
+        None => archive.by_index(index),
+    }
+    .map_err(zip_error)
+}
+
+/// A project name usable as a single folder name
+fn is_folder_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(components.next(), Some(Component::Normal(_)))
+        && components.next().is_none()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\'])
+}
+
+fn zip_error(err: ZipError) -> NoxError {
+    match err {
+        ZipError::Io(e) => NoxError::from(e),
+        ZipError::InvalidPassword => NoxError::Config("Wrong archive password".to_string()),
+        ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED) => {
+            NoxError::Config("Archive is password-protected".to_string())
+        }
+        e => NoxError::Config(format!("Invalid project archive: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_folder_name() {
+        assert!(is_folder_name("Scrim vs Team A"));
+        assert!(!is_folder_name("../escape"));
+        assert!(!is_folder_name("a/b"));
+        assert!(!is_folder_name(".hidden"));
+        assert!(!is_folder_name(""));
+    }
+
+    #[test]
+    fn test_encrypted_zip_roundtrip() {
+        let dir = std::env::temp_dir().join("nox-archive-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("clips")).unwrap();
+        std::fs::write(dir.join(PROJECT_FILE), b"{}").unwrap();
+        std::fs::write(dir.join("clips").join("a.mp4"), b"clip").unwrap();
+
+        let zip_path = dir.join("out.zip");
+        let files = [dir.join(PROJECT_FILE), dir.join("clips").join("a.mp4")];
+        write_zip(&zip_path, &dir, &files, Some("secret")).unwrap();
+
+        let mut archive = ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        let index = archive.index_for_name("clips/a.mp4").unwrap();
+        let err = open_entry(&mut archive, index, None).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Config error: Archive is password-protected"
+        );
+        assert!(open_entry(&mut archive, index, Some("wrong")).is_err());
+
+        let mut content = String::new();
+        std::io::Read::read_to_string(
+            &mut open_entry(&mut archive, index, Some("secret")).unwrap(),
+            &mut content,
+        )
+        .unwrap();
+        assert_eq!(content, "clip");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,275 @@
+//! Migration of legacy clip folder layouts.
+//!
+//! Early versions wrote clips straight into `<project>/clips/` instead of
+//! `<project>/clips/<streamer>/`, so export skipping and clip status didn't see
+//! them. On startup each project's top-level clips are matched back to a
+//! streamer (by action id prefix and streamer name) and moved into place.
+//! Files that can't be attributed or moved stay where they are and are
+//! reported; one failure doesn't stop the others from being migrated.
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use super::schema::{ActionInfo, ClipStatus, ProjectFile, StreamerInfo};
+use crate::config::get_config;
+use crate::error::Result;
+use crate::export::Container;
+
+/// Outcome of migrating one project's clips folder
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+    pub project_name: String,
+    /// Files moved, as `"old name -> streamer/new name"`
+    pub moved: Vec<String>,
+    /// Files left in place because no single streamer matched (or the target exists)
+    pub unresolved: Vec<String>,
+    /// Files that couldn't be moved, as `"name: error"` (or the project's error)
+    pub failed: Vec<String>,
+}
+
+impl MigrationReport {
+    fn new(project_name: &str) -> Self {
+        Self {
+            project_name: project_name.to_string(),
+            moved: Vec::new(),
+            unresolved: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+}
+
+/// Reports from the startup migration
+static LAST_REPORTS: OnceLock<Vec<MigrationReport>> = OnceLock::new();
+
+/// Migrate every project in the work directory, once per run
+pub fn migrate_all() -> &'static [MigrationReport] {
+    LAST_REPORTS.get_or_init(|| {
+        let projects = match super::list_projects() {
+            Ok(projects) => projects,
+            Err(e) => {
+                log::warn!("[Migrate] Failed to list projects: {}", e);
+                return Vec::new();
+            }
+        };
+
+        projects
+            .iter()
+            .filter_map(|name| match migrate_project(name) {
+                Ok(report) => report,
+                Err(e) => {
+                    log::warn!("[Migrate] Failed to migrate {}: {}", name, e);
+                    let mut report = MigrationReport::new(name);
+                    report.failed.push(e.to_string());
+                    Some(report)
+                }
+            })
+            .collect()
+    })
+}
+
+/// Move a project's top-level clips into streamer subfolders
+///
+/// Returns None if the project has no legacy clips.
+pub fn migrate_project(project_name: &str) -> Result<Option<MigrationReport>> {
+    let config = get_config();
+    let clips_dir = config.clips_dir(project_name);
+    if !clips_dir.exists() {
+        return Ok(None);
+    }
+
+    let legacy: Vec<String> = fs::read_dir(&clips_dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "mp4"))
+        .filter_map(|path| path.file_name().map(|n| n.to_string_lossy().to_string()))
+        // Hidden files are temp/scratch leftovers, not clips
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+
+    if legacy.is_empty() {
+        return Ok(None);
+    }
+
+    let Some(project) = super::load_project(project_name)? else {
+        return Ok(None);
+    };
+
+    let mut report = MigrationReport::new(project_name);
+
+    for filename in legacy {
+        let Some((action, streamer)) = attribute_clip(&filename, &project) else {
+            report.unresolved.push(filename);
+            continue;
+        };
+
+        let target_name =
+            crate::commands::generate_filename(&action.id, &action.name, Container::Mp4);
+        let target_dir = match config.ensure_streamer_clips_dir(project_name, &streamer.name) {
+            Ok(dir) => dir,
+            Err(e) => {
+                log::warn!("[Migrate] Failed to create folder for {}: {}", filename, e);
+                report.failed.push(format!("{}: {}", filename, e));
+                continue;
+            }
+        };
+        let target = target_dir.join(&target_name);
+
+        if target.exists() {
+            report.unresolved.push(filename);
+            continue;
+        }
+
+        if let Err(e) = move_file(&clips_dir.join(&filename), &target) {
+            log::warn!("[Migrate] Failed to move {}: {}", filename, e);
+            report.failed.push(format!("{}: {}", filename, e));
+            continue;
+        }
+        report.moved.push(format!(
+            "{} -> {}/{}",
+            filename,
+            target_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            target_name
+        ));
+    }
+
+    log::info!(
+        "[Migrate] {}: moved {} legacy clip(s), {} left in place, {} failed",
+        project_name,
+        report.moved.len(),
+        report.unresolved.len(),
+        report.failed.len()
+    );
+
+    Ok(Some(report))
+}
+
+/// Rename, falling back to copy + delete across volumes
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+/// Work out which action and streamer a legacy clip file belongs to
+///
+/// The action comes from the 6-character id prefix of the filename. The
+/// streamer is the one whose name appears in the filename, or the only
+/// streamer with a clip for that action.
+fn attribute_clip<'a>(
+    filename: &str,
+    project: &'a ProjectFile,
+) -> Option<(&'a ActionInfo, &'a StreamerInfo)> {
+    let prefix = filename.split('_').next()?;
+    let action = project
+        .actions
+        .iter()
+        .find(|a| !prefix.is_empty() && a.id.starts_with(prefix))?;
+
+    let candidates: Vec<&StreamerInfo> = action
+        .clips
+        .iter()
+        .filter(|clip| !matches!(clip.status, ClipStatus::Excluded))
+        .filter_map(|clip| project.streamers.iter().find(|s| s.id == clip.streamer_id))
+        .collect();
+
+    let lower = filename.to_lowercase();
+    let named: Vec<&StreamerInfo> = candidates
+        .iter()
+        .copied()
+        .filter(|s| !s.name.is_empty() && lower.contains(&s.name.to_lowercase()))
+        .collect();
+
+    match (named.as_slice(), candidates.as_slice()) {
+        ([streamer], _) => Some((action, streamer)),
+        ([], [streamer]) => Some((action, streamer)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::schema::ClipInfo;
+    use chrono::DateTime;
+
+    fn streamer(id: &str, name: &str) -> StreamerInfo {
+        StreamerInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            vod_url: String::new(),
+            platform: "twitch".to_string(),
+            sync_offset: None,
+            is_reference: false,
+            color: String::new(),
+            global_streamer_id: None,
+            recorded_at: None,
+            crop: None,
+        }
+    }
+
+    fn clip(action_id: &str, streamer_id: &str) -> ClipInfo {
+        ClipInfo {
+            id: format!("{}-{}", action_id, streamer_id),
+            action_id: action_id.to_string(),
+            streamer_id: streamer_id.to_string(),
+            in_point: -3.0,
+            out_point: 7.0,
+            status: ClipStatus::Included,
+        }
+    }
+
+    fn action(id: &str, streamer_ids: &[&str]) -> ActionInfo {
+        ActionInfo {
+            id: id.to_string(),
+            name: "Ace".to_string(),
+            game_time: 60.0,
+            clips: streamer_ids.iter().map(|s| clip(id, s)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_attribute_clip() {
+        let project = ProjectFile {
+            version: 1,
+            id: "p".to_string(),
+            name: "p".to_string(),
+            created_at: DateTime::UNIX_EPOCH,
+            updated_at: DateTime::UNIX_EPOCH,
+            game_type: None,
+            custom_game_id: None,
+            reference_streamer_id: "a".to_string(),
+            game_start_time: Some(0.0),
+            streamers: vec![streamer("a", "Alpha"), streamer("b", "Bravo")],
+            actions: vec![
+                action("abc123def", &["a"]),
+                action("xyz789uvw", &["a", "b"]),
+            ],
+            max_clip_duration: None,
+            filters: None,
+            timezone: None,
+            montages: Vec::new(),
+        };
+
+        let streamer_of = |filename: &str| {
+            attribute_clip(filename, &project).map(|(a, s)| (a.id.as_str(), s.name.as_str()))
+        };
+
+        // Only one streamer has a clip for the action
+        assert_eq!(streamer_of("abc123_Ace.mp4"), Some(("abc123def", "Alpha")));
+        // Several streamers: the filename has to name one
+        assert_eq!(
+            streamer_of("xyz789_bravo_Ace.mp4"),
+            Some(("xyz789uvw", "Bravo"))
+        );
+        assert_eq!(streamer_of("xyz789_Ace.mp4"), None);
+        // Unknown action
+        assert_eq!(streamer_of("zzz000_Ace.mp4"), None);
+    }
+}
@@ -1,7 +1,12 @@
+pub mod archive;
+pub mod migrate;
 mod schema;
+pub mod timeline;
 
 // Re-export schema types
-pub use schema::ProjectFile;
+pub use schema::{
+    ActionInfo, ClipInfo, ClipStatus, MontagePreset, MontageRender, ProjectFile, StreamerInfo,
+};
 
 use crate::config::get_config;
 use crate::error::{NoxError, Result};
@@ -29,8 +34,61 @@ pub fn load_project(project_name: &str) -> Result<Option<ProjectFile>> {
     Ok(Some(project))
 }
 
+/// Load a project file that has to exist
+fn load_existing(project_name: &str) -> Result<ProjectFile> {
+    load_project(project_name)?
+        .ok_or_else(|| NoxError::Config(format!("Project not found: {}", project_name)))
+}
+
+/// Insert a saved montage, or replace the one with the same ID
+pub fn save_montage(project_name: &str, mut montage: MontagePreset) -> Result<()> {
+    let mut project = load_existing(project_name)?;
+
+    match project.montages.iter_mut().find(|m| m.id == montage.id) {
+        Some(existing) => {
+            // The last render stays, so the list can tell it's out of date
+            montage.last_render = montage.last_render.or(existing.last_render.take());
+            *existing = montage;
+        }
+        None => project.montages.push(montage),
+    }
+
+    save_project(project_name, &project)
+}
+
+/// Remember the latest render of a saved montage
+pub fn record_montage_render(project_name: &str, id: &str, render: MontageRender) -> Result<()> {
+    let mut project = load_existing(project_name)?;
+    let montage = project
+        .montages
+        .iter_mut()
+        .find(|m| m.id == id)
+        .ok_or_else(|| NoxError::Config(format!("Saved montage not found: {}", id)))?;
+    montage.last_render = Some(render);
+    save_project(project_name, &project)
+}
+
+/// Delete a saved montage by ID (no-op if it doesn't exist)
+pub fn delete_montage(project_name: &str, id: &str) -> Result<()> {
+    let mut project = load_existing(project_name)?;
+    let before = project.montages.len();
+    project.montages.retain(|m| m.id != id);
+
+    if project.montages.len() != before {
+        save_project(project_name, &project)?;
+    }
+
+    Ok(())
+}
+
 /// Save a project file
 pub fn save_project(project_name: &str, project: &ProjectFile) -> Result<()> {
+    if let Some(tz) = &project.timezone {
+        if crate::localtime::parse_timezone(tz).is_none() {
+            return Err(NoxError::Config(format!("Unknown timezone: {}", tz)));
+        }
+    }
+
     let config = get_config();
     let project_dir = config.project_dir(project_name);
 
@@ -3,10 +3,14 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::export::{ClipFilters, CropRect};
+use crate::montage::MontageExportInput;
+
 // Current schema version removed as unused (was 1)
 
 /// Project file schema (project.json)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
 pub struct ProjectFile {
     /// Schema version for migrations
     pub version: u32,
@@ -33,10 +37,51 @@ pub struct ProjectFile {
     pub streamers: Vec<StreamerInfo>,
     /// Actions/highlights in this project
     pub actions: Vec<ActionInfo>,
+    /// Maximum clip duration in seconds (overrides the default 1 hour limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_clip_duration: Option<f64>,
+    /// Default filters for every re-encoded clip of the project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filters: Option<ClipFilters>,
+    /// IANA timezone of the event (e.g. "Europe/Berlin"), for times shown in exports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Saved montage setups, to reopen and render again
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub montages: Vec<MontagePreset>,
+}
+
+/// A montage setup saved in the project (clips, order, overlay, music,
+/// transitions)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct MontagePreset {
+    /// Unique ID within the project
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Last time the montage was saved
+    pub updated_at: DateTime<Utc>,
+    /// Export input, with clip paths also stored relative to the work dir
+    pub config: MontageExportInput,
+    /// Last time the saved montage was rendered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_render: Option<MontageRender>,
+}
+
+/// A render of a saved montage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
+pub struct MontageRender {
+    pub output_path: String,
+    pub rendered_at: DateTime<Utc>,
+    /// Hash of the saved config that was rendered
+    pub settings_hash: String,
 }
 
 /// Streamer information
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
 pub struct StreamerInfo {
     /// Unique streamer ID within the project
     pub id: String,
@@ -56,10 +101,17 @@ pub struct StreamerInfo {
     /// Global streamer ID from database (for linking)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub global_streamer_id: Option<String>,
+    /// Wall-clock time the VOD recording started (from platform metadata)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorded_at: Option<DateTime<Utc>>,
+    /// Crop applied to this streamer's clips (e.g. to cut away the stream layout)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crop: Option<CropRect>,
 }
 
 /// Action/highlight information
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
 pub struct ActionInfo {
     /// Unique action ID
     pub id: String,
@@ -73,6 +125,7 @@ pub struct ActionInfo {
 
 /// Clip information for a specific streamer
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
 pub struct ClipInfo {
     /// Unique clip ID
     pub id: String,
@@ -91,6 +144,7 @@ pub struct ClipInfo {
 /// Clip status
 /// Clip status
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(export))]
 #[serde(rename_all = "snake_case")]
 pub enum ClipStatus {
     #[default]
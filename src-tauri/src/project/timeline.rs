@@ -0,0 +1,150 @@
+//! Conversion of wall-clock times (live markers, chat messages) into VOD times.
+//!
+//! Each streamer's VOD offset is the time elapsed since its `recorded_at`;
+//! the project game time comes from the reference streamer's offset.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::ProjectFile;
+use crate::markers::LiveMarker;
+
+/// Offset of a wall-clock time into one streamer's VOD
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamerOffset {
+    pub streamer_id: String,
+    /// Seconds from the start of the VOD
+    pub offset: f64,
+}
+
+/// A live marker converted into project times
+#[derive(Debug, Clone, Serialize)]
+pub struct ConvertedMarker {
+    pub marker_id: String,
+    pub label: String,
+    pub at: DateTime<Utc>,
+    /// Game time for a project action (None without reference recorded_at or game start)
+    pub game_time: Option<f64>,
+    /// VOD offsets for every streamer whose VOD covers the marker
+    pub offsets: Vec<StreamerOffset>,
+}
+
+/// Seconds into a VOD recorded from `recorded_at` (None if `at` is before the VOD starts)
+pub fn vod_offset(recorded_at: DateTime<Utc>, at: DateTime<Utc>) -> Option<f64> {
+    let millis = (at - recorded_at).num_milliseconds();
+    if millis < 0 {
+        None
+    } else {
+        Some(millis as f64 / 1000.0)
+    }
+}
+
+/// Convert wall-clock markers into per-streamer VOD offsets and project game times
+pub fn convert_markers(project: &ProjectFile, markers: &[LiveMarker]) -> Vec<ConvertedMarker> {
+    let reference = project
+        .streamers
+        .iter()
+        .find(|s| s.id == project.reference_streamer_id);
+
+    markers
+        .iter()
+        .map(|marker| {
+            let offsets = project
+                .streamers
+                .iter()
+                .filter_map(|s| {
+                    let offset = vod_offset(s.recorded_at?, marker.at)?;
+                    Some(StreamerOffset {
+                        streamer_id: s.id.clone(),
+                        offset,
+                    })
+                })
+                .collect();
+
+            let game_time = reference
+                .and_then(|r| r.recorded_at)
+                .and_then(|recorded_at| vod_offset(recorded_at, marker.at))
+                .zip(project.game_start_time)
+                .map(|(offset, game_start)| offset - game_start);
+
+            ConvertedMarker {
+                marker_id: marker.id.clone(),
+                label: marker.label.clone(),
+                at: marker.at,
+                game_time,
+                offsets,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::schema::StreamerInfo;
+
+    fn time(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    fn streamer(id: &str, recorded_at: Option<DateTime<Utc>>) -> StreamerInfo {
+        StreamerInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            vod_url: String::new(),
+            platform: "twitch".to_string(),
+            sync_offset: None,
+            is_reference: id == "ref",
+            color: String::new(),
+            global_streamer_id: None,
+            recorded_at,
+            crop: None,
+        }
+    }
+
+    #[test]
+    fn test_vod_offset() {
+        assert_eq!(vod_offset(time(0), time(90)), Some(90.0));
+        assert_eq!(vod_offset(time(100), time(90)), None);
+    }
+
+    #[test]
+    fn test_convert_markers() {
+        let project = ProjectFile {
+            version: 1,
+            id: "p".to_string(),
+            name: "p".to_string(),
+            created_at: time(0),
+            updated_at: time(0),
+            game_type: None,
+            custom_game_id: None,
+            reference_streamer_id: "ref".to_string(),
+            game_start_time: Some(300.0),
+            streamers: vec![
+                streamer("ref", Some(time(0))),
+                streamer("late", Some(time(1000))),
+                streamer("unknown", None),
+            ],
+            actions: vec![],
+            max_clip_duration: None,
+            filters: None,
+            timezone: None,
+            montages: Vec::new(),
+        };
+        let marker = LiveMarker {
+            id: "m".to_string(),
+            label: "ace".to_string(),
+            at: time(1200),
+        };
+
+        let converted = convert_markers(&project, &[marker]);
+        assert_eq!(converted[0].game_time, Some(900.0));
+
+        let offsets: Vec<_> = converted[0]
+            .offsets
+            .iter()
+            .map(|o| (o.streamer_id.as_str(), o.offset))
+            .collect();
+        assert_eq!(offsets, vec![("ref", 1200.0), ("late", 200.0)]);
+    }
+}
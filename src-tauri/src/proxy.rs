@@ -212,10 +212,12 @@ pub async fn start_proxy_server() {
         .allow_methods(Any)
         .allow_headers(Any);
 
+    // The scripting API has its own, localhost-only CORS policy
     let app = Router::new()
         .route("/proxy", get(proxy_handler))
+        .with_state(state)
         .layer(cors)
-        .with_state(state);
+        .merge(crate::api::router());
 
     let addr = format!("127.0.0.1:{}", port);
     
@@ -243,3 +245,8 @@ pub fn get_proxy_url(original_url: &str) -> String {
         urlencoding::encode(original_url)
     )
 }
+
+/// Base URL of the local scripting API
+pub fn get_api_base_url() -> String {
+    format!("http://localhost:{}/api", ACTIVE_PORT.load(Ordering::Relaxed))
+}
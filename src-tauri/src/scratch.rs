@@ -0,0 +1,153 @@
+//! Per-job scratch directories.
+//!
+//! Jobs that run at the same time (a UI export, an API export, a binary
+//! download) must not share temp file names. Each job gets its own
+//! directory, removed again when the job's [`ScratchDir`] is dropped.
+//! Directories a crashed or killed app never dropped are cleared by
+//! [`remove_stale`] when the next job or app start looks at the same place.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Counter that keeps scratch names unique within this process
+static NEXT_SCRATCH_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Age after which another process's scratch directory counts as abandoned
+const STALE_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A job's private scratch directory, deleted on drop
+#[derive(Debug)]
+pub struct ScratchDir {
+    path: PathBuf,
+}
+
+impl ScratchDir {
+    /// Create a scratch directory under the system temp dir
+    pub fn new(job: &str) -> std::io::Result<Self> {
+        Self::new_in(&temp_root(), job)
+    }
+
+    /// Create a scratch directory under `base`
+    ///
+    /// Use this for large files that should stay on the output volume.
+    pub fn new_in(base: &Path, job: &str) -> std::io::Result<Self> {
+        let path = base.join(scratch_name(job));
+        std::fs::create_dir_all(&path)?;
+        log::debug!("Created scratch dir {}", path.display());
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Path of a file inside the scratch directory
+    pub fn join(&self, name: &str) -> PathBuf {
+        self.path.join(name)
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.path) {
+            log::warn!(
+                "Failed to remove scratch dir {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Parent of the scratch directories created by [`ScratchDir::new`]
+pub fn temp_root() -> PathBuf {
+    std::env::temp_dir().join("nox")
+}
+
+/// Remove scratch directories under `base` that another process left behind
+///
+/// Only directories untouched for [`STALE_AFTER`] go, so a second running
+/// instance keeps its jobs.
+pub fn remove_stale(base: &Path) {
+    let Ok(entries) = std::fs::read_dir(base) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if scratch_pid(&name).is_none_or(|pid| pid == std::process::id()) {
+            continue;
+        }
+        let stale = entry
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age >= STALE_AFTER);
+        if stale && entry.path().is_dir() {
+            match std::fs::remove_dir_all(entry.path()) {
+                Ok(()) => log::info!("Removed stale scratch dir {}", entry.path().display()),
+                Err(e) => log::warn!(
+                    "Failed to remove stale scratch dir {}: {}",
+                    entry.path().display(),
+                    e
+                ),
+            }
+        }
+    }
+}
+
+/// Process that created a scratch directory, from its `.<job>-<pid>-<n>` name
+fn scratch_pid(name: &str) -> Option<u32> {
+    let mut parts = name.strip_prefix('.')?.rsplitn(3, '-');
+    parts.next()?.parse::<u64>().ok()?;
+    let pid = parts.next()?.parse().ok()?;
+    parts.next().filter(|job| !job.is_empty())?;
+    Some(pid)
+}
+
+/// `.<job>-<pid>-<n>`: hidden, and unique across processes and jobs
+fn scratch_name(job: &str) -> String {
+    let job: String = job
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!(
+        ".{}-{}-{}",
+        job,
+        std::process::id(),
+        NEXT_SCRATCH_ID.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scratch_dir_lifecycle() {
+        let base = std::env::temp_dir().join("nox-scratch-test");
+        let first = ScratchDir::new_in(&base, "export").unwrap();
+        let second = ScratchDir::new_in(&base, "export").unwrap();
+        assert_ne!(first.path(), second.path());
+        assert!(first.path().is_dir());
+
+        std::fs::write(first.join("part.mp4"), b"data").unwrap();
+        let path = first.path().to_path_buf();
+        drop(first);
+        assert!(!path.exists());
+        assert!(second.path().is_dir());
+    }
+
+    #[test]
+    fn test_scratch_name() {
+        assert!(scratch_name("ffmpeg download").starts_with(".ffmpeg_download-"));
+        assert_eq!(
+            scratch_pid(&scratch_name("export")),
+            Some(std::process::id())
+        );
+        assert_eq!(scratch_pid(".proxies"), None);
+        assert_eq!(scratch_pid("clip-12-3.mp4"), None);
+    }
+}
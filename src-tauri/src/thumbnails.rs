@@ -0,0 +1,288 @@
+//! Storyboard sprite sheets for hover-scrubbing.
+//!
+//! A source (exported clip or resolved VOD URL) is sampled every `interval`
+//! seconds. The frames are tiled into JPEG sheets of `columns` x `rows`
+//! tiles and cached on disk next to a `layout.json`, whose presence marks a
+//! complete set. Tile `n` (the frame at `n * interval`) sits on sheet
+//! `n / (columns * rows)`.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use crate::binaries::get_binary_manager;
+use crate::config::get_config;
+use crate::error::{ExportError, ExportResult};
+use crate::export::cache::{self, vod_key};
+use crate::export::FfmpegExporter;
+use crate::scratch::ScratchDir;
+
+/// Timeout for rendering all sheets of a source (sampling a VOD reads all of it)
+const THUMBNAIL_TIMEOUT: Duration = Duration::from_secs(1800);
+
+/// Tile size (16:9, sources with other ratios are letterboxed)
+const TILE_WIDTH: u32 = 160;
+const TILE_HEIGHT: u32 = 90;
+
+/// Tiles per sheet row / column
+const SHEET_COLUMNS: u32 = 10;
+const SHEET_ROWS: u32 = 10;
+
+/// Most tiles rendered for one source; longer sources get a longer interval
+const MAX_TILES: u32 = 600;
+
+/// Shortest sampling interval (seconds)
+const MIN_INTERVAL: f64 = 1.0;
+
+/// Largest size of the thumbnail cache (oldest sources go first)
+const MAX_CACHE_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// File that holds the layout of a complete cache entry
+const LAYOUT_FILE: &str = "layout.json";
+
+/// Where the tiles of a source are and how they're arranged
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThumbnailLayout {
+    /// Seconds between two tiles
+    pub interval: f64,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    /// Tiles per sheet row
+    pub columns: u32,
+    /// Tile rows per sheet
+    pub rows: u32,
+    /// Total number of tiles across all sheets
+    pub count: u32,
+    /// Sheet image paths, in order
+    pub sheets: Vec<String>,
+}
+
+impl ThumbnailLayout {
+    /// Layout for `duration` seconds sampled every `interval` (sheets not yet set)
+    fn new(duration: f64, interval: f64) -> Self {
+        Self {
+            interval,
+            tile_width: TILE_WIDTH,
+            tile_height: TILE_HEIGHT,
+            columns: SHEET_COLUMNS,
+            rows: SHEET_ROWS,
+            count: ((duration / interval).ceil() as u32).max(1),
+            sheets: Vec::new(),
+        }
+    }
+
+    /// Number of sheet images needed for all tiles
+    #[cfg(test)]
+    fn sheet_count(&self) -> u32 {
+        self.count.div_ceil(self.columns * self.rows)
+    }
+}
+
+/// Sampling interval for a source: the requested one, stretched to stay under
+/// [`MAX_TILES`]
+fn effective_interval(duration: f64, requested: Option<f64>) -> f64 {
+    let requested = requested.filter(|i| i.is_finite()).unwrap_or(MIN_INTERVAL);
+    requested.max(MIN_INTERVAL).max(duration / MAX_TILES as f64)
+}
+
+/// Render (or load from cache) the sprite sheets of a clip file or stream URL
+pub async fn get_thumbnails(source: &str, interval: Option<f64>) -> ExportResult<ThumbnailLayout> {
+    let duration = FfmpegExporter::new()
+        .probe_duration(Path::new(source))
+        .await?;
+    let interval = effective_interval(duration, interval);
+
+    let cache_root = get_config().thumbnail_cache_dir();
+    let entry_dir = cache_root.join(cache_key(source, interval));
+    if let Some(layout) = load_layout(&entry_dir) {
+        return Ok(layout);
+    }
+
+    std::fs::create_dir_all(&cache_root)
+        .map_err(|e| ExportError::OutputDir(format!("Failed to create thumbnail cache: {}", e)))?;
+    let scratch = ScratchDir::new_in(&cache_root, "thumbnails")
+        .map_err(|e| ExportError::OutputDir(format!("Failed to create scratch dir: {}", e)))?;
+
+    let mut layout = ThumbnailLayout::new(duration, interval);
+    log::info!(
+        "[Thumbnails] Rendering {} tiles every {:.1}s for {}",
+        layout.count,
+        interval,
+        source
+    );
+    render_sheets(source, &layout, scratch.path()).await?;
+
+    // Publish the finished set in one rename so readers never see half of it
+    let _ = std::fs::remove_dir_all(&entry_dir);
+    let rendered = scratch.join("sheets");
+    std::fs::rename(&rendered, &entry_dir)
+        .map_err(|e| ExportError::OutputDir(format!("Failed to store thumbnails: {}", e)))?;
+
+    layout.sheets = sheet_paths(&entry_dir);
+    let json = serde_json::to_string_pretty(&layout)
+        .map_err(|e| ExportError::OutputDir(format!("Failed to serialize layout: {}", e)))?;
+    std::fs::write(entry_dir.join(LAYOUT_FILE), json)
+        .map_err(|e| ExportError::OutputDir(format!("Failed to write layout: {}", e)))?;
+    cache::prune_dir(&cache_root, MAX_CACHE_BYTES);
+
+    Ok(layout)
+}
+
+/// Run ffmpeg once, writing `sheets/sheet_001.jpg`, ... into `dir`
+async fn render_sheets(source: &str, layout: &ThumbnailLayout, dir: &Path) -> ExportResult<()> {
+    let sheets_dir = dir.join("sheets");
+    std::fs::create_dir_all(&sheets_dir)
+        .map_err(|e| ExportError::OutputDir(format!("Failed to create sheets dir: {}", e)))?;
+
+    let mut cmd = build_sheet_command(&ffmpeg_path(), source, layout, &sheets_dir);
+    cmd.kill_on_drop(true);
+
+    let output = timeout(THUMBNAIL_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| {
+            ExportError::Timeout(format!(
+                "Thumbnail rendering timed out after {} seconds",
+                THUMBNAIL_TIMEOUT.as_secs()
+            ))
+        })?
+        .map_err(|e| ExportError::Ffmpeg(format!("Failed to run FFmpeg: {}", e)))?;
+
+    if !output.status.success() || !sheets_dir.join(sheet_name(1)).exists() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::warn!("[Thumbnails] FFmpeg failed: {}", stderr.trim());
+        return Err(ExportError::Ffmpeg(format!(
+            "Failed to render thumbnails for {}",
+            source
+        )));
+    }
+
+    Ok(())
+}
+
+fn build_sheet_command(
+    ffmpeg: &str,
+    source: &str,
+    layout: &ThumbnailLayout,
+    sheets_dir: &Path,
+) -> Command {
+    let filter = format!(
+        "fps=1/{interval},scale={w}:{h}:force_original_aspect_ratio=decrease,\
+         pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,tile={cols}x{rows}",
+        interval = layout.interval,
+        w = layout.tile_width,
+        h = layout.tile_height,
+        cols = layout.columns,
+        rows = layout.rows,
+    );
+
+    let mut cmd = Command::new(ffmpeg);
+    cmd.args([
+        "-y",
+        "-skip_frame",
+        "nokey",
+        "-i",
+        source,
+        "-an",
+        "-vf",
+        &filter,
+        "-q:v",
+        "5",
+    ]);
+    cmd.arg(sheets_dir.join("sheet_%03d.jpg"));
+    cmd.stdin(std::process::Stdio::null());
+    cmd.stderr(std::process::Stdio::piped());
+    cmd.stdout(std::process::Stdio::null());
+    #[cfg(target_os = "windows")]
+    cmd.as_std_mut().creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    cmd
+}
+
+fn ffmpeg_path() -> String {
+    get_binary_manager()
+        .ffmpeg_path()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|| "ffmpeg".to_string())
+}
+
+/// Cached layout of a complete entry, if its sheets are all still there
+fn load_layout(entry_dir: &Path) -> Option<ThumbnailLayout> {
+    let content = std::fs::read_to_string(entry_dir.join(LAYOUT_FILE)).ok()?;
+    let layout: ThumbnailLayout = serde_json::from_str(&content).ok()?;
+    layout
+        .sheets
+        .iter()
+        .all(|sheet| Path::new(sheet).is_file())
+        .then_some(layout)
+}
+
+/// Cache key of a source: local files include their modification time
+fn cache_key(source: &str, interval: f64) -> String {
+    let modified = std::fs::metadata(source)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    format!(
+        "{}_{}",
+        vod_key(&format!("{}@{}", source, modified)),
+        (interval * 1000.0).round() as u64
+    )
+}
+
+/// ffmpeg's image2 numbering starts at 1
+fn sheet_name(index: u32) -> String {
+    format!("sheet_{:03}.jpg", index)
+}
+
+/// Sheets ffmpeg actually wrote (the last one may hold fewer tiles than planned)
+fn sheet_paths(dir: &Path) -> Vec<String> {
+    (1..)
+        .map(|i| dir.join(sheet_name(i)))
+        .take_while(|path| path.is_file())
+        .map(|path| path.to_string_lossy().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_interval() {
+        assert_eq!(effective_interval(30.0, None), MIN_INTERVAL);
+        assert_eq!(effective_interval(30.0, Some(0.1)), MIN_INTERVAL);
+        assert_eq!(effective_interval(30.0, Some(5.0)), 5.0);
+        // A 3 hour VOD at 1s would be 10800 tiles
+        assert_eq!(effective_interval(10_800.0, Some(1.0)), 18.0);
+    }
+
+    #[test]
+    fn test_layout() {
+        let layout = ThumbnailLayout::new(250.5, 1.0);
+        assert_eq!(layout.count, 251);
+        assert_eq!(layout.sheet_count(), 3);
+        assert_eq!(ThumbnailLayout::new(0.2, 1.0).count, 1);
+    }
+
+    #[test]
+    fn test_sheet_command() {
+        let layout = ThumbnailLayout::new(60.0, 2.0);
+        let cmd = build_sheet_command("ffmpeg", "/clips/a.mp4", &layout, Path::new("/cache"));
+        assert_eq!(
+            crate::export::argv(&cmd).join(" "),
+            concat!(
+                "-y -skip_frame nokey -i /clips/a.mp4 -an -vf ",
+                "fps=1/2,scale=160:90:force_original_aspect_ratio=decrease,",
+                "pad=160:90:(ow-iw)/2:(oh-ih)/2,tile=10x10 ",
+                "-q:v 5 /cache/sheet_%03d.jpg"
+            )
+        );
+    }
+}
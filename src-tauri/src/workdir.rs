@@ -0,0 +1,117 @@
+//! Path handling for work directories on local, mapped and network drives.
+//!
+//! Windows hands out paths in several spellings: `\\server\share` (UNC),
+//! `Z:\` (mapped drive) and the verbatim forms `\\?\C:\` / `\\?\UNC\server\share`
+//! that `canonicalize` returns. Explorer and FFmpeg's concat demuxer don't
+//! accept the verbatim forms, so paths are stored and passed on in the plain form.
+
+use std::path::{Path, PathBuf};
+
+/// Verbatim prefix for network paths (`\\?\UNC\server\share`)
+const VERBATIM_UNC_PREFIX: &str = r"\\?\UNC\";
+
+/// Verbatim prefix for local paths (`\\?\C:\`)
+const VERBATIM_PREFIX: &str = r"\\?\";
+
+/// Strip a Windows verbatim (`\\?\`) prefix, keeping UNC paths as `\\server\share`
+///
+/// Paths that only make sense in verbatim form (e.g. `\\?\Volume{...}`) are
+/// returned unchanged.
+pub fn simplify(path: &Path) -> PathBuf {
+    let s = path.to_string_lossy();
+
+    if let Some(rest) = s.strip_prefix(VERBATIM_UNC_PREFIX) {
+        return PathBuf::from(format!(r"\\{}", rest));
+    }
+
+    if let Some(rest) = s.strip_prefix(VERBATIM_PREFIX) {
+        let bytes = rest.as_bytes();
+        let is_drive = bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+        if is_drive {
+            return PathBuf::from(rest);
+        }
+    }
+
+    path.to_path_buf()
+}
+
+/// Whether a path points at a network share (UNC, in plain or verbatim form)
+///
+/// Mapped drive letters can't be told apart from local disks without asking
+/// the OS, so they count as local here.
+pub fn is_network_path(path: &Path) -> bool {
+    let s = path.to_string_lossy();
+    s.starts_with(VERBATIM_UNC_PREFIX)
+        || (!s.starts_with(VERBATIM_PREFIX) && (s.starts_with(r"\\") || s.starts_with("//")))
+}
+
+/// Normalize a user-chosen work directory before storing it
+///
+/// Makes the path absolute without resolving symlinks or mapped drives (a
+/// `Z:\` the user picked stays `Z:\` instead of becoming its UNC target).
+pub fn normalize_work_dir(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    simplify(&absolute)
+}
+
+/// Open a folder in the system file explorer
+pub fn open_folder(path: &Path) -> std::io::Result<()> {
+    let path = simplify(path);
+
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("explorer").arg(&path).spawn()?;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open").arg(&path).spawn()?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        std::process::Command::new("xdg-open").arg(&path).spawn()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplify() {
+        assert_eq!(
+            simplify(Path::new(r"\\?\UNC\nas\media\Nox")),
+            PathBuf::from(r"\\nas\media\Nox")
+        );
+        assert_eq!(
+            simplify(Path::new(r"\\?\C:\Users\me\Nox")),
+            PathBuf::from(r"C:\Users\me\Nox")
+        );
+        assert_eq!(
+            simplify(Path::new(r"\\?\Volume{1234}\Nox")),
+            PathBuf::from(r"\\?\Volume{1234}\Nox")
+        );
+        assert_eq!(
+            simplify(Path::new(r"\\nas\media\Nox")),
+            PathBuf::from(r"\\nas\media\Nox")
+        );
+        assert_eq!(simplify(Path::new(r"Z:\Nox")), PathBuf::from(r"Z:\Nox"));
+        assert_eq!(
+            simplify(Path::new("/home/me/Nox")),
+            PathBuf::from("/home/me/Nox")
+        );
+    }
+
+    #[test]
+    fn test_is_network_path() {
+        assert!(is_network_path(Path::new(r"\\nas\media\Nox")));
+        assert!(is_network_path(Path::new("//nas/media/Nox")));
+        assert!(is_network_path(Path::new(r"\\?\UNC\nas\media\Nox")));
+        assert!(!is_network_path(Path::new(r"\\?\C:\Nox")));
+        assert!(!is_network_path(Path::new(r"Z:\Nox")));
+        assert!(!is_network_path(Path::new("/mnt/nas/Nox")));
+    }
+}
@@ -3,6 +3,7 @@ import { Flag, Plus, Trash2, Edit3, Check, X, Clock, UserPlus, ExternalLink } fr
 import { useProjectStore, useEditorStore, useStreamerDatabaseStore } from '@/stores';
 import { formatTime, type GlobalStreamer } from '@/types';
 import { openUrl } from '@tauri-apps/plugin-opener';
+import { renameActionClips } from '@/services/export';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import { Badge } from '@/components/ui/badge';
@@ -63,8 +64,14 @@ export function ActionMode() {
   };
 
   const handleSaveEdit = (actionId: string) => {
-    if (editingName.trim()) {
-      updateAction(actionId, { name: editingName.trim() });
+    const newName = editingName.trim();
+    const oldName = project.actions.find((a) => a.id === actionId)?.name;
+    if (newName) {
+      updateAction(actionId, { name: newName });
+      // Exported clip filenames embed the action name
+      if (oldName && oldName !== newName) {
+        renameActionClips(project.name, actionId, oldName, newName).catch(console.error);
+      }
     }
     setEditingActionId(null);
     setEditingName('');
@@ -170,6 +170,28 @@ export function PreparationMode() {
                 </div>
               )}
 
+              {result.muted.length > 0 && (
+                <div className="bg-yellow-500/10 text-yellow-600 text-sm p-3 rounded-md max-h-32 overflow-auto">
+                  <div className="font-medium">Son coupé par Twitch (DMCA) :</div>
+                  {result.muted.map((clip) => (
+                    <div key={clip.index}>
+                      {clip.filename} ({Math.round(clip.muted_seconds)}s muets)
+                    </div>
+                  ))}
+                </div>
+              )}
+
+              {result.long.length > 0 && (
+                <div className="bg-yellow-500/10 text-yellow-600 text-sm p-3 rounded-md max-h-32 overflow-auto">
+                  <div className="font-medium">Au-delà de la limite par défaut (1 h) :</div>
+                  {result.long.map((clip) => (
+                    <div key={clip.index}>
+                      {clip.filename} ({Math.round(clip.duration / 60)} min)
+                    </div>
+                  ))}
+                </div>
+              )}
+
               <div className="text-xs text-muted-foreground truncate">
                 Dossier: {result.output_dir}
               </div>
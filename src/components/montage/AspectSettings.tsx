@@ -0,0 +1,225 @@
+import { Button } from '@/components/ui/button';
+import { Label } from '@/components/ui/label';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { useMontageStore } from '@/stores';
+import type {
+  MontageAspect,
+  MontageContainer,
+  MontageFit,
+  MontageOutputFormat,
+} from '@/types';
+
+const ASPECTS: { value: MontageAspect; label: string }[] = [
+  { value: 'landscape', label: 'Paysage (16:9)' },
+  { value: 'portrait', label: 'Vertical (9:16) — Shorts, TikTok' },
+  { value: 'square', label: 'Carré (1:1)' },
+];
+
+const OUTPUT_FORMATS: { value: string; label: string; format: MontageOutputFormat | null }[] = [
+  { value: '1080p60', label: '1080p 60 ips', format: { width: 1920, height: 1080, fps: 60 } },
+  { value: '1080p30', label: '1080p 30 ips', format: { width: 1920, height: 1080, fps: 30 } },
+  { value: '720p60', label: '720p 60 ips', format: { width: 1280, height: 720, fps: 60 } },
+  { value: '720p30', label: '720p 30 ips', format: { width: 1280, height: 720, fps: 30 } },
+  { value: 'source', label: 'Identique aux clips', format: null },
+];
+
+const CONTAINERS: { value: MontageContainer; label: string }[] = [
+  { value: 'mp4', label: 'MP4 (H.264)' },
+  { value: 'mkv', label: 'MKV (archivage)' },
+  { value: 'webm', label: 'WebM (VP9, web)' },
+  { value: 'mp3', label: 'MP3 (audio seul)' },
+  { value: 'm4a', label: 'M4A (audio seul)' },
+];
+
+const QUALITIES: { value: string; label: string; crf?: number }[] = [
+  { value: 'default', label: 'Réglages de l\'application' },
+  { value: '18', label: 'Haute (CRF 18)', crf: 18 },
+  { value: '23', label: 'Standard (CRF 23)', crf: 23 },
+  { value: '28', label: 'Légère (CRF 28)', crf: 28 },
+];
+
+const BITRATE_CAPS: { value: string; label: string; kbps?: number }[] = [
+  { value: 'none', label: 'Aucune limite' },
+  { value: '4000', label: '4 Mb/s', kbps: 4000 },
+  { value: '8000', label: '8 Mb/s', kbps: 8000 },
+  { value: '12000', label: '12 Mb/s', kbps: 12000 },
+  { value: '20000', label: '20 Mb/s', kbps: 20000 },
+];
+
+function formatKey(format: MontageOutputFormat | null): string {
+  return format ? `${format.height}p${format.fps}` : 'source';
+}
+
+export function AspectSettings() {
+  const aspect = useMontageStore((s) => s.aspect);
+  const fit = useMontageStore((s) => s.fit);
+  const setAspect = useMontageStore((s) => s.setAspect);
+  const outputFormat = useMontageStore((s) => s.outputFormat);
+  const setOutputFormat = useMontageStore((s) => s.setOutputFormat);
+  const renderMode = useMontageStore((s) => s.renderMode);
+  const setRenderMode = useMontageStore((s) => s.setRenderMode);
+  const encoding = useMontageStore((s) => s.encoding);
+  const setEncoding = useMontageStore((s) => s.setEncoding);
+  const container = useMontageStore((s) => s.container);
+  const setContainer = useMontageStore((s) => s.setContainer);
+
+  return (
+    <div className="space-y-4">
+      <div>
+        <Label className="text-sm font-medium">Format de sortie</Label>
+        <Select value={aspect} onValueChange={(value) => setAspect(value as MontageAspect, fit)}>
+          <SelectTrigger className="mt-2">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {ASPECTS.map((a) => (
+              <SelectItem key={a.value} value={a.value}>
+                {a.label}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </div>
+
+      <div>
+        <Label className="text-sm font-medium">Résolution</Label>
+        <Select
+          value={formatKey(outputFormat)}
+          onValueChange={(value) =>
+            setOutputFormat(OUTPUT_FORMATS.find((f) => f.value === value)?.format ?? null)
+          }
+        >
+          <SelectTrigger className="mt-2">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {OUTPUT_FORMATS.map((f) => (
+              <SelectItem key={f.value} value={f.value}>
+                {f.label}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+        <p className="text-xs text-muted-foreground mt-1">
+          Tous les clips sont convertis à ce format pour s'enchaîner sans accroc
+        </p>
+      </div>
+
+      {aspect !== 'landscape' && (
+        <div>
+          <Label className="text-sm font-medium">Cadrage</Label>
+          <Select value={fit} onValueChange={(value) => setAspect(aspect, value as MontageFit)}>
+            <SelectTrigger className="mt-2">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="crop">Recadrer au centre</SelectItem>
+              <SelectItem value="pad">Image entière avec bandes noires</SelectItem>
+            </SelectContent>
+          </Select>
+          <p className="text-xs text-muted-foreground mt-1">
+            Les textes restent hors des zones couvertes par l'interface des plateformes
+          </p>
+        </div>
+      )}
+
+      <div>
+        <Label className="text-sm font-medium">Type de fichier</Label>
+        <Select
+          value={container}
+          onValueChange={(value) => setContainer(value as MontageContainer)}
+        >
+          <SelectTrigger className="mt-2">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {CONTAINERS.map((c) => (
+              <SelectItem key={c.value} value={c.value}>
+                {c.label}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+        {container === 'webm' && (
+          <p className="text-xs text-muted-foreground mt-1">
+            L'encodage VP9 est plus lent et n'utilise pas l'accélération matérielle
+          </p>
+        )}
+        {(container === 'mp3' || container === 'm4a') && (
+          <p className="text-xs text-muted-foreground mt-1">
+            Son des clips enchaîné en fondu, sans vidéo ni cartons : rapide pour réécouter
+            les comms
+          </p>
+        )}
+      </div>
+
+      <div className="grid grid-cols-2 gap-2">
+        <div>
+          <Label className="text-sm font-medium">Qualité</Label>
+          <Select
+            value={encoding.crf?.toString() ?? 'default'}
+            onValueChange={(value) =>
+              setEncoding({ ...encoding, crf: QUALITIES.find((q) => q.value === value)?.crf })
+            }
+          >
+            <SelectTrigger className="mt-2">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {QUALITIES.map((q) => (
+                <SelectItem key={q.value} value={q.value}>
+                  {q.label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </div>
+        <div>
+          <Label className="text-sm font-medium">Débit maximum</Label>
+          <Select
+            value={encoding.max_bitrate_kbps?.toString() ?? 'none'}
+            onValueChange={(value) =>
+              setEncoding({
+                ...encoding,
+                max_bitrate_kbps: BITRATE_CAPS.find((b) => b.value === value)?.kbps,
+              })
+            }
+          >
+            <SelectTrigger className="mt-2">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {BITRATE_CAPS.map((b) => (
+                <SelectItem key={b.value} value={b.value}>
+                  {b.label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </div>
+        <p className="col-span-2 text-xs text-muted-foreground">
+          Le débit maximum respecte les limites d'envoi des plateformes
+        </p>
+      </div>
+
+      <div>
+        <Button
+          variant={renderMode === 'staged' ? 'default' : 'outline'}
+          size="sm"
+          onClick={() => setRenderMode(renderMode === 'staged' ? 'single' : 'staged')}
+        >
+          Rendu clip par clip
+        </Button>
+        <p className="text-xs text-muted-foreground mt-1">
+          Plus lent, mais un export interrompu reprend là où il s'est arrêté
+        </p>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,131 @@
+import { Film, ListOrdered, Type, X } from 'lucide-react';
+
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { pickVideoFile } from '@/services';
+import { useMontageStore } from '@/stores';
+
+const DEFAULT_CARD_DURATION = 3;
+
+function BookendRow({ which, label }: { which: 'intro' | 'outro'; label: string }) {
+  const bookend = useMontageStore((s) => s[which]);
+  const setBookend = useMontageStore((s) => s.setBookend);
+
+  const handlePickVideo = async () => {
+    const path = await pickVideoFile();
+    if (path) {
+      setBookend(which, { type: 'file', path });
+    }
+  };
+
+  return (
+    <div className="space-y-2">
+      <div className="flex items-center justify-between">
+        <Label className="text-sm font-medium">{label}</Label>
+        {bookend && (
+          <Button variant="ghost" size="icon" className="h-6 w-6" onClick={() => setBookend(which, null)}>
+            <X className="h-3 w-3" />
+          </Button>
+        )}
+      </div>
+
+      {!bookend && (
+        <div className="flex gap-2">
+          <Button variant="outline" size="sm" className="gap-2 flex-1" onClick={handlePickVideo}>
+            <Film className="h-4 w-4" />
+            Vidéo
+          </Button>
+          <Button
+            variant="outline"
+            size="sm"
+            className="gap-2 flex-1"
+            onClick={() =>
+              setBookend(which, { type: 'card', text: '', duration: DEFAULT_CARD_DURATION })
+            }
+          >
+            <Type className="h-4 w-4" />
+            Carton titre
+          </Button>
+          {which === 'outro' && (
+            <Button
+              variant="outline"
+              size="sm"
+              className="gap-2 flex-1"
+              onClick={() =>
+                setBookend(which, { type: 'credits', title: '', duration: DEFAULT_CARD_DURATION })
+              }
+            >
+              <ListOrdered className="h-4 w-4" />
+              Générique
+            </Button>
+          )}
+        </div>
+      )}
+
+      {bookend?.type === 'file' && (
+        <Button variant="outline" size="sm" className="gap-2 w-full min-w-0" onClick={handlePickVideo}>
+          <Film className="h-4 w-4 shrink-0" />
+          <span className="truncate">{bookend.path.split(/[\\/]/).pop()}</span>
+        </Button>
+      )}
+
+      {bookend?.type === 'credits' && (
+        <div className="flex gap-2">
+          <Input
+            value={bookend.title}
+            placeholder="Titre (nom du projet par défaut)"
+            onChange={(e) => setBookend(which, { ...bookend, title: e.target.value })}
+          />
+          <Input
+            type="number"
+            className="w-20"
+            min={0.5}
+            max={30}
+            step={0.5}
+            value={bookend.duration}
+            onChange={(e) =>
+              setBookend(which, {
+                ...bookend,
+                duration: Math.max(0.5, Math.min(30, Number(e.target.value) || DEFAULT_CARD_DURATION)),
+              })
+            }
+          />
+        </div>
+      )}
+
+      {bookend?.type === 'card' && (
+        <div className="flex gap-2">
+          <Input
+            value={bookend.text}
+            placeholder="Texte du carton"
+            onChange={(e) => setBookend(which, { ...bookend, text: e.target.value })}
+          />
+          <Input
+            type="number"
+            className="w-20"
+            min={0.5}
+            max={30}
+            step={0.5}
+            value={bookend.duration}
+            onChange={(e) =>
+              setBookend(which, {
+                ...bookend,
+                duration: Math.max(0.5, Math.min(30, Number(e.target.value) || DEFAULT_CARD_DURATION)),
+              })
+            }
+          />
+        </div>
+      )}
+    </div>
+  );
+}
+
+export function BookendSettings() {
+  return (
+    <div className="space-y-4">
+      <BookendRow which="intro" label="Intro" />
+      <BookendRow which="outro" label="Outro" />
+    </div>
+  );
+}
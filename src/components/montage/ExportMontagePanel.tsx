@@ -1,5 +1,6 @@
 import { useState, useMemo } from 'react';
-import { Download, Folder, Loader2, Check, X, Layers, Users, Zap } from 'lucide-react';
+import { convertFileSrc } from '@tauri-apps/api/core';
+import { Download, Eye, Folder, Loader2, Check, X, Layers, Users, Zap } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card';
 import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs';
@@ -28,6 +29,12 @@ export function ExportMontagePanel({ projectName }: ExportMontagePanelProps) {
   const batchExport = useMontageStore((s) => s.batchExport);
   const openMontagesFolder = useMontageStore((s) => s.openMontagesFolder);
   const clearExportResult = useMontageStore((s) => s.clearExportResult);
+  const cancelExport = useMontageStore((s) => s.cancelExport);
+  const exportCancelled = useMontageStore((s) => s.exportCancelled);
+  const isPreviewing = useMontageStore((s) => s.isPreviewing);
+  const previewPath = useMontageStore((s) => s.previewPath);
+  const previewMontage = useMontageStore((s) => s.previewMontage);
+  const clearPreview = useMontageStore((s) => s.clearPreview);
 
   // Local State
   const [mode, setMode] = useState<'single' | 'batch'>('single');
@@ -80,7 +87,9 @@ export function ExportMontagePanel({ projectName }: ExportMontagePanelProps) {
             <>
               <p className="text-sm text-muted-foreground">
                 {mode === 'single' 
-                  ? `Durée: ${formatDuration(exportResult.duration)}`
+                  ? `Durée: ${formatDuration(exportResult.duration)}${
+                      exportResult.encoder ? ` · ${exportResult.encoder}` : ''
+                    }`
                   : `${batchGroups.length} fichiers exportés`
                 }
               </p>
@@ -142,6 +151,33 @@ export function ExportMontagePanel({ projectName }: ExportMontagePanelProps) {
                {clipCount} clip{clipCount !== 1 ? 's' : ''} • {formatDuration(totalDuration)}
             </span>
           </div>
+
+          <Button
+            variant="outline"
+            className="w-full"
+            onClick={() => previewMontage(projectName)}
+            disabled={clipCount === 0 || isExporting || isPreviewing}
+          >
+            {isPreviewing ? (
+              <Loader2 className="h-4 w-4 mr-2 animate-spin" />
+            ) : (
+              <Eye className="h-4 w-4 mr-2" />
+            )}
+            {isPreviewing ? 'Rendu de l\'aperçu...' : 'Aperçu rapide (480p)'}
+          </Button>
+
+          {previewPath && (
+            <div className="space-y-2">
+              <video
+                src={convertFileSrc(previewPath)}
+                controls
+                className="w-full rounded-md bg-black"
+              />
+              <Button variant="ghost" size="sm" onClick={clearPreview}>
+                Fermer l'aperçu
+              </Button>
+            </div>
+          )}
         </TabsContent>
 
         <TabsContent value="batch" className="space-y-4">
@@ -222,6 +258,18 @@ export function ExportMontagePanel({ projectName }: ExportMontagePanelProps) {
             </>
           )}
         </Button>
+
+        {isExporting && (
+          <Button
+            variant="outline"
+            className="w-full"
+            onClick={cancelExport}
+            disabled={exportCancelled}
+          >
+            <X className="h-4 w-4 mr-2" />
+            Annuler l'export
+          </Button>
+        )}
       </div>
     </div>
   );
@@ -1,7 +1,7 @@
 import { useState, useMemo } from 'react';
 import { Plus, Film, Settings2, Trash2 } from 'lucide-react';
 import { Button } from '@/components/ui/button';
-import { useMontageStore, selectTotalDuration } from '@/stores';
+import { useMontageStore, selectTotalDuration, clipLength } from '@/stores';
 import { BulkAddModal } from './BulkAddModal';
 import { cn } from '@/lib/utils';
 import {
@@ -101,7 +101,7 @@ function SortableClip({ clip, widthPercent, color, isActive, onClick }: Sortable
       )}
     >
       <p className="text-xs font-medium text-white truncate">{clip.actionName}</p>
-      <p className="text-[10px] text-white/70 truncate">{clip.streamerName} • {formatDuration(clip.duration)}</p>
+      <p className="text-[10px] text-white/70 truncate">{clip.streamerName} • {formatDuration(clipLength(clip))}</p>
     </div>
   );
 }
@@ -241,7 +241,7 @@ export function MontageTimeline({ projectName, onOpenSettings }: MontageTimeline
                       <SortableClip
                         key={clip.id}
                         clip={clip}
-                        widthPercent={(clip.duration / totalDuration) * 100}
+                        widthPercent={(clipLength(clip) / totalDuration) * 100}
                         color={getStreamerColor(clip.streamerName, uniqueStreamers)}
                         isActive={index === currentClipIndex}
                         onClick={() => seekToClip(index)}
@@ -0,0 +1,125 @@
+import { useEffect, useState } from 'react';
+import { Music, X } from 'lucide-react';
+
+import { Button } from '@/components/ui/button';
+import { Label } from '@/components/ui/label';
+import { Slider } from '@/components/ui/slider';
+import { analyzeMusicBeats, pickMusicFile } from '@/services';
+import { useMontageStore } from '@/stores';
+import type { BeatAnalysis } from '@/types';
+
+const DEFAULT_MUSIC_VOLUME = 0.3;
+
+/** Streaming platforms' usual loudness */
+const DEFAULT_LOUDNESS_LUFS = -14;
+
+export function MusicSettings() {
+  const music = useMontageStore((s) => s.music);
+  const setMusic = useMontageStore((s) => s.setMusic);
+  const loudnessLufs = useMontageStore((s) => s.loudnessLufs);
+  const setLoudnessLufs = useMontageStore((s) => s.setLoudnessLufs);
+  const [beats, setBeats] = useState<BeatAnalysis | null>(null);
+  const [beatsError, setBeatsError] = useState<string | null>(null);
+
+  // Beats are shown while cutting on them is on
+  const musicPath = music?.path;
+  const snapToBeats = music?.snap_to_beats ?? false;
+  useEffect(() => {
+    setBeats(null);
+    setBeatsError(null);
+    if (!musicPath || !snapToBeats) return;
+    let cancelled = false;
+    analyzeMusicBeats(musicPath)
+      .then((analysis) => !cancelled && setBeats(analysis))
+      .catch((e) => !cancelled && setBeatsError(String(e)));
+    return () => {
+      cancelled = true;
+    };
+  }, [musicPath, snapToBeats]);
+
+  const tempo = beats?.bpm ? `${Math.round(beats.bpm)} BPM, ` : '';
+
+  const handlePick = async () => {
+    const path = await pickMusicFile();
+    if (path) {
+      setMusic({
+        path,
+        volume: music?.volume ?? DEFAULT_MUSIC_VOLUME,
+        snap_to_beats: music?.snap_to_beats,
+      });
+    }
+  };
+
+  return (
+    <div className="space-y-4">
+      <div>
+        <Button
+          variant={loudnessLufs !== null ? 'default' : 'outline'}
+          size="sm"
+          onClick={() => setLoudnessLufs(loudnessLufs !== null ? null : DEFAULT_LOUDNESS_LUFS)}
+        >
+          Égaliser le volume des clips
+        </Button>
+        <p className="text-xs text-muted-foreground mt-1">
+          Chaque clip est ramené à {DEFAULT_LOUDNESS_LUFS} LUFS pour éviter les sauts de volume
+          entre streamers
+        </p>
+      </div>
+
+      <div className="flex items-center gap-2">
+        <Button variant="outline" size="sm" className="gap-2 min-w-0 flex-1" onClick={handlePick}>
+          <Music className="h-4 w-4 shrink-0" />
+          <span className="truncate">
+            {music ? music.path.split(/[\\/]/).pop() : 'Choisir une musique'}
+          </span>
+        </Button>
+        {music && (
+          <Button variant="ghost" size="icon" className="h-8 w-8" onClick={() => setMusic(null)}>
+            <X className="h-4 w-4" />
+          </Button>
+        )}
+      </div>
+
+      {music && (
+        <div>
+          <div className="flex items-center justify-between">
+            <Label className="text-sm font-medium">Volume de la musique</Label>
+            <span className="text-sm text-muted-foreground">
+              {Math.round(music.volume * 100)}%
+            </span>
+          </div>
+          <Slider
+            value={[music.volume]}
+            onValueChange={(values) => setMusic({ ...music, volume: values[0] })}
+            min={0}
+            max={1}
+            step={0.05}
+            className="mt-2"
+          />
+          <p className="text-xs text-muted-foreground mt-1">
+            La musique baisse automatiquement quand les clips ont du son
+          </p>
+        </div>
+      )}
+
+      {music && (
+        <div>
+          <Button
+            variant={snapToBeats ? 'default' : 'outline'}
+            size="sm"
+            onClick={() => setMusic({ ...music, snap_to_beats: snapToBeats ? undefined : true })}
+          >
+            Couper sur le rythme
+          </Button>
+          <p className="text-xs text-muted-foreground mt-1">
+            {beatsError
+              ? `Analyse impossible : ${beatsError}`
+              : beats
+                ? `${tempo}${beats.beats.length} coupes possibles`
+                : 'Chaque clip est raccourci pour que la coupe tombe sur un temps de la musique'}
+          </p>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -1,5 +1,6 @@
 import { Eye } from 'lucide-react';
 import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
 import { Label } from '@/components/ui/label';
 import {
   Select,
@@ -10,16 +11,33 @@ import {
 } from '@/components/ui/select';
 import { Slider } from '@/components/ui/slider';
 import { useMontageStore } from '@/stores';
-import { type OverlayPosition } from '@/types';
+import { type OverlayAnimation, type OverlayConfig, type OverlayPosition } from '@/types';
+
+const DEFAULT_OUTLINE = { width: 3, color: '000000' };
+const DEFAULT_SHADOW = { x: 2, y: 2, color: '000000@0.6' };
 
 export function OverlayEditor() {
   const overlay = useMontageStore((s) => s.overlay);
   const setOverlay = useMontageStore((s) => s.setOverlay);
   const usePreset = useMontageStore((s) => s.usePreset);
 
-  const handlePositionChange = (position: OverlayPosition) => {
+  const update = (changes: Partial<OverlayConfig>) => {
+    if (overlay) {
+      setOverlay({ ...overlay, ...changes });
+    }
+  };
+
+  const handlePositionChange = (value: string) => {
+    if (value === 'custom') {
+      update({ customPosition: overlay?.customPosition ?? { x: 50, y: 90 } });
+    } else {
+      update({ position: value as OverlayPosition, customPosition: undefined });
+    }
+  };
+
+  const handleAnimationChange = (animation: OverlayAnimation) => {
     if (overlay) {
-      setOverlay({ ...overlay, position });
+      setOverlay({ ...overlay, animation });
     }
   };
 
@@ -41,6 +59,13 @@ export function OverlayEditor() {
           >
             Nom du streamer
           </Button>
+          <Button
+            variant={overlay?.type === 'custom_text' ? 'default' : 'outline'}
+            size="sm"
+            onClick={() => usePreset('counter')}
+          >
+            Compteur
+          </Button>
           <Button
             variant="outline"
             size="sm"
@@ -51,13 +76,28 @@ export function OverlayEditor() {
         </div>
       </div>
 
+      {overlay?.type === 'custom_text' && (
+        <div>
+          <Label className="text-sm font-medium">Texte</Label>
+          <Input
+            value={overlay.text ?? ''}
+            onChange={(e) => update({ text: e.target.value })}
+            className="mt-2"
+          />
+          <p className="text-xs text-muted-foreground mt-1">
+            {'{index}/{total}'} : numéro du clip, {'{count}'} : nombre de clips de la même action
+            (ex. compteur de kills), {'{streamer}'}, {'{action}'}
+          </p>
+        </div>
+      )}
+
       {overlay && (
         <>
           <div>
             <Label className="text-sm font-medium">Position</Label>
             <Select
-              value={overlay.position}
-              onValueChange={(v) => handlePositionChange(v as OverlayPosition)}
+              value={overlay.customPosition ? 'custom' : overlay.position}
+              onValueChange={handlePositionChange}
             >
               <SelectTrigger className="mt-2">
                 <SelectValue />
@@ -67,6 +107,51 @@ export function OverlayEditor() {
                 <SelectItem value="top-right">Haut droite</SelectItem>
                 <SelectItem value="bottom-left">Bas gauche</SelectItem>
                 <SelectItem value="bottom-right">Bas droite</SelectItem>
+                <SelectItem value="custom">Libre</SelectItem>
+              </SelectContent>
+            </Select>
+          </div>
+
+          {overlay.customPosition && (
+            <div className="grid grid-cols-2 gap-4">
+              {(['x', 'y'] as const).map((axis) => (
+                <div key={axis}>
+                  <div className="flex items-center justify-between">
+                    <Label className="text-sm font-medium">
+                      {axis === 'x' ? 'Horizontal' : 'Vertical'}
+                    </Label>
+                    <span className="text-sm text-muted-foreground">
+                      {overlay.customPosition![axis]}%
+                    </span>
+                  </div>
+                  <Slider
+                    value={[overlay.customPosition![axis]]}
+                    onValueChange={(v) =>
+                      update({ customPosition: { ...overlay.customPosition!, [axis]: v[0] } })
+                    }
+                    min={0}
+                    max={100}
+                    step={1}
+                    className="mt-2"
+                  />
+                </div>
+              ))}
+            </div>
+          )}
+
+          <div>
+            <Label className="text-sm font-medium">Animation</Label>
+            <Select
+              value={overlay.animation ?? 'none'}
+              onValueChange={(v) => handleAnimationChange(v as OverlayAnimation)}
+            >
+              <SelectTrigger className="mt-2">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="none">Aucune</SelectItem>
+                <SelectItem value="fade">Fondu entrée/sortie</SelectItem>
+                <SelectItem value="slide-in">Glissement</SelectItem>
               </SelectContent>
             </Select>
           </div>
@@ -86,6 +171,43 @@ export function OverlayEditor() {
             />
           </div>
 
+          <div>
+            <Label className="text-sm font-medium">Style</Label>
+            <div className="flex gap-2 mt-2">
+              <Button
+                variant={overlay.outline ? 'default' : 'outline'}
+                size="sm"
+                onClick={() => update({ outline: overlay.outline ? undefined : DEFAULT_OUTLINE })}
+              >
+                Contour
+              </Button>
+              <Button
+                variant={overlay.shadow ? 'default' : 'outline'}
+                size="sm"
+                onClick={() => update({ shadow: overlay.shadow ? undefined : DEFAULT_SHADOW })}
+              >
+                Ombre
+              </Button>
+            </div>
+          </div>
+
+          {overlay.outline && (
+            <div>
+              <div className="flex items-center justify-between">
+                <Label className="text-sm font-medium">Épaisseur du contour</Label>
+                <span className="text-sm text-muted-foreground">{overlay.outline.width}px</span>
+              </div>
+              <Slider
+                value={[overlay.outline.width]}
+                onValueChange={(v) => update({ outline: { ...overlay.outline!, width: v[0] } })}
+                min={1}
+                max={8}
+                step={1}
+                className="mt-2"
+              />
+            </div>
+          )}
+
           {/* Preview */}
           <div>
             <Label className="text-sm font-medium flex items-center gap-2">
@@ -98,10 +220,23 @@ export function OverlayEditor() {
                 className="absolute px-3 py-1.5 bg-black/70 text-white rounded"
                 style={{
                   fontSize: `${Math.min(overlay.fontSize / 2, 20)}px`,
-                  ...(overlay.position === 'top-left' && { top: 8, left: 8 }),
-                  ...(overlay.position === 'top-right' && { top: 8, right: 8 }),
-                  ...(overlay.position === 'bottom-left' && { bottom: 8, left: 8 }),
-                  ...(overlay.position === 'bottom-right' && { bottom: 8, right: 8 }),
+                  ...(overlay.outline && {
+                    WebkitTextStroke: `${overlay.outline.width / 2}px #${overlay.outline.color}`,
+                  }),
+                  ...(overlay.shadow && {
+                    textShadow: `${overlay.shadow.x}px ${overlay.shadow.y}px rgba(0,0,0,0.6)`,
+                  }),
+                  ...(overlay.customPosition
+                    ? {
+                        left: `${overlay.customPosition.x}%`,
+                        top: `${overlay.customPosition.y}%`,
+                        transform: `translate(-${overlay.customPosition.x}%, -${overlay.customPosition.y}%)`,
+                      }
+                    : {}),
+                  ...(!overlay.customPosition && overlay.position === 'top-left' && { top: 8, left: 8 }),
+                  ...(!overlay.customPosition && overlay.position === 'top-right' && { top: 8, right: 8 }),
+                  ...(!overlay.customPosition && overlay.position === 'bottom-left' && { bottom: 8, left: 8 }),
+                  ...(!overlay.customPosition && overlay.position === 'bottom-right' && { bottom: 8, right: 8 }),
                 }}
               >
                 {overlay.type === 'streamer_name' ? 'StreamerName' : overlay.text}
@@ -0,0 +1,100 @@
+import { Plus, X } from 'lucide-react';
+
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { useMontageStore } from '@/stores';
+
+const DEFAULT_SECTION_DURATION = 2;
+
+export function SectionSettings() {
+  const clips = useMontageStore((s) => s.clips);
+  const sections = useMontageStore((s) => s.sections);
+  const addSection = useMontageStore((s) => s.addSection);
+  const updateSection = useMontageStore((s) => s.updateSection);
+  const removeSection = useMontageStore((s) => s.removeSection);
+
+  if (clips.length === 0) {
+    return <p className="text-xs text-muted-foreground">Ajoutez des clips pour créer des sections</p>;
+  }
+
+  return (
+    <div className="space-y-3">
+      {sections.map((section) => (
+        <div key={section.id} className="space-y-2 rounded-md border p-2">
+          <div className="flex gap-2">
+            <Input
+              value={section.text}
+              placeholder="Round 13 – Ace"
+              onChange={(e) => updateSection(section.id, { text: e.target.value })}
+            />
+            <Button
+              variant="ghost"
+              size="icon"
+              className="h-9 w-9 shrink-0"
+              onClick={() => removeSection(section.id)}
+            >
+              <X className="h-4 w-4" />
+            </Button>
+          </div>
+          <div className="flex gap-2">
+            <Select
+              value={section.beforeClipId}
+              onValueChange={(beforeClipId) => updateSection(section.id, { beforeClipId })}
+            >
+              <SelectTrigger className="flex-1 min-w-0">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {clips.map((clip, i) => (
+                  <SelectItem key={clip.id} value={clip.id}>
+                    Avant #{i + 1} {clip.streamerName} – {clip.actionName}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            <Input
+              type="number"
+              className="w-16"
+              min={0.5}
+              max={30}
+              step={0.5}
+              value={section.duration}
+              onChange={(e) =>
+                updateSection(section.id, {
+                  duration: Math.max(0.5, Math.min(30, Number(e.target.value) || DEFAULT_SECTION_DURATION)),
+                })
+              }
+            />
+            <Input
+              type="color"
+              className="w-12 p-1"
+              value={`#${section.background_color ?? '000000'}`}
+              onChange={(e) =>
+                updateSection(section.id, { background_color: e.target.value.slice(1).toUpperCase() })
+              }
+            />
+          </div>
+        </div>
+      ))}
+
+      <Button
+        variant="outline"
+        size="sm"
+        className="gap-2 w-full"
+        onClick={() =>
+          addSection(clips[0].id, { text: '', duration: DEFAULT_SECTION_DURATION })
+        }
+      >
+        <Plus className="h-4 w-4" />
+        Ajouter une section
+      </Button>
+    </div>
+  );
+}
@@ -1,8 +1,30 @@
 import { useState, useRef } from 'react';
-import { Trash2, GripVertical, Film } from 'lucide-react';
+import {
+  Trash2,
+  GripVertical,
+  Film,
+  Eye,
+  EyeOff,
+  Volume2,
+  ZoomIn,
+  Snail,
+  Pause,
+} from 'lucide-react';
 import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
 import { cn } from '@/lib/utils';
-import type { MontageClip } from '@/types';
+import { useMontageStore, clipLength } from '@/stores';
+import type { ClipOverlay, MontageClip, SlowMotion } from '@/types';
+
+const ZOOMS = [1, 1.25, 1.5, 2];
+const SLOWMO_FACTORS = [1, 2, 3, 4];
 
 interface TimelineClipCardProps {
   clip: MontageClip;
@@ -27,6 +49,34 @@ export function TimelineClipCard({
 }: TimelineClipCardProps) {
   const [isHovered, setIsHovered] = useState(false);
   const cardRef = useRef<HTMLDivElement>(null);
+  const setClipOverlay = useMontageStore((s) => s.setClipOverlay);
+  const setClipVolume = useMontageStore((s) => s.setClipVolume);
+  const setClipTrim = useMontageStore((s) => s.setClipTrim);
+  const setClipFraming = useMontageStore((s) => s.setClipFraming);
+  const setClipEffects = useMontageStore((s) => s.setClipEffects);
+
+  // Empty or out-of-range fields clear that side of the trim
+  const parseTrim = (value: string): number | undefined => {
+    const seconds = Number(value);
+    return value === '' || !(seconds > 0) || seconds >= clip.duration ? undefined : seconds;
+  };
+  const overlayHidden = clip.overlay?.enabled === false;
+
+  // Slow motion range defaults to the whole (trimmed) clip
+  const playedLength =
+    Math.min(clip.trimEnd ?? clip.duration, clip.duration) - (clip.trimStart ?? 0);
+  const updateSlowmo = (changes: Partial<SlowMotion>) => {
+    const next = { start: 0, end: playedLength, factor: 2, ...clip.slowmo, ...changes };
+    const valid =
+      next.factor > 1 && next.start >= 0 && next.end > next.start && next.end <= playedLength;
+    setClipEffects(clip.id, valid ? next : undefined, clip.freeze);
+  };
+
+  const updateOverlay = (changes: Partial<ClipOverlay>) => {
+    const next = { ...clip.overlay, ...changes };
+    const isEmpty = next.enabled === undefined && !next.text && !next.position;
+    setClipOverlay(clip.id, isEmpty ? undefined : next);
+  };
 
   const formatDuration = (seconds: number) => {
     const mins = Math.floor(seconds / 60);
@@ -71,8 +121,136 @@ export function TimelineClipCard({
       <div className="flex-1 min-w-0">
         <p className="font-medium truncate">{clip.actionName}</p>
         <p className="text-sm text-muted-foreground truncate">
-          {clip.streamerName} • {formatDuration(clip.duration)}
+          {clip.streamerName} • {formatDuration(clipLength(clip))}
         </p>
+        <div className="flex items-center gap-1 mt-1">
+          <Input
+            value={clip.overlay?.text ?? ''}
+            placeholder="Texte overlay ({streamer}, {action})"
+            disabled={overlayHidden}
+            className="h-7 text-xs"
+            onChange={(e) => updateOverlay({ text: e.target.value || undefined })}
+          />
+          <Button
+            variant="ghost"
+            size="icon"
+            className="h-7 w-7 shrink-0"
+            title={overlayHidden ? "Afficher l'overlay" : "Masquer l'overlay"}
+            onClick={(e) => {
+              e.stopPropagation();
+              updateOverlay({ enabled: overlayHidden ? undefined : false });
+            }}
+          >
+            {overlayHidden ? <EyeOff className="h-4 w-4" /> : <Eye className="h-4 w-4" />}
+          </Button>
+          <Input
+            type="number"
+            min={0}
+            step={0.5}
+            value={clip.trimStart ?? ''}
+            placeholder="Début"
+            title="Début du clip (s)"
+            className="h-7 w-16 text-xs shrink-0"
+            onChange={(e) => setClipTrim(clip.id, parseTrim(e.target.value), clip.trimEnd)}
+          />
+          <Input
+            type="number"
+            min={0}
+            step={0.5}
+            value={clip.trimEnd ?? ''}
+            placeholder="Fin"
+            title="Fin du clip (s)"
+            className="h-7 w-16 text-xs shrink-0"
+            onChange={(e) => setClipTrim(clip.id, clip.trimStart, parseTrim(e.target.value))}
+          />
+          <ZoomIn className="h-4 w-4 shrink-0 text-muted-foreground" />
+          <Select
+            value={String(clip.zoom ?? 1)}
+            onValueChange={(value) =>
+              setClipFraming(clip.id, clip.crop, value === '1' ? undefined : Number(value))
+            }
+          >
+            <SelectTrigger className="h-7 w-20 text-xs shrink-0" title="Zoom au centre de l'image">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {ZOOMS.map((zoom) => (
+                <SelectItem key={zoom} value={String(zoom)}>
+                  {`×${zoom}`}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+          <Volume2 className="h-4 w-4 shrink-0 text-muted-foreground" />
+          <Input
+            type="number"
+            min={0}
+            max={400}
+            step={10}
+            value={Math.round((clip.volume ?? 1) * 100)}
+            title="Volume du clip (%)"
+            className="h-7 w-16 text-xs shrink-0"
+            onChange={(e) => {
+              const percent = Math.min(400, Math.max(0, Number(e.target.value) || 0));
+              setClipVolume(clip.id, percent === 100 ? undefined : percent / 100);
+            }}
+          />
+        </div>
+        <div className="flex items-center gap-1 mt-1">
+          <Snail className="h-4 w-4 shrink-0 text-muted-foreground" />
+          <Select
+            value={String(clip.slowmo?.factor ?? 1)}
+            onValueChange={(value) => updateSlowmo({ factor: Number(value) })}
+          >
+            <SelectTrigger className="h-7 w-20 text-xs shrink-0" title="Ralenti">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {SLOWMO_FACTORS.map((factor) => (
+                <SelectItem key={factor} value={String(factor)}>
+                  {factor === 1 ? 'Normal' : `÷${factor}`}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+          <Input
+            type="number"
+            min={0}
+            step={0.5}
+            value={clip.slowmo?.start ?? ''}
+            placeholder="De"
+            title="Début du ralenti (s, dans le clip coupé)"
+            disabled={!clip.slowmo}
+            className="h-7 w-16 text-xs shrink-0"
+            onChange={(e) => updateSlowmo({ start: Number(e.target.value) || 0 })}
+          />
+          <Input
+            type="number"
+            min={0}
+            step={0.5}
+            value={clip.slowmo?.end ?? ''}
+            placeholder="À"
+            title="Fin du ralenti (s, dans le clip coupé)"
+            disabled={!clip.slowmo}
+            className="h-7 w-16 text-xs shrink-0"
+            onChange={(e) => updateSlowmo({ end: Number(e.target.value) || playedLength })}
+          />
+          <Pause className="h-4 w-4 shrink-0 text-muted-foreground" />
+          <Input
+            type="number"
+            min={0}
+            max={10}
+            step={0.5}
+            value={clip.freeze ?? ''}
+            placeholder="0"
+            title="Arrêt sur la dernière image (s)"
+            className="h-7 w-16 text-xs shrink-0"
+            onChange={(e) => {
+              const seconds = Math.min(10, Math.max(0, Number(e.target.value) || 0));
+              setClipEffects(clip.id, clip.slowmo, seconds > 0 ? seconds : undefined);
+            }}
+          />
+        </div>
       </div>
 
       {/* Order Badge */}
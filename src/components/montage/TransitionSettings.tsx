@@ -1,16 +1,95 @@
 import { Label } from '@/components/ui/label';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
 import { Slider } from '@/components/ui/slider';
 import { useMontageStore } from '@/stores';
+import type { MontageOrder, TransitionStyle } from '@/types';
+
+const TRANSITION_STYLES: { value: TransitionStyle; label: string }[] = [
+  { value: 'fade', label: 'Fondu au noir' },
+  { value: 'dissolve', label: 'Fondu enchaîné' },
+  { value: 'wipe', label: 'Balayage' },
+  { value: 'slide', label: 'Glissement' },
+  { value: 'none', label: 'Coupe franche' },
+];
+
+const ORDERS: { value: MontageOrder['mode']; label: string }[] = [
+  { value: 'as_given', label: 'Ordre de la timeline' },
+  { value: 'chronological', label: 'Chronologique (temps de jeu)' },
+  { value: 'by_streamer', label: 'Groupé par streamer' },
+  { value: 'shuffle', label: 'Aléatoire' },
+];
 
 export function TransitionSettings() {
   const transitionDuration = useMontageStore((s) => s.transitionDuration);
   const setTransitionDuration = useMontageStore((s) => s.setTransitionDuration);
+  const transitionStyle = useMontageStore((s) => s.transitionStyle);
+  const setTransitionStyle = useMontageStore((s) => s.setTransitionStyle);
+  const order = useMontageStore((s) => s.order);
+  const setOrder = useMontageStore((s) => s.setOrder);
+
+  // A new seed each time shuffle is picked; the same one for every export after
+  const handleOrder = (mode: MontageOrder['mode']) => {
+    setOrder(
+      mode === 'shuffle'
+        ? { mode, seed: Math.floor(Math.random() * Number.MAX_SAFE_INTEGER) }
+        : { mode }
+    );
+  };
 
   return (
     <div className="space-y-4">
+      <div>
+        <Label className="text-sm font-medium">Ordre des clips</Label>
+        <Select
+          value={order.mode}
+          onValueChange={(value) => handleOrder(value as MontageOrder['mode'])}
+        >
+          <SelectTrigger className="mt-2">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {ORDERS.map((o) => (
+              <SelectItem key={o.value} value={o.value}>
+                {o.label}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+        {order.mode !== 'as_given' && (
+          <p className="text-xs text-muted-foreground mt-1">
+            Appliqué à l'export, à partir des données du projet
+          </p>
+        )}
+      </div>
+
+      <div>
+        <Label className="text-sm font-medium">Style de transition</Label>
+        <Select
+          value={transitionStyle}
+          onValueChange={(value) => setTransitionStyle(value as TransitionStyle)}
+        >
+          <SelectTrigger className="mt-2">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {TRANSITION_STYLES.map((style) => (
+              <SelectItem key={style.value} value={style.value}>
+                {style.label}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </div>
+
       <div>
         <div className="flex items-center justify-between">
-          <Label className="text-sm font-medium">Durée du fade</Label>
+          <Label className="text-sm font-medium">Durée de la transition</Label>
           <span className="text-sm text-muted-foreground">
             {transitionDuration.toFixed(1)}s
           </span>
@@ -0,0 +1,118 @@
+import { Image, X } from 'lucide-react';
+
+import { Button } from '@/components/ui/button';
+import { Label } from '@/components/ui/label';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { Slider } from '@/components/ui/slider';
+import { pickImageFile } from '@/services';
+import { useMontageStore } from '@/stores';
+import { type MontageImageOverlay, type OverlayPosition } from '@/types';
+
+const DEFAULT_WATERMARK: Omit<MontageImageOverlay, 'path'> = {
+  position: 'top-right',
+  scale: 1,
+  opacity: 0.8,
+};
+
+export function WatermarkSettings() {
+  const imageOverlay = useMontageStore((s) => s.imageOverlay);
+  const setImageOverlay = useMontageStore((s) => s.setImageOverlay);
+
+  const handlePick = async () => {
+    const path = await pickImageFile();
+    if (path) {
+      setImageOverlay({ ...DEFAULT_WATERMARK, ...imageOverlay, path });
+    }
+  };
+
+  const update = (changes: Partial<MontageImageOverlay>) => {
+    if (imageOverlay) {
+      setImageOverlay({ ...imageOverlay, ...changes });
+    }
+  };
+
+  return (
+    <div className="space-y-4">
+      <div className="flex items-center gap-2">
+        <Button variant="outline" size="sm" className="gap-2 min-w-0 flex-1" onClick={handlePick}>
+          <Image className="h-4 w-4 shrink-0" />
+          <span className="truncate">
+            {imageOverlay ? imageOverlay.path.split(/[\\/]/).pop() : 'Choisir un logo'}
+          </span>
+        </Button>
+        {imageOverlay && (
+          <Button
+            variant="ghost"
+            size="icon"
+            className="h-8 w-8"
+            onClick={() => setImageOverlay(null)}
+          >
+            <X className="h-4 w-4" />
+          </Button>
+        )}
+      </div>
+
+      {imageOverlay && (
+        <>
+          <div>
+            <Label className="text-sm font-medium">Position</Label>
+            <Select
+              value={imageOverlay.position}
+              onValueChange={(v) => update({ position: v as OverlayPosition })}
+            >
+              <SelectTrigger className="mt-2">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="top-left">Haut gauche</SelectItem>
+                <SelectItem value="top-right">Haut droite</SelectItem>
+                <SelectItem value="bottom-left">Bas gauche</SelectItem>
+                <SelectItem value="bottom-right">Bas droite</SelectItem>
+              </SelectContent>
+            </Select>
+          </div>
+
+          <div>
+            <div className="flex items-center justify-between">
+              <Label className="text-sm font-medium">Taille</Label>
+              <span className="text-sm text-muted-foreground">
+                {Math.round(imageOverlay.scale * 100)}%
+              </span>
+            </div>
+            <Slider
+              value={[imageOverlay.scale]}
+              onValueChange={(values) => update({ scale: values[0] })}
+              min={0.1}
+              max={2}
+              step={0.05}
+              className="mt-2"
+            />
+          </div>
+
+          <div>
+            <div className="flex items-center justify-between">
+              <Label className="text-sm font-medium">Opacité</Label>
+              <span className="text-sm text-muted-foreground">
+                {Math.round(imageOverlay.opacity * 100)}%
+              </span>
+            </div>
+            <Slider
+              value={[imageOverlay.opacity]}
+              onValueChange={(values) => update({ opacity: values[0] })}
+              min={0.1}
+              max={1}
+              step={0.05}
+              className="mt-2"
+            />
+          </div>
+        </>
+      )}
+    </div>
+  );
+}
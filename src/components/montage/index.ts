@@ -4,4 +4,9 @@ export { TimelineClipCard } from './TimelineClipCard';
 export { BulkAddModal } from './BulkAddModal';
 export { OverlayEditor } from './OverlayEditor';
 export { TransitionSettings } from './TransitionSettings';
+export { AspectSettings } from './AspectSettings';
+export { MusicSettings } from './MusicSettings';
+export { WatermarkSettings } from './WatermarkSettings';
+export { BookendSettings } from './BookendSettings';
+export { SectionSettings } from './SectionSettings';
 export { ExportMontagePanel } from './ExportMontagePanel';
@@ -1,6 +1,7 @@
 import { useEffect } from 'react';
 import { Outlet, useParams, useNavigate } from 'react-router-dom';
 import { useProjectStore } from '@/stores';
+import { prepareProject } from '@/services/project';
 import { ProjectSidebar } from './ProjectSidebar';
 import { Button } from '@/components/ui/button';
 
@@ -12,6 +13,11 @@ export function ProjectLayout() {
   useEffect(() => {
     if (projectId) {
       setCurrentProject(projectId);
+      // Resolve every streamer's VOD up front so the players don't wait one by one
+      const current = useProjectStore.getState().getCurrentProject();
+      if (current) {
+        prepareProject(current).catch(console.error);
+      }
     }
     return () => {
       setCurrentProject(null);
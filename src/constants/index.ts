@@ -1,6 +1,6 @@
 // Application-wide constants
 
-import type { Platform } from '@/types';
+import type { ExportErrorKind, Platform } from '@/types';
 
 // ============ Clip Defaults ============
 
@@ -31,3 +31,16 @@ export const STREAMER_COLORS = [
   '#06B6D4', // cyan
   '#84CC16', // lime
 ] as const;
+
+// ============ Export Error Hints ============
+
+export const EXPORT_ERROR_HINTS: Record<ExportErrorKind, string | null> = {
+  network_error: 'Network problem while fetching the VOD. Check your connection and retry.',
+  forbidden403: 'Access denied. The VOD may be sub-only or deleted, or its link expired.',
+  encoder_error: 'The video encoder failed. Try switching to software encoding.',
+  timeout: 'The export took too long. Try a shorter clip or retry later.',
+  duration_mismatch: 'The exported clip is shorter than expected. The VOD may be incomplete.',
+  disk_full: 'Not enough disk space. Free some space or change the work directory.',
+  still_processing: 'The stream is still live. Retry once the VOD has recorded this part.',
+  other: null,
+};
@@ -45,14 +45,41 @@ export function useExport() {
             setProgress({ current: 0, total: data.total_clips });
             break;
 
+          case 'batch_started':
+            setDetailedProgress((prev) => prev ? {
+              ...prev,
+              currentBatch: { batch: data.batch, totalBatches: data.total_batches, percent: 0 },
+            } : null);
+            break;
+
+          case 'batch_progress':
+            setDetailedProgress((prev) => prev?.currentBatch ? {
+              ...prev,
+              currentBatch: { ...prev.currentBatch, percent: data.percent },
+            } : prev);
+            break;
+
           case 'clip_started':
             setDetailedProgress((prev) => prev ? {
               ...prev,
+              currentBatch: undefined,
               currentClipIndex: data.index,
               currentClipName: data.action_name,
               currentClipStreamer: data.streamer_name,
               currentClipPercent: 0,
               currentClipSpeed: null,
+              currentClipAttempt: undefined,
+              currentClipMethod: undefined,
+            } : null);
+            break;
+
+          case 'clip_attempt_started':
+            setDetailedProgress((prev) => prev ? {
+              ...prev,
+              currentClipPercent: 0,
+              currentClipSpeed: null,
+              currentClipAttempt: data.attempt,
+              currentClipMethod: data.method,
             } : null);
             break;
 
@@ -1,5 +1,6 @@
 import { useState, useEffect } from 'react';
-import { invoke } from '@tauri-apps/api/core';
+import { convertFileSrc, invoke } from '@tauri-apps/api/core';
+import { isLocalVideoPath, localVideoPath } from '@/types';
 
 interface UseVodUrlResult {
   resolvedUrl: string | null;
@@ -10,6 +11,7 @@ interface UseVodUrlResult {
 /**
  * Hook to resolve VOD URLs and proxy them if needed (for Twitch)
  * - Twitch VODs are resolved to m3u8 and proxied through local server
+ * - Local recordings are served through the asset protocol
  * - YouTube/other URLs are passed through as-is (react-player handles them)
  */
 export function useVodUrl(vodUrl: string | null): UseVodUrlResult {
@@ -24,6 +26,12 @@ export function useVodUrl(vodUrl: string | null): UseVodUrlResult {
       return;
     }
 
+    if (isLocalVideoPath(vodUrl)) {
+      setResolvedUrl(convertFileSrc(localVideoPath(vodUrl)));
+      setError(null);
+      return;
+    }
+
     // Check if it's a Twitch URL
     const isTwitch = vodUrl.includes('twitch.tv');
 
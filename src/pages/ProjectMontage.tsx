@@ -9,6 +9,11 @@ import { Sheet, SheetContent, SheetHeader, SheetTitle, SheetTrigger } from '@/co
 import { MontagePreview } from '@/components/montage/MontagePreview';
 import { MontageTimeline } from '@/components/montage/MontageTimeline';
 import { TransitionSettings } from '@/components/montage/TransitionSettings';
+import { AspectSettings } from '@/components/montage/AspectSettings';
+import { MusicSettings } from '@/components/montage/MusicSettings';
+import { WatermarkSettings } from '@/components/montage/WatermarkSettings';
+import { BookendSettings } from '@/components/montage/BookendSettings';
+import { SectionSettings } from '@/components/montage/SectionSettings';
 import { ExportMontagePanel } from '@/components/montage/ExportMontagePanel';
 
 export function ProjectMontage() {
@@ -37,10 +42,30 @@ export function ProjectMontage() {
                   <SheetTitle>Paramètres du montage</SheetTitle>
                 </SheetHeader>
                 <div className="py-6 space-y-6">
+                  <div className="space-y-4">
+                    <h4 className="text-sm font-medium">Format</h4>
+                    <AspectSettings />
+                  </div>
                   <div className="space-y-4">
                     <h4 className="text-sm font-medium">Transitions</h4>
                     <TransitionSettings />
                   </div>
+                  <div className="space-y-4">
+                    <h4 className="text-sm font-medium">Musique</h4>
+                    <MusicSettings />
+                  </div>
+                  <div className="space-y-4">
+                    <h4 className="text-sm font-medium">Logo</h4>
+                    <WatermarkSettings />
+                  </div>
+                  <div className="space-y-4">
+                    <h4 className="text-sm font-medium">Intro / Outro</h4>
+                    <BookendSettings />
+                  </div>
+                  <div className="space-y-4">
+                    <h4 className="text-sm font-medium">Sections</h4>
+                    <SectionSettings />
+                  </div>
                 </div>
               </SheetContent>
             </Sheet>
@@ -8,9 +8,25 @@ import type {
   ClipRequest,
   ExportResult,
   ClipFileStatus,
+  MutedClip,
+  ClipVerification,
+  ClipRename,
+  ManifestSummary,
+  ManifestCheck,
   MontageExportInput,
+  RelinkResult,
+  MontageConfigImport,
+  InputReport,
+  MontageSummary,
   MontageExportResult,
+  BeatAnalysis,
+  GridLayout,
+  PipStyle,
   ClipFileInfo,
+  GenerateProxiesResult,
+  FrameFormat,
+  ThumbnailLayout,
+  SmokeTestReport,
 } from '@/types';
 
 /**
@@ -39,6 +55,75 @@ export async function checkClipsStatus(
   });
 }
 
+/**
+ * Find the clips that overlap parts of their VOD muted by the platform.
+ */
+export async function checkMutedSegments(clips: ClipRequest[]): Promise<MutedClip[]> {
+  return invoke<MutedClip[]>('check_muted_segments', { clips });
+}
+
+/**
+ * Probe every clip of a project; optionally delete broken files so they re-download.
+ * Pass the export `speed` when clips were exported sped up or slowed down.
+ */
+export async function verifyProjectClips(
+  projectName: string,
+  deleteBroken = false,
+  speed?: number
+): Promise<ClipVerification[]> {
+  return invoke<ClipVerification[]>('verify_project_clips', {
+    projectName,
+    deleteBroken,
+    speed,
+  });
+}
+
+/**
+ * Delete one exported clip file (and its proxy) so it can be exported again.
+ */
+export async function deleteClipFile(
+  projectName: string,
+  streamerName: string,
+  filename: string
+): Promise<void> {
+  return invoke('delete_clip_file', { projectName, streamerName, filename });
+}
+
+/**
+ * Rename an action's exported clips (and proxies) to match its new name.
+ */
+export async function renameActionClips(
+  projectName: string,
+  actionId: string,
+  oldName: string,
+  newName: string
+): Promise<ClipRename[]> {
+  return invoke<ClipRename[]>('rename_action_clips', {
+    projectName,
+    actionId,
+    oldName,
+    newName,
+  });
+}
+
+/**
+ * Write checksums.json / checksums.txt for all exported clips and montages.
+ */
+export async function generateManifest(
+  projectName: string
+): Promise<ManifestSummary> {
+  return invoke<ManifestSummary>('generate_manifest', { projectName });
+}
+
+/**
+ * Check the files next to a checksums.json against it.
+ */
+export async function verifyManifest(
+  manifestPath: string
+): Promise<ManifestCheck[]> {
+  return invoke<ManifestCheck[]>('verify_manifest', { manifestPath });
+}
+
 /**
  * List all exported clip files for a project.
  */
@@ -50,6 +135,57 @@ export async function listProjectClips(
   });
 }
 
+/**
+ * Generate 360p proxies of a project's clips (progress on "proxy-progress").
+ */
+export async function generateProxies(
+  projectName: string
+): Promise<GenerateProxiesResult> {
+  return invoke<GenerateProxiesResult>('generate_proxies', { projectName });
+}
+
+/**
+ * Extract a still frame from a clip file or resolved VOD URL.
+ * Returns the image path; display it with convertFileSrc.
+ */
+export async function getFrame(
+  source: string,
+  timestamp: number,
+  format?: FrameFormat
+): Promise<string> {
+  return invoke<string>('get_frame', { source, timestamp, format });
+}
+
+/**
+ * Download a window of a VOD's audio (audio-only rendition where available)
+ * for sync offset detection. Returns the path of a mono 16 kHz WAV.
+ */
+export async function extractSyncAudio(
+  vodUrl: string,
+  start: number,
+  duration: number
+): Promise<string> {
+  return invoke<string>('extract_sync_audio', { vodUrl, start, duration });
+}
+
+/**
+ * Check that a VOD can be clipped: resolve, probe, cut a 3-second clip and
+ * verify it. Failures are reported per stage.
+ */
+export async function smokeTestVod(url: string): Promise<SmokeTestReport> {
+  return invoke<SmokeTestReport>('smoke_test_vod', { url });
+}
+
+/**
+ * Get (rendering on first use) the sprite sheets of a clip file or resolved VOD URL.
+ */
+export async function getThumbnails(
+  source: string,
+  interval?: number
+): Promise<ThumbnailLayout> {
+  return invoke<ThumbnailLayout>('get_thumbnails', { source, interval });
+}
+
 /**
  * Export a montage video.
  */
@@ -63,6 +199,159 @@ export async function exportMontage(
   });
 }
 
+/**
+ * Render a quick 480p preview of a montage; resolves to the preview's path
+ * in the temp dir (display it with convertFileSrc).
+ */
+export async function previewMontage(
+  projectName: string,
+  config: MontageExportInput
+): Promise<string> {
+  return invoke<string>('preview_montage', { projectName, config });
+}
+
+/**
+ * Stop the montage being exported ("montage-cancelled" is emitted once its
+ * partial file is removed). Resolves false if none was running.
+ */
+export async function cancelMontage(): Promise<boolean> {
+  return invoke<boolean>('cancel_montage');
+}
+
+/**
+ * Read a montage config from a JSON file; clips missing on this machine are kept and listed.
+ */
+export async function importMontageConfig(path: string): Promise<MontageConfigImport> {
+  return invoke<MontageConfigImport>('import_montage_config', { path });
+}
+
+/**
+ * Find moved or renamed clip files of a montage config again.
+ */
+export async function relinkMontageClips(
+  projectName: string,
+  config: MontageExportInput
+): Promise<RelinkResult> {
+  return invoke<RelinkResult>('relink_montage_clips', { projectName, config });
+}
+
+/**
+ * Probe a montage's clips for what would make its export fail.
+ */
+export async function validateMontageInputs(
+  projectName: string,
+  config: MontageExportInput
+): Promise<InputReport> {
+  return invoke<InputReport>('validate_montage_inputs', { projectName, config });
+}
+
+/**
+ * List the montages saved in a project, with their last render.
+ */
+export async function listProjectMontages(projectName: string): Promise<MontageSummary[]> {
+  return invoke<MontageSummary[]>('list_project_montages', { projectName });
+}
+
+/**
+ * Save a montage setup in its project (replaces the one with the same ID).
+ */
+export async function saveMontage(
+  projectName: string,
+  id: string,
+  name: string,
+  config: MontageExportInput
+): Promise<void> {
+  return invoke('save_montage', { projectName, id, name, config });
+}
+
+/**
+ * Reopen a saved montage, its clips pointed at their current files.
+ */
+export async function loadMontage(projectName: string, id: string): Promise<MontageExportInput> {
+  return invoke<MontageExportInput>('load_montage', { projectName, id });
+}
+
+/**
+ * Render a saved montage to a file named after it, and remember the render.
+ */
+export async function renderSavedMontage(
+  projectName: string,
+  id: string
+): Promise<MontageExportResult> {
+  return invoke<MontageExportResult>('render_saved_montage', { projectName, id });
+}
+
+/**
+ * Delete a saved montage.
+ */
+export async function deleteMontage(projectName: string, id: string): Promise<void> {
+  return invoke('delete_montage', { projectName, id });
+}
+
+/**
+ * Render several montage variants of one timeline (progress on "montage-batch-progress").
+ */
+export async function exportMontages(
+  projectName: string,
+  configs: MontageExportInput[]
+): Promise<MontageExportResult[]> {
+  return invoke<MontageExportResult[]>('export_montages', {
+    projectName,
+    configs,
+  });
+}
+
+/**
+ * Export every streamer's clip for one action and join them into a single file.
+ */
+export async function exportActionCompilation(
+  projectName: string,
+  actionId: string
+): Promise<MontageExportResult> {
+  return invoke<MontageExportResult>('export_action_compilation', {
+    projectName,
+    actionId,
+  });
+}
+
+/**
+ * Export the clips of one action side by side, in a 2x2 grid or as picture-in-picture.
+ * Cells follow streamerIds (the first included clips when empty; in picture-in-picture the
+ * first fills the frame); audioStreamerId keeps only that streamer's audio instead of
+ * mixing all of them.
+ */
+export async function exportActionGrid(
+  projectName: string,
+  actionId: string,
+  layout: GridLayout,
+  streamerIds: string[] = [],
+  audioStreamerId?: string,
+  pip?: PipStyle
+): Promise<MontageExportResult> {
+  return invoke<MontageExportResult>('export_action_grid', {
+    projectName,
+    actionId,
+    layout,
+    streamerIds,
+    audioStreamerId: audioStreamerId ?? null,
+    pip: pip ?? null,
+  });
+}
+
+/**
+ * Join each streamer's included clips into a personal reel (all streamers if none given).
+ * Progress arrives on "reel-progress" (and "export-progress" for missing clips).
+ */
+export async function exportStreamerReels(
+  projectName: string,
+  streamerIds: string[] = []
+): Promise<MontageExportResult[]> {
+  return invoke<MontageExportResult[]>('export_streamer_reels', {
+    projectName,
+    streamerIds,
+  });
+}
+
 /**
  * Open the clips folder in file explorer.
  */
@@ -76,3 +365,31 @@ export async function openClipsFolder(projectName: string): Promise<void> {
 export async function openMontagesFolder(projectName: string): Promise<void> {
   return invoke('open_montages_folder', { projectName });
 }
+
+/**
+ * Pick a background music file for montages (null if cancelled).
+ */
+export async function pickMusicFile(): Promise<string | null> {
+  return invoke<string | null>('pick_music_file');
+}
+
+/**
+ * Find the beats of a music file, as suggested cut points.
+ */
+export async function analyzeMusicBeats(path: string): Promise<BeatAnalysis> {
+  return invoke<BeatAnalysis>('analyze_music_beats', { path });
+}
+
+/**
+ * Pick an intro/outro video for montages (null if cancelled).
+ */
+export async function pickVideoFile(): Promise<string | null> {
+  return invoke<string | null>('pick_video_file');
+}
+
+/**
+ * Pick a watermark image for montages (null if cancelled).
+ */
+export async function pickImageFile(): Promise<string | null> {
+  return invoke<string | null>('pick_image_file');
+}
@@ -7,10 +7,38 @@
 export {
   exportClips,
   checkClipsStatus,
+  checkMutedSegments,
+  verifyProjectClips,
+  deleteClipFile,
+  renameActionClips,
+  generateManifest,
+  verifyManifest,
   listProjectClips,
+  generateProxies,
+  getFrame,
+  extractSyncAudio,
+  smokeTestVod,
+  getThumbnails,
   exportMontage,
+  exportMontages,
+  previewMontage,
+  cancelMontage,
+  relinkMontageClips,
+  importMontageConfig,
+  validateMontageInputs,
+  listProjectMontages,
+  saveMontage,
+  loadMontage,
+  renderSavedMontage,
+  deleteMontage,
+  exportStreamerReels,
+  exportActionGrid,
   openClipsFolder,
   openMontagesFolder,
+  pickMusicFile,
+  analyzeMusicBeats,
+  pickVideoFile,
+  pickImageFile,
 } from './export';
 
 // Project services
@@ -19,5 +47,11 @@ export {
   loadProject,
   deleteProject,
   listProjects,
+  prepareProject,
+  exportProjectArchive,
+  importProjectArchive,
+  importMatchEvents,
   type ProjectFile,
+  type ArchiveSummary,
+  type StreamerReadiness,
 } from './project';
@@ -4,42 +4,39 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
-import type { Project } from '@/types';
+import type { GameEvent, MatchSource, Project } from '@/types';
+import type { ProjectFile } from '@/types/generated/ProjectFile';
 
-/** Project file format for backend */
-export interface ProjectFile {
-  version: number;
-  id: string;
-  name: string;
-  created_at: string;
-  updated_at: string;
-  game_type: string | null;
-  custom_game_id: string | null;
-  reference_streamer_id: string;
-  game_start_time: number | null;
-  streamers: {
-    id: string;
-    name: string;
-    vod_url: string;
-    platform: string;
-    sync_offset: number | null;
-    is_reference: boolean;
-    color: string;
-    global_streamer_id: string | null;
-  }[];
-  actions: {
-    id: string;
-    name: string;
-    game_time: number;
-    clips: {
-      id: string;
-      action_id: string;
-      streamer_id: string;
-      in_point: number;
-      out_point: number;
-      status: string;
-    }[];
-  }[];
+// Generated from the Rust types (pnpm types:generate)
+export type { ProjectFile };
+
+/** Outcome of migrating one project's legacy clips folder */
+export interface MigrationReport {
+  project_name: string;
+  /** Files moved, as "old name -> streamer/new name" */
+  moved: string[];
+  /** Files left in place because no single streamer matched */
+  unresolved: string[];
+  /** Files (or the whole project) that couldn't be moved, with the error */
+  failed: string[];
+}
+
+/** Project archive written by exportProjectArchive */
+export interface ArchiveSummary {
+  path: string;
+  files: number;
+  /** AES-256 encrypted with the given password */
+  encrypted: boolean;
+}
+
+/** Playback readiness of one streamer's VOD */
+export interface StreamerReadiness {
+  streamer_id: string;
+  streamer_name: string;
+  platform: string;
+  /** URL for the player (proxied HLS for Twitch), null if resolution failed */
+  playback_url: string | null;
+  error: string | null;
 }
 
 /**
@@ -56,6 +53,9 @@ export function mapProjectToBackend(project: Project): ProjectFile {
     custom_game_id: project.customGameId || null,
     reference_streamer_id: project.referenceStreamerId,
     game_start_time: project.gameStartTime,
+    max_clip_duration: project.maxClipDuration ?? null,
+    filters: project.filters ?? null,
+    timezone: project.timezone ?? null,
     streamers: project.streamers.map((s) => ({
       id: s.id,
       name: s.name,
@@ -65,6 +65,8 @@ export function mapProjectToBackend(project: Project): ProjectFile {
       is_reference: s.isReference,
       color: s.color,
       global_streamer_id: s.globalStreamerId,
+      recorded_at: s.recordedAt ?? null,
+      crop: s.crop ?? null,
     })),
     actions: project.actions.map((a) => ({
       id: a.id,
@@ -103,6 +105,15 @@ export async function loadProject(projectName: string): Promise<ProjectFile> {
   return invoke<ProjectFile>('load_project', { projectName });
 }
 
+/**
+ * Resolve all streamer VODs of a project concurrently and warm the URL cache.
+ */
+export async function prepareProject(project: Project): Promise<StreamerReadiness[]> {
+  return invoke<StreamerReadiness[]>('prepare_project', {
+    project: mapProjectToBackend(project),
+  });
+}
+
 /**
  * Delete a project from disk.
  */
@@ -116,3 +127,44 @@ export async function deleteProject(projectName: string): Promise<void> {
 export async function listProjects(): Promise<string[]> {
   return invoke<string[]>('list_projects');
 }
+
+/**
+ * Get the report of the startup migration of legacy clip folders.
+ */
+export async function getMigrationReport(): Promise<MigrationReport[]> {
+  return invoke<MigrationReport[]>('get_migration_report');
+}
+
+/**
+ * Zip a project for sharing; a non-empty password encrypts the archive.
+ */
+export async function exportProjectArchive(
+  projectName: string,
+  outputPath: string,
+  password: string | null = null,
+  includeMedia = false
+): Promise<ArchiveSummary> {
+  return invoke<ArchiveSummary>('export_project_archive', {
+    projectName,
+    outputPath,
+    password,
+    includeMedia,
+  });
+}
+
+/**
+ * Unpack a project archive into the work directory. Returns the project name.
+ */
+export async function importProjectArchive(
+  archivePath: string,
+  password: string | null = null
+): Promise<string> {
+  return invoke<string>('import_project_archive', { archivePath, password });
+}
+
+/**
+ * Fetch a match's kills and objectives from Riot or OpenDota, to create actions.
+ */
+export async function importMatchEvents(source: MatchSource): Promise<GameEvent[]> {
+  return invoke<GameEvent[]>('import_match_events', { source });
+}
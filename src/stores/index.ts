@@ -2,4 +2,4 @@ export { useProjectStore } from './projectStore';
 export { useEditorStore, type EditorMode, type SelectionStep } from './editorStore';
 export { useStreamerDatabaseStore, type AddStreamerResult } from './streamerDatabaseStore';
 export { useSettingsStore, type BinaryInfo, type BinaryStatus } from './settingsStore';
-export { useMontageStore, selectTotalDuration, selectClipCount } from './montageStore';
+export { useMontageStore, selectTotalDuration, selectClipCount, clipLength } from './montageStore';
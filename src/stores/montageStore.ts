@@ -2,9 +2,26 @@ import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type {
   MontageClip,
+  ClipOverlay,
   OverlayConfig,
   MontageExportInput,
   MontageExportResult,
+  MontageAspect,
+  MontageFit,
+  MontageRenderMode,
+  MontageEncoding,
+  MontageContainer,
+  MontageOrder,
+  CropRect,
+  SlowMotion,
+  MontageImageOverlay,
+  MontageOutputFormat,
+  MontageMusic,
+  MontageBookend,
+  MontageSection,
+  TimelineSection,
+  TitleCard,
+  TransitionStyle,
   ClipFileInfo,
   Project,
   ClipRequest,
@@ -54,12 +71,41 @@ interface MontageState {
   overlay: OverlayConfig | null;
   /** Transition duration in seconds */
   transitionDuration: number;
+  /** Transition look between clips */
+  transitionStyle: TransitionStyle;
+  /** Background music (none when null) */
+  music: MontageMusic | null;
+  /** Watermark image (none when null) */
+  imageOverlay: MontageImageOverlay | null;
+  /** Output frame shape, and how clips fill it */
+  aspect: MontageAspect;
+  fit: MontageFit;
+  /** Common frame size and rate (clips kept as they are when null) */
+  outputFormat: MontageOutputFormat | null;
+  /** Clip loudness target in LUFS (levels kept when null) */
+  loudnessLufs: number | null;
+  /** Single pass, or clip by clip (resumable) */
+  renderMode: MontageRenderMode;
+  encoding: MontageEncoding;
+  container: MontageContainer;
+  /** Clip order applied on export (the timeline order when as_given) */
+  order: MontageOrder;
+  /** Intro/outro videos or title cards (none when null) */
+  intro: MontageBookend | null;
+  outro: MontageBookend | null;
+  /** Title cards, each shown before a timeline clip */
+  sections: TimelineSection[];
   /** Export state */
   isExporting: boolean;
   exportProgress: number;
   exportError: string | null;
   exportResult: MontageExportResult | null;
   exportStatus: string | null;
+  /** Set by cancelExport; stops batch loops before their next montage */
+  exportCancelled: boolean;
+  /** Low-resolution preview render (path of the last one) */
+  isPreviewing: boolean;
+  previewPath: string | null;
   /** Available clips from disk */
   availableClips: ClipFileInfo[];
   isLoadingClips: boolean;
@@ -76,6 +122,11 @@ interface MontageActions {
   removeClip: (id: string) => void;
   reorderClips: (fromIndex: number, toIndex: number) => void;
   clearClips: () => void;
+  setClipOverlay: (id: string, overlay: ClipOverlay | undefined) => void;
+  setClipVolume: (id: string, volume: number | undefined) => void;
+  setClipTrim: (id: string, trimStart: number | undefined, trimEnd: number | undefined) => void;
+  setClipFraming: (id: string, crop: CropRect | undefined, zoom: number | undefined) => void;
+  setClipEffects: (id: string, slowmo: SlowMotion | undefined, freeze: number | undefined) => void;
 
   // Overlay
   setOverlay: (overlay: OverlayConfig | null) => void;
@@ -83,6 +134,20 @@ interface MontageActions {
 
   // Settings
   setTransitionDuration: (duration: number) => void;
+  setTransitionStyle: (style: TransitionStyle) => void;
+  setMusic: (music: MontageMusic | null) => void;
+  setImageOverlay: (imageOverlay: MontageImageOverlay | null) => void;
+  setAspect: (aspect: MontageAspect, fit: MontageFit) => void;
+  setOutputFormat: (outputFormat: MontageOutputFormat | null) => void;
+  setLoudnessLufs: (loudnessLufs: number | null) => void;
+  setRenderMode: (renderMode: MontageRenderMode) => void;
+  setEncoding: (encoding: MontageEncoding) => void;
+  setContainer: (container: MontageContainer) => void;
+  setOrder: (order: MontageOrder) => void;
+  setBookend: (which: 'intro' | 'outro', bookend: MontageBookend | null) => void;
+  addSection: (beforeClipId: string, card: TitleCard) => void;
+  updateSection: (id: string, changes: Partial<Omit<TimelineSection, 'id'>>) => void;
+  removeSection: (id: string) => void;
 
   // Available clips
   loadAvailableClips: (projectName: string) => Promise<void>;
@@ -96,11 +161,14 @@ interface MontageActions {
 
   // Export
   exportMontage: (projectName: string) => Promise<MontageExportResult>;
+  previewMontage: (projectName: string) => Promise<void>;
+  clearPreview: () => void;
 
   batchExport: (projectName: string, mode: 'streamer' | 'action') => Promise<void>;
   batchExportProject: (project: Project, mode: 'streamer' | 'action') => Promise<void>;
   openMontagesFolder: (projectName: string) => Promise<void>;
   clearExportResult: () => void;
+  cancelExport: () => Promise<void>;
 
   // Reset
   reset: () => void;
@@ -114,11 +182,28 @@ const initialState: MontageState = {
   clips: [],
   overlay: null,
   transitionDuration: DEFAULT_TRANSITION_DURATION,
+  transitionStyle: 'fade',
+  music: null,
+  imageOverlay: null,
+  aspect: 'landscape',
+  fit: 'crop',
+  outputFormat: { width: 1920, height: 1080, fps: 60 },
+  loudnessLufs: null,
+  renderMode: 'single',
+  encoding: {},
+  container: 'mp4',
+  order: { mode: 'as_given' },
+  intro: null,
+  outro: null,
+  sections: [],
   isExporting: false,
   exportProgress: 0,
   exportError: null,
   exportResult: null,
   exportStatus: null,
+  exportCancelled: false,
+  isPreviewing: false,
+  previewPath: null,
   availableClips: [],
   isLoadingClips: false,
   isPlaying: false,
@@ -161,7 +246,8 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
       const filtered = state.clips.filter((c) => c.id !== id);
       // Reorder remaining clips
       const reordered = filtered.map((c, i) => ({ ...c, order: i }));
-      return { clips: reordered };
+      const sections = state.sections.filter((s) => s.beforeClipId !== id);
+      return { clips: reordered, sections };
     });
   },
 
@@ -176,9 +262,40 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
     });
   },
 
+  setClipOverlay: (id, overlay) => {
+    set((state) => ({
+      clips: state.clips.map((c) => (c.id === id ? { ...c, overlay } : c)),
+    }));
+  },
+
+  setClipVolume: (id, volume) => {
+    set((state) => ({
+      clips: state.clips.map((c) => (c.id === id ? { ...c, volume } : c)),
+    }));
+  },
+
+  setClipTrim: (id, trimStart, trimEnd) => {
+    set((state) => ({
+      clips: state.clips.map((c) => (c.id === id ? { ...c, trimStart, trimEnd } : c)),
+    }));
+  },
+
+  setClipFraming: (id, crop, zoom) => {
+    set((state) => ({
+      clips: state.clips.map((c) => (c.id === id ? { ...c, crop, zoom } : c)),
+    }));
+  },
+
+  setClipEffects: (id, slowmo, freeze) => {
+    set((state) => ({
+      clips: state.clips.map((c) => (c.id === id ? { ...c, slowmo, freeze } : c)),
+    }));
+  },
+
   clearClips: () => {
     set({ 
       clips: [],
+      sections: [],
       currentClipIndex: 0,
       currentTime: 0,
       isPlaying: false,
@@ -209,6 +326,66 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
     set({ transitionDuration: Math.max(0, Math.min(2, duration)) });
   },
 
+  setTransitionStyle: (style) => {
+    set({ transitionStyle: style });
+  },
+
+  setMusic: (music) => {
+    set({ music });
+  },
+
+  setImageOverlay: (imageOverlay) => {
+    set({ imageOverlay });
+  },
+
+  setAspect: (aspect, fit) => {
+    set({ aspect, fit });
+  },
+
+  setOutputFormat: (outputFormat) => {
+    set({ outputFormat });
+  },
+
+  setLoudnessLufs: (loudnessLufs) => {
+    set({ loudnessLufs });
+  },
+
+  setRenderMode: (renderMode) => {
+    set({ renderMode });
+  },
+
+  setEncoding: (encoding) => {
+    set({ encoding });
+  },
+
+  setContainer: (container) => {
+    set({ container });
+  },
+
+  setOrder: (order) => {
+    set({ order });
+  },
+
+  setBookend: (which, bookend) => {
+    set(which === 'intro' ? { intro: bookend } : { outro: bookend });
+  },
+
+  addSection: (beforeClipId, card) => {
+    set((state) => ({
+      sections: [...state.sections, { ...card, id: generateMontageId(), beforeClipId }],
+    }));
+  },
+
+  updateSection: (id, changes) => {
+    set((state) => ({
+      sections: state.sections.map((s) => (s.id === id ? { ...s, ...changes } : s)),
+    }));
+  },
+
+  removeSection: (id) => {
+    set((state) => ({ sections: state.sections.filter((s) => s.id !== id) }));
+  },
+
   // ============ Available Clips ============
 
   loadAvailableClips: async (projectName) => {
@@ -269,7 +446,7 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
   // ============ Export ============
 
   exportMontage: async (projectName) => {
-    const { clips, overlay, transitionDuration } = get();
+    const { clips } = get();
 
     if (clips.length === 0) {
       const error = 'Aucun clip dans la timeline';
@@ -288,27 +465,11 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
       exportError: null,
       exportResult: null,
       exportStatus: 'Export en cours...',
+      exportCancelled: false,
     });
 
     try {
-      const exportInput: MontageExportInput = {
-        clips: clips.map((c) => ({
-          filename: c.filename,
-          path: c.path,
-          duration: c.duration,
-          streamer_name: c.streamerName,
-        })),
-        transition_duration: transitionDuration,
-        overlay: overlay
-          ? {
-              text: overlay.type === 'streamer_name' ? '{streamer}' : (overlay.text || ''),
-              position: overlay.position,
-              font_size: overlay.fontSize,
-              color: overlay.color,
-              box_color: overlay.boxColor,
-            }
-          : undefined,
-      };
+      const exportInput = toExportInput(get());
 
       const result = await invoke<MontageExportResult>('export_montage', {
         projectName,
@@ -319,7 +480,7 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
         isExporting: false,
         exportProgress: 100,
         exportResult: result,
-        exportError: result.error || null,
+        exportError: get().exportCancelled ? 'Export annulé' : result.error || null,
         exportStatus: null,
       });
 
@@ -340,8 +501,47 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
     }
   },
 
+  previewMontage: async (projectName) => {
+    if (get().clips.length === 0) return;
+
+    set({ isPreviewing: true, previewPath: null, exportError: null });
+    try {
+      const previewPath = await invoke<string>('preview_montage', {
+        projectName,
+        config: toExportInput(get()),
+      });
+      set({ isPreviewing: false, previewPath });
+    } catch (error) {
+      set({
+        isPreviewing: false,
+        exportError: error instanceof Error ? error.message : String(error),
+      });
+    }
+  },
+
+  clearPreview: () => {
+    set({ previewPath: null });
+  },
+
   batchExport: async (projectName, mode) => {
-    const { clips, overlay, transitionDuration } = get();
+    const {
+      clips,
+      overlay,
+      transitionDuration,
+      transitionStyle,
+      music,
+      imageOverlay,
+      intro,
+      outro,
+      aspect,
+      fit,
+      outputFormat,
+      loudnessLufs,
+      renderMode,
+      encoding,
+      container,
+      order,
+    } = get();
 
     if (clips.length === 0) {
       set({ exportError: 'Aucun clip à exporter' });
@@ -362,13 +562,15 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
       isExporting: true, 
       exportProgress: 0, 
       exportError: null, 
-      exportResult: null 
+      exportResult: null,
+      exportCancelled: false,
     });
 
     let totalSuccess = true;
     const errors: string[] = [];
 
     for (let i = 0; i < keys.length; i++) {
+      if (get().exportCancelled) break;
       const key = keys[i];
       const groupClips = groups[key];
       
@@ -384,8 +586,31 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
             path: c.path,
             duration: c.duration,
             streamer_name: c.streamerName,
+            action_name: c.actionName,
+            action_id: c.actionId || undefined,
+            overlay: c.overlay,
+            volume: c.volume,
+            trim_start: c.trimStart,
+            trim_end: c.trimEnd,
+            crop: c.crop,
+            zoom: c.zoom,
+            slowmo: c.slowmo,
+            freeze: c.freeze,
           })),
           transition_duration: transitionDuration,
+          transition: transitionStyle,
+          music: music ?? undefined,
+          image_overlay: imageOverlay ?? undefined,
+          aspect,
+          fit,
+          format: outputFormat ?? undefined,
+          loudness_lufs: loudnessLufs ?? undefined,
+          render: renderMode,
+          encoding,
+          container,
+          order,
+          intro: intro ?? undefined,
+          outro: outro ?? undefined,
           overlay: overlay
             ? {
                 text: overlay.type === 'streamer_name' ? '{streamer}' : (overlay.text || ''),
@@ -393,6 +618,11 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
                 font_size: overlay.fontSize,
                 color: overlay.color,
                 box_color: overlay.boxColor,
+                font: overlay.font,
+                animation: overlay.animation,
+                custom_position: overlay.customPosition,
+                outline: overlay.outline,
+                shadow: overlay.shadow,
               }
             : undefined,
           output_filename: `${projectName}_${key.replace(/[^a-zA-Z0-9]/g, '')}`,
@@ -417,10 +647,12 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
       isExporting: false,
       exportProgress: 100,
       exportStatus: null,
-      exportError: errors.length > 0 ? `Erreurs: ${errors.join(', ')}` : null,
+      exportError: get().exportCancelled
+        ? 'Export annulé'
+        : errors.length > 0 ? `Erreurs: ${errors.join(', ')}` : null,
       // We set a dummy result to trigger success UI usually, but batch is different. 
       // Maybe we need a specific 'batchResult' or just reuse exportResult with summary.
-      exportResult: totalSuccess ? {
+      exportResult: totalSuccess && !get().exportCancelled ? {
         success: true,
         output_path: 'Dossier Montages',
         duration: 0,
@@ -430,14 +662,31 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
   },
 
   batchExportProject: async (project, mode) => {
-    const { overlay, transitionDuration } = get();
+    const {
+      overlay,
+      transitionDuration,
+      transitionStyle,
+      music,
+      imageOverlay,
+      intro,
+      outro,
+      aspect,
+      fit,
+      outputFormat,
+      loudnessLufs,
+      renderMode,
+      encoding,
+      container,
+      order,
+    } = get();
 
     set({ 
       isExporting: true, 
       exportProgress: 0, 
       exportError: null, 
       exportResult: null,
-      exportStatus: 'Préparation des sources...'
+      exportStatus: 'Préparation des sources...',
+      exportCancelled: false,
     });
 
     try {
@@ -501,6 +750,8 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
           path: physical.path,
           duration: physical.duration,
           streamer_name: clipMeta.streamer_name,
+          action_name: clipMeta.action_name,
+          action_id: clipMeta.action_id,
         });
       }
 
@@ -514,6 +765,7 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
       const errors: string[] = [];
 
       for (let i = 0; i < keys.length; i++) {
+         if (get().exportCancelled) break;
          const key = keys[i];
          const groupClips = groups[key];
 
@@ -536,12 +788,30 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
                 font_size: overlay.fontSize,
                 color: overlay.color,
                 box_color: overlay.boxColor,
+                font: overlay.font,
+                animation: overlay.animation,
+                custom_position: overlay.customPosition,
+                outline: overlay.outline,
+                shadow: overlay.shadow,
             };
          }
 
          const exportInput: MontageExportInput = {
             clips: groupClips,
             transition_duration: transitionDuration,
+            transition: transitionStyle,
+            music: music ?? undefined,
+            image_overlay: imageOverlay ?? undefined,
+            aspect,
+            fit,
+            format: outputFormat ?? undefined,
+            loudness_lufs: loudnessLufs ?? undefined,
+            render: renderMode,
+            encoding,
+            container,
+            order,
+            intro: intro ?? undefined,
+            outro: outro ?? undefined,
             overlay: finalOverlay,
             output_filename: `${project.name}_${key.replace(/[^a-zA-Z0-9]/g, '')}`,
          };
@@ -561,8 +831,10 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
         isExporting: false,
         exportProgress: 100,
         exportStatus: null,
-        exportError: errors.length > 0 ? `Erreurs: ${errors.join(', ')}` : null,
-        exportResult: totalSuccess ? {
+        exportError: get().exportCancelled
+          ? 'Export annulé'
+          : errors.length > 0 ? `Erreurs: ${errors.join(', ')}` : null,
+        exportResult: totalSuccess && !get().exportCancelled ? {
           success: true,
           output_path: 'Dossier Montages',
           duration: 0,
@@ -591,6 +863,15 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
     set({ exportResult: null, exportError: null });
   },
 
+  cancelExport: async () => {
+    set({ exportCancelled: true, exportStatus: 'Annulation...' });
+    try {
+      await invoke<boolean>('cancel_montage');
+    } catch (error) {
+      console.error('Failed to cancel montage export:', error);
+    }
+  },
+
   // ============ Reset ============
 
   reset: () => {
@@ -598,12 +879,110 @@ export const useMontageStore = create<MontageStore>()((set, get) => ({
   },
 }));
 
+/** Export input for the timeline and settings of `state` */
+function toExportInput(state: MontageState): MontageExportInput {
+  const {
+    clips,
+    overlay,
+    transitionDuration,
+    transitionStyle,
+    music,
+    imageOverlay,
+    intro,
+    outro,
+    sections,
+    aspect,
+    fit,
+    outputFormat,
+    loudnessLufs,
+    renderMode,
+    encoding,
+    container,
+    order,
+  } = state;
+  return {
+    clips: clips.map((c) => ({
+      filename: c.filename,
+      path: c.path,
+      duration: c.duration,
+      streamer_name: c.streamerName,
+      action_name: c.actionName,
+      action_id: c.actionId || undefined,
+      overlay: c.overlay,
+      volume: c.volume,
+      trim_start: c.trimStart,
+      trim_end: c.trimEnd,
+      crop: c.crop,
+      zoom: c.zoom,
+      slowmo: c.slowmo,
+      freeze: c.freeze,
+    })),
+    transition_duration: transitionDuration,
+    transition: transitionStyle,
+    music: music ?? undefined,
+    image_overlay: imageOverlay ?? undefined,
+    aspect,
+    fit,
+    format: outputFormat ?? undefined,
+    loudness_lufs: loudnessLufs ?? undefined,
+    render: renderMode,
+    encoding,
+    container,
+    order,
+    intro: intro ?? undefined,
+    outro: outro ?? undefined,
+    sections: toMontageSections(sections, clips),
+    overlay: overlay
+      ? {
+          text: overlay.type === 'streamer_name' ? '{streamer}' : (overlay.text || ''),
+          position: overlay.position,
+          font_size: overlay.fontSize,
+          color: overlay.color,
+          box_color: overlay.boxColor,
+          font: overlay.font,
+          animation: overlay.animation,
+          custom_position: overlay.customPosition,
+          outline: overlay.outline,
+          shadow: overlay.shadow,
+        }
+      : undefined,
+  };
+}
+
+/** Timeline sections as export input, positioned by the clip they precede */
+function toMontageSections(sections: TimelineSection[], clips: MontageClip[]): MontageSection[] {
+  return sections.flatMap((s) => {
+    const before = clips.findIndex((c) => c.id === s.beforeClipId);
+    if (before === -1) return [];
+    return [
+      {
+        before,
+        text: s.text,
+        duration: s.duration,
+        background_color: s.background_color,
+        background_image: s.background_image,
+      },
+    ];
+  });
+}
+
 // ============ Selectors ============
 
+/** Seconds of a clip that end up in the montage: trimmed, slowed and frozen */
+export const clipLength = (clip: MontageClip): number => {
+  const start = clip.trimStart ?? 0;
+  const end = Math.min(clip.trimEnd ?? clip.duration, clip.duration);
+  const slowed = clip.slowmo ? (clip.slowmo.end - clip.slowmo.start) * (clip.slowmo.factor - 1) : 0;
+  return Math.max(0, end - start) + slowed + (clip.freeze ?? 0);
+};
+
 /** Get total duration of the montage */
 export const selectTotalDuration = (state: MontageState): number => {
   if (state.clips.length === 0) return 0;
-  const clipsDuration = state.clips.reduce((sum, c) => sum + c.duration, 0);
+  const clipsDuration = state.clips.reduce((sum, c) => sum + clipLength(c), 0);
+  // Only crossfades overlap clips; 'fade' goes through black inside each clip
+  const crossfade = state.transitionStyle !== 'none' && state.transitionStyle !== 'fade';
+  if (!crossfade || state.transitionDuration <= 0) return clipsDuration;
   const transitionsCount = state.clips.length - 1;
   return clipsDuration - transitionsCount * state.transitionDuration;
 };
@@ -1,17 +1,10 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
+import type { BinaryInfo } from '@/types/generated/BinaryInfo';
+import type { BinaryStatus } from '@/types/generated/BinaryStatus';
 
-export interface BinaryInfo {
-  installed: boolean;
-  path: string | null;
-  version: string | null;
-  source: 'system' | 'managed' | 'not_found';
-}
-
-export interface BinaryStatus {
-  ffmpeg: BinaryInfo;
-  ytdlp: BinaryInfo;
-}
+// Generated from the Rust types (pnpm types:generate)
+export type { BinaryInfo, BinaryStatus };
 
 interface SettingsState {
   workDir: string | null;
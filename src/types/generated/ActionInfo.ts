@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClipInfo } from "./ClipInfo";
+
+/**
+ * Action/highlight information
+ */
+export type ActionInfo = { 
+/**
+ * Unique action ID
+ */
+id: string, 
+/**
+ * Action name/description
+ */
+name: string, 
+/**
+ * Game time when the action occurred (seconds from game start)
+ */
+game_time: number, 
+/**
+ * Clips for each streamer
+ */
+clips: Array<ClipInfo>, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BinarySource } from "./BinarySource";
+
+export type BinaryInfo = { installed: boolean, path: string | null, version: string | null, source: BinarySource, };
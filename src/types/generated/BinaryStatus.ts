@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BinaryInfo } from "./BinaryInfo";
+
+/**
+ * Status of installed binaries
+ */
+export type BinaryStatus = { ffmpeg: BinaryInfo, ytdlp: BinaryInfo, };
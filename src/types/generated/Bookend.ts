@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CreditsCard } from "./CreditsCard";
+import type { TitleCard } from "./TitleCard";
+
+/**
+ * Video played before or after the clips
+ */
+export type Bookend = { "type": "file", path: string, } | { "type": "card" } & TitleCard | { "type": "credits" } & CreditsCard;
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CropRect } from "./CropRect";
+
+/**
+ * Filters applied when a clip is re-encoded
+ */
+export type ClipFilters = { 
+/**
+ * Crop the source (e.g. cut a webcam or game area out of the stream layout)
+ */
+crop?: CropRect, 
+/**
+ * Scale down to at most this height, keeping the aspect ratio
+ */
+max_height?: number, 
+/**
+ * Light spatial/temporal video denoise (hqdn3d)
+ */
+denoise?: boolean, 
+/**
+ * FFT audio denoise (afftdn), for noisy mics
+ */
+audio_denoise?: boolean, };
@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClipStatus } from "./ClipStatus";
+
+/**
+ * Clip information for a specific streamer
+ */
+export type ClipInfo = { 
+/**
+ * Unique clip ID
+ */
+id: string, 
+/**
+ * Action ID this clip belongs to
+ */
+action_id: string, 
+/**
+ * Streamer ID
+ */
+streamer_id: string, 
+/**
+ * In point relative to action time (seconds, can be negative)
+ */
+in_point: number, 
+/**
+ * Out point relative to action time (seconds)
+ */
+out_point: number, 
+/**
+ * Clip status
+ */
+status: ClipStatus, };
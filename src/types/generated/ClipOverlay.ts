@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OverlayPosition } from "./OverlayPosition";
+
+/**
+ * Per-clip overlay override; unset fields fall back to the montage overlay
+ */
+export type ClipOverlay = { 
+/**
+ * Show or hide the overlay on this clip (unset: shown if the montage has
+ * an overlay or this clip sets its own text)
+ */
+enabled?: boolean, 
+/**
+ * Text for this clip (placeholders as in [`OverlayConfig::text`])
+ */
+text?: string, position?: OverlayPosition, };
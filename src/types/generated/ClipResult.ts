@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExportErrorKind } from "./ExportErrorKind";
+
+/**
+ * Result of a single clip export
+ */
+export type ClipResult = "success" | "skipped" | { "failed": { error: string, error_kind: ExportErrorKind, } };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * End card listing every streamer of the montage under a heading
+ */
+export type CreditsCard = { 
+/**
+ * Heading, e.g. the project name
+ */
+title: string, 
+/**
+ * Seconds on screen
+ */
+duration: number, 
+/**
+ * Hex background color without # (black when unset)
+ */
+background_color?: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Crop rectangle in source pixels
+ */
+export type CropRect = { x: number, y: number, width: number, height: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Broad category of an export failure, so the frontend can suggest a fix
+ */
+export type ExportErrorKind = "network_error" | "forbidden403" | "encoder_error" | "timeout" | "duration_mismatch" | "disk_full" | "still_processing" | "other";
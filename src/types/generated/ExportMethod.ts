@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How an export attempt fetches the clip
+ */
+export type ExportMethod = "copy" | "smart_cut" | "reencode" | "two_pass" | "download";
@@ -0,0 +1,36 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClipResult } from "./ClipResult";
+import type { ExportMethod } from "./ExportMethod";
+
+/**
+ * Progress information for export operations
+ */
+export type ExportProgress = { "type": "started", total_clips: number, } | { "type": "batch_started", 
+/**
+ * 1-based number of the batch
+ */
+batch: number, total_batches: number, 
+/**
+ * Indices of the clips cut from this download
+ */
+clips: Array<number>, } | { "type": "batch_progress", batch: number, percent: number, speed: string | null, } | { "type": "clip_started", index: number, action_name: string, streamer_name: string, 
+/**
+ * Seconds of the clip the platform muted
+ */
+muted_seconds?: number, } | { "type": "clip_progress", index: number, percent: number, speed: string | null, 
+/**
+ * Output time reached so far (seconds)
+ */
+out_time?: number, 
+/**
+ * Bytes written so far
+ */
+bytes_written?: number, 
+/**
+ * Estimated seconds remaining
+ */
+eta_secs?: number, } | { "type": "clip_attempt_started", index: number, attempt: number, method: ExportMethod, } | { "type": "clip_completed", index: number, status: ClipResult, 
+/**
+ * Size of the output file in bytes (if it exists)
+ */
+size_bytes?: number, } | { "type": "finished", exported: number, skipped: number, failed: number, };
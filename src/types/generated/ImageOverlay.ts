@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OverlayPoint } from "./OverlayPoint";
+import type { OverlayPosition } from "./OverlayPosition";
+
+/**
+ * Image (e.g., a logo) drawn over the whole montage, for watermarking
+ */
+export type ImageOverlay = { path: string, position: OverlayPosition, 
+/**
+ * Free position (overrides the corner in `position`)
+ */
+custom_position?: OverlayPoint, 
+/**
+ * Size relative to the image file (1.0 = native size)
+ */
+scale: number, 
+/**
+ * 0.0 (invisible) to 1.0 (opaque)
+ */
+opacity: number, };
@@ -0,0 +1,69 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClipOverlay } from "./ClipOverlay";
+import type { CropRect } from "./CropRect";
+import type { SlowMotion } from "./SlowMotion";
+
+/**
+ * Input for a single clip in the montage
+ */
+export type MontageClipInput = { 
+/**
+ * Clip filename (for display)
+ */
+filename: string, 
+/**
+ * Full path to the clip file
+ */
+path: string, 
+/**
+ * Duration in seconds
+ */
+duration: number, 
+/**
+ * Streamer name for overlay
+ */
+streamer_name: string, 
+/**
+ * Action name for the chapter title
+ */
+action_name?: string, 
+/**
+ * Project action the clip was cut from
+ */
+action_id?: string, 
+/**
+ * Path relative to the work dir, so saved configs survive moving it
+ */
+relative_path?: string, 
+/**
+ * Overlay text/position for this clip instead of the montage overlay
+ */
+overlay?: ClipOverlay, 
+/**
+ * Volume multiplier for this clip (1.0 = unchanged)
+ */
+volume?: number, 
+/**
+ * Seconds cut from the start of the clip
+ */
+trim_start?: number, 
+/**
+ * Where the clip ends, in seconds into the file
+ */
+trim_end?: number, 
+/**
+ * Part of the frame to keep, in source pixels
+ */
+crop?: CropRect, 
+/**
+ * Punch-in factor on the center of the frame
+ */
+zoom?: number, 
+/**
+ * Part of the clip played slower
+ */
+slowmo?: SlowMotion, 
+/**
+ * Seconds the last frame is held
+ */
+freeze?: number, };
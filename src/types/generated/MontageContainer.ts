@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * File type of a montage
+ */
+export type MontageContainer = "mp4" | "mkv" | "webm" | "mp3" | "m4a";
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Encoding overrides for a montage; unset fields use the app's FFmpeg settings
+ */
+export type MontageEncoding = { 
+/**
+ * Quality (lower is better, 0-51)
+ */
+crf?: number, 
+/**
+ * libx264 speed preset; hardware encoders use their own
+ */
+preset?: string, 
+/**
+ * AAC bitrate, e.g. "192k"
+ */
+audio_bitrate?: string, 
+/**
+ * Peak video bitrate in kbit/s, for platforms with upload limits
+ */
+max_bitrate_kbps?: number, };
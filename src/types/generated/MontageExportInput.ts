@@ -0,0 +1,76 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Bookend } from "./Bookend";
+import type { ImageOverlay } from "./ImageOverlay";
+import type { MontageAspect } from "./MontageAspect";
+import type { MontageClipInput } from "./MontageClipInput";
+import type { MontageContainer } from "./MontageContainer";
+import type { MontageEncoding } from "./MontageEncoding";
+import type { MontageFit } from "./MontageFit";
+import type { MontageOrder } from "./MontageOrder";
+import type { MusicInput } from "./MusicInput";
+import type { OutputFormat } from "./OutputFormat";
+import type { OverlayInput } from "./OverlayInput";
+import type { RenderMode } from "./RenderMode";
+import type { SectionBreak } from "./SectionBreak";
+import type { TransitionStyle } from "./TransitionStyle";
+
+/**
+ * Export configuration from frontend
+ */
+export type MontageExportInput = { clips: Array<MontageClipInput>, transition_duration: number, 
+/**
+ * Transition look (fade through black when unset)
+ */
+transition?: TransitionStyle, 
+/**
+ * Background music, ducked under the clip audio
+ */
+music?: MusicInput, 
+/**
+ * Video or title card before the first clip
+ */
+intro?: Bookend, 
+/**
+ * Video or title card after the last clip
+ */
+outro?: Bookend, 
+/**
+ * Title cards between clips
+ */
+sections?: Array<SectionBreak>, overlay?: OverlayInput, 
+/**
+ * Watermark image over the whole montage
+ */
+image_overlay?: ImageOverlay, output_filename?: string, 
+/**
+ * Output frame shape (16:9 when unset)
+ */
+aspect?: MontageAspect, 
+/**
+ * Crop or letterbox clips into a portrait/square frame
+ */
+fit?: MontageFit, 
+/**
+ * Common frame size and rate for all clips (keeps the clips' own when unset)
+ */
+format?: OutputFormat, 
+/**
+ * Loudness every clip is normalized to before joining (LUFS)
+ */
+loudness_lufs?: number, 
+/**
+ * Render clip by clip so a failed export can resume
+ */
+render?: RenderMode, 
+/**
+ * CRF, preset, audio bitrate and bitrate cap overrides
+ */
+encoding?: MontageEncoding, 
+/**
+ * MP4, MKV or WebM (VP9 + Opus)
+ */
+container?: MontageContainer, 
+/**
+ * Rearrange the clips (as sent when unset)
+ */
+order?: MontageOrder, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a 16:9 clip is fitted into a portrait or square frame
+ */
+export type MontageFit = "crop" | "pad";
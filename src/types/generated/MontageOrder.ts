@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a montage's clips are ordered
+ */
+export type MontageOrder = { "mode": "as_given" } | { "mode": "chronological" } | { "mode": "by_streamer" } | { "mode": "shuffle", seed: number, };
@@ -0,0 +1,29 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MontageExportInput } from "./MontageExportInput";
+import type { MontageRender } from "./MontageRender";
+
+/**
+ * A montage setup saved in the project (clips, order, overlay, music,
+ * transitions)
+ */
+export type MontagePreset = { 
+/**
+ * Unique ID within the project
+ */
+id: string, 
+/**
+ * Display name
+ */
+name: string, 
+/**
+ * Last time the montage was saved
+ */
+updated_at: string, 
+/**
+ * Export input, with clip paths also stored relative to the work dir
+ */
+config: MontageExportInput, 
+/**
+ * Last time the saved montage was rendered
+ */
+last_render?: MontageRender | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A render of a saved montage
+ */
+export type MontageRender = { output_path: string, rendered_at: string, 
+/**
+ * Hash of the saved config that was rendered
+ */
+settings_hash: string, };
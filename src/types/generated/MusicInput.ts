@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Background music input from frontend
+ */
+export type MusicInput = { path: string, 
+/**
+ * Linear gain (1.0 = unchanged)
+ */
+volume: number, 
+/**
+ * Cut clips on the beats of the track
+ */
+snap_to_beats?: boolean, };
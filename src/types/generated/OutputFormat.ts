@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Frame size and rate every clip is converted to before joining, so clips
+ * from different sources (e.g. 720p30 next to 1080p60) line up
+ */
+export type OutputFormat = { width: number, height: number, fps: number, };
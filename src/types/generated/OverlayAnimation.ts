@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Entrance/exit animation for overlay text
+ */
+export type OverlayAnimation = "none" | "fade" | "slide-in";
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OverlayAnimation } from "./OverlayAnimation";
+import type { OverlayOutline } from "./OverlayOutline";
+import type { OverlayPoint } from "./OverlayPoint";
+import type { OverlayPositionInput } from "./OverlayPositionInput";
+import type { OverlayShadow } from "./OverlayShadow";
+
+/**
+ * Overlay configuration input from frontend
+ */
+export type OverlayInput = { text: string, position: OverlayPositionInput, font_size: number, color: string, box_color?: string, 
+/**
+ * Font file (bundled Roboto when unset)
+ */
+font?: string, animation?: OverlayAnimation, custom_position?: OverlayPoint, outline?: OverlayOutline, shadow?: OverlayShadow, };
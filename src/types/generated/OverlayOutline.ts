@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Outline drawn around each glyph
+ */
+export type OverlayOutline = { 
+/**
+ * Width in pixels
+ */
+width: number, 
+/**
+ * Hex color or name (e.g., "000000", "black")
+ */
+color: string, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Free overlay position in percent of the room around the text
+ * (0,0 = flush top-left, 100,100 = flush bottom-right), so the text never
+ * leaves the frame
+ */
+export type OverlayPoint = { x: number, y: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Position for overlay text
+ */
+export type OverlayPosition = "top-left" | "top-right" | "bottom-left" | "bottom-right";
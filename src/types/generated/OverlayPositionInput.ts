@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Overlay position for the frontend
+ */
+export type OverlayPositionInput = "top-left" | "top-right" | "bottom-left" | "bottom-right";
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Drop shadow behind the text
+ */
+export type OverlayShadow = { 
+/**
+ * Offset in pixels (positive = right/down)
+ */
+x: number, y: number, 
+/**
+ * Hex color or name, optionally with opacity (e.g., "000000@0.6")
+ */
+color: string, };
@@ -0,0 +1,70 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ActionInfo } from "./ActionInfo";
+import type { ClipFilters } from "./ClipFilters";
+import type { MontagePreset } from "./MontagePreset";
+import type { StreamerInfo } from "./StreamerInfo";
+
+/**
+ * Project file schema (project.json)
+ */
+export type ProjectFile = { 
+/**
+ * Schema version for migrations
+ */
+version: number, 
+/**
+ * Unique project ID
+ */
+id: string, 
+/**
+ * Project name
+ */
+name: string, 
+/**
+ * Creation timestamp
+ */
+created_at: string, 
+/**
+ * Last update timestamp
+ */
+updated_at: string, 
+/**
+ * Game type (e.g., "valorant", "league", etc.)
+ */
+game_type: string | null, 
+/**
+ * Custom game ID (if game_type is "custom")
+ */
+custom_game_id: string | null, 
+/**
+ * Reference streamer ID
+ */
+reference_streamer_id: string, 
+/**
+ * Game start time in the reference VOD (seconds)
+ */
+game_start_time: number | null, 
+/**
+ * Streamers in this project
+ */
+streamers: Array<StreamerInfo>, 
+/**
+ * Actions/highlights in this project
+ */
+actions: Array<ActionInfo>, 
+/**
+ * Maximum clip duration in seconds (overrides the default 1 hour limit)
+ */
+max_clip_duration?: number | null, 
+/**
+ * Default filters for every re-encoded clip of the project
+ */
+filters?: ClipFilters | null, 
+/**
+ * IANA timezone of the event (e.g. "Europe/Berlin"), for times shown in exports
+ */
+timezone?: string | null, 
+/**
+ * Saved montage setups, to reopen and render again
+ */
+montages?: Array<MontagePreset>, };
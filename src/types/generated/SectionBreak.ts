@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Title card shown between clips, e.g. "Round 13 – Ace"
+ */
+export type SectionBreak = { 
+/**
+ * Index of the clip the card is shown before
+ */
+before: number, text: string, 
+/**
+ * Seconds on screen
+ */
+duration: number, 
+/**
+ * Hex background color without # (black when unset)
+ */
+background_color?: string, 
+/**
+ * Background image, scaled to fill the frame (wins over the color)
+ */
+background_image?: string, };
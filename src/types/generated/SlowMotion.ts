@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Part of a clip played slower, for impact moments
+ */
+export type SlowMotion = { 
+/**
+ * Seconds into the (trimmed) clip
+ */
+start: number, end: number, 
+/**
+ * How many times slower, e.g. 2 = half speed
+ */
+factor: number, };
@@ -0,0 +1,47 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CropRect } from "./CropRect";
+
+/**
+ * Streamer information
+ */
+export type StreamerInfo = { 
+/**
+ * Unique streamer ID within the project
+ */
+id: string, 
+/**
+ * Streamer display name
+ */
+name: string, 
+/**
+ * VOD URL
+ */
+vod_url: string, 
+/**
+ * Platform (twitch, youtube, etc.)
+ */
+platform: string, 
+/**
+ * Sync offset relative to reference streamer (seconds)
+ */
+sync_offset: number | null, 
+/**
+ * Whether this is the reference streamer
+ */
+is_reference: boolean, 
+/**
+ * Display color
+ */
+color: string, 
+/**
+ * Global streamer ID from database (for linking)
+ */
+global_streamer_id: string | null, 
+/**
+ * Wall-clock time the VOD recording started (from platform metadata)
+ */
+recorded_at: string | null, 
+/**
+ * Crop applied to this streamer's clips (e.g. to cut away the stream layout)
+ */
+crop?: CropRect | null, };
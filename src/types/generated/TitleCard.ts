@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Generated full-frame title card: centered white text, silent
+ */
+export type TitleCard = { text: string, 
+/**
+ * Seconds on screen
+ */
+duration: number, 
+/**
+ * Hex background color without # (black when unset)
+ */
+background_color?: string, 
+/**
+ * Background image, scaled to fill the frame (wins over the color)
+ */
+background_image?: string, };
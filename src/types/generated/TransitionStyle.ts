@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How one clip hands over to the next
+ */
+export type TransitionStyle = "none" | "fade" | "dissolve" | "wipe" | "slide";
@@ -7,6 +7,8 @@ export * from './models/globalStreamer';
 export * from './models/preset';
 export * from './models/montage';
 export * from './models/export';
+export * from './models/inbox';
+export * from './models/markers';
 
 // Re-export from new locations for backwards compatibility
 // TODO: Update imports across codebase to use @/utils and @/constants directly
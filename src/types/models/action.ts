@@ -18,3 +18,15 @@ export interface Action {
   gameTime: number; // Seconds from game start
   clips: Clip[];
 }
+
+/** Match API an action timeline is imported from (import_match_events) */
+export type MatchSource =
+  | { provider: 'riot'; match_id: string; api_key: string }
+  | { provider: 'opendota'; match_id: string };
+
+/** Kill (or merged fight) or objective of a match, ready to become an action */
+export interface GameEvent {
+  game_time: number;
+  name: string;
+  kind: 'kill' | 'objective';
+}
@@ -3,6 +3,23 @@
  * Used by useExport hook, montageStore, and Tauri backend.
  */
 
+import type { ClipFilters } from '../generated/ClipFilters';
+import type { ClipResult as ClipResultStatus } from '../generated/ClipResult';
+import type { CropRect } from '../generated/CropRect';
+import type { ExportErrorKind } from '../generated/ExportErrorKind';
+import type { ExportMethod } from '../generated/ExportMethod';
+import type { ExportProgress } from '../generated/ExportProgress';
+
+// Generated from the Rust types (pnpm types:generate)
+export type {
+  ClipFilters,
+  ClipResultStatus,
+  CropRect,
+  ExportErrorKind,
+  ExportMethod,
+  ExportProgress,
+};
+
 // ============ Clip Request ============
 
 /** Request to export a single clip */
@@ -17,8 +34,28 @@ export interface ClipRequest {
   in_point: number;
   out_point: number;
   index: number;
+  /** Maximum output size in MB (clip is re-encoded to fit) */
+  max_size_mb?: number;
+  /** Frame-accurate start (re-encode only up to the first keyframe) */
+  smart_cut?: boolean;
+  /** Playback speed factor (0.5 = slow motion, 2.0 = timelapse) */
+  speed?: number;
+  /** Burn the absolute VOD timecode into a corner of the clip */
+  burn_timecode?: boolean;
+  /** Export without audio (e.g. to avoid music copyright claims) */
+  strip_audio?: boolean;
+  /** Output container (default mp4) */
+  container?: ClipContainer;
+  /** Crop/scale/denoise filters (override the streamer crop and project defaults) */
+  filters?: ClipFilters;
 }
 
+/** Output container for exported clips */
+export type ClipContainer = 'mp4' | 'mkv' | 'mov' | 'ts';
+
+/** Image format for extracted still frames */
+export type FrameFormat = 'jpeg' | 'png';
+
 // ============ Export Result ============
 
 /** Result of an export operation */
@@ -28,33 +65,83 @@ export interface ExportResult {
   failed: number;
   errors: string[];
   output_dir: string;
+  /** When the export finished, in the project's timezone */
+  finished_at: string;
+  /** Exported clips with audio the platform muted */
+  muted: MutedClip[];
+  /** Clips above the default duration limit, allowed by the project's raised one */
+  long: LongClip[];
+}
+
+/** A clip overlapping muted parts of its VOD (DMCA'd music on Twitch) */
+export interface MutedClip {
+  index: number;
+  filename: string;
+  /** Seconds of the clip without audio */
+  muted_seconds: number;
+}
+
+/** A clip longer than the default duration limit */
+export interface LongClip {
+  index: number;
+  filename: string;
+  /** Length of the clip in the VOD (seconds) */
+  duration: number;
 }
 
 // ============ Clip File Status ============
 
 /** Status of a clip file on disk */
 export interface ClipFileStatus {
+  action_id: string;
   action_name: string;
   streamer_name: string;
   filename: string;
   is_downloaded: boolean;
 }
 
-// ============ Progress Events ============
+/** Health of a clip file, from verify_project_clips */
+export type ClipHealth =
+  | { status: 'ok' }
+  | { status: 'missing' }
+  | { status: 'empty' }
+  | { status: 'corrupt'; error: string }
+  | { status: 'duration_mismatch'; expected: number; actual: number };
 
-/** Progress event types from Rust backend */
-export type ExportProgress =
-  | { type: 'started'; total_clips: number }
-  | { type: 'clip_started'; index: number; action_name: string; streamer_name: string }
-  | { type: 'clip_progress'; index: number; percent: number; speed: string | null }
-  | { type: 'clip_completed'; index: number; status: ClipResultStatus }
-  | { type: 'finished'; exported: number; skipped: number; failed: number };
+/** Verification result for one project clip */
+export type ClipVerification = ClipHealth & {
+  path: string;
+  streamer_name: string;
+  action_name: string;
+  /** The broken file was deleted, so the next export downloads it again */
+  deleted: boolean;
+};
 
-/** Status of a single clip result */
-export type ClipResultStatus =
-  | 'success'
-  | 'skipped'
-  | { failed: { error: string } };
+/** One clip file renamed after its action was renamed */
+export interface ClipRename {
+  from: string;
+  to: string;
+  /** Why the file was left in place */
+  error: string | null;
+}
+
+/** Checksum manifest written to the project folder */
+export interface ManifestSummary {
+  manifest_path: string;
+  checksums_path: string;
+  files: number;
+  total_bytes: number;
+}
+
+/** Result of checking one file against a manifest */
+export type ManifestCheck = { path: string } & (
+  | { status: 'ok' }
+  | { status: 'missing' }
+  | { status: 'size_mismatch'; expected: number; actual: number }
+  | { status: 'hash_mismatch' }
+);
+
+// ============ Progress Events ============
 
 /** Detailed progress state for UI display */
 export interface DetailedProgress {
@@ -64,7 +151,32 @@ export interface DetailedProgress {
   currentClipStreamer: string;
   currentClipPercent: number;
   currentClipSpeed: string | null;
+  /** Current attempt number and method (set when an attempt starts) */
+  currentClipAttempt?: number;
+  currentClipMethod?: ExportMethod;
+  /** Shared download of overlapping clips running before the clips are cut */
+  currentBatch?: { batch: number; totalBatches: number; percent: number };
   completedClips: number;
   failedClips: number;
   skippedClips: number;
 }
+
+/** Stage of a VOD smoke test, in the order they run */
+export type SmokeStage = 'resolve' | 'probe' | 'cut' | 'verify';
+
+export interface SmokeStageResult {
+  stage: SmokeStage;
+  ok: boolean;
+  error: string | null;
+  elapsed_ms: number;
+}
+
+/** Result of smoke_test_vod */
+export interface SmokeTestReport {
+  /** Every stage passed */
+  ok: boolean;
+  /** VOD duration in seconds, if it could be probed */
+  duration: number | null;
+  /** Stages that ran; the test stops at the first failure */
+  stages: SmokeStageResult[];
+}
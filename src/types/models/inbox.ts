@@ -0,0 +1,13 @@
+import type { Platform } from './streamer';
+
+/**
+ * Watch-later VOD link saved outside any project (backend inbox.json).
+ */
+export interface InboxItem {
+  id: string;
+  vod_url: string;
+  platform: Platform;
+  streamer_name?: string;
+  note: string;
+  added_at: string;
+}
@@ -0,0 +1,26 @@
+/**
+ * Live action markers recorded during a match (backend live_markers.json).
+ * Times are wall-clock ISO strings; they are converted to VOD offsets later.
+ */
+export interface LiveMarker {
+  id: string;
+  label: string;
+  at: string;
+}
+
+export interface MarkerSession {
+  id: string;
+  name: string;
+  started_at: string;
+  ended_at?: string;
+  markers: LiveMarker[];
+}
+
+/** Marker converted into per-streamer VOD offsets (convert_marker_session) */
+export interface ConvertedMarker {
+  marker_id: string;
+  label: string;
+  at: string;
+  game_time: number | null;
+  offsets: { streamer_id: string; offset: number }[];
+}
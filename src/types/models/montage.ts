@@ -3,11 +3,65 @@
  * Types for the montage timeline and export feature
  */
 
-// ============ Timeline Types ============
+import type { CropRect } from './export';
+import type { Bookend as MontageBookend } from '../generated/Bookend';
+import type { ClipOverlay } from '../generated/ClipOverlay';
+import type { CreditsCard } from '../generated/CreditsCard';
+import type {
+  ImageOverlay as MontageImageOverlay,
+} from '../generated/ImageOverlay';
+import type { MontageAspect } from '../generated/MontageAspect';
+import type { MontageContainer } from '../generated/MontageContainer';
+import type { MontageEncoding } from '../generated/MontageEncoding';
+import type { MontageExportInput } from '../generated/MontageExportInput';
+import type { MontageFit } from '../generated/MontageFit';
+import type { MontageOrder } from '../generated/MontageOrder';
+import type { MontagePreset } from '../generated/MontagePreset';
+import type { MontageRender } from '../generated/MontageRender';
+import type { MusicInput as MontageMusic } from '../generated/MusicInput';
+import type {
+  OutputFormat as MontageOutputFormat,
+} from '../generated/OutputFormat';
+import type { OverlayAnimation } from '../generated/OverlayAnimation';
+import type { OverlayOutline } from '../generated/OverlayOutline';
+import type { OverlayPoint } from '../generated/OverlayPoint';
+import type { OverlayPosition } from '../generated/OverlayPosition';
+import type { OverlayShadow } from '../generated/OverlayShadow';
+import type { RenderMode as MontageRenderMode } from '../generated/RenderMode';
+import type { SectionBreak as MontageSection } from '../generated/SectionBreak';
+import type { SlowMotion } from '../generated/SlowMotion';
+import type { TitleCard } from '../generated/TitleCard';
+import type { TransitionStyle } from '../generated/TransitionStyle';
 
-export type TransitionType = 'none' | 'fade';
+// Generated from the Rust types (pnpm types:generate)
+export type {
+  ClipOverlay,
+  CreditsCard,
+  MontageAspect,
+  MontageBookend,
+  MontageContainer,
+  MontageEncoding,
+  MontageExportInput,
+  MontageFit,
+  MontageImageOverlay,
+  MontageMusic,
+  MontageOrder,
+  MontageOutputFormat,
+  MontagePreset,
+  MontageRender,
+  MontageRenderMode,
+  MontageSection,
+  OverlayAnimation,
+  OverlayOutline,
+  OverlayPoint,
+  OverlayPosition,
+  OverlayShadow,
+  SlowMotion,
+  TitleCard,
+  TransitionStyle,
+};
 
-export type OverlayPosition = 'top-left' | 'top-right' | 'bottom-left' | 'bottom-right';
+// ============ Timeline Types ============
 
 /** A clip in the montage timeline */
 export interface MontageClip {
@@ -26,6 +80,22 @@ export interface MontageClip {
   duration: number;
   /** Order in the timeline */
   order: number;
+  /** Overlay override for this clip */
+  overlay?: ClipOverlay;
+  /** Volume multiplier, 0-4 (unchanged when unset) */
+  volume?: number;
+  /** Seconds cut from the start of the clip file */
+  trimStart?: number;
+  /** Where the clip ends, in seconds into the file */
+  trimEnd?: number;
+  /** Part of the frame to keep, in source pixels */
+  crop?: CropRect;
+  /** Punch-in on the center of the (cropped) frame, 1-4 */
+  zoom?: number;
+  /** Part of the clip played slower */
+  slowmo?: SlowMotion;
+  /** Seconds the last frame is held, 0-10 */
+  freeze?: number;
 }
 
 /** Overlay configuration for text display */
@@ -40,6 +110,14 @@ export interface OverlayConfig {
   color: string;
   /** Background box color with opacity (e.g., "000000@0.5") */
   boxColor?: string;
+  /** Font file path (bundled Roboto when unset) */
+  font?: string;
+  /** Entrance/exit animation (static when unset) */
+  animation?: OverlayAnimation;
+  /** Free position (overrides the corner in position) */
+  customPosition?: OverlayPoint;
+  outline?: OverlayOutline;
+  shadow?: OverlayShadow;
 }
 
 /** Complete montage configuration */
@@ -51,23 +129,97 @@ export interface MontageConfig {
 
 // ============ Export Types ============
 
-/** Input for the export_montage Tauri command */
-export interface MontageExportInput {
-  clips: {
-    filename: string;
-    path: string;
-    duration: number;
-    streamer_name: string;
-  }[];
-  transition_duration: number;
-  overlay?: {
-    text: string;
-    position: OverlayPosition;
-    font_size: number;
-    color: string;
-    box_color?: string;
-  };
-  output_filename?: string;
+/** Beats found in a music track, as suggested cut points */
+export interface BeatAnalysis {
+  /** Track length in seconds (the montage loops it) */
+  duration: number;
+  /** Estimated tempo (absent when there's no clear beat) */
+  bpm?: number;
+  /** Onset times in seconds, ascending */
+  beats: number[];
+}
+
+/**
+ * Multi-POV arrangement: 2 clips side by side, up to 4 in a 2x2 grid, or the
+ * second clip inset in a corner of the first
+ */
+export type GridLayout = 'side_by_side' | 'grid_2x2' | 'picture_in_picture';
+
+/** Inset look for picture-in-picture (unset fields use the defaults) */
+export interface PipStyle {
+  /** Default bottom-right */
+  position?: OverlayPosition;
+  /** Inset width as a fraction of the frame width, 0.15-0.5 (default 0.3) */
+  size?: number;
+  /** Border width in pixels, 0 = none (default 4) */
+  border?: number;
+  /** Hex border color without # (default "FFFFFF") */
+  border_color?: string;
+}
+
+/** Section card in the timeline, attached to the clip it precedes */
+export interface TimelineSection extends TitleCard {
+  id: string;
+  /** MontageClip.id of the following clip */
+  beforeClipId: string;
+}
+
+/** A montage saved in its project, as listed */
+export interface MontageSummary {
+  id: string;
+  name: string;
+  updated_at: string;
+  clip_count: number;
+  last_render?: MontageRender;
+  /** Saved with other settings, or a clip file changed, since the last render */
+  stale: boolean;
+}
+
+/** Montage config with its clips pointed at their current files */
+export interface RelinkResult {
+  config: MontageExportInput;
+  /** Clips whose path was changed */
+  relinked: number;
+  /** Filenames of clips that couldn't be found */
+  missing: string[];
+}
+
+/** A montage config read from a file */
+export interface MontageConfigImport {
+  config: MontageExportInput;
+  /** Filenames of clips whose files don't exist here; relink them with relinkMontageClips */
+  missing: string[];
+}
+
+/** What would make a clip fail the montage export (`no_audio` clips are played with silence) */
+export type ClipIssue =
+  | { kind: 'missing' }
+  | { kind: 'unreadable'; error: string }
+  | { kind: 'no_video' }
+  | { kind: 'no_audio' }
+  | {
+      kind: 'size_mismatch';
+      width: number;
+      height: number;
+      expected_width: number;
+      expected_height: number;
+    };
+
+/** What ffprobe found in one clip of a montage */
+export interface ClipCheck {
+  /** Position in the montage */
+  index: number;
+  path: string;
+  video_codec?: string;
+  width?: number;
+  height?: number;
+  audio_codec?: string;
+  /** Empty when the clip is fine */
+  issues: ClipIssue[];
+}
+
+export interface InputReport {
+  clips: ClipCheck[];
 }
 
 /** Result from the export_montage Tauri command */
@@ -76,6 +228,8 @@ export interface MontageExportResult {
   output_path: string;
   duration: number;
   error?: string;
+  /** Encoder that wrote the file (absent when clips were joined by stream copy) */
+  encoder?: 'libx264' | 'h264_amf' | 'h264_nvenc' | 'h264_qsv' | 'libvpx-vp9';
 }
 
 // ============ Clip Info Types ============
@@ -85,9 +239,75 @@ export interface ClipFileInfo {
   filename: string;
   duration: number;
   path: string;
+  /** Low-res 360p proxy for scrubbing/previews, if generated */
+  proxy_path?: string;
   streamerName?: string;
 }
 
+/** Progress events emitted as "proxy-progress" while generating proxies */
+export type ProxyProgress =
+  | { type: 'started'; total_clips: number }
+  | { type: 'clip_started'; index: number; filename: string }
+  | { type: 'clip_progress'; index: number; percent: number }
+  | {
+      type: 'clip_completed';
+      index: number;
+      proxy_path: string | null;
+      error: string | null;
+    }
+  | { type: 'finished'; generated: number; skipped: number; failed: number };
+
+/** Progress events for batch montage rendering ("montage-batch-progress") */
+export type MontageBatchProgress =
+  | { type: 'started'; total_variants: number; total_clips: number }
+  | { type: 'variant_started'; index: number; output_path: string }
+  | { type: 'progress'; percent: number }
+  | { type: 'variant_completed'; index: number; result: MontageExportResult }
+  | { type: 'finished'; succeeded: number; failed: number };
+
+/** Progress events for per-streamer reels ("reel-progress") */
+export type ReelProgress =
+  | { type: 'started'; total_streamers: number }
+  | {
+      type: 'streamer_started';
+      index: number;
+      streamer_name: string;
+      total_clips: number;
+    }
+  | { type: 'streamer_progress'; index: number; percent: number }
+  | {
+      type: 'streamer_completed';
+      index: number;
+      streamer_name: string;
+      result: MontageExportResult;
+    }
+  | { type: 'finished'; succeeded: number; failed: number };
+
+/** Result of generating proxies for a project */
+export interface GenerateProxiesResult {
+  generated: number;
+  skipped: number;
+  failed: number;
+  proxies_dir: string;
+}
+
+/**
+ * Storyboard sprite sheets for hover-scrubbing.
+ * Tile n (frame at n * interval) is on sheet floor(n / (columns * rows)).
+ */
+export interface ThumbnailLayout {
+  /** Seconds between two tiles */
+  interval: number;
+  tile_width: number;
+  tile_height: number;
+  columns: number;
+  rows: number;
+  /** Total number of tiles across all sheets */
+  count: number;
+  /** Sheet image paths (display with convertFileSrc) */
+  sheets: string[];
+}
+
 // ============ Helpers ============
 
 /** Generate a unique ID for montage items */
@@ -104,6 +324,7 @@ export const OVERLAY_PRESETS = {
     fontSize: 32,
     color: 'FFFFFF',
     boxColor: '000000@0.5',
+    animation: 'fade' as OverlayAnimation,
   },
   minimal: {
     type: 'streamer_name' as const,
@@ -113,6 +334,15 @@ export const OVERLAY_PRESETS = {
     color: 'FFFFFF',
     boxColor: undefined,
   },
+  counter: {
+    type: 'custom_text' as const,
+    text: '{streamer} · Highlight {index}/{total}',
+    position: 'top-right' as OverlayPosition,
+    fontSize: 32,
+    color: 'FFFFFF',
+    boxColor: '000000@0.5',
+    animation: 'fade' as OverlayAnimation,
+  },
 };
 
 /** Default transition duration in seconds */
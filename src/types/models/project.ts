@@ -1,6 +1,7 @@
 import type { Streamer } from './streamer';
 import type { Action } from './action';
 import type { GameType } from './game';
+import type { ClipFilters } from './export';
 
 export interface Project {
   id: string;
@@ -12,6 +13,15 @@ export interface Project {
   referenceStreamerId: string;
   gameStartTime: number | null;
 
+  /** Maximum clip duration in seconds (overrides the default 1 hour limit) */
+  maxClipDuration?: number | null;
+
+  /** Default filters for every re-encoded clip */
+  filters?: ClipFilters | null;
+
+  /** IANA timezone of the event (e.g. "Europe/Berlin"), for times shown in exports */
+  timezone?: string | null;
+
   // Game association (optional)
   gameType?: GameType | null;
   customGameId?: string;
@@ -1,3 +1,5 @@
+import type { CropRect } from './export';
+
 export type Platform = 'twitch' | 'youtube' | 'other';
 
 export interface Streamer {
@@ -17,4 +19,10 @@ export interface Streamer {
 
   // Link to global streamer database
   globalStreamerId: string | null;
+
+  // Wall-clock VOD recording start (ISO), used to convert live markers
+  recordedAt?: string | null;
+
+  // Crop applied to this streamer's clips (e.g. to cut away the stream layout)
+  crop?: CropRect | null;
 }
@@ -18,6 +18,24 @@ export function detectPlatform(url: string): Platform {
   return 'other';
 }
 
+/**
+ * Whether a VOD URL is a local recording (file:// URL or absolute path)
+ */
+export function isLocalVideoPath(url: string): boolean {
+  return /^(file:\/\/|\/|[a-zA-Z]:[\\/]|\\\\)/.test(url.trim());
+}
+
+/**
+ * Filesystem path of a local recording URL
+ */
+export function localVideoPath(url: string): string {
+  const trimmed = url.trim();
+  if (!trimmed.startsWith('file://')) return trimmed;
+  const path = decodeURIComponent(trimmed.slice('file://'.length));
+  // file:///C:/... on Windows
+  return /^\/[a-zA-Z]:/.test(path) ? path.slice(1) : path;
+}
+
 /**
  * Format seconds into human-readable time string (e.g., "1:23:45" or "3:45")
  */
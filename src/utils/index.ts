@@ -1,6 +1,8 @@
 export {
   generateId,
   detectPlatform,
+  isLocalVideoPath,
+  localVideoPath,
   formatTime,
   parseTime,
   calculateVodTime,